@@ -1,5 +1,13 @@
 //! OpenCL extensions which don't have external (OpenGL, D3D) dependencies.
 
+#![allow(non_camel_case_types, dead_code, non_upper_case_globals)]
+
+use libc::{c_void, size_t};
+use crate::cl_h::{cl_int, cl_uint, cl_ulong, cl_char, cl_bool, cl_bitfield, cl_platform_id,
+    cl_device_id, cl_context, cl_command_queue, cl_program, cl_kernel, cl_event,
+    cl_queue_properties, cl_device_info, cl_kernel_sub_group_info, cl_mem_migration_flags,
+    cl_kernel_exec_info, cl_command_type};
+
 // /*******************************************************************************
 //  * Copyright (c) 2008-2015 The Khronos Group Inc.
 //  *
@@ -320,4 +328,487 @@
 // #endif
 
 
-// #endif /* __CL_EXT_H */
\ No newline at end of file
+// #endif /* __CL_EXT_H */
+
+
+//################################ NEW #####################################
+
+// /***************************************
+//  * cl_khr_priority_hints extension     *
+//  ***************************************/
+// #define cl_khr_priority_hints 1
+
+pub type cl_queue_priority_khr = cl_uint;
+
+// cl_command_queue_properties
+pub const CL_QUEUE_PRIORITY_KHR:                        cl_queue_properties = 0x1096;
+
+// cl_queue_priority_khr
+pub const CL_QUEUE_PRIORITY_HIGH_KHR:                   cl_queue_priority_khr = 1 << 0;
+pub const CL_QUEUE_PRIORITY_MED_KHR:                    cl_queue_priority_khr = 1 << 1;
+pub const CL_QUEUE_PRIORITY_LOW_KHR:                    cl_queue_priority_khr = 1 << 2;
+
+// /***************************************
+//  * cl_khr_throttle_hints extension     *
+//  ***************************************/
+// #define cl_khr_throttle_hints 1
+
+pub type cl_queue_throttle_khr = cl_uint;
+
+// cl_command_queue_properties
+pub const CL_QUEUE_THROTTLE_KHR:                        cl_queue_properties = 0x1097;
+
+// cl_queue_throttle_khr
+pub const CL_QUEUE_THROTTLE_HIGH_KHR:                   cl_queue_throttle_khr = 1 << 0;
+pub const CL_QUEUE_THROTTLE_MED_KHR:                    cl_queue_throttle_khr = 1 << 1;
+pub const CL_QUEUE_THROTTLE_LOW_KHR:                    cl_queue_throttle_khr = 1 << 2;
+
+// /***************************************
+//  * cl_khr_icd extension                *
+//  ***************************************/
+// #define cl_khr_icd 1
+
+// cl_platform_info
+pub const CL_PLATFORM_ICD_SUFFIX_KHR:                   cl_uint = 0x0920;
+
+// typedef CL_API_ENTRY cl_int (CL_API_CALL *clIcdGetPlatformIDsKHR_fn)(
+//     cl_uint          /* num_entries */,
+//     cl_platform_id * /* platforms */,
+//     cl_uint *        /* num_platforms */);
+pub type clIcdGetPlatformIDsKHR_fn = unsafe extern "system" fn(
+    num_entries: cl_uint,
+    platforms: *mut cl_platform_id,
+    num_platforms: *mut cl_uint)
+    -> cl_int;
+
+// /***************************************
+//  * cl_khr_terminate_context extension  *
+//  ***************************************/
+// #define cl_khr_terminate_context 1
+
+pub const CL_DEVICE_TERMINATE_CAPABILITY_KHR:           cl_device_info = 0x200F;
+pub const CL_CONTEXT_TERMINATE_KHR:                     cl_uint = 0x2010;
+
+// typedef CL_API_ENTRY cl_int (CL_API_CALL *clTerminateContextKHR_fn)(cl_context /* context */) CL_EXT_SUFFIX__VERSION_1_2;
+pub type clTerminateContextKHR_fn = unsafe extern "system" fn(context: cl_context) -> cl_int;
+
+// /***************************************
+//  * cl_khr_spir extension               *
+//  ***************************************/
+
+pub const CL_DEVICE_SPIR_VERSIONS:                      cl_device_info = 0x40E0;
+pub const CL_PROGRAM_BINARY_TYPE_INTERMEDIATE:          cl_bitfield = 0x40E1;
+
+// /***************************************
+//  * cl_khr_il_program extension         *
+//  ***************************************/
+// #define cl_khr_il_program 1
+
+// cl_device_info
+pub const CL_DEVICE_IL_VERSION_KHR:                     cl_device_info = 0x105B;
+
+// cl_program_info
+pub const CL_PROGRAM_IL_KHR:                            cl_uint = 0x1169;
+
+// typedef CL_API_ENTRY cl_program (CL_API_CALL *clCreateProgramWithILKHR_fn)(
+//     cl_context   context,
+//     const void*  il,
+//     size_t       length,
+//     cl_int*      errcode_ret) CL_EXT_SUFFIX__VERSION_1_2;
+pub type clCreateProgramWithILKHR_fn = unsafe extern "system" fn(
+    context: cl_context,
+    il: *const c_void,
+    length: size_t,
+    errcode_ret: *mut cl_int)
+    -> cl_program;
+
+// /***************************************
+//  * cl_khr_create_command_queue extension
+//  ***************************************/
+// #define cl_khr_create_command_queue 1
+
+pub type cl_queue_properties_khr = cl_bitfield;
+
+// typedef CL_API_ENTRY cl_command_queue (CL_API_CALL *clCreateCommandQueueWithPropertiesKHR_fn)(
+//     cl_context                     context,
+//     cl_device_id                   device,
+//     const cl_queue_properties_khr* properties,
+//     cl_int*                        errcode_ret) CL_EXT_SUFFIX__VERSION_1_2;
+pub type clCreateCommandQueueWithPropertiesKHR_fn = unsafe extern "system" fn(
+    context: cl_context,
+    device: cl_device_id,
+    properties: *const cl_queue_properties_khr,
+    errcode_ret: *mut cl_int)
+    -> cl_command_queue;
+
+// /***************************************
+//  * cl_khr_subgroups extension          *
+//  ***************************************/
+// #define cl_khr_subgroups 1
+
+// cl_kernel_sub_group_info
+pub const CL_KERNEL_MAX_SUB_GROUP_SIZE_FOR_NDRANGE_KHR: cl_kernel_sub_group_info = 0x2033;
+pub const CL_KERNEL_SUB_GROUP_COUNT_FOR_NDRANGE_KHR:    cl_kernel_sub_group_info = 0x2034;
+
+// typedef CL_API_ENTRY cl_int (CL_API_CALL *clGetKernelSubGroupInfoKHR_fn)(
+//     cl_kernel                in_kernel,
+//     cl_device_id             in_device,
+//     cl_kernel_sub_group_info param_name,
+//     size_t                   input_value_size,
+//     const void*              input_value,
+//     size_t                   param_value_size,
+//     void*                    param_value,
+//     size_t*                  param_value_size_ret) CL_EXT_SUFFIX__VERSION_2_0_DEPRECATED;
+pub type clGetKernelSubGroupInfoKHR_fn = unsafe extern "system" fn(
+    in_kernel: cl_kernel,
+    in_device: cl_device_id,
+    param_name: cl_kernel_sub_group_info,
+    input_value_size: size_t,
+    input_value: *const c_void,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t)
+    -> cl_int;
+
+// /***************************************
+//  * cl_khr_device_uuid extension        *
+//  ***************************************/
+// #define cl_khr_device_uuid 1
+
+pub const CL_UUID_SIZE_KHR:                             usize = 16;
+pub const CL_LUID_SIZE_KHR:                             usize = 8;
+
+// cl_device_info
+pub const CL_DEVICE_UUID_KHR:                           cl_device_info = 0x106A;
+pub const CL_DRIVER_UUID_KHR:                           cl_device_info = 0x106B;
+pub const CL_DEVICE_LUID_VALID_KHR:                     cl_device_info = 0x106C;
+pub const CL_DEVICE_LUID_KHR:                           cl_device_info = 0x106D;
+pub const CL_DEVICE_NODE_MASK_KHR:                      cl_device_info = 0x106E;
+
+// /***************************************
+//  * cl_khr_pci_bus_info extension       *
+//  ***************************************/
+// #define cl_khr_pci_bus_info 1
+
+// typedef struct _cl_device_pci_bus_info_khr {
+//     cl_uint pci_domain;
+//     cl_uint pci_bus;
+//     cl_uint pci_device;
+//     cl_uint pci_function;
+// } cl_device_pci_bus_info_khr;
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct cl_device_pci_bus_info_khr {
+    pub pci_domain: cl_uint,
+    pub pci_bus: cl_uint,
+    pub pci_device: cl_uint,
+    pub pci_function: cl_uint,
+}
+
+// cl_device_info
+pub const CL_DEVICE_PCI_BUS_INFO_KHR:                   cl_device_info = 0x410F;
+
+// /***************************************
+//  * cl_nv_device_attribute_query        *
+//  ***************************************/
+
+// cl_device_info
+pub const CL_DEVICE_COMPUTE_CAPABILITY_MAJOR_NV:        cl_device_info = 0x4000;
+pub const CL_DEVICE_COMPUTE_CAPABILITY_MINOR_NV:        cl_device_info = 0x4001;
+pub const CL_DEVICE_REGISTERS_PER_BLOCK_NV:             cl_device_info = 0x4002;
+pub const CL_DEVICE_WARP_SIZE_NV:                       cl_device_info = 0x4003;
+pub const CL_DEVICE_GPU_OVERLAP_NV:                     cl_device_info = 0x4004;
+pub const CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV:             cl_device_info = 0x4005;
+pub const CL_DEVICE_INTEGRATED_MEMORY_NV:               cl_device_info = 0x4006;
+pub const CL_DEVICE_ATTRIBUTE_ASYNC_ENGINE_COUNT_NV:    cl_device_info = 0x4007;
+pub const CL_DEVICE_PCI_BUS_ID_NV:                      cl_device_info = 0x4008;
+pub const CL_DEVICE_PCI_SLOT_ID_NV:                     cl_device_info = 0x4009;
+pub const CL_DEVICE_PCI_DOMAIN_ID_NV:                   cl_device_info = 0x400A;
+
+// /***************************************
+//  * cl_amd_device_attribute_query       *
+//  ***************************************/
+
+// cl_device_info
+pub const CL_DEVICE_PROFILING_TIMER_OFFSET_AMD:         cl_device_info = 0x4036;
+pub const CL_DEVICE_TOPOLOGY_AMD:                       cl_device_info = 0x4037;
+pub const CL_DEVICE_BOARD_NAME_AMD:                     cl_device_info = 0x4038;
+pub const CL_DEVICE_GLOBAL_FREE_MEMORY_AMD:             cl_device_info = 0x4039;
+pub const CL_DEVICE_SIMD_PER_COMPUTE_UNIT_AMD:          cl_device_info = 0x4040;
+pub const CL_DEVICE_SIMD_WIDTH_AMD:                     cl_device_info = 0x4041;
+pub const CL_DEVICE_SIMD_INSTRUCTION_WIDTH_AMD:         cl_device_info = 0x4042;
+pub const CL_DEVICE_WAVEFRONT_WIDTH_AMD:                cl_device_info = 0x4043;
+pub const CL_DEVICE_GLOBAL_MEM_CHANNELS_AMD:            cl_device_info = 0x4044;
+pub const CL_DEVICE_GLOBAL_MEM_CHANNEL_BANKS_AMD:       cl_device_info = 0x4045;
+pub const CL_DEVICE_GLOBAL_MEM_CHANNEL_BANK_WIDTH_AMD:  cl_device_info = 0x4046;
+pub const CL_DEVICE_LOCAL_MEM_SIZE_PER_COMPUTE_UNIT_AMD: cl_device_info = 0x4047;
+pub const CL_DEVICE_LOCAL_MEM_BANKS_AMD:                cl_device_info = 0x4048;
+pub const CL_DEVICE_THREAD_TRACE_SUPPORTED_AMD:         cl_device_info = 0x4049;
+pub const CL_DEVICE_GFXIP_MAJOR_AMD:                    cl_device_info = 0x404A;
+pub const CL_DEVICE_GFXIP_MINOR_AMD:                    cl_device_info = 0x404B;
+pub const CL_DEVICE_AVAILABLE_ASYNC_QUEUES_AMD:         cl_device_info = 0x404C;
+pub const CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_AMD:      cl_device_info = 0x4030;
+pub const CL_DEVICE_MAX_WORK_GROUP_SIZE_AMD:            cl_device_info = 0x4031;
+pub const CL_DEVICE_PREFERRED_CONSTANT_BUFFER_SIZE_AMD: cl_device_info = 0x4033;
+pub const CL_DEVICE_PCIE_ID_AMD:                        cl_device_info = 0x4034;
+
+// CL_DEVICE_TOPOLOGY_AMD types
+pub const CL_DEVICE_TOPOLOGY_TYPE_PCIE_AMD:             cl_uint = 1;
+
+// typedef union {
+//     struct { cl_uint type; cl_uint data[5]; } raw;
+//     struct { cl_uint type; cl_char unused[17]; cl_char bus; cl_char device; cl_char function; } pcie;
+// } cl_device_topology_amd;
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct cl_device_topology_amd_raw {
+    pub type_: cl_uint,
+    pub data: [cl_uint; 5],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct cl_device_topology_amd_pcie {
+    pub type_: cl_uint,
+    pub unused: [cl_char; 17],
+    pub bus: cl_char,
+    pub device: cl_char,
+    pub function: cl_char,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union cl_device_topology_amd {
+    pub raw: cl_device_topology_amd_raw,
+    pub pcie: cl_device_topology_amd_pcie,
+}
+
+// /***************************************
+//  * cl_arm_printf extension             *
+//  ***************************************/
+// #define cl_arm_printf 1
+
+// cl_context_properties
+pub const CL_PRINTF_CALLBACK_ARM:                       cl_uint = 0x40B0;
+pub const CL_PRINTF_BUFFERSIZE_ARM:                     cl_uint = 0x40B1;
+
+// /***************************************
+//  * cl_intel_unified_shared_memory      *
+//  ***************************************/
+// #define cl_intel_unified_shared_memory 1
+
+pub type cl_device_unified_shared_memory_capabilities_intel = cl_bitfield;
+pub type cl_mem_properties_intel = cl_ulong;
+pub type cl_mem_alloc_flags_intel = cl_bitfield;
+pub type cl_mem_info_intel = cl_uint;
+pub type cl_unified_shared_memory_type_intel = cl_uint;
+pub type cl_mem_advice_intel = cl_uint;
+
+// cl_device_info
+pub const CL_DEVICE_HOST_MEM_CAPABILITIES_INTEL:        cl_device_info = 0x4190;
+pub const CL_DEVICE_DEVICE_MEM_CAPABILITIES_INTEL:      cl_device_info = 0x4191;
+pub const CL_DEVICE_SINGLE_DEVICE_SHARED_MEM_CAPABILITIES_INTEL: cl_device_info = 0x4192;
+pub const CL_DEVICE_CROSS_DEVICE_SHARED_MEM_CAPABILITIES_INTEL: cl_device_info = 0x4193;
+pub const CL_DEVICE_SHARED_SYSTEM_MEM_CAPABILITIES_INTEL: cl_device_info = 0x4194;
+
+// cl_device_unified_shared_memory_capabilities_intel - bitfield
+pub const CL_UNIFIED_SHARED_MEMORY_ACCESS_INTEL:        cl_bitfield = 1 << 0;
+pub const CL_UNIFIED_SHARED_MEMORY_ATOMIC_ACCESS_INTEL: cl_bitfield = 1 << 1;
+pub const CL_UNIFIED_SHARED_MEMORY_CONCURRENT_ACCESS_INTEL: cl_bitfield = 1 << 2;
+pub const CL_UNIFIED_SHARED_MEMORY_CONCURRENT_ATOMIC_ACCESS_INTEL: cl_bitfield = 1 << 3;
+
+// cl_mem_properties_intel
+pub const CL_MEM_ALLOC_FLAGS_INTEL:                     cl_mem_properties_intel = 0x4195;
+
+// cl_mem_alloc_flags_intel - bitfield
+pub const CL_MEM_ALLOC_WRITE_COMBINED_INTEL:            cl_bitfield = 1 << 0;
+pub const CL_MEM_ALLOC_INITIAL_PLACEMENT_DEVICE_INTEL:  cl_bitfield = 1 << 1;
+pub const CL_MEM_ALLOC_INITIAL_PLACEMENT_HOST_INTEL:    cl_bitfield = 1 << 2;
+
+// cl_mem_alloc_info_intel
+pub const CL_MEM_ALLOC_TYPE_INTEL:                      cl_mem_info_intel = 0x419A;
+pub const CL_MEM_ALLOC_BASE_PTR_INTEL:                  cl_mem_info_intel = 0x419B;
+pub const CL_MEM_ALLOC_SIZE_INTEL:                      cl_mem_info_intel = 0x419C;
+pub const CL_MEM_ALLOC_DEVICE_INTEL:                    cl_mem_info_intel = 0x419D;
+
+// cl_unified_shared_memory_type_intel
+pub const CL_MEM_TYPE_UNKNOWN_INTEL:                    cl_unified_shared_memory_type_intel = 0x4196;
+pub const CL_MEM_TYPE_HOST_INTEL:                       cl_unified_shared_memory_type_intel = 0x4197;
+pub const CL_MEM_TYPE_DEVICE_INTEL:                     cl_unified_shared_memory_type_intel = 0x4198;
+pub const CL_MEM_TYPE_SHARED_INTEL:                     cl_unified_shared_memory_type_intel = 0x4199;
+
+// cl_kernel_exec_info
+pub const CL_KERNEL_EXEC_INFO_INDIRECT_HOST_ACCESS_INTEL: cl_kernel_exec_info = 0x4200;
+pub const CL_KERNEL_EXEC_INFO_INDIRECT_DEVICE_ACCESS_INTEL: cl_kernel_exec_info = 0x4201;
+pub const CL_KERNEL_EXEC_INFO_INDIRECT_SHARED_ACCESS_INTEL: cl_kernel_exec_info = 0x4202;
+pub const CL_KERNEL_EXEC_INFO_USM_PTRS_INTEL:           cl_kernel_exec_info = 0x4203;
+
+// cl_command_type
+pub const CL_COMMAND_MEMFILL_INTEL:                     cl_command_type = 0x4204;
+pub const CL_COMMAND_MEMCPY_INTEL:                      cl_command_type = 0x4205;
+pub const CL_COMMAND_MIGRATEMEM_INTEL:                  cl_command_type = 0x4206;
+pub const CL_COMMAND_MEMADVISE_INTEL:                   cl_command_type = 0x4207;
+
+// typedef CL_API_ENTRY void* (CL_API_CALL *clHostMemAllocINTEL_fn)(
+//     cl_context context,
+//     const cl_mem_properties_intel* properties,
+//     size_t size,
+//     cl_uint alignment,
+//     cl_int* errcode_ret);
+pub type clHostMemAllocINTEL_fn = unsafe extern "system" fn(
+    context: cl_context,
+    properties: *const cl_mem_properties_intel,
+    size: size_t,
+    alignment: cl_uint,
+    errcode_ret: *mut cl_int)
+    -> *mut c_void;
+
+// typedef CL_API_ENTRY void* (CL_API_CALL *clDeviceMemAllocINTEL_fn)(
+//     cl_context context,
+//     cl_device_id device,
+//     const cl_mem_properties_intel* properties,
+//     size_t size,
+//     cl_uint alignment,
+//     cl_int* errcode_ret);
+pub type clDeviceMemAllocINTEL_fn = unsafe extern "system" fn(
+    context: cl_context,
+    device: cl_device_id,
+    properties: *const cl_mem_properties_intel,
+    size: size_t,
+    alignment: cl_uint,
+    errcode_ret: *mut cl_int)
+    -> *mut c_void;
+
+// typedef CL_API_ENTRY void* (CL_API_CALL *clSharedMemAllocINTEL_fn)(
+//     cl_context context,
+//     cl_device_id device,
+//     const cl_mem_properties_intel* properties,
+//     size_t size,
+//     cl_uint alignment,
+//     cl_int* errcode_ret);
+pub type clSharedMemAllocINTEL_fn = unsafe extern "system" fn(
+    context: cl_context,
+    device: cl_device_id,
+    properties: *const cl_mem_properties_intel,
+    size: size_t,
+    alignment: cl_uint,
+    errcode_ret: *mut cl_int)
+    -> *mut c_void;
+
+// typedef CL_API_ENTRY cl_int (CL_API_CALL *clMemFreeINTEL_fn)(
+//     cl_context context,
+//     void* ptr);
+pub type clMemFreeINTEL_fn = unsafe extern "system" fn(
+    context: cl_context,
+    ptr: *mut c_void)
+    -> cl_int;
+
+// typedef CL_API_ENTRY cl_int (CL_API_CALL *clMemBlockingFreeINTEL_fn)(
+//     cl_context context,
+//     void* ptr);
+pub type clMemBlockingFreeINTEL_fn = unsafe extern "system" fn(
+    context: cl_context,
+    ptr: *mut c_void)
+    -> cl_int;
+
+// typedef CL_API_ENTRY cl_int (CL_API_CALL *clGetMemAllocInfoINTEL_fn)(
+//     cl_context context,
+//     const void* ptr,
+//     cl_mem_info_intel param_name,
+//     size_t param_value_size,
+//     void* param_value,
+//     size_t* param_value_size_ret);
+pub type clGetMemAllocInfoINTEL_fn = unsafe extern "system" fn(
+    context: cl_context,
+    ptr: *const c_void,
+    param_name: cl_mem_info_intel,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t)
+    -> cl_int;
+
+// typedef CL_API_ENTRY cl_int (CL_API_CALL *clSetKernelArgMemPointerINTEL_fn)(
+//     cl_kernel kernel,
+//     cl_uint arg_index,
+//     const void* arg_value);
+pub type clSetKernelArgMemPointerINTEL_fn = unsafe extern "system" fn(
+    kernel: cl_kernel,
+    arg_index: cl_uint,
+    arg_value: *const c_void)
+    -> cl_int;
+
+// typedef CL_API_ENTRY cl_int (CL_API_CALL *clEnqueueMemFillINTEL_fn)(
+//     cl_command_queue command_queue,
+//     void* dst_ptr,
+//     const void* pattern,
+//     size_t pattern_size,
+//     size_t size,
+//     cl_uint num_events_in_wait_list,
+//     const cl_event* event_wait_list,
+//     cl_event* event);
+pub type clEnqueueMemFillINTEL_fn = unsafe extern "system" fn(
+    command_queue: cl_command_queue,
+    dst_ptr: *mut c_void,
+    pattern: *const c_void,
+    pattern_size: size_t,
+    size: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event)
+    -> cl_int;
+
+// typedef CL_API_ENTRY cl_int (CL_API_CALL *clEnqueueMemcpyINTEL_fn)(
+//     cl_command_queue command_queue,
+//     cl_bool blocking,
+//     void* dst_ptr,
+//     const void* src_ptr,
+//     size_t size,
+//     cl_uint num_events_in_wait_list,
+//     const cl_event* event_wait_list,
+//     cl_event* event);
+pub type clEnqueueMemcpyINTEL_fn = unsafe extern "system" fn(
+    command_queue: cl_command_queue,
+    blocking: cl_bool,
+    dst_ptr: *mut c_void,
+    src_ptr: *const c_void,
+    size: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event)
+    -> cl_int;
+
+// typedef CL_API_ENTRY cl_int (CL_API_CALL *clEnqueueMigrateMemINTEL_fn)(
+//     cl_command_queue command_queue,
+//     const void* ptr,
+//     size_t size,
+//     cl_mem_migration_flags flags,
+//     cl_uint num_events_in_wait_list,
+//     const cl_event* event_wait_list,
+//     cl_event* event);
+pub type clEnqueueMigrateMemINTEL_fn = unsafe extern "system" fn(
+    command_queue: cl_command_queue,
+    ptr: *const c_void,
+    size: size_t,
+    flags: cl_mem_migration_flags,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event)
+    -> cl_int;
+
+// typedef CL_API_ENTRY cl_int (CL_API_CALL *clEnqueueMemAdviseINTEL_fn)(
+//     cl_command_queue command_queue,
+//     const void* ptr,
+//     size_t size,
+//     cl_mem_advice_intel advice,
+//     cl_uint num_events_in_wait_list,
+//     const cl_event* event_wait_list,
+//     cl_event* event);
+pub type clEnqueueMemAdviseINTEL_fn = unsafe extern "system" fn(
+    command_queue: cl_command_queue,
+    ptr: *const c_void,
+    size: size_t,
+    advice: cl_mem_advice_intel,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event)
+    -> cl_int;
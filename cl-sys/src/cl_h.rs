@@ -86,6 +86,10 @@ pub type cl_command_type                    = cl_uint;
 pub type cl_profiling_info                  = cl_uint;
 pub type cl_sampler_properties              = cl_bitfield;
 pub type cl_kernel_exec_info                = cl_uint;
+pub type cl_version                         = cl_uint;
+pub type cl_mem_properties                  = cl_ulong;
+pub type cl_device_atomic_capabilities      = cl_bitfield;
+pub type cl_device_device_enqueue_capabilities = cl_bitfield;
 
 #[repr(C)]
 pub struct cl_image_format {
@@ -114,6 +118,15 @@ pub struct cl_buffer_region {
     pub size:       size_t,
 }
 
+pub const CL_NAME_VERSION_MAX_NAME_SIZE:                usize = 64;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct cl_name_version {
+    pub version:    cl_version,
+    pub name:       [c_char; CL_NAME_VERSION_MAX_NAME_SIZE],
+}
+
 // Error Codes:
 pub const CL_SUCCESS:                                      cl_int = 0;
 pub const CL_DEVICE_NOT_FOUND:                             cl_int = -1;
@@ -177,6 +190,8 @@ pub const CL_INVALID_LINKER_OPTIONS:                       cl_int = -67;
 pub const CL_INVALID_DEVICE_PARTITION_COUNT:               cl_int = -68;
 pub const CL_INVALID_PIPE_SIZE:                            cl_int = -69;
 pub const CL_INVALID_DEVICE_QUEUE:                         cl_int = -70;
+pub const CL_INVALID_SPEC_ID:                              cl_int = -71;
+pub const CL_MAX_SIZE_RESTRICTION_EXCEEDED:                cl_int = -72;
 pub const CL_PLATFORM_NOT_FOUND_KHR:                       cl_int = -1001;
 
 
@@ -186,6 +201,16 @@ pub const CL_VERSION_1_1:                               cl_bool = 1;
 pub const CL_VERSION_1_2:                               cl_bool = 1;
 pub const CL_VERSION_2_0:                               cl_bool = 1;
 pub const CL_VERSION_2_1:                               cl_bool = 1;
+pub const CL_VERSION_2_2:                               cl_bool = 1;
+pub const CL_VERSION_3_0:                               cl_bool = 1;
+
+// cl_version:
+pub const CL_VERSION_MAJOR_BITS:                        cl_uint = 10;
+pub const CL_VERSION_MINOR_BITS:                        cl_uint = 10;
+pub const CL_VERSION_PATCH_BITS:                        cl_uint = 12;
+pub const CL_VERSION_MAJOR_MASK:                        cl_uint = (1 << CL_VERSION_MAJOR_BITS) - 1;
+pub const CL_VERSION_MINOR_MASK:                        cl_uint = (1 << CL_VERSION_MINOR_BITS) - 1;
+pub const CL_VERSION_PATCH_MASK:                        cl_uint = (1 << CL_VERSION_PATCH_BITS) - 1;
 
 // cl_bool:
 pub const CL_FALSE:                                     cl_bool = 0;
@@ -202,6 +227,8 @@ pub const CL_PLATFORM_VENDOR:                           cl_uint = 0x0903;
 pub const CL_PLATFORM_EXTENSIONS:                       cl_uint = 0x0904;
     //###### NEW ########
     pub const CL_PLATFORM_HOST_TIMER_RESOLUTION:            cl_uint = 0x0905;
+    pub const CL_PLATFORM_NUMERIC_VERSION:                  cl_uint = 0x0906;
+    pub const CL_PLATFORM_EXTENSIONS_WITH_VERSION:          cl_uint = 0x0907;
 
 // cl_device_type - bitfield:
 pub const CL_DEVICE_TYPE_DEFAULT:                      cl_bitfield = 1 << 0;
@@ -310,6 +337,21 @@ pub const CL_DEVICE_IMAGE_BASE_ADDRESS_ALIGNMENT:           cl_uint = 0x104B;
     pub const CL_DEVICE_IL_VERSION:                             cl_uint = 0x105B;
     pub const CL_DEVICE_MAX_NUM_SUB_GROUPS:                     cl_uint = 0x105C;
     pub const CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS: cl_uint = 0x105D;
+    pub const CL_DEVICE_NUMERIC_VERSION:                        cl_uint = 0x105E;
+    pub const CL_DEVICE_EXTENSIONS_WITH_VERSION:                cl_uint = 0x1060;
+    pub const CL_DEVICE_ILS_WITH_VERSION:                       cl_uint = 0x1061;
+    pub const CL_DEVICE_BUILT_IN_KERNELS_WITH_VERSION:          cl_uint = 0x1062;
+    pub const CL_DEVICE_ATOMIC_MEMORY_CAPABILITIES:             cl_uint = 0x1063;
+    pub const CL_DEVICE_ATOMIC_FENCE_CAPABILITIES:              cl_uint = 0x1064;
+    pub const CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT:         cl_uint = 0x1065;
+    pub const CL_DEVICE_OPENCL_C_ALL_VERSIONS:                  cl_uint = 0x1066;
+    pub const CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_MULTIPLE:     cl_uint = 0x1067;
+    pub const CL_DEVICE_WORK_GROUP_COLLECTIVE_FUNCTIONS_SUPPORT: cl_uint = 0x1068;
+    pub const CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT:          cl_uint = 0x1069;
+    pub const CL_DEVICE_OPENCL_C_FEATURES:                      cl_uint = 0x106F;
+    pub const CL_DEVICE_DEVICE_ENQUEUE_CAPABILITIES:            cl_uint = 0x1070;
+    pub const CL_DEVICE_PIPE_SUPPORT:                           cl_uint = 0x1071;
+    pub const CL_DEVICE_LATEST_CONFORMANCE_VERSION_PASSED:      cl_uint = 0x1072;
 
 // cl_device_fp_config - bitfield:
 pub const CL_FP_DENORM:                                 cl_bitfield = 1 << 0;
@@ -372,6 +414,21 @@ pub const CL_DEVICE_AFFINITY_DOMAIN_NEXT_PARTITIONABLE: cl_bitfield = 1 << 5;
     pub const CL_DEVICE_SVM_FINE_GRAIN_SYSTEM:             cl_bitfield = 1 << 2;
     pub const CL_DEVICE_SVM_ATOMICS:                       cl_bitfield = 1 << 3;
 
+    //###### NEW ########
+    // cl_device_atomic_capabilities:
+    pub const CL_DEVICE_ATOMIC_ORDER_RELAXED:              cl_bitfield = 1 << 0;
+    pub const CL_DEVICE_ATOMIC_ORDER_ACQ_REL:              cl_bitfield = 1 << 1;
+    pub const CL_DEVICE_ATOMIC_ORDER_SEQ_CST:              cl_bitfield = 1 << 2;
+    pub const CL_DEVICE_ATOMIC_SCOPE_WORK_ITEM:            cl_bitfield = 1 << 3;
+    pub const CL_DEVICE_ATOMIC_SCOPE_WORK_GROUP:           cl_bitfield = 1 << 4;
+    pub const CL_DEVICE_ATOMIC_SCOPE_DEVICE:               cl_bitfield = 1 << 5;
+    pub const CL_DEVICE_ATOMIC_SCOPE_ALL_DEVICES:          cl_bitfield = 1 << 6;
+
+    //###### NEW ########
+    // cl_device_device_enqueue_capabilities:
+    pub const CL_DEVICE_QUEUE_SUPPORTED:                   cl_bitfield = 1 << 0;
+    pub const CL_DEVICE_QUEUE_REPLACEABLE_DEFAULT:         cl_bitfield = 1 << 1;
+
 // cl_command_queue_info:
 pub const CL_QUEUE_CONTEXT:                             cl_uint = 0x1090;
 pub const CL_QUEUE_DEVICE:                              cl_uint = 0x1091;
@@ -467,6 +524,7 @@ pub const CL_MEM_ASSOCIATED_MEMOBJECT:                  cl_uint = 0x1107;
 pub const CL_MEM_OFFSET:                                cl_uint = 0x1108;
     //###### NEW ########
     pub const CL_MEM_USES_SVM_POINTER:                      cl_uint = 0x1109;
+    pub const CL_MEM_PROPERTIES:                            cl_uint = 0x110A;
 
 // cl_image_info:
 pub const CL_IMAGE_FORMAT:                              cl_uint = 0x1110;
@@ -661,11 +719,7 @@ pub const CL_PROFILING_COMMAND_END:                     cl_uint = 0x1283;
     pub const CL_PROFILING_COMMAND_COMPLETE:                cl_uint = 0x1284;
 
 
-//#[link_args = "-L$OPENCL_LIB -lOpenCL"]
-#[cfg_attr(target_os = "macos", link(name = "OpenCL", kind = "framework"))]
-#[cfg_attr(target_os = "windows", link(name = "OpenCL"))]
-#[cfg_attr(not(target_os = "macos"), link(name = "OpenCL"))]
-extern "system" {
+cl_api! {
     // Platform API:
     pub fn clGetPlatformIDs(num_entries: cl_uint,
                             platforms: *mut cl_platform_id,
@@ -697,7 +751,7 @@ extern "system" {
     //                    cl_device_id *                       /* out_devices */,
     //                    cl_uint *                            /* num_devices_ret */) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clCreateSubDevices(in_device: cl_device_id,
                        properties: *const cl_device_partition_property,
                        num_devices: cl_uint,
@@ -707,13 +761,13 @@ extern "system" {
     // extern CL_API_ENTRY cl_int CL_API_CALL
     // clRetainDevice(cl_device_id /* device */) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clRetainDevice(device: cl_device_id) -> cl_int;
 
     // extern CL_API_ENTRY cl_int CL_API_CALL
     // clReleaseDevice(cl_device_id /* device */) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clReleaseDevice(device: cl_device_id ) -> cl_int;
 
     // extern CL_API_ENTRY cl_int CL_API_CALL
@@ -721,7 +775,7 @@ extern "system" {
     //                                cl_device_id         /* device */,
     //                                cl_command_queue     /* command_queue */) CL_API_SUFFIX__VERSION_2_1;
     //############################### NEW 2.1 #################################
-    #[cfg(feature = "opencl_version_2_1")]
+    @since "2.1"
     pub fn clSetDefaultDeviceCommandQueue(context: cl_context,
                                           device: cl_device_id,
                                           command_queue: cl_command_queue) -> cl_int;
@@ -731,18 +785,18 @@ extern "system" {
     //                         cl_ulong*       /* device_timestamp */,
     //                         cl_ulong*       /* host_timestamp */) CL_API_SUFFIX__VERSION_2_1;
     //############################### NEW 2.1 #################################
-    #[cfg(feature = "opencl_version_2_1")]
+    @since "2.1"
     pub fn clGetDeviceAndHostTimer(device: cl_device_id,
-                                   device_timestamp: cl_ulong,
-                                   host_timestamp: cl_ulong) -> cl_int;
+                                   device_timestamp: *mut cl_ulong,
+                                   host_timestamp: *mut cl_ulong) -> cl_int;
 
     // extern CL_API_ENTRY cl_int CL_API_CALL
     // clGetHostTimer(cl_device_id /* device */,
     //                cl_ulong *   /* host_timestamp */)  CL_API_SUFFIX__VERSION_2_1;
     //############################### NEW 2.1 #################################
-    #[cfg(feature = "opencl_version_2_1")]
+    @since "2.1"
     pub fn clGetHostTimer(device: cl_device_id,
-                          host_timestamp: cl_ulong) -> cl_int;
+                          host_timestamp: *mut cl_ulong) -> cl_int;
 
     // Context APIs:
     pub fn clCreateContext(properties: *const cl_context_properties,
@@ -781,7 +835,7 @@ extern "system" {
     //                                    const cl_queue_properties *    /* properties */,
     //                                    cl_int *                 /* errcode_ret */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clCreateCommandQueueWithProperties(context: cl_context,
                                               device: cl_device_id,
                                               properties: *const cl_queue_properties,
@@ -797,6 +851,16 @@ extern "system" {
                              param_value: *mut c_void,
                              param_value_size_ret: *mut size_t) -> cl_int;
 
+    // extern CL_API_ENTRY cl_int CL_API_CALL
+    // clSetContextDestructorCallback(cl_context         /* context */,
+    //                                void (CL_CALLBACK * /* pfn_notify */)(cl_context /* context */, void * /* user_data */),
+    //                                void *             /* user_data */) CL_API_SUFFIX__VERSION_3_0;
+    //############################### NEW 3.0 #################################
+    @since "3.0"
+    pub fn clSetContextDestructorCallback(context: cl_context,
+                  pfn_notify: Option<extern "C" fn (context: cl_context, user_data: *mut c_void)>,
+                  user_data: *mut c_void) -> cl_int;
+
     // Memory Object APIs:
     pub fn clCreateBuffer(context: cl_context,
                       flags: cl_mem_flags,
@@ -804,6 +868,22 @@ extern "system" {
                       host_ptr: *mut c_void,
                       errcode_ret: *mut cl_int) -> cl_mem;
 
+    // extern CL_API_ENTRY cl_mem CL_API_CALL
+    // clCreateBufferWithProperties(cl_context                /* context */,
+    //                              const cl_mem_properties * /* properties */,
+    //                              cl_mem_flags              /* flags */,
+    //                              size_t                    /* size */,
+    //                              void *                    /* host_ptr */,
+    //                              cl_int *                  /* errcode_ret */) CL_API_SUFFIX__VERSION_3_0;
+    //############################### NEW 3.0 #################################
+    @since "3.0"
+    pub fn clCreateBufferWithProperties(context: cl_context,
+                      properties: *const cl_mem_properties,
+                      flags: cl_mem_flags,
+                      size: size_t,
+                      host_ptr: *mut c_void,
+                      errcode_ret: *mut cl_int) -> cl_mem;
+
     pub fn clCreateSubBuffer(buffer: cl_mem,
                         flags: cl_mem_flags,
                         buffer_create_type: cl_buffer_create_type,
@@ -833,7 +913,7 @@ extern "system" {
                     errcode_ret: *mut cl_int) -> cl_mem;
 
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clCreateImage(context: cl_context,
                         flags: cl_mem_flags,
                         image_format: *const cl_image_format,
@@ -841,6 +921,24 @@ extern "system" {
                         host_ptr: *mut c_void,
                         errcode_ret: *mut cl_int) -> cl_mem;
 
+    // extern CL_API_ENTRY cl_mem CL_API_CALL
+    // clCreateImageWithProperties(cl_context                /* context */,
+    //                             const cl_mem_properties * /* properties */,
+    //                             cl_mem_flags              /* flags */,
+    //                             const cl_image_format *   /* image_format */,
+    //                             const cl_image_desc *     /* image_desc */,
+    //                             void *                    /* host_ptr */,
+    //                             cl_int *                  /* errcode_ret */) CL_API_SUFFIX__VERSION_3_0;
+    //############################### NEW 3.0 #################################
+    @since "3.0"
+    pub fn clCreateImageWithProperties(context: cl_context,
+                        properties: *const cl_mem_properties,
+                        flags: cl_mem_flags,
+                        image_format: *const cl_image_format,
+                        image_desc: *const cl_image_desc,
+                        host_ptr: *mut c_void,
+                        errcode_ret: *mut cl_int) -> cl_mem;
+
     // extern CL_API_ENTRY cl_mem CL_API_CALL
     // clCreatePipe(cl_context                 /* context */,
     //              cl_mem_flags               /* flags */,
@@ -849,7 +947,7 @@ extern "system" {
     //              const cl_pipe_properties * /* properties */,
     //              cl_int *                   /* errcode_ret */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clCreatePipe(context: cl_context,
                         flags: cl_mem_flags,
                         pipe_packet_size: cl_uint,
@@ -887,7 +985,7 @@ extern "system" {
     //               void *           /* param_value */,
     //               size_t *         /* param_value_size_ret */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clGetPipeInfo(pipe: cl_mem,
                          param_name: cl_pipe_info,
                          param_value_size: size_t,
@@ -905,7 +1003,7 @@ extern "system" {
     //            size_t           /* size */,
     //            cl_uint          /* alignment */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clSVMAlloc(context: cl_context,
                       flags: cl_svm_mem_flags,
                       size: size_t,
@@ -915,7 +1013,7 @@ extern "system" {
     // clSVMFree(cl_context        /* context */,
     //           void *            /* svm_pointer */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clSVMFree(context: cl_context,
                      svm_pointer: *mut c_void);
 
@@ -931,7 +1029,7 @@ extern "system" {
     //                               const cl_sampler_properties *  /* normalized_coords */,
     //                               cl_int *                       /* errcode_ret */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clCreateSamplerWithProperties(context: cl_context,
                                          normalized_coords: *const cl_sampler_properties,
                                          errcode_ret: *mut cl_int) -> cl_sampler;
@@ -968,11 +1066,11 @@ extern "system" {
     //                                  const char *          /* kernel_names */,
     //                                  cl_int *              /* errcode_ret */) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clCreateProgramWithBuiltInKernels(context: cl_context,
                                      num_devices: cl_uint,
                                      device_list: *const cl_device_id,
-                                     kernel_names: *mut char,
+                                     kernel_names: *const c_char,
                                      errcode_ret: *mut cl_int) -> cl_program;
 
     // extern CL_API_ENTRY cl_program CL_API_CALL
@@ -981,7 +1079,7 @@ extern "system" {
     //                      size_t         /* length */,
     //                      cl_int*        /* errcode_ret */) CL_API_SUFFIX__VERSION_2_1;
     //############################### NEW 2.1 #################################
-    #[cfg(feature = "opencl_version_2_1")]
+    @since "2.1"
     pub fn clCreateProgramWithIL(context: cl_context,
                                  il: *const c_void,
                                  length: size_t,
@@ -1012,7 +1110,7 @@ extern "system" {
     //                 void (CL_CALLBACK *  /* pfn_notify */)(cl_program /* program */, void * /* user_data */),
     //                 void *               /* user_data */) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clCompileProgram(program: cl_program,
                     num_devices: cl_uint,
                     device_list: *const cl_device_id,
@@ -1020,7 +1118,7 @@ extern "system" {
                     num_input_headers: cl_uint,
                     input_headers: *const cl_program,
                     header_include_names: *const *const c_char,
-                    pfn_notify: Option<extern fn (program: cl_program, user_data: *mut c_void)>,
+                    pfn_notify: Option<extern "C" fn (program: cl_program, user_data: *mut c_void)>,
                     user_data: *mut c_void) -> cl_int;
 
     // extern CL_API_ENTRY cl_program CL_API_CALL
@@ -1034,23 +1132,44 @@ extern "system" {
     //               void *               /* user_data */,
     //               cl_int *             /* errcode_ret */ ) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clLinkProgram(context: cl_context,
                   num_devices: cl_uint,
                   device_list: *const cl_device_id,
                   options: *const c_char,
                   num_input_programs: cl_uint,
                   input_programs: *const cl_program,
-                  pfn_notify: Option<extern fn (program: cl_program, user_data: *mut c_void)>,
+                  pfn_notify: Option<extern "C" fn (program: cl_program, user_data: *mut c_void)>,
                   user_data: *mut c_void,
                   errcode_ret: *mut cl_int) -> cl_program;
 
+    // extern CL_API_ENTRY cl_int CL_API_CALL
+    // clSetProgramReleaseCallback(cl_program          /* program */,
+    //                             void (CL_CALLBACK * /* pfn_notify */)(cl_program /* program */, void * /* user_data */),
+    //                             void *              /* user_data */) CL_API_SUFFIX__VERSION_2_2;
+    //############################### NEW 2.2 #################################
+    @since "2.2"
+    pub fn clSetProgramReleaseCallback(program: cl_program,
+                  pfn_notify: Option<extern "C" fn (program: cl_program, user_data: *mut c_void)>,
+                  user_data: *mut c_void) -> cl_int;
+
+    // extern CL_API_ENTRY cl_int CL_API_CALL
+    // clSetProgramSpecializationConstant(cl_program  /* program */,
+    //                                    cl_uint     /* spec_id */,
+    //                                    size_t      /* spec_size */,
+    //                                    const void* /* spec_value */) CL_API_SUFFIX__VERSION_2_2;
+    //############################### NEW 2.2 #################################
+    @since "2.2"
+    pub fn clSetProgramSpecializationConstant(program: cl_program,
+                  spec_id: cl_uint,
+                  spec_size: size_t,
+                  spec_value: *const c_void) -> cl_int;
+
     // extern CL_API_ENTRY cl_int CL_API_CALL
     // clUnloadPlatformCompiler(cl_platform_id /* platform */) CL_API_SUFFIX__VERSION_1_2;
     // //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
-    // [DISABLED DUE TO PLATFORM INCOMPATABILITY]
-    // pub fn clUnloadPlatformCompiler(platform: cl_platform_id) -> cl_int;
+    @since "1.2"
+    pub fn clUnloadPlatformCompiler(platform: cl_platform_id) -> cl_int;
 
     pub fn clGetProgramInfo(program: cl_program,
                         param_name: cl_program_info,
@@ -1079,7 +1198,7 @@ extern "system" {
     // clCloneKernel(cl_kernel     /* source_kernel */,
     //               cl_int*       /* errcode_ret */) CL_API_SUFFIX__VERSION_2_1;
     //############################### NEW 2.1 #################################
-    #[cfg(feature = "opencl_version_2_1")]
+    @since "2.1"
     pub fn clCloneKernel(source_kernel: cl_kernel,
                          errcode_ret: *mut cl_int) -> cl_kernel;
 
@@ -1098,7 +1217,7 @@ extern "system" {
     //                          cl_uint      /* arg_index */,
     //                          const void * /* arg_value */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clSetKernelArgSVMPointer(kernel: cl_kernel,
                                     arg_index: cl_uint,
                                     arg_value: *const c_void) -> cl_int;
@@ -1110,7 +1229,7 @@ extern "system" {
     //                     size_t               /* param_value_size */,
     //                     const void *         /* param_value */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clSetKernelExecInfo(kernel: cl_kernel,
                                param_name: cl_kernel_exec_info,
                                param_value_size: size_t,
@@ -1131,7 +1250,7 @@ extern "system" {
     //                   void *          /* param_value */,
     //                   size_t *        /* param_value_size_ret */) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clGetKernelArgInfo(kernel: cl_kernel,
                       arg_indx: cl_uint,
                       param_name: cl_kernel_arg_info,
@@ -1156,7 +1275,7 @@ extern "system" {
     //                         void*                       /* param_value */,
     //                         size_t*                     /* param_value_size_ret */ ) CL_API_SUFFIX__VERSION_2_1;
     //############################### NEW 2.1 #################################
-    #[cfg(feature = "opencl_version_2_1")]
+    @since "2.1"
     pub fn clGetKernelSubGroupInfo(kernel: cl_kernel,
                                    device: cl_device_id,
                                    param_name: cl_kernel_sub_group_info,
@@ -1266,7 +1385,7 @@ extern "system" {
     //                 const cl_event *   /* event_wait_list */,
     //                 cl_event *         /* event */) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clEnqueueFillBuffer(command_queue: cl_command_queue,
                     buffer: cl_mem,
                     pattern: *const c_void,
@@ -1335,7 +1454,7 @@ extern "system" {
     //                   const cl_event *   /* event_wait_list */,
     //                   cl_event *         /* event */) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clEnqueueFillImage(command_queue: cl_command_queue,
                       image: cl_mem,
                       fill_color: *const c_void,
@@ -1415,7 +1534,7 @@ extern "system" {
     //                           const cl_event *       /* event_wait_list */,
     //                           cl_event *             /* event */) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clEnqueueMigrateMemObjects(command_queue: cl_command_queue,
                               num_mem_objects: cl_uint,
                               mem_objects: *const cl_mem,
@@ -1462,7 +1581,7 @@ extern "system" {
     //          const cl_event *  /* event_wait_list */,
     //          cl_event *        /* event */) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clEnqueueMarkerWithWaitList(command_queue: cl_command_queue,
              num_events_in_wait_list: cl_uint,
              event_wait_list: *const cl_event,
@@ -1488,7 +1607,7 @@ extern "system" {
     //           // event
     //      ) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clEnqueueBarrierWithWaitList(
              command_queue: cl_command_queue,
              num_events_in_wait_list: cl_uint,
@@ -1509,7 +1628,7 @@ extern "system" {
     //                  const cl_event *  /* event_wait_list */,
     //                  cl_event *        /* event */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clEnqueueSVMFree(command_queue: cl_command_queue,
                             num_svm_pointers: cl_uint,
                             svm_pointers: *const *const c_void,
@@ -1534,7 +1653,7 @@ extern "system" {
     //                    const cl_event *  /* event_wait_list */,
     //                    cl_event *        /* event */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clEnqueueSVMMemcpy(command_queue: cl_command_queue,
                               blocking_copy: cl_bool,
                               dst_ptr: *mut c_void,
@@ -1555,7 +1674,7 @@ extern "system" {
     //                     const cl_event *  /* event_wait_list */,
     //                     cl_event *        /* event */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clEnqueueSVMMemFill(command_queue: cl_command_queue,
                                svm_ptr: *mut c_void,
                                pattern: *const c_void,
@@ -1576,7 +1695,7 @@ extern "system" {
     //                 const cl_event *  /* event_wait_list */,
     //                 cl_event *        /* event */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clEnqueueSVMMap(command_queue: cl_command_queue,
                            blocking_map: cl_bool,
                            flags: cl_map_flags,
@@ -1594,7 +1713,7 @@ extern "system" {
     //                   const cl_event *  /* event_wait_list */,
     //                   cl_event *        /* event */) CL_API_SUFFIX__VERSION_2_0;
     //############################### NEW 2.0 #################################
-    #[cfg(feature = "opencl_version_2_0")]
+    @since "2.0"
     pub fn clEnqueueSVMUnmap(command_queue: cl_command_queue,
                              svm_ptr: *mut c_void,
                              num_events_in_wait_list: cl_uint,
@@ -1612,7 +1731,7 @@ extern "system" {
     //                        const cl_event *         /* event_wait_list */,
     //                        cl_event *               /* event */) CL_API_SUFFIX__VERSION_2_1;
     //############################### NEW 2.1 #################################
-    #[cfg(feature = "opencl_version_2_1")]
+    @since "2.1"
     pub fn clEnqueueSVMMigrateMem(command_queue: cl_command_queue,
                                   num_svm_pointers: cl_uint,
                                   svm_pointers: *const *const c_void,
@@ -1640,7 +1759,7 @@ extern "system" {
     //                     // func_name
     //                    ) CL_API_SUFFIX__VERSION_1_2;
     //############################### NEW 1.2 #################################
-    #[cfg(feature = "opencl_version_1_2")]
+    @since "1.2"
     pub fn clGetExtensionFunctionAddressForPlatform(platform: cl_platform_id,
                        func_name: *const c_char) -> *mut c_void;
 }
@@ -5,10 +5,25 @@
 //!
 //! See [ocl-core] for usage examples.
 //!
+//! ## Runtime Loading
+//!
+//! By default the OpenCL library is linked at build time. Functions
+//! introduced after OpenCL 1.1 are always resolved from the library when
+//! first called, so a single binary can be used with any ICD loader version
+//! (see [`function_is_available`]). Enable the `dynamic_loading` feature to
+//! load the library itself when first used (see [`load_library`]). The
+//! functions exported by this crate keep the same names and signatures
+//! either way.
+//!
 //! [ocl-core]: https://github.com/cogciprocate/ocl-core
+//! [`load_library`]: fn.load_library.html
+//! [`function_is_available`]: fn.function_is_available.html
 
 pub extern crate libc;
+extern crate libloading;
+#[macro_use] extern crate lazy_static;
 
+#[macro_use] mod loader;
 mod platform_h;
 mod glcorearb_h;
 mod cl_gl_h;
@@ -17,11 +32,15 @@ mod cl_gl_ext_h;
 mod cl_dx9_media_sharing_h;
 mod cl_d3d10_h;
 mod cl_d3d11_h;
+mod cl_ext_h;
 mod cl_h;
 
 pub use libc::{c_void, size_t, c_char, c_double, c_float, c_int, c_longlong, c_short, c_uchar,
     c_uint, c_ulonglong, c_ushort};
 
+pub use self::loader::{load_library, library_is_loaded, function_is_available, LoadError,
+    LIBRARY_PATH_ENV_VAR};
+
 pub use self::platform_h::{cl_GLuint, cl_GLint, cl_GLenum};
 
 pub use self::glcorearb_h::{GL_TEXTURE_1D, GL_TEXTURE_1D_ARRAY, GL_TEXTURE_BUFFER,
@@ -58,6 +77,57 @@ pub use self::cl_dx9_media_sharing_h::{CL_CONTEXT_ADAPTER_D3D9_KHR, CL_CONTEXT_A
 
 pub use self::cl_d3d11_h::CL_CONTEXT_D3D11_DEVICE_KHR;
 
+pub use self::cl_ext_h::{cl_queue_priority_khr, cl_queue_throttle_khr, cl_queue_properties_khr,
+    cl_device_pci_bus_info_khr, cl_device_topology_amd_raw, cl_device_topology_amd_pcie,
+    cl_device_topology_amd, cl_device_unified_shared_memory_capabilities_intel,
+    cl_mem_properties_intel, cl_mem_alloc_flags_intel, cl_mem_info_intel,
+    cl_unified_shared_memory_type_intel, cl_mem_advice_intel};
+
+pub use self::cl_ext_h::{CL_QUEUE_PRIORITY_KHR, CL_QUEUE_PRIORITY_HIGH_KHR,
+    CL_QUEUE_PRIORITY_MED_KHR, CL_QUEUE_PRIORITY_LOW_KHR, CL_QUEUE_THROTTLE_KHR,
+    CL_QUEUE_THROTTLE_HIGH_KHR, CL_QUEUE_THROTTLE_MED_KHR, CL_QUEUE_THROTTLE_LOW_KHR,
+    CL_PLATFORM_ICD_SUFFIX_KHR, CL_DEVICE_TERMINATE_CAPABILITY_KHR, CL_CONTEXT_TERMINATE_KHR, CL_DEVICE_SPIR_VERSIONS,
+    CL_PROGRAM_BINARY_TYPE_INTERMEDIATE, CL_DEVICE_IL_VERSION_KHR, CL_PROGRAM_IL_KHR,
+    CL_KERNEL_MAX_SUB_GROUP_SIZE_FOR_NDRANGE_KHR, CL_KERNEL_SUB_GROUP_COUNT_FOR_NDRANGE_KHR,
+    CL_UUID_SIZE_KHR, CL_LUID_SIZE_KHR, CL_DEVICE_UUID_KHR, CL_DRIVER_UUID_KHR,
+    CL_DEVICE_LUID_VALID_KHR, CL_DEVICE_LUID_KHR, CL_DEVICE_NODE_MASK_KHR,
+    CL_DEVICE_PCI_BUS_INFO_KHR, CL_DEVICE_COMPUTE_CAPABILITY_MAJOR_NV,
+    CL_DEVICE_COMPUTE_CAPABILITY_MINOR_NV, CL_DEVICE_REGISTERS_PER_BLOCK_NV, CL_DEVICE_WARP_SIZE_NV,
+    CL_DEVICE_GPU_OVERLAP_NV, CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV, CL_DEVICE_INTEGRATED_MEMORY_NV,
+    CL_DEVICE_ATTRIBUTE_ASYNC_ENGINE_COUNT_NV, CL_DEVICE_PCI_BUS_ID_NV, CL_DEVICE_PCI_SLOT_ID_NV,
+    CL_DEVICE_PCI_DOMAIN_ID_NV, CL_DEVICE_PROFILING_TIMER_OFFSET_AMD, CL_DEVICE_TOPOLOGY_AMD,
+    CL_DEVICE_BOARD_NAME_AMD, CL_DEVICE_GLOBAL_FREE_MEMORY_AMD, CL_DEVICE_SIMD_PER_COMPUTE_UNIT_AMD,
+    CL_DEVICE_SIMD_WIDTH_AMD, CL_DEVICE_SIMD_INSTRUCTION_WIDTH_AMD, CL_DEVICE_WAVEFRONT_WIDTH_AMD,
+    CL_DEVICE_GLOBAL_MEM_CHANNELS_AMD, CL_DEVICE_GLOBAL_MEM_CHANNEL_BANKS_AMD,
+    CL_DEVICE_GLOBAL_MEM_CHANNEL_BANK_WIDTH_AMD, CL_DEVICE_LOCAL_MEM_SIZE_PER_COMPUTE_UNIT_AMD,
+    CL_DEVICE_LOCAL_MEM_BANKS_AMD, CL_DEVICE_THREAD_TRACE_SUPPORTED_AMD, CL_DEVICE_GFXIP_MAJOR_AMD,
+    CL_DEVICE_GFXIP_MINOR_AMD, CL_DEVICE_AVAILABLE_ASYNC_QUEUES_AMD,
+    CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_AMD, CL_DEVICE_MAX_WORK_GROUP_SIZE_AMD,
+    CL_DEVICE_PREFERRED_CONSTANT_BUFFER_SIZE_AMD, CL_DEVICE_PCIE_ID_AMD,
+    CL_DEVICE_TOPOLOGY_TYPE_PCIE_AMD, CL_PRINTF_CALLBACK_ARM, CL_PRINTF_BUFFERSIZE_ARM,
+    CL_DEVICE_HOST_MEM_CAPABILITIES_INTEL, CL_DEVICE_DEVICE_MEM_CAPABILITIES_INTEL,
+    CL_DEVICE_SINGLE_DEVICE_SHARED_MEM_CAPABILITIES_INTEL,
+    CL_DEVICE_CROSS_DEVICE_SHARED_MEM_CAPABILITIES_INTEL,
+    CL_DEVICE_SHARED_SYSTEM_MEM_CAPABILITIES_INTEL, CL_UNIFIED_SHARED_MEMORY_ACCESS_INTEL,
+    CL_UNIFIED_SHARED_MEMORY_ATOMIC_ACCESS_INTEL, CL_UNIFIED_SHARED_MEMORY_CONCURRENT_ACCESS_INTEL,
+    CL_UNIFIED_SHARED_MEMORY_CONCURRENT_ATOMIC_ACCESS_INTEL, CL_MEM_ALLOC_FLAGS_INTEL,
+    CL_MEM_ALLOC_WRITE_COMBINED_INTEL, CL_MEM_ALLOC_INITIAL_PLACEMENT_DEVICE_INTEL,
+    CL_MEM_ALLOC_INITIAL_PLACEMENT_HOST_INTEL, CL_MEM_ALLOC_TYPE_INTEL, CL_MEM_ALLOC_BASE_PTR_INTEL,
+    CL_MEM_ALLOC_SIZE_INTEL, CL_MEM_ALLOC_DEVICE_INTEL, CL_MEM_TYPE_UNKNOWN_INTEL,
+    CL_MEM_TYPE_HOST_INTEL, CL_MEM_TYPE_DEVICE_INTEL, CL_MEM_TYPE_SHARED_INTEL,
+    CL_KERNEL_EXEC_INFO_INDIRECT_HOST_ACCESS_INTEL,
+    CL_KERNEL_EXEC_INFO_INDIRECT_DEVICE_ACCESS_INTEL,
+    CL_KERNEL_EXEC_INFO_INDIRECT_SHARED_ACCESS_INTEL, CL_KERNEL_EXEC_INFO_USM_PTRS_INTEL,
+    CL_COMMAND_MEMFILL_INTEL, CL_COMMAND_MEMCPY_INTEL, CL_COMMAND_MIGRATEMEM_INTEL,
+    CL_COMMAND_MEMADVISE_INTEL};
+
+pub use self::cl_ext_h::{clIcdGetPlatformIDsKHR_fn, clTerminateContextKHR_fn,
+    clCreateProgramWithILKHR_fn, clCreateCommandQueueWithPropertiesKHR_fn,
+    clGetKernelSubGroupInfoKHR_fn, clHostMemAllocINTEL_fn, clDeviceMemAllocINTEL_fn,
+    clSharedMemAllocINTEL_fn, clMemFreeINTEL_fn, clMemBlockingFreeINTEL_fn,
+    clGetMemAllocInfoINTEL_fn, clSetKernelArgMemPointerINTEL_fn, clEnqueueMemFillINTEL_fn,
+    clEnqueueMemcpyINTEL_fn, clEnqueueMigrateMemINTEL_fn, clEnqueueMemAdviseINTEL_fn};
+
 // Types:
 pub use self::cl_h::{cl_platform_id, cl_device_id, cl_context, cl_command_queue, cl_mem,
     cl_program, cl_kernel, cl_event, cl_sampler, cl_char, cl_uchar, cl_short, cl_ushort, cl_int,
@@ -73,10 +143,11 @@ pub use self::cl_h::{cl_platform_id, cl_device_id, cl_context, cl_command_queue,
     cl_kernel_info, cl_kernel_arg_info, cl_kernel_arg_address_qualifier,
     cl_kernel_arg_access_qualifier, cl_kernel_arg_type_qualifier, cl_kernel_work_group_info,
     cl_kernel_sub_group_info, cl_event_info, cl_command_type, cl_profiling_info,
-    cl_sampler_properties, cl_kernel_exec_info};
+    cl_sampler_properties, cl_kernel_exec_info, cl_version, cl_mem_properties,
+    cl_device_atomic_capabilities, cl_device_device_enqueue_capabilities};
 
 // Structs:
-pub use self::cl_h::{cl_image_format, cl_image_desc, cl_buffer_region};
+pub use self::cl_h::{cl_image_format, cl_image_desc, cl_buffer_region, cl_name_version};
 
 // Consts:
 pub use self::cl_h::{CL_SUCCESS, CL_DEVICE_NOT_FOUND, CL_DEVICE_NOT_AVAILABLE,
@@ -98,6 +169,7 @@ pub use self::cl_h::{CL_SUCCESS, CL_DEVICE_NOT_FOUND, CL_DEVICE_NOT_AVAILABLE,
     CL_INVALID_MIP_LEVEL, CL_INVALID_GLOBAL_WORK_SIZE, CL_INVALID_PROPERTY,
     CL_INVALID_IMAGE_DESCRIPTOR, CL_INVALID_COMPILER_OPTIONS, CL_INVALID_LINKER_OPTIONS,
     CL_INVALID_DEVICE_PARTITION_COUNT, CL_INVALID_PIPE_SIZE, CL_INVALID_DEVICE_QUEUE,
+    CL_INVALID_SPEC_ID, CL_MAX_SIZE_RESTRICTION_EXCEEDED,
     CL_PLATFORM_NOT_FOUND_KHR,};
 
 pub use self::cl_h::{CL_VERSION_1_0, CL_VERSION_1_1, CL_VERSION_1_2, CL_VERSION_2_0,
@@ -215,6 +287,22 @@ pub use self::cl_h::{CL_VERSION_1_0, CL_VERSION_1_1, CL_VERSION_1_2, CL_VERSION_
     CL_BUFFER_CREATE_TYPE_REGION, CL_PROFILING_COMMAND_QUEUED, CL_PROFILING_COMMAND_SUBMIT,
     CL_PROFILING_COMMAND_START, CL_PROFILING_COMMAND_END, CL_PROFILING_COMMAND_COMPLETE};
 
+pub use self::cl_h::{CL_VERSION_2_2, CL_VERSION_3_0, CL_NAME_VERSION_MAX_NAME_SIZE,
+    CL_VERSION_MAJOR_BITS, CL_VERSION_MINOR_BITS, CL_VERSION_PATCH_BITS, CL_VERSION_MAJOR_MASK,
+    CL_VERSION_MINOR_MASK, CL_VERSION_PATCH_MASK, CL_PLATFORM_NUMERIC_VERSION,
+    CL_PLATFORM_EXTENSIONS_WITH_VERSION, CL_DEVICE_NUMERIC_VERSION,
+    CL_DEVICE_EXTENSIONS_WITH_VERSION, CL_DEVICE_ILS_WITH_VERSION,
+    CL_DEVICE_BUILT_IN_KERNELS_WITH_VERSION, CL_DEVICE_ATOMIC_MEMORY_CAPABILITIES,
+    CL_DEVICE_ATOMIC_FENCE_CAPABILITIES, CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT,
+    CL_DEVICE_OPENCL_C_ALL_VERSIONS, CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_MULTIPLE,
+    CL_DEVICE_WORK_GROUP_COLLECTIVE_FUNCTIONS_SUPPORT, CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT,
+    CL_DEVICE_OPENCL_C_FEATURES, CL_DEVICE_DEVICE_ENQUEUE_CAPABILITIES, CL_DEVICE_PIPE_SUPPORT,
+    CL_DEVICE_LATEST_CONFORMANCE_VERSION_PASSED, CL_DEVICE_ATOMIC_ORDER_RELAXED,
+    CL_DEVICE_ATOMIC_ORDER_ACQ_REL, CL_DEVICE_ATOMIC_ORDER_SEQ_CST,
+    CL_DEVICE_ATOMIC_SCOPE_WORK_ITEM, CL_DEVICE_ATOMIC_SCOPE_WORK_GROUP,
+    CL_DEVICE_ATOMIC_SCOPE_DEVICE, CL_DEVICE_ATOMIC_SCOPE_ALL_DEVICES, CL_DEVICE_QUEUE_SUPPORTED,
+    CL_DEVICE_QUEUE_REPLACEABLE_DEFAULT, CL_MEM_PROPERTIES};
+
 // Functions:
 pub use self::cl_h::{clGetPlatformIDs, clGetPlatformInfo, clGetDeviceIDs, clGetDeviceInfo,
     clCreateContext, clCreateContextFromType, clRetainContext, clReleaseContext, clGetContextInfo,
@@ -234,18 +322,21 @@ pub use self::cl_h::{clGetPlatformIDs, clGetPlatformInfo, clGetDeviceIDs, clGetD
     clEnqueueNDRangeKernel, clEnqueueTask, clEnqueueNativeKernel, clEnqueueMarker,
     clEnqueueWaitForEvents, clEnqueueBarrier, clGetExtensionFunctionAddress};
 
-#[cfg(feature = "opencl_version_1_2")]
+// Functions introduced after OpenCL 1.1 are resolved at runtime:
 pub use self::cl_h::{clCreateSubDevices, clRetainDevice, clReleaseDevice, clCreateImage,
-    clCreateProgramWithBuiltInKernels, clCompileProgram, clLinkProgram, clGetKernelArgInfo,
+    clCreateProgramWithBuiltInKernels, clCompileProgram, clUnloadPlatformCompiler, clLinkProgram, clGetKernelArgInfo,
     clEnqueueFillBuffer, clEnqueueFillImage, clEnqueueMigrateMemObjects,
     clEnqueueMarkerWithWaitList, clEnqueueBarrierWithWaitList,
     clGetExtensionFunctionAddressForPlatform};
 
-#[cfg(feature = "opencl_version_2_0")]
 pub use self::cl_h::{clCreateCommandQueueWithProperties, clCreatePipe, clGetPipeInfo, clSVMAlloc,
     clSVMFree, clCreateSamplerWithProperties, clSetKernelArgSVMPointer, clSetKernelExecInfo,
     clEnqueueSVMFree, clEnqueueSVMMemcpy, clEnqueueSVMMemFill, clEnqueueSVMMap, clEnqueueSVMUnmap};
 
-#[cfg(feature = "opencl_version_2_1")]
 pub use self::cl_h::{clSetDefaultDeviceCommandQueue, clGetDeviceAndHostTimer, clGetHostTimer,
     clCreateProgramWithIL, clCloneKernel, clGetKernelSubGroupInfo, clEnqueueSVMMigrateMem};
+
+pub use self::cl_h::{clSetProgramReleaseCallback, clSetProgramSpecializationConstant};
+
+pub use self::cl_h::{clSetContextDestructorCallback, clCreateBufferWithProperties,
+    clCreateImageWithProperties};
@@ -0,0 +1,279 @@
+//! OpenCL library linking and runtime loading.
+//!
+//! By default every OpenCL 1.1 `cl*` function is declared within an ordinary
+//! `extern "system"` block and the OpenCL ICD loader is linked when the
+//! final binary is built.
+//!
+//! Functions introduced in OpenCL 1.2 or later (marked with `@since` below)
+//! are never linked. Each is instead a thin shim with an identical name and
+//! signature which resolves its entry point from the linked OpenCL library
+//! (by looking the symbol up within the running process) the first time it
+//! is called. A binary can therefore expose every API and still start on a
+//! machine with an older ICD loader installed. Use [`function_is_available`]
+//! to check whether the installed library exports a given function before
+//! calling it.
+//!
+//! When the `dynamic_loading` feature is enabled, every function (including
+//! those from OpenCL 1.1) becomes such a shim. The library itself is opened
+//! lazily (see [`load_library`]) and the binary has no link-time dependency
+//! on OpenCL, allowing it to start (and to report a sensible error) on
+//! machines without an ICD loader.
+//!
+//! With the `dynamic_loading` feature the library path can be overridden by
+//! setting the `OCL_LIBRARY_PATH` environment variable. Without it, the
+//! variable is ignored and every function comes from the linked library.
+//!
+//! [`load_library`]: fn.load_library.html
+//! [`function_is_available`]: fn.function_is_available.html
+
+pub use self::runtime::{load_library, library_is_loaded, function_is_available, LoadError,
+    LIBRARY_PATH_ENV_VAR};
+pub(crate) use self::runtime::resolve;
+
+
+/// Declares OpenCL API functions.
+///
+/// Functions preceded by `@since "<version>"` were introduced after OpenCL
+/// 1.1 and are always resolved at runtime. Others are linked unless the
+/// `dynamic_loading` feature is enabled.
+macro_rules! cl_api {
+    ($( $(@since $ver:literal)* $(#[$attr:meta])* pub fn $name:ident($($arg:ident: $arg_ty:ty),* $(,)*)
+            $(-> $ret:ty)*; )*) => {
+        $(
+            cl_api_fn! {
+                [$($ver)*] $(#[$attr])* pub fn $name($($arg: $arg_ty),*) $(-> $ret)*;
+            }
+        )*
+    };
+}
+
+/// Declares a single OpenCL API function.
+///
+/// Without the `dynamic_loading` feature, OpenCL 1.1 functions expand to a
+/// plain `extern "system"` block linked to the OpenCL library.
+#[cfg(not(feature = "dynamic_loading"))]
+macro_rules! cl_api_fn {
+    ([] $(#[$attr:meta])* pub fn $name:ident($($arg:ident: $arg_ty:ty),*) $(-> $ret:ty)*;) => {
+        //#[link_args = "-L$OPENCL_LIB -lOpenCL"]
+        #[cfg_attr(target_os = "macos", link(name = "OpenCL", kind = "framework"))]
+        #[cfg_attr(target_os = "windows", link(name = "OpenCL"))]
+        #[cfg_attr(not(target_os = "macos"), link(name = "OpenCL"))]
+        extern "system" {
+            $(#[$attr])*
+            pub fn $name($($arg: $arg_ty),*) $(-> $ret)*;
+        }
+    };
+    ([$ver:literal] $($fn_def:tt)*) => {
+        cl_api_shim! { $($fn_def)* }
+    };
+}
+
+/// Declares a single OpenCL API function.
+///
+/// With the `dynamic_loading` feature every function is resolved at runtime.
+#[cfg(feature = "dynamic_loading")]
+macro_rules! cl_api_fn {
+    ([$($ver:literal)*] $($fn_def:tt)*) => {
+        cl_api_shim! { $($fn_def)* }
+    };
+}
+
+/// Declares a shim which resolves (and caches) its symbol from the
+/// runtime-loaded OpenCL library.
+///
+/// ## Panics
+///
+/// Calling a function whose symbol cannot be resolved (the library is
+/// missing or does not export it) panics. Use `load_library` or
+/// `function_is_available` beforehand to check for availability.
+macro_rules! cl_api_shim {
+    ($(#[$attr:meta])* pub fn $name:ident($($arg:ident: $arg_ty:ty),*) $(-> $ret:ty)*;) => {
+        $(#[$attr])*
+        #[inline]
+        #[allow(non_snake_case, clippy::too_many_arguments, clippy::missing_safety_doc)]
+        pub unsafe fn $name($($arg: $arg_ty),*) $(-> $ret)* {
+            static ADDR: ::std::sync::atomic::AtomicUsize =
+                ::std::sync::atomic::AtomicUsize::new(0);
+            let addr = crate::loader::resolve(&ADDR, concat!(stringify!($name), "\0"));
+            let func: unsafe extern "system" fn($($arg_ty),*) $(-> $ret)* =
+                ::std::mem::transmute(addr);
+            func($($arg),*)
+        }
+    };
+}
+
+
+mod runtime {
+    use std::error::Error;
+    use std::fmt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use libloading::Library;
+    use crate::c_void;
+
+    /// The environment variable which, if set, specifies the path of the
+    /// OpenCL library to load.
+    pub const LIBRARY_PATH_ENV_VAR: &str = "OCL_LIBRARY_PATH";
+
+    #[cfg(target_os = "windows")]
+    static DEFAULT_LIBRARY_PATHS: &[&str] = &["OpenCL.dll"];
+
+    #[cfg(target_os = "macos")]
+    static DEFAULT_LIBRARY_PATHS: &[&str] = &[
+        "/System/Library/Frameworks/OpenCL.framework/OpenCL",
+        "libOpenCL.dylib",
+    ];
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[cfg_attr(not(feature = "dynamic_loading"), allow(dead_code))]
+    static DEFAULT_LIBRARY_PATHS: &[&str] = &["libOpenCL.so.1", "libOpenCL.so"];
+
+    /// An error encountered while loading the OpenCL library.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct LoadError {
+        paths: Vec<String>,
+        reason: String,
+    }
+
+    impl LoadError {
+        /// Returns the list of paths which were tried.
+        pub fn paths(&self) -> &[String] {
+            &self.paths
+        }
+
+        /// Returns the error message reported by the system loader for the
+        /// last path tried.
+        pub fn reason(&self) -> &str {
+            &self.reason
+        }
+    }
+
+    impl fmt::Display for LoadError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "OpenCL library not found (tried: {}): {}", self.paths.join(", "),
+                self.reason)
+        }
+    }
+
+    impl Error for LoadError {
+        fn description(&self) -> &str {
+            "OpenCL library not found"
+        }
+    }
+
+    lazy_static! {
+        static ref LIBRARY: Result<Library, LoadError> = open();
+    }
+
+    /// Returns the linked OpenCL library (every library loaded by the
+    /// process is searched for symbols).
+    #[cfg(all(not(feature = "dynamic_loading"), unix))]
+    fn open() -> Result<Library, LoadError> {
+        Ok(Library::from(::libloading::os::unix::Library::this()))
+    }
+
+    /// Returns the linked OpenCL library (already loaded by the process, so
+    /// opening it by name returns the same module).
+    #[cfg(all(not(feature = "dynamic_loading"), not(unix)))]
+    fn open() -> Result<Library, LoadError> {
+        open_paths(DEFAULT_LIBRARY_PATHS.iter().map(|&p| p.to_owned()).collect())
+    }
+
+    /// Opens the library at `OCL_LIBRARY_PATH` or the first of the default
+    /// paths found.
+    #[cfg(feature = "dynamic_loading")]
+    fn open() -> Result<Library, LoadError> {
+        let paths: Vec<String> = match ::std::env::var(LIBRARY_PATH_ENV_VAR) {
+            Ok(ref path) if !path.trim().is_empty() => vec![path.trim().to_owned()],
+            _ => DEFAULT_LIBRARY_PATHS.iter().map(|&p| p.to_owned()).collect(),
+        };
+
+        open_paths(paths)
+    }
+
+    #[cfg_attr(all(not(feature = "dynamic_loading"), unix), allow(dead_code))]
+    fn open_paths(paths: Vec<String>) -> Result<Library, LoadError> {
+        let mut reason = String::new();
+
+        for path in paths.iter() {
+            match Library::new(path) {
+                Ok(lib) => return Ok(lib),
+                Err(err) => reason = err.to_string(),
+            }
+        }
+
+        Err(LoadError { paths, reason })
+    }
+
+    fn library() -> &'static Result<Library, LoadError> {
+        &LIBRARY
+    }
+
+    /// Loads the OpenCL library if it has not already been loaded.
+    ///
+    /// The library is loaded at most once per process. With the
+    /// `dynamic_loading` feature, the path specified by the `OCL_LIBRARY_PATH`
+    /// environment variable is used if set, otherwise the platform's default
+    /// library names are tried in order. Without it, the linked library is
+    /// used.
+    ///
+    /// Calling this function is optional: the library will be loaded
+    /// automatically the first time any API function is called.
+    pub fn load_library() -> Result<(), LoadError> {
+        match *library() {
+            Ok(_) => Ok(()),
+            Err(ref err) => Err(err.clone()),
+        }
+    }
+
+    /// Returns true if the OpenCL library has been (or can be) loaded.
+    pub fn library_is_loaded() -> bool {
+        library().is_ok()
+    }
+
+    /// Returns true if the OpenCL library exports the function named `name`
+    /// (e.g. `"clCreateProgramWithIL"`).
+    ///
+    /// The installed ICD loader, rather than any particular platform or
+    /// device, determines which functions are exported. Check the platform
+    /// and device versions as well before calling a function.
+    pub fn function_is_available(name: &str) -> bool {
+        let lib = match *library() {
+            Ok(ref lib) => lib,
+            Err(_) => return false,
+        };
+
+        unsafe { lib.get::<*mut c_void>(name.as_bytes()) }
+            .map(|sym| !(*sym).is_null())
+            .unwrap_or(false)
+    }
+
+    /// Returns the address of the symbol named `name` (which must be null
+    /// terminated), caching it in `cache`.
+    #[doc(hidden)]
+    pub fn resolve(cache: &AtomicUsize, name: &'static str) -> usize {
+        let addr = cache.load(Ordering::Acquire);
+        if addr != 0 { return addr; }
+
+        let fn_name = &name[..name.len() - 1];
+
+        let lib = match *library() {
+            Ok(ref lib) => lib,
+            Err(ref err) => panic!("cl-sys: unable to call '{}': {}", fn_name, err),
+        };
+
+        let addr = unsafe {
+            match lib.get::<*mut c_void>(name.as_bytes()) {
+                Ok(sym) => *sym as usize,
+                Err(err) => panic!("cl-sys: unable to resolve '{}' from the OpenCL \
+                    library: {}", fn_name, err),
+            }
+        };
+
+        if addr == 0 {
+            panic!("cl-sys: the OpenCL library returned a null address for '{}'", fn_name);
+        }
+
+        cache.store(addr, Ordering::Release);
+        addr
+    }
+}
@@ -0,0 +1,427 @@
+//! Structured program build logs.
+//!
+//! Build logs are free-form text whose format depends on the compiler used by
+//! each platform. [`parse_build_log`] recognizes the formats used by common
+//! compilers and returns each error, warning and note as a [`Diagnostic`]:
+//!
+//! * Clang-based compilers (Intel, AMD ROCm, NVIDIA, pocl, Apple, Mesa):
+//!   `<source>:12:5: error: message`, followed by the source line and a caret.
+//! * EDG-based compilers (legacy AMD and NVIDIA):
+//!   `"/tmp/OCL1234.cl", line 12: error: message`.
+//! * NVIDIA PTX assembly: `ptxas application ptx input, line 12; error : message`.
+//!
+//! Lines which are not recognized (summaries such as `1 error generated.`)
+//! are ignored. A [`ProgramBuildLog`] holds the log, build status and
+//! diagnostics of each device a program was built for and renders them in
+//! the style of `rustc` when displayed.
+//!
+//! [`parse_build_log`]: fn.parse_build_log.html
+//! [`Diagnostic`]: struct.Diagnostic.html
+//! [`ProgramBuildLog`]: struct.ProgramBuildLog.html
+
+use std::fmt;
+use std::slice;
+use crate::{DeviceId, ProgramBuildStatus};
+
+
+/// The severity of a build diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        })
+    }
+}
+
+
+/// An error, warning or note reported within a build log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The file reported by the compiler, such as `<source>` or
+    /// `/tmp/OCL1234.cl`, if any.
+    pub file: Option<String>,
+    /// The line number (1-based), if any.
+    pub line: Option<usize>,
+    /// The column number (1-based), if any.
+    pub column: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+    /// The source line the diagnostic refers to, when included in the log.
+    pub source: Option<String>,
+}
+
+impl Diagnostic {
+    /// Returns true if this is an error.
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Returns true if this is a warning.
+    pub fn is_warning(&self) -> bool {
+        self.severity == Severity::Warning
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    /// Renders the diagnostic in the style of `rustc`:
+    ///
+    /// ```text
+    /// error: use of undeclared identifier 'oops'
+    ///  --> <source>:5:5
+    ///   |
+    /// 5 |     oops
+    ///   |     ^
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}: {}", self.severity, self.message)?;
+
+        let line = match self.line {
+            Some(line) => line,
+            None => return match self.file {
+                Some(ref file) => writeln!(f, " --> {}", file),
+                None => Ok(()),
+            },
+        };
+
+        let gutter = " ".repeat(line.to_string().len());
+        write!(f, "{}--> {}:{}", gutter, self.file.as_ref().map_or("<source>", |s| s), line)?;
+        match self.column {
+            Some(column) => writeln!(f, ":{}", column)?,
+            None => writeln!(f)?,
+        }
+
+        if let Some(ref source) = self.source {
+            writeln!(f, "{} |", gutter)?;
+            writeln!(f, "{} | {}", line, source)?;
+            if let Some(column) = self.column {
+                // Tabs are kept so that the caret lines up with the source:
+                let indent: String = source.chars().take(column.saturating_sub(1))
+                    .map(|c| if c == '\t' { '\t' } else { ' ' }).collect();
+                writeln!(f, "{} | {}^", gutter, indent)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+
+/// The build log of a single device.
+#[derive(Debug, Clone)]
+pub struct DeviceBuildLog {
+    pub device: DeviceId,
+    pub device_name: String,
+    pub status: ProgramBuildStatus,
+    /// The unmodified log.
+    pub log: String,
+    /// The diagnostics parsed from `log`.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl DeviceBuildLog {
+    /// Returns a new device build log, parsing the diagnostics of `log`.
+    pub fn new(device: DeviceId, device_name: String, status: ProgramBuildStatus, log: String)
+            -> DeviceBuildLog {
+        let diagnostics = parse_build_log(&log);
+        DeviceBuildLog { device, device_name, status, log, diagnostics }
+    }
+
+    /// Returns true if the build failed for this device, either according to
+    /// its build status or because the log contains errors.
+    pub fn has_errors(&self) -> bool {
+        self.status == ProgramBuildStatus::Error || self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// Returns an iterator over the errors within the log.
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.is_error())
+    }
+
+    /// Returns an iterator over the warnings within the log.
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.is_warning())
+    }
+
+    /// Returns true if the log contains nothing but whitespace.
+    pub fn is_empty(&self) -> bool {
+        self.log.trim().is_empty()
+    }
+
+    /// Returns true if `other` would be rendered identically.
+    fn same_output(&self, other: &DeviceBuildLog) -> bool {
+        self.log == other.log && self.diagnostics == other.diagnostics
+            && self.has_errors() == other.has_errors()
+    }
+
+    /// Writes a summary such as `build failed, 1 error, 2 warnings`.
+    fn fmt_summary(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+        let (errors, warnings) = (self.errors().count(), self.warnings().count());
+
+        f.write_str(if self.has_errors() { "build failed" } else { "build succeeded" })?;
+        if errors > 0 { write!(f, ", {} error{}", errors, plural(errors))?; }
+        if warnings > 0 { write!(f, ", {} warning{}", warnings, plural(warnings))?; }
+        Ok(())
+    }
+}
+
+
+/// The build logs of each device a program was built for.
+///
+/// Returned by [`get_program_build_log`] and contained within
+/// `ProgramBuildError::BuildLog` when a build fails.
+///
+/// When displayed, the diagnostics of each device are rendered in the style
+/// of `rustc`. Devices with identical logs are grouped together and logs
+/// without any recognized diagnostics are displayed as is.
+///
+/// [`get_program_build_log`]: fn.get_program_build_log.html
+#[derive(Debug, Clone, Default)]
+pub struct ProgramBuildLog {
+    devices: Vec<DeviceBuildLog>,
+}
+
+impl ProgramBuildLog {
+    /// Returns a new build log from the logs of each device.
+    pub fn new(devices: Vec<DeviceBuildLog>) -> ProgramBuildLog {
+        ProgramBuildLog { devices }
+    }
+
+    /// Returns the log of each device.
+    pub fn devices(&self) -> &[DeviceBuildLog] {
+        &self.devices
+    }
+
+    /// Returns the log of each device, mutably.
+    pub fn devices_mut(&mut self) -> &mut [DeviceBuildLog] {
+        &mut self.devices
+    }
+
+    /// Returns true if the build failed for any device.
+    pub fn has_errors(&self) -> bool {
+        self.devices.iter().any(DeviceBuildLog::has_errors)
+    }
+
+    /// Returns an iterator over the diagnostics of every device.
+    pub fn diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.devices.iter().flat_map(|d| d.diagnostics.iter())
+    }
+
+    /// Returns an iterator over the errors of every device.
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics().filter(|d| d.is_error())
+    }
+
+    /// Returns an iterator over the warnings of every device.
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics().filter(|d| d.is_warning())
+    }
+
+    /// Returns true if the log of every device is empty.
+    pub fn is_empty(&self) -> bool {
+        self.devices.iter().all(DeviceBuildLog::is_empty)
+    }
+}
+
+impl<'a> IntoIterator for &'a ProgramBuildLog {
+    type Item = &'a DeviceBuildLog;
+    type IntoIter = slice::Iter<'a, DeviceBuildLog>;
+
+    fn into_iter(self) -> slice::Iter<'a, DeviceBuildLog> {
+        self.devices.iter()
+    }
+}
+
+impl fmt::Display for ProgramBuildLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rendered: Vec<bool> = vec![false; self.devices.len()];
+
+        for (idx, device_log) in self.devices.iter().enumerate() {
+            if rendered[idx] || device_log.is_empty() { continue; }
+
+            let mut names = Vec::with_capacity(1);
+            for (other_idx, other) in self.devices.iter().enumerate().skip(idx) {
+                if !rendered[other_idx] && other.same_output(device_log) {
+                    names.push(format!("'{}'", other.device_name));
+                    rendered[other_idx] = true;
+                }
+            }
+
+            if idx > 0 { writeln!(f)?; }
+            write!(f, "{} {} (", if names.len() == 1 { "Device" } else { "Devices" },
+                names.join(", "))?;
+            device_log.fmt_summary(f)?;
+            writeln!(f, "):")?;
+            writeln!(f)?;
+
+            if device_log.diagnostics.is_empty() {
+                writeln!(f, "{}", device_log.log.trim_end())?;
+            } else {
+                for (i, diagnostic) in device_log.diagnostics.iter().enumerate() {
+                    if i > 0 { writeln!(f)?; }
+                    write!(f, "{}", diagnostic)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+
+/// Parses a build log, returning each diagnostic it contains.
+pub fn parse_build_log(log: &str) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = log.lines().collect();
+    let mut diagnostics = Vec::new();
+    let mut idx = 0;
+
+    while idx < lines.len() {
+        if let Some(mut diagnostic) = parse_line(lines[idx]) {
+            // Clang and EDG print the source line followed by a caret:
+            if let (Some(&source), Some(&caret)) = (lines.get(idx + 1), lines.get(idx + 2)) {
+                if is_caret_line(caret) && parse_line(source).is_none() {
+                    diagnostic.source = Some(source.to_owned());
+                    idx += 2;
+                }
+            }
+            diagnostics.push(diagnostic);
+        }
+        idx += 1;
+    }
+
+    diagnostics
+}
+
+/// Parses a single line of a log in any of the recognized formats.
+fn parse_line(line: &str) -> Option<Diagnostic> {
+    let line = line.trim_end();
+    parse_edg(line).or_else(|| parse_ptxas(line)).or_else(|| parse_clang(line))
+}
+
+/// Parses `"file", line 12: error: message` (optionally with an error number
+/// such as `error #20:` or `warning #177-D:`).
+fn parse_edg(line: &str) -> Option<Diagnostic> {
+    let rest = line.strip_prefix('"')?;
+    let file_end = rest.find("\", line ")?;
+    let file = &rest[..file_end];
+    let (line_no, rest) = split_number(&rest[file_end + 8..])?;
+    let (severity, message) = parse_severity(rest.strip_prefix(": ")?)?;
+
+    Some(Diagnostic {
+        file: Some(file.to_owned()),
+        line: Some(line_no),
+        column: None,
+        severity,
+        message,
+        source: None,
+    })
+}
+
+/// Parses `ptxas application ptx input, line 12; error   : message` and
+/// `ptxas fatal   : message`.
+fn parse_ptxas(line: &str) -> Option<Diagnostic> {
+    if !line.starts_with("ptxas ") { return None; }
+    let colon = line.find(':')?;
+    let (head, message) = (&line[..colon], line[colon + 1..].trim());
+
+    let severity = match head.split_whitespace().last()? {
+        "error" | "fatal" => Severity::Error,
+        "warning" => Severity::Warning,
+        "info" => Severity::Note,
+        _ => return None,
+    };
+    let line_no = head.find(", line ")
+        .and_then(|idx| split_number(&head[idx + 7..]))
+        .map(|(line_no, _)| line_no);
+
+    Some(Diagnostic {
+        file: None,
+        line: line_no,
+        column: None,
+        severity,
+        message: message.to_owned(),
+        source: None,
+    })
+}
+
+/// Parses `file:12:5: error: message`, `file:12: error: message` and
+/// `error: message`.
+fn parse_clang(line: &str) -> Option<Diagnostic> {
+    let (location, (severity, message)) = match parse_severity(line) {
+        Some(parsed) => ("", parsed),
+        None => line.match_indices(": ")
+            .find_map(|(idx, _)| parse_severity(&line[idx + 2..]).map(|p| (&line[..idx], p)))?,
+    };
+
+    // File names may themselves contain colons (`C:\...`):
+    let mut parts = location.rsplitn(3, ':');
+    let (last, middle, first) = (parts.next(), parts.next(), parts.next());
+    let number = |s: Option<&str>| s.and_then(|s| s.parse::<usize>().ok());
+
+    let (file, line_no, column) = match (number(middle), number(last)) {
+        (Some(line_no), Some(column)) => (first, Some(line_no), Some(column)),
+        (None, Some(line_no)) => (Some(&location[..location.len() - last?.len()]), Some(line_no),
+            None),
+        _ => (None, None, None),
+    };
+    let file = file.map(|f| f.trim_end_matches(':').trim()).filter(|f| !f.is_empty());
+
+    Some(Diagnostic {
+        file: file.map(str::to_owned),
+        line: line_no,
+        column,
+        severity,
+        message,
+        source: None,
+    })
+}
+
+/// Parses a severity followed by a colon (`error: `, `fatal error: `,
+/// `warning #177-D: `), returning the severity and the remaining text.
+fn parse_severity(text: &str) -> Option<(Severity, String)> {
+    const SEVERITIES: &[(&str, Severity)] = &[
+        ("fatal error", Severity::Error),
+        ("catastrophic error", Severity::Error),
+        ("error", Severity::Error),
+        ("warning", Severity::Warning),
+        ("note", Severity::Note),
+        ("remark", Severity::Note),
+    ];
+
+    let (word, severity) = SEVERITIES.iter().cloned()
+        .find(|&(word, _)| text.len() >= word.len()
+            && text.is_char_boundary(word.len())
+            && text[..word.len()].eq_ignore_ascii_case(word))?;
+    let mut rest = &text[word.len()..];
+
+    // EDG error numbers:
+    if let Some(number) = rest.strip_prefix(" #") {
+        let end = number.find(':')?;
+        if !number[..end].trim_end_matches("-D").chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        rest = &number[end..];
+    }
+
+    let message = rest.strip_prefix(':')?;
+    if !(message.is_empty() || message.starts_with(' ')) { return None; }
+    Some((severity, message.trim().to_owned()))
+}
+
+/// Splits leading decimal digits from `text`.
+fn split_number(text: &str) -> Option<(usize, &str)> {
+    let end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    text[..end].parse().ok().map(|number| (number, &text[end..]))
+}
+
+/// Returns true for a line consisting of a caret (`^`) and optional tildes.
+fn is_caret_line(line: &str) -> bool {
+    line.contains('^') && line.chars().all(|c| c == '^' || c == '~' || c.is_whitespace())
+}
@@ -0,0 +1,166 @@
+//! Extension function loaders.
+//!
+//! Extension functions are not exported by the OpenCL ICD loader and must be
+//! looked up per platform with `clGetExtensionFunctionAddressForPlatform`.
+//! Each of the tables below contains the entry points of a single extension
+//! and is loaded with `::load`. Tables are cached per platform: only the
+//! first load for a given platform looks up any functions.
+//!
+//! A platform may return entry points for an extension which some (or all)
+//! of its devices do not support. Check the device extension list
+//! (`DeviceInfo::Extensions`) before calling any of these functions.
+//!
+//! ### Example
+//!
+//! ```rust,ignore
+//! let usm = core::IntelUsmFns::load(&platform)?;
+//! let mut errcode = 0;
+//! let ptr = unsafe { (usm.host_mem_alloc)(context.as_ptr(), ptr::null(), 1024, 0, &mut errcode) };
+//! ```
+
+use std::fmt;
+use std::mem;
+use std::sync::{Mutex, Once};
+use crate::ffi::{self, c_void};
+use crate::functions::{self, ApiWrapperError};
+use crate::{PlatformId, ErrorKind, Result as OclCoreResult};
+
+
+/// Returns the address of the extension function named `function`.
+fn load_fn(platform: &PlatformId, function: &'static str, extension: &'static str)
+        -> OclCoreResult<*mut c_void> {
+    unsafe { functions::get_extension_function_address_for_platform(platform, function, None) }
+        .map_err(|err| match *err.kind() {
+            ErrorKind::ApiWrapper(ApiWrapperError::GetExtensionFunctionAddressForPlatformInvalidFunction) => {
+                ApiWrapperError::ExtensionFunctionUnavailable { function, extension }.into()
+            },
+            _ => err,
+        })
+}
+
+
+/// Declares a table of extension functions which is loaded (and cached) per
+/// platform.
+macro_rules! extension_fns {
+    ($(#[$attr:meta])* pub struct $name:ident($ext:expr) {
+        $( $(#[$fattr:meta])* pub $field:ident: $fn_ty:ident = $fn_name:expr, )*
+    }) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy)]
+        pub struct $name {
+            $( $(#[$fattr])* pub $field: ffi::$fn_ty, )*
+        }
+
+        impl $name {
+            /// The name of the extension.
+            pub const EXTENSION: &'static str = $ext;
+
+            /// Returns the functions of this extension for `platform`,
+            /// looking them up on the first call for each platform.
+            ///
+            /// Returns an error if any of the functions is unavailable or if
+            /// `platform` does not support OpenCL 1.2+.
+            pub fn load(platform: &PlatformId) -> OclCoreResult<$name> {
+                static INIT: Once = Once::new();
+                static mut CACHE: *const Mutex<Vec<(usize, $name)>> = 0 as *const _;
+
+                let cache = unsafe {
+                    INIT.call_once(|| {
+                        CACHE = Box::into_raw(Box::new(Mutex::new(Vec::new())));
+                    });
+                    &*CACHE
+                };
+
+                let key = platform.as_ptr() as usize;
+                let mut cache = cache.lock().unwrap();
+
+                if let Some(&(_, fns)) = cache.iter().find(|&&(p, _)| p == key) {
+                    return Ok(fns);
+                }
+
+                let fns = $name {
+                    $( $field: unsafe {
+                        mem::transmute::<*mut c_void, ffi::$fn_ty>(load_fn(platform, $fn_name, $ext)?)
+                    }, )*
+                };
+
+                cache.push((key, fns));
+                Ok(fns)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    $( .field(stringify!($field), &(self.$field as *const c_void)) )*
+                    .finish()
+            }
+        }
+    };
+}
+
+
+extension_fns! {
+    /// Functions of the `cl_khr_terminate_context` extension.
+    pub struct KhrTerminateContextFns("cl_khr_terminate_context") {
+        /// `clTerminateContextKHR`
+        pub terminate_context: clTerminateContextKHR_fn = "clTerminateContextKHR",
+    }
+}
+
+extension_fns! {
+    /// Functions of the `cl_khr_il_program` extension, which allows programs
+    /// to be created from SPIR-V on OpenCL 1.2 platforms.
+    pub struct KhrIlProgramFns("cl_khr_il_program") {
+        /// `clCreateProgramWithILKHR`
+        pub create_program_with_il: clCreateProgramWithILKHR_fn = "clCreateProgramWithILKHR",
+    }
+}
+
+extension_fns! {
+    /// Functions of the `cl_khr_create_command_queue` extension, which allows
+    /// queues to be created with properties on OpenCL 1.2 platforms.
+    pub struct KhrCreateCommandQueueFns("cl_khr_create_command_queue") {
+        /// `clCreateCommandQueueWithPropertiesKHR`
+        pub create_command_queue_with_properties: clCreateCommandQueueWithPropertiesKHR_fn =
+            "clCreateCommandQueueWithPropertiesKHR",
+    }
+}
+
+extension_fns! {
+    /// Functions of the `cl_khr_subgroups` extension.
+    pub struct KhrSubgroupsFns("cl_khr_subgroups") {
+        /// `clGetKernelSubGroupInfoKHR`
+        pub get_kernel_sub_group_info: clGetKernelSubGroupInfoKHR_fn =
+            "clGetKernelSubGroupInfoKHR",
+    }
+}
+
+extension_fns! {
+    /// Functions of the `cl_intel_unified_shared_memory` extension.
+    pub struct IntelUsmFns("cl_intel_unified_shared_memory") {
+        /// `clHostMemAllocINTEL`
+        pub host_mem_alloc: clHostMemAllocINTEL_fn = "clHostMemAllocINTEL",
+        /// `clDeviceMemAllocINTEL`
+        pub device_mem_alloc: clDeviceMemAllocINTEL_fn = "clDeviceMemAllocINTEL",
+        /// `clSharedMemAllocINTEL`
+        pub shared_mem_alloc: clSharedMemAllocINTEL_fn = "clSharedMemAllocINTEL",
+        /// `clMemFreeINTEL`
+        pub mem_free: clMemFreeINTEL_fn = "clMemFreeINTEL",
+        /// `clMemBlockingFreeINTEL`
+        pub mem_blocking_free: clMemBlockingFreeINTEL_fn = "clMemBlockingFreeINTEL",
+        /// `clGetMemAllocInfoINTEL`
+        pub get_mem_alloc_info: clGetMemAllocInfoINTEL_fn = "clGetMemAllocInfoINTEL",
+        /// `clSetKernelArgMemPointerINTEL`
+        pub set_kernel_arg_mem_pointer: clSetKernelArgMemPointerINTEL_fn =
+            "clSetKernelArgMemPointerINTEL",
+        /// `clEnqueueMemFillINTEL`
+        pub enqueue_mem_fill: clEnqueueMemFillINTEL_fn = "clEnqueueMemFillINTEL",
+        /// `clEnqueueMemcpyINTEL`
+        pub enqueue_memcpy: clEnqueueMemcpyINTEL_fn = "clEnqueueMemcpyINTEL",
+        /// `clEnqueueMigrateMemINTEL`
+        pub enqueue_migrate_mem: clEnqueueMigrateMemINTEL_fn = "clEnqueueMigrateMemINTEL",
+        /// `clEnqueueMemAdviseINTEL`
+        pub enqueue_mem_advise: clEnqueueMemAdviseINTEL_fn = "clEnqueueMemAdviseINTEL",
+    }
+}
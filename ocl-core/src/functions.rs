@@ -14,11 +14,13 @@
 
 use std::ptr;
 use std::mem;
+use std::panic;
 use std::ffi::CString;
 use std::iter;
 use std::thread;
 use std::time::Duration;
 use std::env;
+use std::sync::{Arc, Mutex};
 use std::fmt;
 use failure::Fail;
 use crate::ffi::{size_t, c_void};
@@ -52,8 +54,10 @@ use crate::{OclPrm, PlatformId, DeviceId, Context, ContextProperties, ContextInf
     ClWaitListPtr, EventInfo, EventInfoResult, ProfilingInfo, ProfilingInfoResult,
     CreateContextCallbackFn, UserDataPtr, ClPlatformIdPtr, ClDeviceIdPtr, ClContextPtr,
     EventCallbackFn, BuildProgramCallbackFn, MemMigrationFlags, MapFlags, BufferRegion,
-    BufferCreateType, OpenclVersion, ClVersions, Status, CommandQueueProperties, MemMap, AsMem,
-    MemCmdRw, MemCmdAll, Event, ImageFormatParseResult};
+    BufferCreateType, OpenclVersion, ClVersions, Status, CommandQueueProperties, QueueProperties,
+    MemMap, AsMem, MemCmdRw, MemCmdAll, Event, ImageFormatParseResult, DevicePartition,
+    MemDestructorCallbackFn, NativeKernelFn, SamplerProperties, BuildProgramCompleteFn};
+use crate::build_log::{ProgramBuildLog, DeviceBuildLog};
 
 #[cfg(not(feature="opencl_vendor_mesa"))]
 use crate::{GlContextInfo, GlContextInfoResult};
@@ -122,6 +126,62 @@ pub extern "C" fn _complete_user_event(src_event_ptr: cl_event, event_status: i3
     }
 }
 
+/// Runs the boxed `MemDestructorCallbackFn` pointed to by `user_data`.
+///
+/// Panics within the callback are caught (and discarded) rather than
+/// unwinding into the OpenCL runtime.
+extern "C" fn _mem_destructor_callback(mem_ptr: cl_mem, user_data: *mut c_void) {
+    if mem_ptr.is_null() || user_data.is_null() { return; }
+
+    let callback = unsafe { Box::from_raw(user_data as *mut MemDestructorCallbackFn) };
+
+    // The memory object is being destroyed and must not be retained or
+    // released again:
+    let mem = mem::ManuallyDrop::new(unsafe { Mem::from_raw_create_ptr(mem_ptr) });
+
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(move || callback(&mem)));
+}
+
+/// Runs the boxed `BuildProgramCompleteFn` shared through `user_data` (see
+/// `build_program_async`), unless it has already been taken.
+///
+/// Panics within the callback are caught (and discarded) rather than
+/// unwinding into the OpenCL runtime.
+extern "C" fn _build_program_callback(program_ptr: cl_program, user_data: *mut c_void) {
+    if user_data.is_null() { return; }
+
+    let slot = unsafe { Arc::from_raw(user_data as *const Mutex<Option<BuildProgramCompleteFn>>) };
+    let callback = slot.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+
+    if let Some(callback) = callback {
+        // The program is owned by the caller of `build_program_async`:
+        let program = mem::ManuallyDrop::new(unsafe { Program::from_raw_create_ptr(program_ptr) });
+        let _ = panic::catch_unwind(panic::AssertUnwindSafe(move || callback(&program)));
+    }
+}
+
+/// Runs the boxed `ProgramReleaseCallbackFn` pointed to by `user_data`.
+///
+/// Panics within the callback are caught (and discarded) rather than
+/// unwinding into the OpenCL runtime.
+extern "C" fn _program_release_callback(_program_ptr: cl_program, user_data: *mut c_void) {
+    if user_data.is_null() { return; }
+
+    let callback = unsafe { Box::from_raw(user_data as *mut crate::ProgramReleaseCallbackFn) };
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(move || callback()));
+}
+
+/// Runs the boxed `ContextDestructorCallbackFn` pointed to by `user_data`.
+///
+/// Panics within the callback are caught (and discarded) rather than
+/// unwinding into the OpenCL runtime.
+extern "C" fn _context_destructor_callback(_context_ptr: cl_context, user_data: *mut c_void) {
+    if user_data.is_null() { return; }
+
+    let callback = unsafe { Box::from_raw(user_data as *mut crate::ContextDestructorCallbackFn) };
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(move || callback()));
+}
+
 //============================================================================
 //============================================================================
 //============================ ERROR HANDLING ================================
@@ -220,15 +280,49 @@ pub enum ProgramBuildError {
         \n\n",
         _0
     )]
-    BuildLog(String),
+    BuildLog(ProgramBuildLog),
     #[fail(display = "{}", _0)]
     InfoResult(Box<OclCoreError>),
 }
 
 
-/// If the program pointed to by `cl_program` for any of the devices listed in
-/// `device_ids` has a build log of any length, it will be returned as an
-/// errcode result.
+/// Returns the build status and log of each of the devices listed in
+/// `device_ids`, parsed into diagnostics.
+///
+/// Useful for retrieving the warnings of a successful build.
+pub fn get_program_build_log<D: ClDeviceIdPtr>(program: &Program, device_ids: &[D])
+        -> OclCoreResult<ProgramBuildLog> {
+    let mut device_logs = Vec::with_capacity(device_ids.len());
+
+    for device_id in device_ids.iter().cloned() {
+        let status = match get_program_build_info(program, device_id, ProgramBuildInfo::BuildStatus)? {
+            ProgramBuildInfoResult::BuildStatus(status) => status,
+            _ => panic!("Unexpected 'ProgramBuildInfoResult' variant."),
+        };
+        let log = match get_program_build_info(program, device_id, ProgramBuildInfo::BuildLog)? {
+            ProgramBuildInfoResult::BuildLog(log) => log,
+            _ => panic!("Unexpected 'ProgramBuildInfoResult' variant."),
+        };
+        let name = match get_device_info(device_id, DeviceInfo::Name)? {
+            DeviceInfoResult::Name(name) => name,
+            _ => panic!("Unexpected 'DeviceInfoResult' variant."),
+        };
+
+        let device = unsafe { DeviceId::from_raw(device_id.as_ptr()) };
+        device_logs.push(DeviceBuildLog::new(device, name, status, log));
+    }
+
+    Ok(ProgramBuildLog::new(device_logs))
+}
+
+
+/// If the build of the program pointed to by `cl_program` failed for any of
+/// the devices listed in `device_ids`, returns the build log of every device
+/// as an error.
+///
+/// A build is considered failed if its build status is
+/// `ProgramBuildStatus::Error` or its log contains errors. Builds whose logs
+/// contain only warnings are not.
 ///
 pub fn program_build_err<D: ClDeviceIdPtr>(program: &Program, device_ids: &[D])
         -> Result<(), ProgramBuildError> {
@@ -236,31 +330,36 @@ pub fn program_build_err<D: ClDeviceIdPtr>(program: &Program, device_ids: &[D])
         return Err(ProgramBuildError::DeviceListEmpty);
     }
 
-    for device_id in device_ids.iter().cloned() {
-        match get_program_build_info(program, device_id, ProgramBuildInfo::BuildLog) {
-            Ok(ProgramBuildInfoResult::BuildLog(log)) => {
-                if log.len() > 1 {
-                    return Err(ProgramBuildError::BuildLog(log));
-                }
-            },
-            Err(err) => return Err(ProgramBuildError::InfoResult(Box::new(err))),
-            _ => panic!("Unexpected 'ProgramBuildInfoResult' variant."),
-        }
+    match get_program_build_log(program, device_ids) {
+        Ok(ref log) if !log.has_errors() => Ok(()),
+        Ok(log) => Err(ProgramBuildError::BuildLog(log)),
+        Err(err) => Err(ProgramBuildError::InfoResult(Box::new(err))),
     }
-
-    Ok(())
 }
 
 
 /// An API function identifier.
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub(crate) enum ApiFunction {
     None,
+    CreateSubDevices,
     RetainDevice,
     ReleaseDevice,
+    GetDeviceAndHostTimer,
+    GetHostTimer,
+    CloneKernel,
+    GetKernelSubGroupInfo,
     CreateProgramWithIl,
+    SetProgramSpecializationConstant,
+    SetProgramReleaseCallback,
+    CreateProgramWithBuiltInKernels,
     CreateImage,
+    SetContextDestructorCallback,
+    CreateBufferWithProperties,
+    CreateImageWithProperties,
+    CreatePipe,
+    GetPipeInfo,
     CreateFromGLTexture,
     GetKernelArgInfo,
     EnqueueFillBuffer,
@@ -268,9 +367,64 @@ pub(crate) enum ApiFunction {
     EnqueueMigrateMemObjects,
     EnqueueMarkerWithWaitList,
     EnqueueBarrierWithWaitList,
+    SvmAlloc,
+    SvmFree,
+    SetKernelArgSvmPointer,
+    SetKernelExecInfo,
+    EnqueueSvmMemcpy,
+    EnqueueSvmMemFill,
+    EnqueueSvmMap,
+    EnqueueSvmUnmap,
     GetExtensionFunctionAddressForPlatform,
     CompileProgram,
     LinkProgram,
+    UnloadPlatformCompiler,
+}
+
+impl ApiFunction {
+    /// Returns the name of the OpenCL function.
+    fn name(&self) -> Option<&'static str> {
+        let name = match *self {
+            ApiFunction::None => return None,
+            ApiFunction::CreateSubDevices => "clCreateSubDevices",
+            ApiFunction::RetainDevice => "clRetainDevice",
+            ApiFunction::ReleaseDevice => "clReleaseDevice",
+            ApiFunction::GetDeviceAndHostTimer => "clGetDeviceAndHostTimer",
+            ApiFunction::GetHostTimer => "clGetHostTimer",
+            ApiFunction::CloneKernel => "clCloneKernel",
+            ApiFunction::GetKernelSubGroupInfo => "clGetKernelSubGroupInfo",
+            ApiFunction::CreateProgramWithIl => "clCreateProgramWithIL",
+            ApiFunction::SetProgramSpecializationConstant => "clSetProgramSpecializationConstant",
+            ApiFunction::SetProgramReleaseCallback => "clSetProgramReleaseCallback",
+            ApiFunction::CreateProgramWithBuiltInKernels => "clCreateProgramWithBuiltInKernels",
+            ApiFunction::CreateImage => "clCreateImage",
+            ApiFunction::SetContextDestructorCallback => "clSetContextDestructorCallback",
+            ApiFunction::CreateBufferWithProperties => "clCreateBufferWithProperties",
+            ApiFunction::CreateImageWithProperties => "clCreateImageWithProperties",
+            ApiFunction::CreatePipe => "clCreatePipe",
+            ApiFunction::GetPipeInfo => "clGetPipeInfo",
+            ApiFunction::CreateFromGLTexture => "clCreateFromGLTexture",
+            ApiFunction::GetKernelArgInfo => "clGetKernelArgInfo",
+            ApiFunction::EnqueueFillBuffer => "clEnqueueFillBuffer",
+            ApiFunction::EnqueueFillImage => "clEnqueueFillImage",
+            ApiFunction::EnqueueMigrateMemObjects => "clEnqueueMigrateMemObjects",
+            ApiFunction::EnqueueMarkerWithWaitList => "clEnqueueMarkerWithWaitList",
+            ApiFunction::EnqueueBarrierWithWaitList => "clEnqueueBarrierWithWaitList",
+            ApiFunction::SvmAlloc => "clSVMAlloc",
+            ApiFunction::SvmFree => "clSVMFree",
+            ApiFunction::SetKernelArgSvmPointer => "clSetKernelArgSVMPointer",
+            ApiFunction::SetKernelExecInfo => "clSetKernelExecInfo",
+            ApiFunction::EnqueueSvmMemcpy => "clEnqueueSVMMemcpy",
+            ApiFunction::EnqueueSvmMemFill => "clEnqueueSVMMemFill",
+            ApiFunction::EnqueueSvmMap => "clEnqueueSVMMap",
+            ApiFunction::EnqueueSvmUnmap => "clEnqueueSVMUnmap",
+            ApiFunction::GetExtensionFunctionAddressForPlatform => "clGetExtensionFunctionAddressForPlatform",
+            ApiFunction::CompileProgram => "clCompileProgram",
+            ApiFunction::LinkProgram => "clLinkProgram",
+            ApiFunction::UnloadPlatformCompiler => "clUnloadPlatformCompiler",
+        };
+        Some(name)
+    }
 }
 
 
@@ -310,15 +464,39 @@ pub enum ApiWrapperError {
         used to create a context associated with OpenGL. [FIXME: determine recommended \
         resolution - gl_device list fn doesn't work yet].")]
     CreateContextClGlSharingUnsupported,
+    #[fail(display = "Queue properties other than the 'CommandQueueProperties' flags \
+        (on-device queues, queue size, priority and throttle hints) require a device \
+        supporting OpenCL 2.0+.")]
+    CreateCommandQueueWithPropertiesUnsupported,
+    #[fail(display = "Sampler properties other than normalized coordinates, addressing mode \
+        and filter mode (mip filter mode and level of detail) require devices supporting \
+        OpenCL 2.0+.")]
+    CreateSamplerWithPropertiesUnsupported,
     #[fail(display = "Length of 'devices' must be greater than zero.")]
     CreateProgramWithBinaryDevicesLenZero,
     #[fail(display = "Length of 'devices' must equal the length of 'binaries' \
         (e.g. one binary per device).")]
     CreateProgramWithBinaryDevicesLenMismatch,
+    #[fail(display = "Length of 'devices' must be greater than zero.")]
+    CreateProgramWithBuiltInKernelsDevicesLenZero,
+    #[fail(display = "No built-in kernel names specified.")]
+    CreateProgramWithBuiltInKernelsNoKernelNames,
+    #[fail(display = "Length of 'mem_list' must equal the length of 'args_mem_offsets'.")]
+    EnqueueNativeKernelMemListLenMismatch,
+    #[fail(display = "A memory object offset ({}) does not leave room for a pointer within \
+        'args' (length: {}).", offset, args_len)]
+    EnqueueNativeKernelMemOffsetOor { offset: usize, args_len: usize },
+    #[fail(display = "Unable to allocate {} bytes of shared virtual memory. The size or \
+        alignment may be invalid or the flags unsupported by a device in the context.", _0)]
+    SvmAllocFailed(usize),
     #[fail(display = "The specified function does not exist for the implementation or \
         'platform' is not a valid platform.")]
     GetExtensionFunctionAddressForPlatformInvalidFunction,
-    #[fail(display = "No OpenCL platforms found. Check your driver.")]
+    #[fail(display = "The '{}' function of the '{}' extension is not available on this \
+        platform.", function, extension)]
+    ExtensionFunctionUnavailable { function: &'static str, extension: &'static str },
+    #[fail(display = "No OpenCL platforms found. Check your driver (see \
+        'core::diagnose_icd').")]
     DefaultPlatformNoPlatforms,
     #[fail(display = "The default platform set by the environment variable \
         'OCL_DEFAULT_PLATFORM_IDX' has an index which is out of range \
@@ -328,6 +506,11 @@ pub enum ApiWrapperError {
         'OCL_DEFAULT_DEVICE_TYPE': ('{}') is invalid. Valid types are: 'DEFAULT', 'CPU', \
         'GPU', 'ACCELERATOR', 'CUSTOM', and 'ALL'.", _0)]
     DefaultDeviceTypeInvalidType(String),
+    #[fail(display = "{}", _0)]
+    OpenclLibraryNotFound(String),
+    #[fail(display = "The '{}' function is not exported by the installed OpenCL library \
+        (ICD loader). The library may be older than the platform or device.", _0)]
+    FunctionUnavailable(&'static str),
 }
 
 
@@ -429,6 +612,21 @@ pub(crate) fn verify_versions(versions: &[OpenclVersion], required_version: [u16
     Ok(())
 }
 
+/// Verifies that the OpenCL library exports the function identified by
+/// `function`.
+///
+/// Functions introduced after OpenCL 1.1 are resolved at runtime and may be
+/// missing from older libraries even when the platform or device reports a
+/// sufficient version.
+fn verify_function_available(function: ApiFunction) -> OclCoreResult<()> {
+    match function.name() {
+        Some(name) if !ffi::function_is_available(name) => {
+            Err(ApiWrapperError::FunctionUnavailable(name).into())
+        },
+        _ => Ok(()),
+    }
+}
+
 // Verifies that a platform version (`provided_version`) is above a threshold
 // (`required_version`).
 fn verify_platform_version<V: ClVersions>(provided_version: Option<&OpenclVersion>,
@@ -437,10 +635,11 @@ fn verify_platform_version<V: ClVersions>(provided_version: Option<&OpenclVersio
     match provided_version {
         Some(pv) => {
             let vers = [*pv];
-            verify_versions(&vers, required_version, function, VersionKind::Platform)
+            verify_versions(&vers, required_version, function, VersionKind::Platform)?
         },
-        None => fallback_version_source.verify_platform_version(required_version),
+        None => fallback_version_source.verify_platform_version(required_version)?,
     }
+    verify_function_available(function)
 }
 
 // Verifies that a device version (`provided_version`) is above a threshold
@@ -451,10 +650,11 @@ fn verify_device_version<V: ClVersions>(provided_version: Option<&OpenclVersion>
     match provided_version {
         Some(pv) => {
             let ver = [*pv];
-            verify_versions(&ver, required_version, function, VersionKind::Device)
+            verify_versions(&ver, required_version, function, VersionKind::Device)?
         },
-        None => fallback_version_source.verify_device_versions(required_version),
+        None => fallback_version_source.verify_device_versions(required_version)?,
     }
+    verify_function_available(function)
 }
 
 // Verifies multiple device versions.
@@ -462,9 +662,10 @@ fn verify_device_versions<V: ClVersions>(provided_versions: Option<&[OpenclVersi
         required_version: [u16; 2], fallback_versions_source: &V, function: ApiFunction)
         -> OclCoreResult<()> {
     match provided_versions {
-        Some(pv) => verify_versions(pv, required_version, function, VersionKind::Device),
-        None => fallback_versions_source.verify_device_versions(required_version),
+        Some(pv) => verify_versions(pv, required_version, function, VersionKind::Device)?,
+        None => fallback_versions_source.verify_device_versions(required_version)?,
     }
+    verify_function_available(function)
 }
 
 //============================================================================
@@ -478,7 +679,17 @@ fn verify_device_versions<V: ClVersions>(provided_versions: Option<&[OpenclVersi
 //============================================================================
 
 /// Returns a list of available platforms as 'core' objects.
+///
+/// When built with the `dynamic_loading` feature, an error is returned if
+/// the OpenCL library could not be loaded.
 pub fn get_platform_ids() -> OclCoreResult<Vec<PlatformId>> {
+    #[cfg(feature = "dynamic_loading")]
+    {
+        if let Err(err) = ffi::load_library() {
+            return Err(ApiWrapperError::OpenclLibraryNotFound(err.to_string()).into());
+        }
+    }
+
     let mut num_platforms = 0 as cl_uint;
 
     // Get a count of available platforms:
@@ -690,18 +901,41 @@ pub fn get_device_info<D: ClDeviceIdPtr>(device: D, request: DeviceInfo)
     }
 }
 
-/// [UNIMPLEMENTED: Please implement me]
+/// Partitions a device into sub-devices as specified by `partition`.
+///
+/// Each returned sub-device has a reference count of one and must
+/// eventually be released with `release_device`.
 ///
 /// [Version Controlled: OpenCL 1.2+] See module docs for more info.
-pub fn create_sub_devices(device_version: Option<&OpenclVersion>) -> OclCoreResult<()> {
-    // clCreateSubDevices(in_device: cl_device_id,
-    //                    properties: *const cl_device_partition_property,
-    //                    num_devices: cl_uint,
-    //                    out_devices: *mut cl_device_id,
-    //                    num_devices_ret: *mut cl_uint) -> cl_int;
+pub fn create_sub_devices(device: &DeviceId, partition: &DevicePartition,
+        device_version: Option<&OpenclVersion>) -> OclCoreResult<Vec<DeviceId>>
+{
+    verify_device_version(device_version, [1, 2], device, ApiFunction::CreateSubDevices)?;
+
+    let properties = partition.to_raw();
+    let mut num_devices: cl_uint = 0;
+
+    // Determine the number of sub-devices which will be created:
+    let errcode = unsafe { ffi::clCreateSubDevices(
+        device.as_ptr(),
+        properties.as_ptr(),
+        0,
+        ptr::null_mut(),
+        &mut num_devices,
+    ) };
+    eval_errcode(errcode, (), "clCreateSubDevices", None::<String>)?;
 
-    let _ = device_version;
-    unimplemented!();
+    let mut sub_devices: Vec<DeviceId> = iter::repeat(unsafe { DeviceId::null() })
+        .take(num_devices as usize).collect();
+
+    let errcode = unsafe { ffi::clCreateSubDevices(
+        device.as_ptr(),
+        properties.as_ptr(),
+        num_devices,
+        sub_devices.as_mut_ptr() as *mut cl_device_id,
+        ptr::null_mut(),
+    ) };
+    eval_errcode(errcode, sub_devices, "clCreateSubDevices", None::<String>)
 }
 
 /// Increments the reference count of a device.
@@ -722,6 +956,48 @@ pub unsafe fn release_device(device: &DeviceId, device_version: Option<&OpenclVe
     eval_errcode(ffi::clReleaseDevice(device.as_ptr()), (), "clReleaseDevice", None::<String>)
 }
 
+/// Returns a reasonably synchronized pair of timestamps, `(device_timestamp,
+/// host_timestamp)`, from the device timer and the host timer as seen by
+/// `device`.
+///
+/// Both timestamps are in nanoseconds. The device timestamp is in the same
+/// time base as the values returned by `get_event_profiling_info`.
+///
+/// [Version Controlled: OpenCL 2.1+] See module docs for more info.
+pub fn get_device_and_host_timer<D>(device: D, device_version: Option<&OpenclVersion>)
+        -> OclCoreResult<(u64, u64)>
+        where D: ClDeviceIdPtr + ClVersions
+{
+    verify_device_version(device_version, [2, 1], &device, ApiFunction::GetDeviceAndHostTimer)?;
+
+    let mut device_timestamp: ffi::cl_ulong = 0;
+    let mut host_timestamp: ffi::cl_ulong = 0;
+
+    let errcode = unsafe { ffi::clGetDeviceAndHostTimer(
+        device.as_ptr(),
+        &mut device_timestamp,
+        &mut host_timestamp,
+    ) };
+    eval_errcode(errcode, (device_timestamp, host_timestamp), "clGetDeviceAndHostTimer",
+        None::<String>)
+}
+
+/// Returns the current value of the host timer as seen by `device`, in
+/// nanoseconds.
+///
+/// [Version Controlled: OpenCL 2.1+] See module docs for more info.
+pub fn get_host_timer<D>(device: D, device_version: Option<&OpenclVersion>)
+        -> OclCoreResult<u64>
+        where D: ClDeviceIdPtr + ClVersions
+{
+    verify_device_version(device_version, [2, 1], &device, ApiFunction::GetHostTimer)?;
+
+    let mut host_timestamp: ffi::cl_ulong = 0;
+
+    let errcode = unsafe { ffi::clGetHostTimer(device.as_ptr(), &mut host_timestamp) };
+    eval_errcode(errcode, host_timestamp, "clGetHostTimer", None::<String>)
+}
+
 //============================================================================
 //============================= Context APIs  ================================
 //============================================================================
@@ -958,6 +1234,41 @@ pub fn get_context_platform<C>(context: C) -> OclCoreResult<Option<PlatformId>>
     }
 }
 
+/// Registers a callback to be run when `context` is destroyed (after its
+/// reference count reaches zero and all of the objects attached to it have
+/// been released).
+///
+/// Callbacks are run in the reverse order of registration, possibly from a
+/// thread belonging to the OpenCL runtime. A panic within the callback is
+/// caught and does not propagate into the runtime.
+///
+/// [Version Controlled: OpenCL 3.0+] See module docs for more info.
+pub fn set_context_destructor_callback<C>(
+        context: C,
+        callback: crate::ContextDestructorCallbackFn,
+        device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<()>
+        where C: ClContextPtr
+{
+    verify_device_versions(device_versions, [3, 0], &context.as_ptr(),
+        ApiFunction::SetContextDestructorCallback)?;
+
+    let user_data = Box::into_raw(Box::new(callback));
+
+    let errcode = unsafe { ffi::clSetContextDestructorCallback(
+        context.as_ptr(),
+        Some(_context_destructor_callback),
+        user_data as *mut c_void,
+    ) };
+
+    if errcode != Status::CL_SUCCESS as i32 {
+        // The callback will never be run:
+        unsafe { drop(Box::from_raw(user_data)); }
+    }
+
+    eval_errcode(errcode, (), "clSetContextDestructorCallback", None::<String>)
+}
+
 /// [INOPERATIVE: Needs troubleshooting] Returns OpenGL context information.
 ///
 /// Used to query current or available devices associated with an existing
@@ -996,6 +1307,9 @@ pub fn get_gl_context_info_khr(properties: &ContextProperties, request: GlContex
             },
         };
 
+        verify_platform_version(None, [1, 2], &plat,
+            ApiFunction::GetExtensionFunctionAddressForPlatform)?;
+
         let fn_ptr = ffi::clGetExtensionFunctionAddressForPlatform(plat.as_ptr(),
             fn_name.as_ptr() as *mut _);
 
@@ -1082,6 +1396,52 @@ pub fn create_command_queue<C, D>(
 
 }
 
+/// Returns a new command queue pointer created using a list of properties.
+///
+/// On devices supporting OpenCL 2.0+ the queue is created with
+/// `clCreateCommandQueueWithProperties`. Otherwise it is created with
+/// `clCreateCommandQueue` and an error is returned if `properties` contains
+/// anything other than the out of order and profiling flags.
+///
+/// The version of `device` is queried if `device_version` is `None`.
+pub fn create_command_queue_with_properties<C, D>(
+            context: C,
+            device: D,
+            properties: &QueueProperties,
+            device_version: Option<&OpenclVersion>,
+        ) -> OclCoreResult<CommandQueue>
+        where C: ClContextPtr, D: ClDeviceIdPtr + ClVersions
+{
+    let device_version = match device_version {
+        Some(&dv) => dv,
+        None => device.device_versions()?[0],
+    };
+
+    if device_version >= [2, 0].into()
+            && ffi::function_is_available("clCreateCommandQueueWithProperties") {
+        verify_context(context)?;
+
+        let props = properties.to_raw();
+        let mut errcode: cl_int = 0;
+
+        let cq_ptr = unsafe { ffi::clCreateCommandQueueWithProperties(
+            context.as_ptr(),
+            device.as_ptr(),
+            props.as_ptr(),
+            &mut errcode
+        ) };
+        return eval_errcode(errcode, cq_ptr, "clCreateCommandQueueWithProperties",
+                None::<String>)
+            .map(|cq_ptr| unsafe { CommandQueue::from_raw_create_ptr(cq_ptr) });
+    }
+
+    if !properties.is_bitfield_only() {
+        return Err(ApiWrapperError::CreateCommandQueueWithPropertiesUnsupported.into());
+    }
+
+    create_command_queue(context, device, Some(properties.get_flags()))
+}
+
 /// Increments the reference count of a command queue.
 pub unsafe fn retain_command_queue(queue: &CommandQueue) -> OclCoreResult<()> {
     eval_errcode(ffi::clRetainCommandQueue(queue.as_ptr()), (), "clRetainCommandQueue", None::<String>)
@@ -1176,6 +1536,56 @@ pub unsafe fn create_buffer<C, T>(
         .map(|ptr| Mem::from_raw_create_ptr(ptr))
 }
 
+/// Returns a new buffer pointer with size (bytes): `len` * sizeof(T),
+/// created with a list of memory object properties.
+///
+/// ## Safety
+///
+/// The caller must ensure that correct and appropriate `flags` and
+/// `properties` are being used.
+///
+/// [Version Controlled: OpenCL 3.0+] See module docs for more info.
+pub unsafe fn create_buffer_with_properties<C, T>(
+            context: C,
+            properties: &crate::MemProperties,
+            flags: MemFlags,
+            len: usize,
+            data: Option<&[T]>,
+            device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<Mem>
+        where C: ClContextPtr, T: OclPrm
+{
+    verify_context(context)?;
+    verify_device_versions(device_versions, [3, 0], &context.as_ptr(),
+        ApiFunction::CreateBufferWithProperties)?;
+
+    let mut errcode: cl_int = 0;
+
+    let host_ptr = match data {
+        Some(d) => {
+            if d.len() != len {
+                return Err(ApiWrapperError::CreateBufferDataLengthMismatch.into())
+            }
+            d.as_ptr() as cl_mem
+        },
+        None => ptr::null_mut(),
+    };
+
+    let props = properties.to_raw();
+
+    let buf_ptr = ffi::clCreateBufferWithProperties(
+        context.as_ptr(),
+        props.as_ptr(),
+        flags.bits() as cl_mem_flags,
+        len * mem::size_of::<T>(),
+        host_ptr,
+        &mut errcode,
+    );
+
+    eval_errcode(errcode, buf_ptr, "clCreateBufferWithProperties", None::<String>)
+        .map(|ptr| Mem::from_raw_create_ptr(ptr))
+}
+
 /// Return a buffer pointer from a `OpenGL` buffer object.
 ///
 /// ## Safety
@@ -1442,6 +1852,53 @@ pub unsafe fn create_image<C, T>(
         .map(|ptr| Mem::from_raw_create_ptr(ptr))
 }
 
+/// Returns a new image (mem) pointer, created with a list of memory object
+/// properties.
+///
+/// ## Safety
+///
+/// The caller must ensure that correct and appropriate `flags` and
+/// `properties` are being used.
+///
+/// [Version Controlled: OpenCL 3.0+] See module docs for more info.
+pub unsafe fn create_image_with_properties<C, T>(
+            context: C,
+            properties: &crate::MemProperties,
+            flags: MemFlags,
+            format: &ImageFormat,
+            desc: &ImageDescriptor,
+            data: Option<&[T]>,
+            device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<Mem>
+        where C: ClContextPtr, T: OclPrm
+{
+    verify_context(context)?;
+    verify_device_versions(device_versions, [3, 0], &context.as_ptr(),
+        ApiFunction::CreateImageWithProperties)?;
+
+    let mut errcode: cl_int = 0;
+
+    let host_ptr = match data {
+        Some(d) => d.as_ptr() as cl_mem,
+        None => ptr::null_mut(),
+    };
+
+    let props = properties.to_raw();
+
+    let image_ptr = ffi::clCreateImageWithProperties(
+        context.as_ptr(),
+        props.as_ptr(),
+        flags.bits() as cl_mem_flags,
+        &format.to_raw() as *const cl_image_format,
+        &desc.to_raw() as *const cl_image_desc,
+        host_ptr,
+        &mut errcode as *mut cl_int,
+    );
+
+    eval_errcode(errcode, image_ptr, "clCreateImageWithProperties", None::<String>)
+        .map(|ptr| Mem::from_raw_create_ptr(ptr))
+}
+
 /// Increments the reference counter of a mem object.
 pub unsafe fn retain_mem_object(mem: &Mem) -> OclCoreResult<()> {
     eval_errcode(ffi::clRetainMemObject(mem.as_ptr()), (), "clRetainMemObject", None::<String>)
@@ -1572,12 +2029,154 @@ pub fn get_image_info(obj: &Mem, request: ImageInfo) -> OclCoreResult<ImageInfoR
     ImageInfoResult::from_bytes(request, result)
 }
 
-/// [UNIMPLEMENTED: Please implement me]
-pub fn set_mem_object_destructor_callback() -> OclCoreResult<()> {
-    // ffi::clSetMemObjectDestructorCallback(memobj: cl_mem,
-    //                                     pfn_notify: extern fn (cl_mem, *mut c_void),
-    //                                     user_data: *mut c_void) -> cl_int;
-    unimplemented!();
+/// Registers a callback to be run when `mem` is destroyed (after its
+/// reference count reaches zero and the resources it uses, including any
+/// `CL_MEM_USE_HOST_PTR` host region, are no longer in use).
+///
+/// Callbacks are run in the reverse order of registration, possibly from a
+/// thread belonging to the OpenCL runtime. The `&Mem` passed to the callback
+/// must not be retained, released, or used in any OpenCL call. A panic within
+/// the callback is caught and does not propagate into the runtime.
+pub fn set_mem_object_destructor_callback(mem: &Mem, callback: MemDestructorCallbackFn)
+        -> OclCoreResult<()>
+{
+    let user_data = Box::into_raw(Box::new(callback));
+
+    let errcode = unsafe { ffi::clSetMemObjectDestructorCallback(
+        mem.as_ptr(),
+        Some(_mem_destructor_callback),
+        user_data as *mut c_void,
+    ) };
+
+    if errcode != Status::CL_SUCCESS as i32 {
+        // The callback will never be run:
+        unsafe { drop(Box::from_raw(user_data)); }
+    }
+
+    eval_errcode(errcode, (), "clSetMemObjectDestructorCallback", None::<String>)
+}
+
+/// Returns a new pipe memory object which stores up to `max_packets` packets
+/// of `packet_size` bytes each.
+///
+/// Pipes may only be accessed by kernels (using the built-in pipe functions)
+/// and are not readable or writable from the host. `flags` may only contain
+/// `MEM_READ_WRITE` and/or `MEM_HOST_NO_ACCESS` (the default when empty).
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+pub fn create_pipe<C>(
+        context: C,
+        flags: MemFlags,
+        packet_size: u32,
+        max_packets: u32,
+        device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<Mem>
+        where C: ClContextPtr + ClVersions
+{
+    verify_context(context)?;
+    verify_device_versions(device_versions, [2, 0], &context, ApiFunction::CreatePipe)?;
+
+    let mut errcode: cl_int = 0;
+
+    let pipe_ptr = unsafe { ffi::clCreatePipe(
+        context.as_ptr(),
+        flags.bits() as cl_mem_flags,
+        packet_size,
+        max_packets,
+        ptr::null(),
+        &mut errcode as *mut cl_int,
+    ) };
+
+    eval_errcode(errcode, pipe_ptr, "clCreatePipe", None::<String>)
+        .map(|ptr| unsafe { Mem::from_raw_create_ptr(ptr) })
+}
+
+/// Get pipe info.
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+pub fn get_pipe_info(obj: &Mem, request: crate::PipeInfo) -> OclCoreResult<crate::PipeInfoResult> {
+    use crate::PipeInfoResult;
+
+    verify_function_available(ApiFunction::GetPipeInfo)?;
+
+    let mut result_size: size_t = 0;
+
+    let errcode = unsafe { ffi::clGetPipeInfo(
+        obj.as_ptr() as cl_mem,
+        request as ffi::cl_pipe_info,
+        0 as size_t,
+        ptr::null_mut(),
+        &mut result_size as *mut size_t,
+    ) };
+
+    eval_errcode(errcode, (), "clGetPipeInfo", None::<String>)?;
+
+    // If result size is zero, return an empty info result directly:
+    if result_size == 0 {
+        return PipeInfoResult::from_bytes(request, vec![]);
+    }
+
+    let mut result: Vec<u8> = iter::repeat(0u8).take(result_size).collect();
+
+    let errcode = unsafe { ffi::clGetPipeInfo(
+        obj.as_ptr() as cl_mem,
+        request as ffi::cl_pipe_info,
+        result_size,
+        result.as_mut_ptr() as *mut _ as *mut c_void,
+        ptr::null_mut(),
+    ) };
+
+    let result = eval_errcode(errcode, result, "clGetPipeInfo", None::<String>)?;
+    PipeInfoResult::from_bytes(request, result)
+}
+
+/// Allocates a shared virtual memory (SVM) buffer of `size` bytes which can
+/// be shared by the host and all devices in `context`.
+///
+/// An `alignment` of zero uses the default alignment (the size of the
+/// largest OpenCL data type supported by the devices in the context).
+///
+/// The returned pointer must eventually be freed with `svm_free`.
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+pub fn svm_alloc<C>(
+        context: C,
+        flags: crate::SvmMemFlags,
+        size: usize,
+        alignment: u32,
+        device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<*mut c_void>
+        where C: ClContextPtr + ClVersions
+{
+    verify_device_versions(device_versions, [2, 0], &context, ApiFunction::SvmAlloc)?;
+
+    let svm_ptr = unsafe { ffi::clSVMAlloc(
+        context.as_ptr(),
+        flags.bits() as ffi::cl_svm_mem_flags,
+        size,
+        alignment,
+    ) };
+
+    if svm_ptr.is_null() {
+        Err(ApiWrapperError::SvmAllocFailed(size).into())
+    } else {
+        Ok(svm_ptr)
+    }
+}
+
+/// Frees a shared virtual memory buffer allocated with `svm_alloc`.
+///
+/// ## Safety
+///
+/// `svm_pointer` must have been returned by `svm_alloc` using the same
+/// context and must not already have been freed. All enqueued commands using
+/// the buffer must have completed.
+pub unsafe fn svm_free<C: ClContextPtr>(context: C, svm_pointer: *mut c_void)
+        -> OclCoreResult<()>
+{
+    verify_function_available(ApiFunction::SvmFree)?;
+    ffi::clSVMFree(context.as_ptr(), svm_pointer);
+    Ok(())
 }
 
 //============================================================================
@@ -1593,15 +2192,67 @@ pub fn create_sampler<C>(context: C, normalize_coords: bool, addressing_mode: Ad
 {
     let mut errcode = 0;
 
-    let sampler = unsafe { Sampler::from_raw_create_ptr(ffi::clCreateSampler(
+    let sampler_ptr = unsafe { ffi::clCreateSampler(
         context.as_ptr(),
         normalize_coords as cl_bool,
         addressing_mode as cl_addressing_mode,
         filter_mode as cl_filter_mode,
         &mut errcode,
-    )) };
+    ) };
+
+    eval_errcode(errcode, sampler_ptr, "clCreateSampler", None::<String>)
+        .map(|ptr| unsafe { Sampler::from_raw_create_ptr(ptr) })
+}
+
+/// Creates and returns a new sampler object using a list of properties.
+///
+/// When every device in `context` supports OpenCL 2.0+ the sampler is
+/// created with `clCreateSamplerWithProperties`. Otherwise it is created with
+/// `clCreateSampler` and an error is returned if `properties` specifies a mip
+/// filter mode or level of detail.
+///
+/// The versions of the devices in `context` are queried if
+/// `device_versions` is `None`.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clCreateSamplerWithProperties.html)
+pub fn create_sampler_with_properties<C>(
+            context: C,
+            properties: &SamplerProperties,
+            device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<Sampler>
+        where C: ClContextPtr + ClVersions
+{
+    let queried_versions;
+    let device_versions = match device_versions {
+        Some(dvs) => dvs,
+        None => {
+            queried_versions = context.device_versions()?;
+            &queried_versions[..]
+        },
+    };
+
+    if device_versions.iter().all(|dv| *dv >= [2, 0].into())
+            && ffi::function_is_available("clCreateSamplerWithProperties") {
+        let props = properties.to_raw();
+        let mut errcode = 0;
 
-    eval_errcode(errcode, sampler, "clCreateSampler", None::<String>)
+        let sampler_ptr = unsafe { ffi::clCreateSamplerWithProperties(
+            context.as_ptr(),
+            props.as_ptr(),
+            &mut errcode,
+        ) };
+
+        return eval_errcode(errcode, sampler_ptr, "clCreateSamplerWithProperties",
+                None::<String>)
+            .map(|ptr| unsafe { Sampler::from_raw_create_ptr(ptr) });
+    }
+
+    if !properties.is_basic() {
+        return Err(ApiWrapperError::CreateSamplerWithPropertiesUnsupported.into());
+    }
+
+    create_sampler(context, properties.get_normalized_coords(), properties.get_addressing_mode(),
+        properties.get_filter_mode())
 }
 
 /// Increments a sampler reference counter.
@@ -1737,44 +2388,140 @@ pub fn create_program_with_binary<C, D>(
     unsafe { Ok(Program::from_raw_create_ptr(program)) }
 }
 
-/// [UNIMPLEMENTED: Please implement me]
+/// Creates a program object for a context, and loads the information related
+/// to the built-in kernels into that program object.
+///
+/// Each of `devices` must support every kernel in `kernel_names` (see
+/// `DeviceInfo::BuiltInKernels`). The resulting program does not need to be
+/// (and cannot be) built.
 ///
 /// [Version Controlled: OpenCL 1.2+] See module docs for more info.
-pub fn create_program_with_built_in_kernels(device_version: Option<&OpenclVersion>)
-            -> OclCoreResult<()> {
-    // clCreateProgramWithBuiltInKernels(context: cl_context,
-    //                                  num_devices: cl_uint,
-    //                                  device_list: *const cl_device_id,
-    //                                  kernel_names: *mut char,
-    //                                  errcode_ret: *mut cl_int) -> cl_program;
-    let _ =  device_version;
-    unimplemented!();
+pub fn create_program_with_built_in_kernels<C, D, S>(
+            context: C,
+            devices: &[D],
+            kernel_names: &[S],
+            device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<Program>
+        where C: ClContextPtr + ClVersions, D: ClDeviceIdPtr, S: AsRef<str>
+{
+    verify_device_versions(device_versions, [1, 2], &context,
+        ApiFunction::CreateProgramWithBuiltInKernels)?;
+
+    if devices.is_empty() {
+        return Err(ApiWrapperError::CreateProgramWithBuiltInKernelsDevicesLenZero.into())
+    }
+
+    if kernel_names.is_empty() {
+        return Err(ApiWrapperError::CreateProgramWithBuiltInKernelsNoKernelNames.into())
+    }
+
+    let device_ptrs: Vec<cl_device_id> = devices.iter().map(|d| d.as_ptr()).collect();
+    let names = kernel_names.iter().map(|n| n.as_ref()).collect::<Vec<_>>().join(";");
+    let names = CString::new(names)?;
+    let mut errcode: cl_int = 0;
+
+    let program_ptr = unsafe { ffi::clCreateProgramWithBuiltInKernels(
+        context.as_ptr(),
+        device_ptrs.len() as cl_uint,
+        device_ptrs.as_ptr(),
+        names.as_ptr(),
+        &mut errcode,
+    ) };
+
+    eval_errcode(errcode, program_ptr, "clCreateProgramWithBuiltInKernels",
+            Some(names.to_string_lossy()))
+        .map(|ptr| unsafe { Program::from_raw_create_ptr(ptr) })
 }
 
 /// Returns a new `Program` loaded with the provided IL bytes.
 ///
 /// [Version Controlled: OpenCL 2.1+] See module docs for more info.
-#[cfg(feature = "opencl_version_2_1")]
 pub fn create_program_with_il<C>(
         context: C,
         il: &[u8],
         device_versions: Option<&[OpenclVersion]>,
-        ) -> OclCoreResult<Program>
-        where C: ClContextPtr + ClVersions
+        ) -> OclCoreResult<Program>
+        where C: ClContextPtr + ClVersions
+{
+    verify_device_versions(device_versions, [2, 1], &context, ApiFunction::CreateProgramWithIl)?;
+
+    let mut errcode: cl_int = 0;
+
+    let program_ptr = unsafe { ffi::clCreateProgramWithIL(
+        context.as_ptr(),
+        il.as_ptr() as *mut c_void,
+        il.len(),
+        &mut errcode,
+    ) };
+
+    eval_errcode(errcode, program_ptr, "clCreateProgramWithIL", None::<String>)
+        .map(|ptr| unsafe { Program::from_raw_create_ptr(ptr) })
+}
+
+/// Sets the value of a SPIR-V specialization constant in a program created
+/// from IL.
+///
+/// `spec_value` contains the bytes of the value and its length must match the
+/// size of the constant identified by `spec_id` (one byte for a boolean
+/// constant). Specialization constants must be set before the program is
+/// built and apply to subsequent builds. Constants which are not set use the
+/// default value specified in the module.
+///
+/// [Version Controlled: OpenCL 2.2+] See module docs for more info.
+pub fn set_program_specialization_constant(
+        program: &Program,
+        spec_id: u32,
+        spec_value: &[u8],
+        device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<()>
+{
+    verify_device_versions(device_versions, [2, 2], program,
+        ApiFunction::SetProgramSpecializationConstant)?;
+
+    let errcode = unsafe { ffi::clSetProgramSpecializationConstant(
+        program.as_ptr(),
+        spec_id,
+        spec_value.len(),
+        spec_value.as_ptr() as *const c_void,
+    ) };
+
+    eval_errcode(errcode, (), "clSetProgramSpecializationConstant",
+        Some(format!("spec_id: {}, spec_size: {}", spec_id, spec_value.len())))
+}
+
+/// Registers a callback to be run when `program` is destroyed (after its
+/// reference count reaches zero and any program scope global variable
+/// destructors have run).
+///
+/// Callbacks are run in the reverse order of registration, possibly from a
+/// thread belonging to the OpenCL runtime. A panic within the callback is
+/// caught and does not propagate into the runtime. Some platforms do not
+/// support release callbacks and return an error.
+///
+/// [Version Controlled: OpenCL 2.2+] See module docs for more info.
+pub fn set_program_release_callback(
+        program: &Program,
+        callback: crate::ProgramReleaseCallbackFn,
+        device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<()>
 {
-    verify_device_versions(device_versions, [2, 1], &context, ApiFunction::CreateProgramWithIl)?;
+    verify_device_versions(device_versions, [2, 2], program,
+        ApiFunction::SetProgramReleaseCallback)?;
 
-    let mut errcode: cl_int = 0;
+    let user_data = Box::into_raw(Box::new(callback));
 
-    let program_ptr = unsafe { ffi::clCreateProgramWithIL(
-        context.as_ptr(),
-        il.as_ptr() as *mut c_void,
-        il.len(),
-        &mut errcode,
+    let errcode = unsafe { ffi::clSetProgramReleaseCallback(
+        program.as_ptr(),
+        Some(_program_release_callback),
+        user_data as *mut c_void,
     ) };
 
-    eval_errcode(errcode, program_ptr, "clCreateProgramWithIL", None::<String>)
-        .map(|ptr| unsafe { Program::from_raw_create_ptr(ptr) })
+    if errcode != Status::CL_SUCCESS as i32 {
+        // The callback will never be run:
+        unsafe { drop(Box::from_raw(user_data)); }
+    }
+
+    eval_errcode(errcode, (), "clSetProgramReleaseCallback", None::<String>)
 }
 
 /// Increments a program reference counter.
@@ -1797,8 +2544,8 @@ impl UserDataPh {
 
 /// Builds a program.
 ///
-/// Callback functions are not yet supported. Please file an issue if you have
-/// need of this functionality.
+/// Callback functions are not yet supported. Use `build_program_async` to
+/// build without blocking.
 ///
 //
 // [NOTE]: Despite what the spec says, some platforms segfault when `null` is
@@ -1831,22 +2578,73 @@ pub fn build_program<D: ClDeviceIdPtr>(
     ) };
 
     if errcode == Status::CL_BUILD_PROGRAM_FAILURE as i32 {
-        if let Some(ds) = devices {
-            program_build_err(program, ds).map_err(|err| err.into())
-        } else {
-            let ds = program.devices()?;
-            program_build_err(program, &ds).map_err(|err| err.into())
+        match devices {
+            Some(ds) => program_build_err(program, ds)?,
+            None => program_build_err(program, &program.devices()?)?,
         }
-    } else {
-        eval_errcode(errcode, (), "clBuildProgram", None::<String>)
     }
+    // Failures without errors in any log are reported by status:
+    eval_errcode(errcode, (), "clBuildProgram", None::<String>)
+}
+
+/// Builds a program without waiting for the build to complete, running
+/// `callback` once it has (whether or not it succeeded).
+///
+/// Check the outcome of the build within the callback, or after it has run,
+/// with `program_build_err` or `get_program_build_log`. The callback may be
+/// run from a thread belonging to the OpenCL runtime, or, on platforms which
+/// build synchronously, before this function returns. A panic within the
+/// callback is caught and does not propagate into the runtime.
+///
+/// Build failures are reported to the callback rather than returned. If an
+/// error is returned the callback will not be run after this function
+/// returns. Otherwise the program must not be built again, or its kernels
+/// created, until it has run.
+pub fn build_program_async<D: ClDeviceIdPtr>(
+            program: &Program,
+            devices: Option<&[D]>,
+            options: &CString,
+            callback: BuildProgramCompleteFn,
+        ) -> OclCoreResult<()>
+{
+    let device_ptrs = DevicePtrList::from(devices);
+
+    // Shared with the runtime, which may run the callback even when an error
+    // is returned:
+    let slot = Arc::new(Mutex::new(Some(callback)));
+    let user_data = Arc::into_raw(slot.clone());
+
+    let errcode = unsafe { ffi::clBuildProgram(
+        program.as_ptr() as cl_program,
+        device_ptrs.num(),
+        device_ptrs.as_ptr(),
+        options.as_ptr(),
+        Some(_build_program_callback),
+        user_data as *mut c_void,
+    ) };
+
+    if errcode == Status::CL_SUCCESS as i32 {
+        return Ok(());
+    }
+
+    // Ensure the callback is only run for completed (failed) builds. The
+    // reference held by the runtime is leaked if it never runs the callback:
+    let callback = slot.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+
+    if errcode == Status::CL_BUILD_PROGRAM_FAILURE as i32 {
+        if let Some(callback) = callback {
+            let _ = panic::catch_unwind(panic::AssertUnwindSafe(move || callback(program)));
+        }
+        return Ok(());
+    }
+
+    eval_errcode(errcode, (), "clBuildProgram", None::<String>)
 }
 
 /// Compiles a program’s source for all the devices or a specific device(s) in
 /// the OpenCL context associated with program.
 ///
 /// [Version Controlled: OpenCL 1.2+] See module docs for more info.
-#[cfg(feature = "opencl_version_1_2")]
 pub fn compile_program<D: ClDeviceIdPtr>(
             program: &Program,
             devices: Option<&[D]>,
@@ -1896,22 +2694,19 @@ pub fn compile_program<D: ClDeviceIdPtr>(
     ) };
 
     if errcode == Status::CL_COMPILE_PROGRAM_FAILURE as i32 {
-        if let Some(ds) = devices {
-            program_build_err(program, ds).map_err(|err| err.into())
-        } else {
-            let ds = program.devices()?;
-            program_build_err(program, &ds).map_err(|err| err.into())
+        match devices {
+            Some(ds) => program_build_err(program, ds)?,
+            None => program_build_err(program, &program.devices()?)?,
         }
-    } else {
-        eval_errcode(errcode, (), "clCompileProgram", None::<String>)
     }
+    // Failures without errors in any log are reported by status:
+    eval_errcode(errcode, (), "clCompileProgram", None::<String>)
 }
 
 /// Links a set of compiled program objects and libraries for all the devices
 /// or a specific device(s) in the OpenCL context and creates an executable.
 ///
 /// [Version Controlled: OpenCL 1.2+] See module docs for more info.
-#[cfg(feature = "opencl_version_1_2")]
 pub fn link_program<D: ClDeviceIdPtr, C: ClContextPtr>(
             context: C,
             devices: Option<&[D]>,
@@ -1951,20 +2746,32 @@ pub fn link_program<D: ClDeviceIdPtr, C: ClContextPtr>(
         &mut errcode,
     ) };
 
+    // Failed links may still return a program containing the link log:
+    if errcode == Status::CL_LINK_PROGRAM_FAILURE as i32 && !program_ptr.is_null() {
+        let program = unsafe { Program::from_raw_create_ptr(program_ptr) };
+        match devices {
+            Some(ds) => program_build_err(&program, ds)?,
+            None => program_build_err(&program, &program.devices()?)?,
+        }
+    }
+
     eval_errcode(errcode, program_ptr, "clLinkProgram", None::<String>)
         .map(|ptr| unsafe { Program::from_raw_create_ptr(ptr) })
 }
 
-// [DISABLED DUE TO PLATFORM INCOMPATABILITY]
-// /// [UNTESTED]
-// /// Unloads a platform compiler.
-// ///
-// /// [Version Controlled: OpenCL 1.2+] See module docs for more info.
-// pub fn unload_platform_compiler(platform: &PlatformId,
-//          device_version: Option<&OpenclVersion>) -> OclCoreResult<()> {
-//     unsafe { eval_errcode("clUnloadPlatformCompiler", "",
-//         ffi::clUnloadPlatformCompiler(platform.as_ptr())) }
-// }
+/// Unloads a platform compiler.
+///
+/// The compiler is reloaded automatically if a program is subsequently built.
+///
+/// [Version Controlled: OpenCL 1.2+] See module docs for more info.
+pub fn unload_platform_compiler(platform: &PlatformId,
+        platform_version: Option<&OpenclVersion>) -> OclCoreResult<()> {
+    verify_platform_version(platform_version, [1, 2], platform,
+        ApiFunction::UnloadPlatformCompiler)?;
+
+    let errcode = unsafe { ffi::clUnloadPlatformCompiler(platform.as_ptr()) };
+    eval_errcode(errcode, (), "clUnloadPlatformCompiler", None::<String>)
+}
 
 
 fn get_program_info_raw(program: &Program, request: ProgramInfo) -> OclCoreResult<Vec<u8>> {
@@ -2093,13 +2900,55 @@ pub fn create_kernel<S: AsRef<str>>(program: &Program, name: S) -> OclCoreResult
     }
 }
 
-/// [UNIMPLEMENTED: Please implement me]
-pub fn create_kernels_in_program() -> OclCoreResult<()> {
-    // ffi::clCreateKernelsInProgram(program: cl_program,
-    //                             num_kernels: cl_uint,
-    //                             kernels: *mut cl_kernel,
-    //                             num_kernels_ret: *mut cl_uint) -> cl_int;
-    unimplemented!();
+/// Creates a kernel object for every kernel function in `program`.
+///
+/// The program must have been successfully built for at least one device.
+/// Kernels are returned in no particular order; use
+/// `get_kernel_info(.., KernelInfo::FunctionName)` to identify them.
+pub fn create_kernels_in_program(program: &Program) -> OclCoreResult<Vec<Kernel>> {
+    let mut num_kernels: cl_uint = 0;
+
+    let errcode = unsafe { ffi::clCreateKernelsInProgram(
+        program.as_ptr(),
+        0,
+        ptr::null_mut(),
+        &mut num_kernels,
+    ) };
+    eval_errcode(errcode, (), "clCreateKernelsInProgram", None::<String>)?;
+
+    if num_kernels == 0 { return Ok(Vec::new()); }
+
+    let mut kernel_ptrs: Vec<cl_kernel> = vec![ptr::null_mut(); num_kernels as usize];
+
+    let errcode = unsafe { ffi::clCreateKernelsInProgram(
+        program.as_ptr(),
+        num_kernels,
+        kernel_ptrs.as_mut_ptr(),
+        ptr::null_mut(),
+    ) };
+    eval_errcode(errcode, (), "clCreateKernelsInProgram", None::<String>)?;
+
+    Ok(kernel_ptrs.into_iter().map(|ptr| unsafe { Kernel::from_raw_create_ptr(ptr) }).collect())
+}
+
+/// Returns a copy of `source_kernel` including the values of any arguments
+/// which have been set.
+///
+/// The copy shares nothing else with the original: setting an argument on
+/// one does not affect the other.
+///
+/// [Version Controlled: OpenCL 2.1+] See module docs for more info.
+pub fn clone_kernel(source_kernel: &Kernel, device_versions: Option<&[OpenclVersion]>)
+        -> OclCoreResult<Kernel>
+{
+    verify_device_versions(device_versions, [2, 1], source_kernel, ApiFunction::CloneKernel)?;
+
+    let mut errcode: cl_int = 0;
+
+    let kernel_ptr = unsafe { ffi::clCloneKernel(source_kernel.as_ptr(), &mut errcode) };
+
+    eval_errcode(errcode, kernel_ptr, "clCloneKernel", None::<String>)
+        .map(|ptr| unsafe { Kernel::from_raw_create_ptr(ptr) })
 }
 
 /// Increments a kernel reference counter.
@@ -2129,6 +2978,16 @@ pub fn set_kernel_arg(kernel: &Kernel, index: u32, arg_val: ArgVal)
 {
     let (size, value) = arg_val.as_raw();
 
+    if arg_val.is_svm() {
+        verify_function_available(ApiFunction::SetKernelArgSvmPointer)?;
+        let err = unsafe { ffi::clSetKernelArgSVMPointer(kernel.as_ptr(), index, value) };
+        if err != Status::CL_SUCCESS as i32 {
+            let name = get_kernel_name(kernel)?;
+            return eval_errcode(err, (), "clSetKernelArgSVMPointer", Some(name));
+        }
+        return Ok(());
+    }
+
     let err = unsafe { ffi::clSetKernelArg(
             kernel.as_ptr(),
             index,
@@ -2144,6 +3003,54 @@ pub fn set_kernel_arg(kernel: &Kernel, index: u32, arg_val: ArgVal)
     }
 }
 
+/// Sets the kernel argument at `index` to a shared virtual memory pointer
+/// (or a pointer into an SVM allocation).
+///
+/// Equivalent to calling `set_kernel_arg` with an `ArgVal::svm_pointer`
+/// except that the device version is checked.
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+pub fn set_kernel_arg_svm_pointer(kernel: &Kernel, index: u32, svm_ptr: *const c_void,
+        device_versions: Option<&[OpenclVersion]>) -> OclCoreResult<()>
+{
+    verify_device_versions(device_versions, [2, 0], kernel,
+        ApiFunction::SetKernelArgSvmPointer)?;
+    set_kernel_arg(kernel, index, ArgVal::svm_pointer(svm_ptr))
+}
+
+/// Passes additional information, such as the shared virtual memory pointers
+/// a kernel will access indirectly, to a kernel.
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+pub fn set_kernel_exec_info(kernel: &Kernel, info: &crate::KernelExecInfo,
+        device_versions: Option<&[OpenclVersion]>) -> OclCoreResult<()>
+{
+    use crate::KernelExecInfo;
+
+    verify_device_versions(device_versions, [2, 0], kernel, ApiFunction::SetKernelExecInfo)?;
+
+    let fine_grain_system: cl_bool;
+
+    let (param_name, param_value_size, param_value) = match *info {
+        KernelExecInfo::SvmPtrs(ptrs) => (ffi::CL_KERNEL_EXEC_INFO_SVM_PTRS,
+            mem::size_of::<*const c_void>() * ptrs.len(), ptrs.as_ptr() as *const c_void),
+        KernelExecInfo::SvmFineGrainSystem(enabled) => {
+            fine_grain_system = enabled as cl_bool;
+            (ffi::CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM, mem::size_of::<cl_bool>(),
+                &fine_grain_system as *const cl_bool as *const c_void)
+        },
+    };
+
+    let errcode = unsafe { ffi::clSetKernelExecInfo(
+        kernel.as_ptr(),
+        param_name as ffi::cl_kernel_exec_info,
+        param_value_size,
+        param_value,
+    ) };
+
+    eval_errcode(errcode, (), "clSetKernelExecInfo", None::<String>)
+}
+
 /// Get kernel info.
 pub fn get_kernel_info(obj: &Kernel, request: KernelInfo) -> OclCoreResult<KernelInfoResult> {
     let mut result_size: size_t = 0;
@@ -2282,6 +3189,67 @@ pub fn get_kernel_work_group_info<D: ClDeviceIdPtr>(obj: &Kernel, device_obj: D,
     KernelWorkGroupInfoResult::from_bytes(request, result)
 }
 
+/// Returns sub-group information for a kernel running on `device_obj`.
+///
+/// `input_value` must contain the local work size (one element per
+/// dimension) for `KernelSubGroupInfo::MaxSubGroupSizeForNdrange` and
+/// `::SubGroupCountForNdrange`, the number of sub-groups for
+/// `::LocalSizeForSubGroupCount` and should be empty otherwise.
+///
+/// [Version Controlled: OpenCL 2.1+] See module docs for more info.
+pub fn get_kernel_sub_group_info<D>(obj: &Kernel, device_obj: D,
+            request: crate::KernelSubGroupInfo, input_value: &[usize],
+            device_version: Option<&OpenclVersion>)
+            -> OclCoreResult<crate::KernelSubGroupInfoResult>
+        where D: ClDeviceIdPtr + ClVersions
+{
+    use crate::KernelSubGroupInfoResult;
+
+    verify_device_version(device_version, [2, 1], &device_obj,
+        ApiFunction::GetKernelSubGroupInfo)?;
+
+    let (input_size, input_ptr) = if input_value.is_empty() {
+        (0, ptr::null())
+    } else {
+        (input_value.len() * mem::size_of::<usize>(), input_value.as_ptr() as *const c_void)
+    };
+
+    let mut result_size: size_t = 0;
+
+    let errcode = unsafe { ffi::clGetKernelSubGroupInfo(
+        obj.as_ptr() as cl_kernel,
+        device_obj.as_ptr() as cl_device_id,
+        request as ffi::cl_kernel_sub_group_info,
+        input_size,
+        input_ptr,
+        0 as size_t,
+        ptr::null_mut(),
+        &mut result_size as *mut size_t,
+    ) };
+    eval_errcode(errcode, (), "clGetKernelSubGroupInfo", None::<String>)?;
+
+    // If result size is zero, return an empty info result directly:
+    if result_size == 0 {
+        return KernelSubGroupInfoResult::from_bytes(request, vec![]);
+    }
+
+    let mut result: Vec<u8> = iter::repeat(0u8).take(result_size).collect();
+
+    let errcode = unsafe { ffi::clGetKernelSubGroupInfo(
+        obj.as_ptr() as cl_kernel,
+        device_obj.as_ptr() as cl_device_id,
+        request as ffi::cl_kernel_sub_group_info,
+        input_size,
+        input_ptr,
+        result_size,
+        result.as_mut_ptr() as *mut _ as *mut c_void,
+        ptr::null_mut(),
+    ) };
+
+    let result = eval_errcode(errcode, result, "clGetKernelSubGroupInfo", None::<String>)?;
+    KernelSubGroupInfoResult::from_bytes(request, result)
+}
+
 //============================================================================
 //========================== Event Object APIs ===============================
 //============================================================================
@@ -3324,6 +4292,168 @@ pub fn enqueue_migrate_mem_objects<En: ClNullEventPtr, Ewl: ClWaitListPtr>(
     eval_errcode(errcode, (), "clEnqueueMigrateMemObjects", None::<String>)
 }
 
+/// Enqueues a command to copy `len` elements between two shared virtual
+/// memory buffers (either of which may instead be an ordinary host pointer).
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clEnqueueSVMMemcpy.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+///
+/// ## Safety
+///
+/// Both regions must be valid for `len` elements, must not overlap, and must
+/// not be used elsewhere until the command completes.
+pub unsafe fn enqueue_svm_memcpy<T, En, Ewl>(
+            command_queue: &CommandQueue,
+            block: bool,
+            dst_ptr: *mut T,
+            src_ptr: *const T,
+            len: usize,
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+            device_version: Option<&OpenclVersion>
+        ) -> OclCoreResult<()>
+        where T: OclPrm, En: ClNullEventPtr, Ewl: ClWaitListPtr
+{
+    verify_device_version(device_version, [2, 0], command_queue,
+        ApiFunction::EnqueueSvmMemcpy)?;
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr)
+        = resolve_event_ptrs(wait_list, new_event);
+
+    let errcode = ffi::clEnqueueSVMMemcpy(
+        command_queue.as_ptr(),
+        block as cl_uint,
+        dst_ptr as *mut c_void,
+        src_ptr as *const c_void,
+        len * mem::size_of::<T>(),
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    );
+    eval_errcode(errcode, (), "clEnqueueSVMMemcpy", None::<String>)
+}
+
+/// Enqueues a command to fill `len` elements of a shared virtual memory
+/// buffer with `pattern`.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clEnqueueSVMMemFill.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+///
+/// ## Safety
+///
+/// The region must be valid for `len` elements and must not be used
+/// elsewhere until the command completes.
+pub unsafe fn enqueue_svm_mem_fill<T, En, Ewl>(
+            command_queue: &CommandQueue,
+            svm_ptr: *mut T,
+            pattern: T,
+            len: usize,
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+            device_version: Option<&OpenclVersion>
+        ) -> OclCoreResult<()>
+        where T: OclPrm, En: ClNullEventPtr, Ewl: ClWaitListPtr
+{
+    verify_device_version(device_version, [2, 0], command_queue,
+        ApiFunction::EnqueueSvmMemFill)?;
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr)
+        = resolve_event_ptrs(wait_list, new_event);
+
+    let errcode = ffi::clEnqueueSVMMemFill(
+        command_queue.as_ptr(),
+        svm_ptr as *mut c_void,
+        &pattern as *const T as *const c_void,
+        mem::size_of::<T>(),
+        len * mem::size_of::<T>(),
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    );
+    eval_errcode(errcode, (), "clEnqueueSVMMemFill", None::<String>)
+}
+
+/// Enqueues a command to map `len` elements of a coarse-grained shared
+/// virtual memory buffer for access by the host.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clEnqueueSVMMap.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+///
+/// ## Safety
+///
+/// The region must not be accessed by the host until the map is complete
+/// (use `new_event` to monitor it) and must be unmapped with
+/// `enqueue_svm_unmap` before being used by a device.
+pub unsafe fn enqueue_svm_map<T, En, Ewl>(
+            command_queue: &CommandQueue,
+            block: bool,
+            map_flags: MapFlags,
+            svm_ptr: *mut T,
+            len: usize,
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+            device_version: Option<&OpenclVersion>
+        ) -> OclCoreResult<()>
+        where T: OclPrm, En: ClNullEventPtr, Ewl: ClWaitListPtr
+{
+    verify_device_version(device_version, [2, 0], command_queue,
+        ApiFunction::EnqueueSvmMap)?;
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr)
+        = resolve_event_ptrs(wait_list, new_event);
+
+    let errcode = ffi::clEnqueueSVMMap(
+        command_queue.as_ptr(),
+        block as cl_uint,
+        map_flags.bits(),
+        svm_ptr as *mut c_void,
+        len * mem::size_of::<T>(),
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    );
+    eval_errcode(errcode, (), "clEnqueueSVMMap", None::<String>)
+}
+
+/// Enqueues a command to unmap a shared virtual memory region previously
+/// mapped with `enqueue_svm_map`.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clEnqueueSVMUnmap.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+///
+/// ## Safety
+///
+/// `svm_ptr` must be the pointer previously passed to `enqueue_svm_map`.
+/// The host must not access the region once this command has been enqueued.
+pub unsafe fn enqueue_svm_unmap<T, En, Ewl>(
+            command_queue: &CommandQueue,
+            svm_ptr: *mut T,
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+            device_version: Option<&OpenclVersion>
+        ) -> OclCoreResult<()>
+        where T: OclPrm, En: ClNullEventPtr, Ewl: ClWaitListPtr
+{
+    verify_device_version(device_version, [2, 0], command_queue,
+        ApiFunction::EnqueueSvmUnmap)?;
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr)
+        = resolve_event_ptrs(wait_list, new_event);
+
+    let errcode = ffi::clEnqueueSVMUnmap(
+        command_queue.as_ptr(),
+        svm_ptr as *mut c_void,
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    );
+    eval_errcode(errcode, (), "clEnqueueSVMUnmap", None::<String>)
+}
+
 /// Enqueues a command to execute a kernel on a device.
 ///
 /// ## Safety
@@ -3461,19 +4591,80 @@ pub unsafe fn enqueue_task<En: ClNullEventPtr, Ewl: ClWaitListPtr>(
     eval_errcode(errcode, (), "clEnqueueTask", kernel_name)
 }
 
-/// [UNIMPLEMENTED: Please implement me]
-pub fn enqueue_native_kernel() -> OclCoreResult<()> {
-    // ffi::clEnqueueNativeKernel(command_queue: cl_command_queue,
-    //                          user_func: extern fn (*mut c_void),
-    //                          args: *mut c_void,
-    //                          cb_args: size_t,
-    //                          num_mem_objects: cl_uint,
-    //                          mem_list: *const cl_mem,
-    //                          args_mem_loc: *const *const c_void,
-    //                          num_events_in_wait_list: cl_uint,
-    //                          event_wait_list: *const cl_event,
-    //                          event: *mut cl_event) -> cl_int;
-    unimplemented!();
+/// Enqueues a command to execute a native (host) function not compiled
+/// using the OpenCL compiler (see the [SDK]).
+///
+/// `args` is copied by the OpenCL implementation and a pointer to the copy is
+/// passed to `user_func`. For each memory object in `mem_list`, the
+/// pointer-sized location at the corresponding byte offset in
+/// `args_mem_offsets` is replaced (within the copy) by a pointer to the
+/// memory object's storage before `user_func` is called. The locations may
+/// not be suitably aligned for a pointer.
+///
+/// The device associated with `command_queue` must support native kernels
+/// (see `DeviceInfo::ExecutionCapabilities` and
+/// `DeviceExecCapabilities::NATIVE_KERNEL`).
+///
+/// [SDK]: https://www.khronos.org/registry/OpenCL/sdk/1.2/docs/man/xhtml/clEnqueueNativeKernel.html
+///
+/// ## Safety
+///
+/// `user_func` will be called, possibly from a thread belonging to the
+/// OpenCL runtime, with a pointer to a copy of `args` and must interpret it
+/// correctly.
+pub unsafe fn enqueue_native_kernel<En: ClNullEventPtr, Ewl: ClWaitListPtr>(
+            command_queue: &CommandQueue,
+            user_func: NativeKernelFn,
+            args: &[u8],
+            mem_list: &[&Mem],
+            args_mem_offsets: &[usize],
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+        ) -> OclCoreResult<()>
+{
+    if mem_list.len() != args_mem_offsets.len() {
+        return Err(ApiWrapperError::EnqueueNativeKernelMemListLenMismatch.into());
+    }
+
+    // Store each memory object in its location within (our copy of) `args`:
+    let mut args = args.to_vec();
+    for (mem, &offset) in mem_list.iter().zip(args_mem_offsets) {
+        if offset.checked_add(mem::size_of::<cl_mem>()).map_or(true, |end| end > args.len()) {
+            return Err(ApiWrapperError::EnqueueNativeKernelMemOffsetOor {
+                offset, args_len: args.len() }.into());
+        }
+        ptr::write_unaligned(args.as_mut_ptr().add(offset) as *mut cl_mem, mem.as_ptr());
+    }
+
+    let mem_ptrs: Vec<cl_mem> = mem_list.iter().map(|mem| mem.as_ptr()).collect();
+    let mem_locs: Vec<*const c_void> = args_mem_offsets.iter()
+        .map(|&offset| args.as_ptr().add(offset) as *const c_void)
+        .collect();
+
+    let (args_ptr, mem_list_ptr, mem_locs_ptr) = if args.is_empty() {
+        (ptr::null_mut(), ptr::null(), ptr::null())
+    } else if mem_ptrs.is_empty() {
+        (args.as_mut_ptr() as *mut c_void, ptr::null(), ptr::null())
+    } else {
+        (args.as_mut_ptr() as *mut c_void, mem_ptrs.as_ptr(), mem_locs.as_ptr())
+    };
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr) =
+        resolve_event_ptrs(wait_list, new_event);
+
+    let errcode = ffi::clEnqueueNativeKernel(
+        command_queue.as_ptr(),
+        Some(user_func),
+        args_ptr,
+        args.len(),
+        mem_ptrs.len() as cl_uint,
+        mem_list_ptr,
+        mem_locs_ptr,
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    );
+    eval_errcode(errcode, (), "clEnqueueNativeKernel", None::<String>)
 }
 
 /// Enqueues a marker command which waits for either a list of events to
@@ -0,0 +1,364 @@
+//! ICD vendor discovery diagnostics.
+//!
+//! The OpenCL ICD loader finds vendor implementations by reading the `.icd`
+//! files within a vendors directory (`/etc/OpenCL/vendors` on Linux), each
+//! of which contains the name or path of a vendor library. When no
+//! platforms are found, [`diagnose_icd`] repeats that search and reports
+//! which vendor libraries could not be used and why: a missing file, a
+//! library which fails to load, a missing ICD entry point or a library which
+//! reports zero platforms.
+//!
+//! As with the Khronos and ocl-icd loaders, the `OCL_ICD_VENDORS`
+//! environment variable replaces the vendors directory (or names a single
+//! `.icd` file) and `OCL_ICD_FILENAMES` lists additional vendor libraries
+//! separated by `:` (`;` on Windows).
+//!
+//! ### Example
+//!
+//! ```rust,ignore
+//! if core::get_platform_ids()?.is_empty() {
+//!     println!("{}", core::diagnose_icd());
+//! }
+//! ```
+//!
+//! [`diagnose_icd`]: fn.diagnose_icd.html
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use libloading::Library;
+use num_traits::FromPrimitive;
+use crate::ffi::{self, c_char, c_void, cl_int, cl_uint};
+use crate::Status;
+
+
+/// The environment variable which, if set, specifies the directory (or
+/// single `.icd` file) to read vendor libraries from.
+pub const ICD_VENDORS_ENV_VAR: &str = "OCL_ICD_VENDORS";
+
+/// The environment variable which, if set, lists additional vendor
+/// libraries.
+pub const ICD_FILENAMES_ENV_VAR: &str = "OCL_ICD_FILENAMES";
+
+/// The directory searched for `.icd` files when `OCL_ICD_VENDORS` is not set.
+#[cfg(not(target_os = "windows"))]
+pub const DEFAULT_ICD_VENDORS_DIR: Option<&str> = Some("/etc/OpenCL/vendors");
+
+/// The directory searched for `.icd` files when `OCL_ICD_VENDORS` is not set.
+///
+/// Vendors are listed in the registry on Windows, which is not searched.
+#[cfg(target_os = "windows")]
+pub const DEFAULT_ICD_VENDORS_DIR: Option<&str> = None;
+
+#[cfg(not(target_os = "windows"))]
+const ICD_FILENAMES_SEPARATOR: char = ':';
+#[cfg(target_os = "windows")]
+const ICD_FILENAMES_SEPARATOR: char = ';';
+
+const ICD_ENTRY_POINT: &str = "clIcdGetPlatformIDsKHR";
+
+type GetExtensionFunctionAddressFn = unsafe extern "system" fn(*const c_char) -> *mut c_void;
+
+
+/// The result of loading a single ICD vendor library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IcdVendorStatus {
+    /// The library was loaded and reported one or more platforms.
+    Ok { platform_count: u32 },
+    /// The `.icd` file could not be read or does not name a library.
+    InvalidIcdFile(String),
+    /// The library file does not exist.
+    MissingFile,
+    /// The library could not be loaded. Contains the error reported by the
+    /// system loader (often naming a missing dependency).
+    LoadFailed(String),
+    /// The library does not export the named ICD entry point.
+    MissingSymbol(&'static str),
+    /// The ICD entry point returned an error code.
+    PlatformQueryFailed(cl_int),
+    /// The library reported zero platforms (the driver may not support any
+    /// installed hardware).
+    NoPlatforms,
+}
+
+impl IcdVendorStatus {
+    /// Returns true if the library reported one or more platforms.
+    pub fn is_ok(&self) -> bool {
+        match *self {
+            IcdVendorStatus::Ok { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for IcdVendorStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IcdVendorStatus::Ok { platform_count } => write!(f, "ok ({} platform{})",
+                platform_count, if platform_count == 1 { "" } else { "s" }),
+            IcdVendorStatus::InvalidIcdFile(ref reason) => {
+                write!(f, "invalid .icd file: {}", reason)
+            },
+            IcdVendorStatus::MissingFile => write!(f, "library file not found"),
+            IcdVendorStatus::LoadFailed(ref reason) => {
+                write!(f, "unable to load library: {}", reason)
+            },
+            IcdVendorStatus::MissingSymbol(symbol) => {
+                write!(f, "library does not export '{}' (not an ICD vendor library)", symbol)
+            },
+            IcdVendorStatus::PlatformQueryFailed(errcode) => match Status::from_i32(errcode) {
+                Some(status) => write!(f, "platform query failed: {:?}", status),
+                None => write!(f, "platform query failed: error code {}", errcode),
+            },
+            IcdVendorStatus::NoPlatforms => write!(f, "library reported zero platforms"),
+        }
+    }
+}
+
+
+/// An ICD vendor library and the result of loading it.
+#[derive(Debug, Clone)]
+pub struct IcdVendor {
+    icd_file: Option<PathBuf>,
+    library: String,
+    status: IcdVendorStatus,
+}
+
+impl IcdVendor {
+    /// Returns the `.icd` file which listed this library, if any (libraries
+    /// listed by `OCL_ICD_FILENAMES` have none).
+    pub fn icd_file(&self) -> Option<&Path> {
+        self.icd_file.as_ref().map(|p| p.as_path())
+    }
+
+    /// Returns the library name or path as listed.
+    pub fn library(&self) -> &str {
+        &self.library
+    }
+
+    /// Returns the result of loading the library.
+    pub fn status(&self) -> &IcdVendorStatus {
+        &self.status
+    }
+}
+
+impl fmt::Display for IcdVendor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref icd_file) = self.icd_file {
+            write!(f, "{}: ", icd_file.display())?;
+        }
+        if !self.library.is_empty() {
+            write!(f, "'{}': ", self.library)?;
+        }
+        write!(f, "{}", self.status)
+    }
+}
+
+
+/// The result of searching for and loading ICD vendor libraries.
+#[derive(Debug, Clone)]
+pub struct IcdDiagnostics {
+    vendors_path: Option<PathBuf>,
+    vendors_path_error: Option<String>,
+    vendors: Vec<IcdVendor>,
+}
+
+impl IcdDiagnostics {
+    /// Returns the directory (or `.icd` file) which was searched.
+    pub fn vendors_path(&self) -> Option<&Path> {
+        self.vendors_path.as_ref().map(|p| p.as_path())
+    }
+
+    /// Returns the reason the vendors path could not be read, if any.
+    pub fn vendors_path_error(&self) -> Option<&str> {
+        self.vendors_path_error.as_ref().map(|s| s.as_str())
+    }
+
+    /// Returns every vendor library found.
+    pub fn vendors(&self) -> &[IcdVendor] {
+        &self.vendors
+    }
+
+    /// Returns the vendor libraries which could not be used.
+    pub fn failed(&self) -> impl Iterator<Item = &IcdVendor> {
+        self.vendors.iter().filter(|v| !v.status.is_ok())
+    }
+
+    /// Returns the total number of platforms reported by all vendors.
+    pub fn platform_count(&self) -> u32 {
+        self.vendors.iter().map(|v| match v.status {
+            IcdVendorStatus::Ok { platform_count } => platform_count,
+            _ => 0,
+        }).sum()
+    }
+
+    /// Returns true if at least one vendor reported a platform.
+    pub fn is_ok(&self) -> bool {
+        self.vendors.iter().any(|v| v.status.is_ok())
+    }
+}
+
+impl fmt::Display for IcdDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.vendors_path {
+            Some(ref path) => writeln!(f, "OpenCL ICD vendors ({}):", path.display())?,
+            None => writeln!(f, "OpenCL ICD vendors:")?,
+        }
+        if let Some(ref err) = self.vendors_path_error {
+            writeln!(f, "    Unable to read vendors: {}", err)?;
+        }
+        if self.vendors.is_empty() {
+            writeln!(f, "    No vendor libraries found.")?;
+        }
+        for vendor in self.vendors.iter() {
+            writeln!(f, "    {}", vendor)?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Returns the `.icd` files within `path` (or `path` itself if it is a
+/// file), sorted by name.
+fn icd_files(path: &Path) -> Result<Vec<PathBuf>, String> {
+    if path.is_file() {
+        return Ok(vec![path.to_owned()]);
+    }
+
+    let mut files = fs::read_dir(path)
+        .map_err(|err| err.to_string())?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().map(|ext| ext == "icd").unwrap_or(false))
+        .collect::<Vec<_>>();
+
+    files.sort();
+    Ok(files)
+}
+
+/// Reads the library name from an `.icd` file.
+fn read_icd_file(icd_file: &Path) -> Result<String, String> {
+    let contents = fs::read_to_string(icd_file).map_err(|err| err.to_string())?;
+
+    contents.lines()
+        .map(|l| l.trim())
+        .find(|l| !l.is_empty())
+        .map(|l| l.to_owned())
+        .ok_or_else(|| "the file does not name a library".to_owned())
+}
+
+/// Loads `library` and queries the number of platforms it provides.
+fn probe_library(library: &str) -> IcdVendorStatus {
+    let path = Path::new(library);
+    if path.components().count() > 1 && !path.exists() {
+        return IcdVendorStatus::MissingFile;
+    }
+
+    let lib = match Library::new(library) {
+        Ok(lib) => lib,
+        Err(err) => return IcdVendorStatus::LoadFailed(err.to_string()),
+    };
+
+    let get_platform_ids = unsafe {
+        match lib.get::<ffi::clIcdGetPlatformIDsKHR_fn>(ICD_ENTRY_POINT.as_bytes()) {
+            Ok(sym) => Some(*sym),
+            Err(_) => {
+                // Older vendor libraries only expose the entry point through
+                // `clGetExtensionFunctionAddress`:
+                lib.get::<GetExtensionFunctionAddressFn>(b"clGetExtensionFunctionAddress\0")
+                    .ok()
+                    .map(|get_addr| get_addr(b"clIcdGetPlatformIDsKHR\0".as_ptr() as *const _))
+                    .filter(|addr| !addr.is_null())
+                    .map(|addr| std::mem::transmute::<_, ffi::clIcdGetPlatformIDsKHR_fn>(addr))
+            },
+        }
+    };
+
+    let get_platform_ids = match get_platform_ids {
+        Some(f) => f,
+        None => return IcdVendorStatus::MissingSymbol(ICD_ENTRY_POINT),
+    };
+
+    let mut platform_count: cl_uint = 0;
+    let errcode = unsafe { get_platform_ids(0, ptr::null_mut(), &mut platform_count) };
+
+    if errcode == Status::CL_PLATFORM_NOT_FOUND_KHR as cl_int
+            || (errcode == Status::CL_SUCCESS as cl_int && platform_count == 0) {
+        IcdVendorStatus::NoPlatforms
+    } else if errcode != Status::CL_SUCCESS as cl_int {
+        IcdVendorStatus::PlatformQueryFailed(errcode)
+    } else {
+        IcdVendorStatus::Ok { platform_count }
+    }
+}
+
+/// Loads the vendor library listed by each `.icd` file.
+fn diagnose_icd_files(path: &Path, diagnostics: &mut IcdDiagnostics) {
+    let files = match icd_files(path) {
+        Ok(files) => files,
+        Err(err) => {
+            diagnostics.vendors_path_error = Some(err);
+            return;
+        },
+    };
+
+    for icd_file in files {
+        let (library, status) = match read_icd_file(&icd_file) {
+            Ok(library) => {
+                let status = probe_library(&library);
+                (library, status)
+            },
+            Err(err) => (String::new(), IcdVendorStatus::InvalidIcdFile(err)),
+        };
+
+        diagnostics.vendors.push(IcdVendor { icd_file: Some(icd_file), library, status });
+    }
+}
+
+/// Searches for ICD vendor libraries in the same way as the ICD loader and
+/// reports whether each could be loaded.
+///
+/// Reads the `.icd` files within `OCL_ICD_VENDORS` (a directory or a single
+/// `.icd` file) if set, or within `/etc/OpenCL/vendors` otherwise, along
+/// with any libraries listed by `OCL_ICD_FILENAMES`. See the module
+/// documentation for more.
+///
+/// Each vendor library is loaded into the current process in order to
+/// query its platform count.
+pub fn diagnose_icd() -> IcdDiagnostics {
+    let vendors_path = match env::var_os(ICD_VENDORS_ENV_VAR) {
+        Some(ref path) if !path.is_empty() => Some(PathBuf::from(path)),
+        _ => DEFAULT_ICD_VENDORS_DIR.map(PathBuf::from),
+    };
+
+    let mut diagnostics = match vendors_path {
+        Some(path) => diagnose_icd_vendors(path),
+        None => IcdDiagnostics { vendors_path: None, vendors_path_error: None, vendors: Vec::new() },
+    };
+
+    if let Ok(filenames) = env::var(ICD_FILENAMES_ENV_VAR) {
+        for library in filenames.split(ICD_FILENAMES_SEPARATOR).filter(|l| !l.is_empty()) {
+            let status = probe_library(library);
+            diagnostics.vendors.push(IcdVendor { icd_file: None, library: library.to_owned(),
+                status });
+        }
+    }
+
+    diagnostics
+}
+
+/// Reports whether the vendor library listed by each `.icd` file within
+/// `path` (a directory or a single `.icd` file) can be loaded.
+///
+/// Environment variables are ignored.
+pub fn diagnose_icd_vendors<P: AsRef<Path>>(path: P) -> IcdDiagnostics {
+    let mut diagnostics = IcdDiagnostics {
+        vendors_path: Some(path.as_ref().to_owned()),
+        vendors_path_error: None,
+        vendors: Vec::new(),
+    };
+
+    diagnose_icd_files(path.as_ref(), &mut diagnostics);
+    diagnostics
+}
@@ -62,7 +62,7 @@
 //! device(s) at the start of your program and passing it each time you call
 //! a version controlled function is the fastest and safest method (see the
 //! `ocl` library for an example). The cost of this check is little more than
-//! a single `if` statement.
+//! a single `if` statement and a symbol lookup (see below).
 //!
 //! Passing `None` for `device_version` will cause an automated version check
 //! which has a small cost (calling info function, parsing the version number
@@ -73,6 +73,14 @@
 //! above. Only do this if you're absolutely sure you know what you're doing
 //! and are not concerned about segfaults and data integrity.
 //!
+//! Every function is available regardless of which `opencl_version_*`
+//! features are enabled (those features are deprecated and have no effect).
+//! Functions introduced after OpenCL 1.1 are resolved from the OpenCL library
+//! the first time they are called, allowing a single binary to run against
+//! older drivers. Version controlled functions also check that the installed
+//! library (ICD loader) exports the function and return an error
+//! (`ApiWrapperError::FunctionUnavailable`) if it does not.
+//!
 
 //!
 //!
@@ -112,15 +120,27 @@ extern crate num_complex;
 extern crate ocl_core_vector as vector;
 #[macro_use]
 extern crate failure;
+extern crate libloading;
 pub extern crate cl_sys as ffi;
 
 #[cfg(test)] mod tests;
 mod functions;
+mod extensions;
+mod icd;
+mod build_log;
 pub mod types;
 pub mod error;
 pub mod util;
 
 pub use self::error::{Error, Result, ErrorKind};
+pub use self::functions::ProgramBuildError;
+pub use self::build_log::{parse_build_log, Diagnostic, Severity, DeviceBuildLog, ProgramBuildLog};
+
+pub use self::extensions::{KhrTerminateContextFns, KhrIlProgramFns, KhrCreateCommandQueueFns,
+    KhrSubgroupsFns, IntelUsmFns};
+
+pub use self::icd::{diagnose_icd, diagnose_icd_vendors, IcdDiagnostics, IcdVendor,
+    IcdVendorStatus, ICD_VENDORS_ENV_VAR, ICD_FILENAMES_ENV_VAR, DEFAULT_ICD_VENDORS_DIR};
 
 pub use self::types::abs::{ClWaitListPtr, ClNullEventPtr, ClEventPtrRef, ClPlatformIdPtr,
     ClDeviceIdPtr, ClContextPtr, EventRefWrapper, PlatformId, DeviceId, Context, CommandQueue, Mem,
@@ -128,23 +148,27 @@ pub use self::types::abs::{ClWaitListPtr, ClNullEventPtr, ClEventPtrRef, ClPlatf
 
 pub use self::types::structs::{self, OpenclVersion, ContextProperties, ImageFormatParseError,
     ImageFormatParseResult, ImageFormat, ImageDescriptor, BufferRegion, ContextPropertyValue,
-    ArgVal};
+    ArgVal, DevicePartition, QueueProperties, SamplerProperties, KernelExecInfo, NumericVersion,
+    NameVersion, MemProperties};
 
 pub use self::types::enums::{EmptyInfoResultError, PlatformInfoResult, DeviceInfoResult,
     ContextInfoResult, GlContextInfoResult, CommandQueueInfoResult, MemInfoResult, ImageInfoResult,
-    SamplerInfoResult, ProgramInfoResult, ProgramBuildInfoResult, KernelInfoResult,
-    KernelArgInfoResult, KernelWorkGroupInfoResult, EventInfoResult, ProfilingInfoResult};
+    PipeInfoResult, SamplerInfoResult, ProgramInfoResult, ProgramBuildInfoResult, KernelInfoResult,
+    KernelArgInfoResult, KernelWorkGroupInfoResult, KernelSubGroupInfoResult, EventInfoResult,
+    ProfilingInfoResult};
 
 pub use self::functions::{get_platform_ids, get_platform_info, get_device_ids, get_device_info,
     create_sub_devices, retain_device, release_device, create_context, create_context_from_type,
     retain_context, release_context, get_context_info,
-    create_command_queue, retain_command_queue, release_command_queue, get_command_queue_info,
+    create_command_queue, create_command_queue_with_properties, retain_command_queue,
+    release_command_queue, get_command_queue_info,
     create_buffer, create_sub_buffer, create_image, retain_mem_object, release_mem_object,
     get_supported_image_formats, get_mem_object_info, get_image_info,
-    set_mem_object_destructor_callback, create_sampler, retain_sampler, release_sampler,
-    get_sampler_info, create_program_with_source, create_program_with_binary,
+    set_mem_object_destructor_callback, create_sampler, create_sampler_with_properties,
+    retain_sampler, release_sampler, get_sampler_info, create_program_with_source, create_program_with_binary,
     create_program_with_built_in_kernels, retain_program, release_program, build_program,
-    compile_program, link_program, create_build_program, get_program_info, get_program_build_info,
+    build_program_async, compile_program, link_program, unload_platform_compiler,
+    create_build_program, get_program_info, get_program_build_info,
     create_kernel, create_kernels_in_program, retain_kernel, release_kernel, set_kernel_arg,
     get_kernel_info, get_kernel_arg_info, get_kernel_work_group_info, wait_for_events,
     get_event_info, create_user_event, retain_event, release_event, set_user_event_status,
@@ -156,9 +180,9 @@ pub use self::functions::{get_platform_ids, get_platform_info, get_device_ids, g
     enqueue_map_image, enqueue_unmap_mem_object, enqueue_migrate_mem_objects, enqueue_kernel,
     enqueue_task, enqueue_native_kernel, enqueue_marker_with_wait_list,
     enqueue_barrier_with_wait_list, get_extension_function_address_for_platform, wait_for_event,
-    event_status, default_platform_idx, program_build_err, verify_context, default_platform,
-    default_device_type, device_versions, event_is_complete, _dummy_event_callback,
-    _complete_user_event, get_context_platform, get_device_info_raw};
+    event_status, default_platform_idx, program_build_err, get_program_build_log, verify_context,
+    default_platform, default_device_type, device_versions, event_is_complete,
+    _dummy_event_callback, _complete_user_event, get_context_platform, get_device_info_raw};
 
 #[cfg(not(feature="opencl_vendor_mesa"))]
 pub use self::functions::{
@@ -187,9 +211,16 @@ pub use self::vector::{
 #[cfg(feature = "ocl-core-vector")]
 pub use crate::traits::OclVec;
 
-#[cfg(feature = "opencl_version_2_1")]
-pub use self::functions::{create_program_with_il};
+pub use self::functions::{create_pipe, get_pipe_info, svm_alloc, svm_free, set_kernel_arg_svm_pointer, set_kernel_exec_info,
+    enqueue_svm_memcpy, enqueue_svm_mem_fill, enqueue_svm_map, enqueue_svm_unmap};
+
+pub use self::functions::{create_program_with_il, get_device_and_host_timer, get_host_timer,
+    clone_kernel, get_kernel_sub_group_info};
 
+pub use self::functions::{set_program_specialization_constant, set_program_release_callback};
+
+pub use self::functions::{create_buffer_with_properties, create_image_with_properties,
+    set_context_destructor_callback};
 
 
 
@@ -207,6 +238,11 @@ pub type EventCallbackFn = extern "C" fn (ffi::cl_event, i32, *mut ffi::c_void);
 pub type CreateContextCallbackFn = extern "C" fn (*const ffi::c_char, *const ffi::c_void,
     ffi::size_t, *mut ffi::c_void);
 pub type BuildProgramCallbackFn = extern "C" fn (*mut ffi::c_void, *mut ffi::c_void);
+pub type MemDestructorCallbackFn = Box<dyn FnOnce(&Mem) + Send + 'static>;
+pub type BuildProgramCompleteFn = Box<dyn FnOnce(&Program) + Send + 'static>;
+pub type ProgramReleaseCallbackFn = Box<dyn FnOnce() + Send + 'static>;
+pub type ContextDestructorCallbackFn = Box<dyn FnOnce() + Send + 'static>;
+pub type NativeKernelFn = extern "C" fn (*mut ffi::c_void);
 pub type UserDataPtr = *mut ffi::c_void;
 
 //=============================================================================
@@ -415,6 +451,45 @@ pub const EXEC_KERNEL: DeviceExecCapabilities = DeviceExecCapabilities::KERNEL;
 pub const EXEC_NATIVE_KERNEL: DeviceExecCapabilities = DeviceExecCapabilities::NATIVE_KERNEL;
 
 
+bitflags! {
+    /// cl_device_svm_capabilities - bitfield
+    pub struct DeviceSvmCapabilities: u64 {
+        const COARSE_GRAIN_BUFFER = 1;
+        const FINE_GRAIN_BUFFER = 1 << 1;
+        const FINE_GRAIN_SYSTEM = 1 << 2;
+        const ATOMICS = 1 << 3;
+    }
+}
+
+pub const DEVICE_SVM_COARSE_GRAIN_BUFFER: DeviceSvmCapabilities = DeviceSvmCapabilities::COARSE_GRAIN_BUFFER;
+pub const DEVICE_SVM_FINE_GRAIN_BUFFER: DeviceSvmCapabilities = DeviceSvmCapabilities::FINE_GRAIN_BUFFER;
+pub const DEVICE_SVM_FINE_GRAIN_SYSTEM: DeviceSvmCapabilities = DeviceSvmCapabilities::FINE_GRAIN_SYSTEM;
+pub const DEVICE_SVM_ATOMICS: DeviceSvmCapabilities = DeviceSvmCapabilities::ATOMICS;
+
+
+bitflags! {
+    /// cl_device_atomic_capabilities - bitfield
+    pub struct DeviceAtomicCapabilities: u64 {
+        const ORDER_RELAXED = 1;
+        const ORDER_ACQ_REL = 1 << 1;
+        const ORDER_SEQ_CST = 1 << 2;
+        const SCOPE_WORK_ITEM = 1 << 3;
+        const SCOPE_WORK_GROUP = 1 << 4;
+        const SCOPE_DEVICE = 1 << 5;
+        const SCOPE_ALL_DEVICES = 1 << 6;
+    }
+}
+
+
+bitflags! {
+    /// cl_device_device_enqueue_capabilities - bitfield
+    pub struct DeviceEnqueueCapabilities: u64 {
+        const QUEUE_SUPPORTED = 1;
+        const QUEUE_REPLACEABLE_DEFAULT = 1 << 1;
+    }
+}
+
+
 bitflags! {
     /// cl_command_queue_properties - bitfield
     pub struct CommandQueueProperties: u64 {
@@ -431,6 +506,10 @@ impl CommandQueueProperties {
         CommandQueueProperties::OUT_OF_ORDER_EXEC_MODE_ENABLE }
     #[inline] pub fn profiling(self) -> CommandQueueProperties { self |
         CommandQueueProperties::PROFILING_ENABLE }
+    #[inline] pub fn on_device(self) -> CommandQueueProperties { self |
+        CommandQueueProperties::ON_DEVICE }
+    #[inline] pub fn on_device_default(self) -> CommandQueueProperties { self |
+        CommandQueueProperties::ON_DEVICE_DEFAULT }
 }
 
 impl Default for CommandQueueProperties {
@@ -511,6 +590,34 @@ pub const MEM_HOST_READ_ONLY: MemFlags = MemFlags::HOST_READ_ONLY;
 pub const MEM_HOST_NO_ACCESS: MemFlags = MemFlags::HOST_NO_ACCESS;
 
 
+bitflags! {
+    /// cl_svm_mem_flags - bitfield
+    pub struct SvmMemFlags: u64 {
+        const READ_WRITE = 1;
+        const WRITE_ONLY = 1 << 1;
+        const READ_ONLY = 1 << 2;
+        const FINE_GRAIN_BUFFER = 1 << 10;
+        const ATOMICS = 1 << 11;
+    }
+}
+
+impl SvmMemFlags {
+    #[inline] pub fn new() -> SvmMemFlags { SvmMemFlags::empty() }
+    #[inline] pub fn read_write(self) -> SvmMemFlags { self | SvmMemFlags::READ_WRITE }
+    #[inline] pub fn write_only(self) -> SvmMemFlags { self | SvmMemFlags::WRITE_ONLY }
+    #[inline] pub fn read_only(self) -> SvmMemFlags { self | SvmMemFlags::READ_ONLY }
+    #[inline] pub fn fine_grain_buffer(self) -> SvmMemFlags { self | SvmMemFlags::FINE_GRAIN_BUFFER }
+    #[inline] pub fn atomics(self) -> SvmMemFlags { self | SvmMemFlags::ATOMICS }
+}
+
+impl Default for SvmMemFlags {
+    #[inline] fn default() -> SvmMemFlags { SvmMemFlags::READ_WRITE }
+}
+
+pub const MEM_SVM_FINE_GRAIN_BUFFER: SvmMemFlags = SvmMemFlags::FINE_GRAIN_BUFFER;
+pub const MEM_SVM_ATOMICS: SvmMemFlags = SvmMemFlags::ATOMICS;
+
+
 bitflags! {
     /// cl_mem_migration_flags - bitfield
     pub struct MemMigrationFlags: u64 {
@@ -572,6 +679,7 @@ bitflags! {
         const CONST = 1;
         const RESTRICT = 1 << 1;
         const VOLATILE = 1 << 2;
+        const PIPE = 1 << 3;
     }
 }
 
@@ -579,6 +687,7 @@ pub const KERNEL_ARG_TYPE_NONE: KernelArgTypeQualifier = KernelArgTypeQualifier:
 pub const KERNEL_ARG_TYPE_CONST: KernelArgTypeQualifier = KernelArgTypeQualifier::CONST;
 pub const KERNEL_ARG_TYPE_RESTRICT: KernelArgTypeQualifier = KernelArgTypeQualifier::RESTRICT;
 pub const KERNEL_ARG_TYPE_VOLATILE: KernelArgTypeQualifier = KernelArgTypeQualifier::VOLATILE;
+pub const KERNEL_ARG_TYPE_PIPE: KernelArgTypeQualifier = KernelArgTypeQualifier::PIPE;
 
 //=============================================================================
 //=============================== ENUMERATORS =================================
@@ -653,6 +762,8 @@ enum_from_primitive! {
         CL_INVALID_DEVICE_PARTITION_COUNT               = -68,
         CL_INVALID_PIPE_SIZE                            = -69,
         CL_INVALID_DEVICE_QUEUE                         = -70,
+        CL_INVALID_SPEC_ID                              = -71,
+        CL_MAX_SIZE_RESTRICTION_EXCEEDED                = -72,
         CL_INVALID_GL_SHAREGROUP_REFERENCE_KHR          = -1000,
         CL_PLATFORM_NOT_FOUND_KHR                       = -1001,
         CL_NV_INVALID_MEM_ACCESS                        = -9999,
@@ -805,6 +916,8 @@ enum_from_primitive! {
         Name = ffi::CL_PLATFORM_NAME as isize,
         Vendor = ffi::CL_PLATFORM_VENDOR as isize,
         Extensions = ffi::CL_PLATFORM_EXTENSIONS as isize,
+        NumericVersion = ffi::CL_PLATFORM_NUMERIC_VERSION as isize,
+        ExtensionsWithVersion = ffi::CL_PLATFORM_EXTENSIONS_WITH_VERSION as isize,
     }
 }
 
@@ -890,6 +1003,25 @@ enum_from_primitive! {
         PrintfBufferSize = ffi::CL_DEVICE_PRINTF_BUFFER_SIZE as isize,
         ImagePitchAlignment = ffi::CL_DEVICE_IMAGE_PITCH_ALIGNMENT as isize,
         ImageBaseAddressAlignment = ffi::CL_DEVICE_IMAGE_BASE_ADDRESS_ALIGNMENT as isize,
+        SvmCapabilities = ffi::CL_DEVICE_SVM_CAPABILITIES as isize,
+        MaxPipeArgs = ffi::CL_DEVICE_MAX_PIPE_ARGS as isize,
+        PipeMaxActiveReservations = ffi::CL_DEVICE_PIPE_MAX_ACTIVE_RESERVATIONS as isize,
+        PipeMaxPacketSize = ffi::CL_DEVICE_PIPE_MAX_PACKET_SIZE as isize,
+        NumericVersion = ffi::CL_DEVICE_NUMERIC_VERSION as isize,
+        ExtensionsWithVersion = ffi::CL_DEVICE_EXTENSIONS_WITH_VERSION as isize,
+        IlsWithVersion = ffi::CL_DEVICE_ILS_WITH_VERSION as isize,
+        BuiltInKernelsWithVersion = ffi::CL_DEVICE_BUILT_IN_KERNELS_WITH_VERSION as isize,
+        AtomicMemoryCapabilities = ffi::CL_DEVICE_ATOMIC_MEMORY_CAPABILITIES as isize,
+        AtomicFenceCapabilities = ffi::CL_DEVICE_ATOMIC_FENCE_CAPABILITIES as isize,
+        NonUniformWorkGroupSupport = ffi::CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT as isize,
+        OpenclCAllVersions = ffi::CL_DEVICE_OPENCL_C_ALL_VERSIONS as isize,
+        PreferredWorkGroupSizeMultiple = ffi::CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_MULTIPLE as isize,
+        WorkGroupCollectiveFunctionsSupport = ffi::CL_DEVICE_WORK_GROUP_COLLECTIVE_FUNCTIONS_SUPPORT as isize,
+        GenericAddressSpaceSupport = ffi::CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT as isize,
+        OpenclCFeatures = ffi::CL_DEVICE_OPENCL_C_FEATURES as isize,
+        DeviceEnqueueCapabilities = ffi::CL_DEVICE_DEVICE_ENQUEUE_CAPABILITIES as isize,
+        PipeSupport = ffi::CL_DEVICE_PIPE_SUPPORT as isize,
+        LatestConformanceVersionPassed = ffi::CL_DEVICE_LATEST_CONFORMANCE_VERSION_PASSED as isize,
     }
 }
 
@@ -1009,6 +1141,31 @@ enum_from_primitive! {
         Device = ffi::CL_QUEUE_DEVICE as isize,
         ReferenceCount = ffi::CL_QUEUE_REFERENCE_COUNT as isize,
         Properties = ffi::CL_QUEUE_PROPERTIES as isize,
+        Size = ffi::CL_QUEUE_SIZE as isize,
+    }
+}
+
+
+enum_from_primitive! {
+    /// cl_queue_priority_khr
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum QueuePriority {
+        High = ffi::CL_QUEUE_PRIORITY_HIGH_KHR as isize,
+        Med = ffi::CL_QUEUE_PRIORITY_MED_KHR as isize,
+        Low = ffi::CL_QUEUE_PRIORITY_LOW_KHR as isize,
+    }
+}
+
+
+enum_from_primitive! {
+    /// cl_queue_throttle_khr
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum QueueThrottle {
+        High = ffi::CL_QUEUE_THROTTLE_HIGH_KHR as isize,
+        Med = ffi::CL_QUEUE_THROTTLE_MED_KHR as isize,
+        Low = ffi::CL_QUEUE_THROTTLE_LOW_KHR as isize,
     }
 }
 
@@ -1050,6 +1207,7 @@ enum_from_primitive! {
         Image1d = ffi::CL_MEM_OBJECT_IMAGE1D as isize,
         Image1dArray = ffi::CL_MEM_OBJECT_IMAGE1D_ARRAY as isize,
         Image1dBuffer = ffi::CL_MEM_OBJECT_IMAGE1D_BUFFER as isize,
+        Pipe = ffi::CL_MEM_OBJECT_PIPE as isize,
     }
 }
 
@@ -1092,6 +1250,17 @@ enum_from_primitive! {
 }
 
 
+enum_from_primitive! {
+    /// cl_pipe_info
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum PipeInfo {
+        PacketSize = ffi::CL_PIPE_PACKET_SIZE as isize,
+        MaxPackets = ffi::CL_PIPE_MAX_PACKETS as isize,
+    }
+}
+
+
 enum_from_primitive! {
     /// cl_addressing_mode
     #[repr(C)]
@@ -1127,6 +1296,9 @@ enum_from_primitive! {
         NormalizedCoords = ffi::CL_SAMPLER_NORMALIZED_COORDS as isize,
         AddressingMode = ffi::CL_SAMPLER_ADDRESSING_MODE as isize,
         FilterMode = ffi::CL_SAMPLER_FILTER_MODE as isize,
+        MipFilterMode = ffi::CL_SAMPLER_MIP_FILTER_MODE as isize,
+        LodMin = ffi::CL_SAMPLER_LOD_MIN as isize,
+        LodMax = ffi::CL_SAMPLER_LOD_MAX as isize,
     }
 }
 
@@ -1249,6 +1421,24 @@ enum_from_primitive! {
 }
 
 
+enum_from_primitive! {
+    /// cl_kernel_sub_group_info
+    ///
+    /// `MaxSubGroupSizeForNdrange` and `SubGroupCountForNdrange` require a
+    /// local work size as input and `LocalSizeForSubGroupCount` requires a
+    /// sub-group count.
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum KernelSubGroupInfo {
+        MaxSubGroupSizeForNdrange = ffi::CL_KERNEL_MAX_SUB_GROUP_SIZE_FOR_NDRANGE as isize,
+        SubGroupCountForNdrange = ffi::CL_KERNEL_SUB_GROUP_COUNT_FOR_NDRANGE as isize,
+        LocalSizeForSubGroupCount = ffi::CL_KERNEL_LOCAL_SIZE_FOR_SUB_GROUP_COUNT as isize,
+        MaxNumSubGroups = ffi::CL_KERNEL_MAX_NUM_SUB_GROUPS as isize,
+        CompileNumSubGroups = ffi::CL_KERNEL_COMPILE_NUM_SUB_GROUPS as isize,
+    }
+}
+
+
 enum_from_primitive! {
     /// cl_event_info
     #[repr(C)]
@@ -0,0 +1,72 @@
+use crate::{parse_build_log, Diagnostic, Severity};
+
+fn diagnostic(file: Option<&str>, line: Option<usize>, column: Option<usize>, severity: Severity,
+        message: &str, source: Option<&str>) -> Diagnostic {
+    Diagnostic {
+        file: file.map(str::to_owned),
+        line,
+        column,
+        severity,
+        message: message.to_owned(),
+        source: source.map(str::to_owned),
+    }
+}
+
+#[test]
+fn parse_clang_log() {
+    let log = "<source>:5:5: error: use of undeclared identifier 'oops'\n    \
+        oops;\n    ^\n<source>:2:10: warning: unused variable 'x' [-Wunused-variable]\n\
+        \tint x;\n\t    ^~\n/home/me/inc.cl:3: note: previous definition is here\n\
+        C:\\src\\k.cl:7:1: fatal error: 'missing.h' file not found\n\
+        error: front end compiler failed build.\n1 error generated.\n";
+
+    assert_eq!(parse_build_log(log), vec![
+        diagnostic(Some("<source>"), Some(5), Some(5), Severity::Error,
+            "use of undeclared identifier 'oops'", Some("    oops;")),
+        diagnostic(Some("<source>"), Some(2), Some(10), Severity::Warning,
+            "unused variable 'x' [-Wunused-variable]", Some("\tint x;")),
+        diagnostic(Some("/home/me/inc.cl"), Some(3), None, Severity::Note,
+            "previous definition is here", None),
+        diagnostic(Some("C:\\src\\k.cl"), Some(7), Some(1), Severity::Error,
+            "'missing.h' file not found", None),
+        diagnostic(None, None, None, Severity::Error, "front end compiler failed build.", None),
+    ]);
+
+    // Intel reports an empty file name:
+    assert_eq!(parse_build_log(":3:1: error: expected ';'"), vec![
+        diagnostic(None, Some(3), Some(1), Severity::Error, "expected ';'", None),
+    ]);
+}
+
+#[test]
+fn parse_edg_and_ptxas_logs() {
+    let log = "\"/tmp/OCL1234.cl\", line 12: error: identifier \"oops\" is undefined\n      \
+        oops;\n      ^\n\n\"/tmp/OCL1234.cl\", line 3: warning #177-D: variable \"x\" was \
+        declared but never referenced\n\n1 error detected in the compilation of \
+        \"/tmp/OCL1234.cl\".\nptxas application ptx input, line 40; error   : Call has wrong \
+        number of parameters\nptxas fatal   : Ptx assembly aborted due to errors\n";
+
+    assert_eq!(parse_build_log(log), vec![
+        diagnostic(Some("/tmp/OCL1234.cl"), Some(12), None, Severity::Error,
+            "identifier \"oops\" is undefined", Some("      oops;")),
+        diagnostic(Some("/tmp/OCL1234.cl"), Some(3), None, Severity::Warning,
+            "variable \"x\" was declared but never referenced", None),
+        diagnostic(None, Some(40), None, Severity::Error,
+            "Call has wrong number of parameters", None),
+        diagnostic(None, None, None, Severity::Error, "Ptx assembly aborted due to errors", None),
+    ]);
+
+    assert!(parse_build_log("Compilation started\nCompilation done\nerrors: none\n").is_empty());
+}
+
+#[test]
+fn render_diagnostic() {
+    let rendered = diagnostic(Some("k.cl"), Some(12), Some(5), Severity::Error,
+        "use of undeclared identifier 'oops'", Some("    oops;")).to_string();
+    assert_eq!(rendered, "error: use of undeclared identifier 'oops'\n  --> k.cl:12:5\n   |\n\
+        12 |     oops;\n   |     ^\n");
+
+    let rendered = diagnostic(Some("k.cl"), Some(3), None, Severity::Warning, "unused", None)
+        .to_string();
+    assert_eq!(rendered, "warning: unused\n --> k.cl:3\n");
+}
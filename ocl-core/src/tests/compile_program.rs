@@ -1,6 +1,7 @@
 use std::ffi::CString;
 
 #[test]
+#[ignore = "requires a real OpenCL platform (printf)"]
 fn compile_program() {
     let header = r#"
         static void world() {
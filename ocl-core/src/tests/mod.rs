@@ -19,6 +19,7 @@ pub mod buffer_copy;
 pub mod buffer_fill;
 pub mod vector_types;
 pub mod compile_program;
+pub mod build_log;
 use self::rand::Rng;
 use crate::error::{Result as OclCoreResult};
 use crate::{OclScl, PlatformId, DeviceId, Context};
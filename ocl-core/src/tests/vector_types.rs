@@ -305,6 +305,7 @@ fn add_char16(context: &Context, queue: &CommandQueue) {
 }
 
 #[test]
+#[ignore = "requires a real OpenCL platform (vector loads and stores)"]
 fn test_vector_types() {
     for (_, device, ref context) in get_available_contexts() {
         let queue = crate::create_command_queue(context, &device, None).unwrap();
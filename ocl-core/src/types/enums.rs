@@ -13,17 +13,18 @@ use std::fmt;
 use failure::Fail;
 use num_traits::FromPrimitive;
 use crate::util;
-use crate::ffi::{cl_image_format, cl_context_properties, c_void};
+use crate::ffi::{cl_image_format, cl_context_properties, cl_device_partition_property, c_void};
 
 use crate::{CommandQueueProperties, PlatformId, PlatformInfo, DeviceId, DeviceInfo, ContextInfo,
     GlContextInfo, Context, CommandQueue, CommandQueueInfo, CommandType, CommandExecutionStatus,
     Mem, MemInfo, MemObjectType, MemFlags, SamplerInfo, AddressingMode, FilterMode,
     ProgramInfo, ProgramBuildInfo, Program, ProgramBuildStatus, ProgramBinaryType, KernelInfo,
-    KernelArgInfo, KernelWorkGroupInfo, KernelArgAddressQualifier, KernelArgAccessQualifier,
-    KernelArgTypeQualifier, ImageInfo, ImageFormat, EventInfo, ProfilingInfo, DeviceType,
+    KernelArgInfo, KernelWorkGroupInfo, KernelSubGroupInfo, KernelArgAddressQualifier, KernelArgAccessQualifier,
+    KernelArgTypeQualifier, ImageInfo, PipeInfo, ImageFormat, EventInfo, ProfilingInfo, DeviceType,
     DeviceFpConfig, DeviceMemCacheType, DeviceLocalMemType, DeviceExecCapabilities,
-    DevicePartitionProperty, DeviceAffinityDomain, OpenclVersion, ContextProperties,
-    ImageFormatParseResult, Status};
+    DeviceSvmCapabilities, DevicePartitionProperty, DeviceAffinityDomain, OpenclVersion,
+    ContextProperties, ImageFormatParseResult, Status, DeviceAtomicCapabilities,
+    DeviceEnqueueCapabilities, NumericVersion, NameVersion};
 
 use crate::error::{Result as OclCoreResult, Error as OclCoreError};
 
@@ -44,6 +45,8 @@ pub enum EmptyInfoResultError {
     Mem,
     #[fail(display = "Image info unavailable")]
     Image,
+    #[fail(display = "Pipe info unavailable")]
+    Pipe,
     #[fail(display = "Sampler info unavailable")]
     Sampler,
     #[fail(display = "Program info unavailable")]
@@ -56,6 +59,8 @@ pub enum EmptyInfoResultError {
     KernelArg,
     #[fail(display = "Kernel work-group info unavailable")]
     KernelWorkGroup,
+    #[fail(display = "Kernel sub-group info unavailable")]
+    KernelSubGroup,
     #[fail(display = "Event info unavailable")]
     Event,
     #[fail(display = "Event profiling info unavailable")]
@@ -156,6 +161,16 @@ impl fmt::Debug for EmptyInfoResultError {
 // }
 
 
+/// Formats a list of names and versions as a comma-separated list.
+fn fmt_name_versions(f: &mut fmt::Formatter, list: &[NameVersion]) -> fmt::Result {
+    for (i, nv) in list.iter().enumerate() {
+        if i != 0 { write!(f, ", ")?; }
+        write!(f, "{}", nv)?;
+    }
+    Ok(())
+}
+
+
 /// Platform info result.
 ///
 // #[derive(Clone, Copy, Debug, PartialEq)]
@@ -165,15 +180,30 @@ pub enum PlatformInfoResult {
     Name(String),
     Vendor(String),
     Extensions(String),
+    NumericVersion(NumericVersion),
+    ExtensionsWithVersion(Vec<NameVersion>),
 }
 
 impl PlatformInfoResult {
     pub fn from_bytes(request: PlatformInfo, result: Vec<u8>)
             -> OclCoreResult<PlatformInfoResult> {
+        match request {
+            PlatformInfo::ExtensionsWithVersion => {
+                return Ok(PlatformInfoResult::ExtensionsWithVersion(
+                    NameVersion::list_from_bytes(&result)?));
+            },
+            _ => (),
+        }
+
         if result.is_empty() {
             return Err(OclCoreError::from(EmptyInfoResultError::Platform));
         }
 
+        if let PlatformInfo::NumericVersion = request {
+            let r = unsafe { util::bytes_into::<u32>(result)? };
+            return Ok(PlatformInfoResult::NumericVersion(NumericVersion::from_raw(r)));
+        }
+
         let string = util::bytes_into_string(result)?;
 
         Ok(match request {
@@ -182,6 +212,7 @@ impl PlatformInfoResult {
             PlatformInfo::Name => PlatformInfoResult::Name(string),
             PlatformInfo::Vendor => PlatformInfoResult::Vendor(string),
             PlatformInfo::Extensions => PlatformInfoResult::Extensions(string),
+            PlatformInfo::NumericVersion | PlatformInfo::ExtensionsWithVersion => unreachable!(),
         })
     }
 
@@ -211,6 +242,8 @@ impl fmt::Display for PlatformInfoResult {
             PlatformInfoResult::Name(ref s) => write!(f, "{}", s),
             PlatformInfoResult::Vendor(ref s) => write!(f, "{}", s),
             PlatformInfoResult::Extensions(ref s) => write!(f, "{}", s),
+            PlatformInfoResult::NumericVersion(ref s) => write!(f, "{}", s),
+            PlatformInfoResult::ExtensionsWithVersion(ref s) => fmt_name_versions(f, s),
         }
     }
 }
@@ -223,6 +256,8 @@ impl From<PlatformInfoResult> for String {
             | PlatformInfoResult::Name(string)
             | PlatformInfoResult::Vendor(string)
             | PlatformInfoResult::Extensions(string) => string,
+            ir @ PlatformInfoResult::NumericVersion(_)
+            | ir @ PlatformInfoResult::ExtensionsWithVersion(_) => ir.to_string(),
         }
     }
 }
@@ -308,6 +343,25 @@ pub enum DeviceInfoResult {
     PrintfBufferSize(usize),         // usize
     ImagePitchAlignment(u32),      // cl_uint
     ImageBaseAddressAlignment(u32),// cl_uint
+    SvmCapabilities(DeviceSvmCapabilities),  // cl_device_svm_capabilities    FLAGS u64
+    MaxPipeArgs(u32),              // cl_uint
+    PipeMaxActiveReservations(u32),// cl_uint
+    PipeMaxPacketSize(u32),        // cl_uint
+    NumericVersion(NumericVersion),                     // cl_version
+    ExtensionsWithVersion(Vec<NameVersion>),            // cl_name_version[]
+    IlsWithVersion(Vec<NameVersion>),                   // cl_name_version[]
+    BuiltInKernelsWithVersion(Vec<NameVersion>),        // cl_name_version[]
+    AtomicMemoryCapabilities(DeviceAtomicCapabilities), // cl_device_atomic_capabilities   FLAGS u64
+    AtomicFenceCapabilities(DeviceAtomicCapabilities),  // cl_device_atomic_capabilities   FLAGS u64
+    NonUniformWorkGroupSupport(bool),                   // cl_bool
+    OpenclCAllVersions(Vec<NameVersion>),               // cl_name_version[]
+    PreferredWorkGroupSizeMultiple(usize),              // usize
+    WorkGroupCollectiveFunctionsSupport(bool),          // cl_bool
+    GenericAddressSpaceSupport(bool),                   // cl_bool
+    OpenclCFeatures(Vec<NameVersion>),                  // cl_name_version[]
+    DeviceEnqueueCapabilities(DeviceEnqueueCapabilities), // cl_device_device_enqueue_capabilities   FLAGS u64
+    PipeSupport(bool),                                  // cl_bool
+    LatestConformanceVersionPassed(String),             // String
 }
 
 impl DeviceInfoResult {
@@ -350,7 +404,15 @@ impl DeviceInfoResult {
     /// Returns a new `DeviceInfoResult` for all variants except `MaxWorkItemSizes`.
     pub fn from_bytes(request: DeviceInfo, result: Vec<u8>)
             -> OclCoreResult<DeviceInfoResult> {
-        if result.is_empty() {
+        // Lists of `cl_name_version` are empty when there is nothing to list:
+        let is_name_version_list = match request {
+            DeviceInfo::ExtensionsWithVersion | DeviceInfo::IlsWithVersion |
+                DeviceInfo::BuiltInKernelsWithVersion | DeviceInfo::OpenclCAllVersions |
+                DeviceInfo::OpenclCFeatures => true,
+            _ => false,
+        };
+
+        if result.is_empty() && !is_name_version_list {
             return Err(OclCoreError::from(
                 EmptyInfoResultError::Device));
         }
@@ -657,16 +719,17 @@ impl DeviceInfoResult {
                 DeviceInfoResult::PartitionMaxSubDevices(r)
             },
             DeviceInfo::PartitionProperties => {
-                // [FIXME]: INCOMPLETE:
-                //
-                // let r = unsafe { util::bytes_into::<u32>(result)? };
-                // match DevicePartitionProperty::from_u32(r) {
-                //     Some(e) => DeviceInfoResult::PartitionProperties(e),
-                //     None => DeviceInfoResult::Error(Box::new(
-                //         OclCoreError::from(format!("Error converting '{:X}' to \
-                //             DevicePartitionProperty.", r)))),
-                // }
-                DeviceInfoResult::PartitionProperties(Vec::with_capacity(0))
+                // A single zero is returned by devices which can not be
+                // partitioned:
+                let props_raw = unsafe {
+                    util::bytes_into_vec::<cl_device_partition_property>(result)?
+                };
+                // Unrecognized (e.g. vendor specific) properties are skipped:
+                let props = props_raw.iter()
+                    .filter(|&&p| p != 0)
+                    .filter_map(|&p| DevicePartitionProperty::from_isize(p))
+                    .collect();
+                DeviceInfoResult::PartitionProperties(props)
             },
             DeviceInfo::PartitionAffinityDomain => {
                 let r = unsafe { util::bytes_into::<DeviceAffinityDomain>(result)? };
@@ -704,6 +767,76 @@ impl DeviceInfoResult {
                 let r = unsafe { util::bytes_into::<u32>(result)? };
                 DeviceInfoResult::ImageBaseAddressAlignment(r)
             },
+            DeviceInfo::SvmCapabilities => {
+                let r = unsafe { util::bytes_into::<DeviceSvmCapabilities>(result)? };
+                DeviceInfoResult::SvmCapabilities(r)
+            },
+            DeviceInfo::MaxPipeArgs => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                DeviceInfoResult::MaxPipeArgs(r)
+            },
+            DeviceInfo::PipeMaxActiveReservations => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                DeviceInfoResult::PipeMaxActiveReservations(r)
+            },
+            DeviceInfo::PipeMaxPacketSize => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                DeviceInfoResult::PipeMaxPacketSize(r)
+            },
+            DeviceInfo::NumericVersion => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                DeviceInfoResult::NumericVersion(NumericVersion::from_raw(r))
+            },
+            DeviceInfo::ExtensionsWithVersion => {
+                DeviceInfoResult::ExtensionsWithVersion(NameVersion::list_from_bytes(&result)?)
+            },
+            DeviceInfo::IlsWithVersion => {
+                DeviceInfoResult::IlsWithVersion(NameVersion::list_from_bytes(&result)?)
+            },
+            DeviceInfo::BuiltInKernelsWithVersion => {
+                DeviceInfoResult::BuiltInKernelsWithVersion(NameVersion::list_from_bytes(&result)?)
+            },
+            DeviceInfo::AtomicMemoryCapabilities => {
+                let r = unsafe { util::bytes_into::<DeviceAtomicCapabilities>(result)? };
+                DeviceInfoResult::AtomicMemoryCapabilities(r)
+            },
+            DeviceInfo::AtomicFenceCapabilities => {
+                let r = unsafe { util::bytes_into::<DeviceAtomicCapabilities>(result)? };
+                DeviceInfoResult::AtomicFenceCapabilities(r)
+            },
+            DeviceInfo::NonUniformWorkGroupSupport => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                DeviceInfoResult::NonUniformWorkGroupSupport(r != 0)
+            },
+            DeviceInfo::OpenclCAllVersions => {
+                DeviceInfoResult::OpenclCAllVersions(NameVersion::list_from_bytes(&result)?)
+            },
+            DeviceInfo::PreferredWorkGroupSizeMultiple => {
+                let r = unsafe { util::bytes_into::<usize>(result)? };
+                DeviceInfoResult::PreferredWorkGroupSizeMultiple(r)
+            },
+            DeviceInfo::WorkGroupCollectiveFunctionsSupport => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                DeviceInfoResult::WorkGroupCollectiveFunctionsSupport(r != 0)
+            },
+            DeviceInfo::GenericAddressSpaceSupport => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                DeviceInfoResult::GenericAddressSpaceSupport(r != 0)
+            },
+            DeviceInfo::OpenclCFeatures => {
+                DeviceInfoResult::OpenclCFeatures(NameVersion::list_from_bytes(&result)?)
+            },
+            DeviceInfo::DeviceEnqueueCapabilities => {
+                let r = unsafe { util::bytes_into::<DeviceEnqueueCapabilities>(result)? };
+                DeviceInfoResult::DeviceEnqueueCapabilities(r)
+            },
+            DeviceInfo::PipeSupport => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                DeviceInfoResult::PipeSupport(r != 0)
+            },
+            DeviceInfo::LatestConformanceVersionPassed => {
+                DeviceInfoResult::LatestConformanceVersionPassed(util::bytes_into_string(result)?)
+            },
             // _ => DeviceInfoResult::TemporaryPlaceholderVariant(result),
         };
 
@@ -807,6 +940,25 @@ impl fmt::Display for DeviceInfoResult {
             DeviceInfoResult::PrintfBufferSize(ref s) => write!(f, "{}", s),
             DeviceInfoResult::ImagePitchAlignment(ref s) => write!(f, "{}", s),
             DeviceInfoResult::ImageBaseAddressAlignment(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::SvmCapabilities(ref s) => write!(f, "{:?}", s),
+            DeviceInfoResult::MaxPipeArgs(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::PipeMaxActiveReservations(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::PipeMaxPacketSize(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::NumericVersion(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::ExtensionsWithVersion(ref s) => fmt_name_versions(f, s),
+            DeviceInfoResult::IlsWithVersion(ref s) => fmt_name_versions(f, s),
+            DeviceInfoResult::BuiltInKernelsWithVersion(ref s) => fmt_name_versions(f, s),
+            DeviceInfoResult::AtomicMemoryCapabilities(ref s) => write!(f, "{:?}", s),
+            DeviceInfoResult::AtomicFenceCapabilities(ref s) => write!(f, "{:?}", s),
+            DeviceInfoResult::NonUniformWorkGroupSupport(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::OpenclCAllVersions(ref s) => fmt_name_versions(f, s),
+            DeviceInfoResult::PreferredWorkGroupSizeMultiple(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::WorkGroupCollectiveFunctionsSupport(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::GenericAddressSpaceSupport(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::OpenclCFeatures(ref s) => fmt_name_versions(f, s),
+            DeviceInfoResult::DeviceEnqueueCapabilities(ref s) => write!(f, "{:?}", s),
+            DeviceInfoResult::PipeSupport(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::LatestConformanceVersionPassed(ref s) => write!(f, "{}", s),
         }
     }
 }
@@ -946,6 +1098,7 @@ pub enum CommandQueueInfoResult {
     Device(DeviceId),
     ReferenceCount(u32),
     Properties(CommandQueueProperties),
+    Size(u32),
 }
 
 impl CommandQueueInfoResult {
@@ -973,6 +1126,10 @@ impl CommandQueueInfoResult {
                 let r = unsafe { util::bytes_into::<CommandQueueProperties>(result)? };
                 CommandQueueInfoResult::Properties(r)
             }
+            CommandQueueInfo::Size => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                CommandQueueInfoResult::Size(r)
+            }
         };
         Ok(ir)
     }
@@ -991,6 +1148,7 @@ impl fmt::Display for CommandQueueInfoResult {
             CommandQueueInfoResult::Device(ref s) => write!(f, "{:?}", s),
             CommandQueueInfoResult::ReferenceCount(ref s) => write!(f, "{}", s),
             CommandQueueInfoResult::Properties(ref s) => write!(f, "{:?}", s),
+            CommandQueueInfoResult::Size(ref s) => write!(f, "{}", s),
             // _ => panic!("CommandQueueInfoResult: Converting this variant to string not yet implemented."),
         }
     }
@@ -1253,6 +1411,53 @@ impl From<ImageInfoResult> for String {
 }
 
 
+/// A pipe info result.
+pub enum PipeInfoResult {
+    PacketSize(u32),
+    MaxPackets(u32),
+}
+
+impl PipeInfoResult {
+    pub fn from_bytes(request: PipeInfo, result: Vec<u8>) -> OclCoreResult<PipeInfoResult> {
+        if result.is_empty() {
+            return Err(OclCoreError::from(
+                EmptyInfoResultError::Pipe));
+        }
+        let ir = match request {
+            PipeInfo::PacketSize => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                PipeInfoResult::PacketSize(r)
+            },
+            PipeInfo::MaxPackets => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                PipeInfoResult::MaxPackets(r)
+            },
+        };
+        Ok(ir)
+    }
+}
+
+impl fmt::Debug for PipeInfoResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &self.to_string())
+    }
+}
+
+impl fmt::Display for PipeInfoResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PipeInfoResult::PacketSize(s) => write!(f, "{}", s),
+            PipeInfoResult::MaxPackets(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<PipeInfoResult> for String {
+    fn from(ir: PipeInfoResult) -> String {
+        ir.to_string()
+    }
+}
+
 /// A sampler info result.
 pub enum SamplerInfoResult {
     ReferenceCount(u32),
@@ -1260,6 +1465,9 @@ pub enum SamplerInfoResult {
     NormalizedCoords(bool),
     AddressingMode(AddressingMode),
     FilterMode(FilterMode),
+    MipFilterMode(FilterMode),
+    LodMin(f32),
+    LodMax(f32),
 }
 
 impl SamplerInfoResult {
@@ -1297,6 +1505,22 @@ impl SamplerInfoResult {
                         FilterMode.", r))),
                 }
             },
+            SamplerInfo::MipFilterMode => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                match FilterMode::from_u32(r) {
+                    Some(fm) => SamplerInfoResult::MipFilterMode(fm),
+                    None => return Err(OclCoreError::from(format!("Error converting '{}' to \
+                        FilterMode.", r))),
+                }
+            },
+            SamplerInfo::LodMin => {
+                let r = unsafe { util::bytes_into::<f32>(result)? };
+                SamplerInfoResult::LodMin(r)
+            },
+            SamplerInfo::LodMax => {
+                let r = unsafe { util::bytes_into::<f32>(result)? };
+                SamplerInfoResult::LodMax(r)
+            },
         };
         Ok(ir)
     }
@@ -1316,6 +1540,9 @@ impl fmt::Display for SamplerInfoResult {
             SamplerInfoResult::NormalizedCoords(ref s) => write!(f, "{}", s),
             SamplerInfoResult::AddressingMode(ref s) => write!(f, "{:?}", s),
             SamplerInfoResult::FilterMode(ref s) => write!(f, "{:?}", s),
+            SamplerInfoResult::MipFilterMode(ref s) => write!(f, "{:?}", s),
+            SamplerInfoResult::LodMin(ref s) => write!(f, "{}", s),
+            SamplerInfoResult::LodMax(ref s) => write!(f, "{}", s),
         }
     }
 }
@@ -1342,6 +1569,8 @@ pub enum ProgramInfoResult {
     /// each device.
     Binaries(Vec<Vec<u8>>),
     NumKernels(usize),
+    /// Contains a semicolon-separated list of the names of every kernel
+    /// function in the program.
     KernelNames(String),
 }
 
@@ -1757,6 +1986,75 @@ impl From<KernelWorkGroupInfoResult> for String {
 }
 
 
+/// A kernel sub-group info result.
+pub enum KernelSubGroupInfoResult {
+    MaxSubGroupSizeForNdrange(usize),
+    SubGroupCountForNdrange(usize),
+    /// Contains the local work size (one element per dimension) which would
+    /// produce the requested number of sub-groups, or zeros if none would.
+    LocalSizeForSubGroupCount(Vec<usize>),
+    MaxNumSubGroups(usize),
+    CompileNumSubGroups(usize),
+}
+
+impl KernelSubGroupInfoResult {
+    pub fn from_bytes(request: KernelSubGroupInfo, result: Vec<u8>)
+            -> OclCoreResult<KernelSubGroupInfoResult> {
+        if result.is_empty() {
+            return Err(OclCoreError::from(
+                EmptyInfoResultError::KernelSubGroup));
+        }
+        let ir = match request {
+            KernelSubGroupInfo::MaxSubGroupSizeForNdrange => {
+                let r = unsafe { util::bytes_into::<usize>(result)? };
+                KernelSubGroupInfoResult::MaxSubGroupSizeForNdrange(r)
+            },
+            KernelSubGroupInfo::SubGroupCountForNdrange => {
+                let r = unsafe { util::bytes_into::<usize>(result)? };
+                KernelSubGroupInfoResult::SubGroupCountForNdrange(r)
+            },
+            KernelSubGroupInfo::LocalSizeForSubGroupCount => {
+                let r = unsafe { util::bytes_into_vec::<usize>(result)? };
+                KernelSubGroupInfoResult::LocalSizeForSubGroupCount(r)
+            },
+            KernelSubGroupInfo::MaxNumSubGroups => {
+                let r = unsafe { util::bytes_into::<usize>(result)? };
+                KernelSubGroupInfoResult::MaxNumSubGroups(r)
+            },
+            KernelSubGroupInfo::CompileNumSubGroups => {
+                let r = unsafe { util::bytes_into::<usize>(result)? };
+                KernelSubGroupInfoResult::CompileNumSubGroups(r)
+            },
+        };
+        Ok(ir)
+    }
+}
+
+impl fmt::Debug for KernelSubGroupInfoResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &self.to_string())
+    }
+}
+
+impl fmt::Display for KernelSubGroupInfoResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KernelSubGroupInfoResult::MaxSubGroupSizeForNdrange(s) => write!(f, "{}", s),
+            KernelSubGroupInfoResult::SubGroupCountForNdrange(s) => write!(f, "{}", s),
+            KernelSubGroupInfoResult::LocalSizeForSubGroupCount(ref s) => write!(f, "{:?}", s),
+            KernelSubGroupInfoResult::MaxNumSubGroups(s) => write!(f, "{}", s),
+            KernelSubGroupInfoResult::CompileNumSubGroups(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<KernelSubGroupInfoResult> for String {
+    fn from(ir: KernelSubGroupInfoResult) -> String {
+        ir.to_string()
+    }
+}
+
+
 /// An event info result.
 pub enum EventInfoResult {
     CommandQueue(CommandQueue),
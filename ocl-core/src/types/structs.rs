@@ -8,9 +8,11 @@ use std::collections::HashMap;
 use num_traits::FromPrimitive;
 use crate::error::{Error as OclCoreError, Result as OclCoreResult};
 use crate::ffi::{self,cl_mem, cl_sampler, cl_buffer_region, cl_context_properties, cl_platform_id,
-    c_void, size_t};
+    cl_device_partition_property, cl_queue_properties, cl_sampler_properties, cl_mem_properties,
+    cl_version, cl_name_version, c_void, size_t};
 use crate::{Mem, MemObjectType, ImageChannelOrder, ImageChannelDataType, ContextProperty,
-    PlatformId, OclPrm, Sampler};
+    PlatformId, OclPrm, Sampler, DeviceAffinityDomain, CommandQueueProperties, QueuePriority,
+    QueueThrottle, AddressingMode, FilterMode};
 
 
 // Until everything can be implemented:
@@ -33,6 +35,7 @@ pub struct ArgVal<'a> {
     size: size_t,
     value: *const c_void,
     is_mem: bool,
+    is_svm: bool,
     _p: PhantomData<&'a c_void>,
 }
 
@@ -43,6 +46,7 @@ impl<'a> ArgVal<'a> {
             size: mem::size_of::<cl_mem>() as size_t,
             value: mem as *const _ as *const c_void,
             is_mem: true,
+            is_svm: false,
             _p: PhantomData,
         }
     }
@@ -53,6 +57,7 @@ impl<'a> ArgVal<'a> {
             size: mem::size_of::<cl_mem>() as size_t,
             value: ptr::null(),
             is_mem: true,
+            is_svm: false,
             _p: PhantomData,
         }
     }
@@ -63,6 +68,7 @@ impl<'a> ArgVal<'a> {
             size: mem::size_of::<cl_sampler>() as size_t,
             value: sampler as *const _ as *const c_void,
             is_mem: false,
+            is_svm: false,
             _p: PhantomData,
         }
     }
@@ -73,6 +79,7 @@ impl<'a> ArgVal<'a> {
             size: mem::size_of::<cl_sampler>() as size_t,
             value: ptr::null(),
             is_mem: false,
+            is_svm: false,
             _p: PhantomData,
         }
     }
@@ -88,6 +95,7 @@ impl<'a> ArgVal<'a> {
             size: mem::size_of::<T>() as size_t,
             value: prm as *const T as *const c_void,
             is_mem: false,
+            is_svm: false,
             _p: PhantomData,
         }
     }
@@ -114,6 +122,23 @@ impl<'a> ArgVal<'a> {
             size: (mem::size_of::<T>() * length) as size_t,
             value: ptr::null(),
             is_mem: false,
+            is_svm: false,
+            _p: PhantomData,
+        }
+    }
+
+    /// Returns a new `ArgVal` referring to a shared virtual memory (SVM)
+    /// pointer, such as one returned by `::svm_alloc`.
+    ///
+    /// The pointer may refer to any location within an SVM allocation.
+    ///
+    /// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+    pub fn svm_pointer<T>(svm_ptr: *const T) -> ArgVal<'a> {
+        ArgVal {
+            size: mem::size_of::<*const T>() as size_t,
+            value: svm_ptr as *const c_void,
+            is_mem: false,
+            is_svm: true,
             _p: PhantomData,
         }
     }
@@ -131,6 +156,7 @@ impl<'a> ArgVal<'a> {
             size,
             value,
             is_mem,
+            is_svm: false,
             _p: PhantomData,
         }
     }
@@ -147,6 +173,12 @@ impl<'a> ArgVal<'a> {
     pub fn is_mem_null(&self) -> bool {
         self.is_mem && self.value.is_null()
     }
+
+    /// Returns `true` if this `ArgVal` represents a shared virtual memory
+    /// pointer (in which case the raw pointer is the argument value itself).
+    pub fn is_svm(&self) -> bool {
+        self.is_svm
+    }
 }
 
 
@@ -234,6 +266,117 @@ impl std::fmt::Display for OpenclVersion {
     }
 }
 
+impl From<NumericVersion> for OpenclVersion {
+    fn from(ver: NumericVersion) -> OpenclVersion {
+        OpenclVersion::new(ver.major(), ver.minor())
+    }
+}
+
+
+/// A version number in the packed `cl_version` format introduced in OpenCL
+/// 3.0 (10 bits major, 10 bits minor and 12 bits patch).
+///
+/// ex.: `CL_DEVICE_NUMERIC_VERSION` of an OpenCL 3.0 device ->
+/// `NumericVersion { major: 3, minor: 0, patch: 0 }`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NumericVersion {
+    major: u16,
+    minor: u16,
+    patch: u16,
+}
+
+impl NumericVersion {
+    pub fn new(major: u16, minor: u16, patch: u16) -> NumericVersion {
+        NumericVersion { major, minor, patch }
+    }
+
+    /// Unpacks a `cl_version`.
+    pub fn from_raw(raw: cl_version) -> NumericVersion {
+        NumericVersion {
+            major: ((raw >> (ffi::CL_VERSION_MINOR_BITS + ffi::CL_VERSION_PATCH_BITS))
+                & ffi::CL_VERSION_MAJOR_MASK) as u16,
+            minor: ((raw >> ffi::CL_VERSION_PATCH_BITS) & ffi::CL_VERSION_MINOR_MASK) as u16,
+            patch: (raw & ffi::CL_VERSION_PATCH_MASK) as u16,
+        }
+    }
+
+    /// Returns this version packed into a `cl_version`.
+    pub fn to_raw(&self) -> cl_version {
+        ((self.major as cl_version & ffi::CL_VERSION_MAJOR_MASK)
+                << (ffi::CL_VERSION_MINOR_BITS + ffi::CL_VERSION_PATCH_BITS)) |
+            ((self.minor as cl_version & ffi::CL_VERSION_MINOR_MASK) << ffi::CL_VERSION_PATCH_BITS) |
+            (self.patch as cl_version & ffi::CL_VERSION_PATCH_MASK)
+    }
+
+    pub fn major(&self) -> u16 {
+        self.major
+    }
+
+    pub fn minor(&self) -> u16 {
+        self.minor
+    }
+
+    pub fn patch(&self) -> u16 {
+        self.patch
+    }
+}
+
+impl std::fmt::Display for NumericVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+
+/// A name (such as an extension, IL or OpenCL C feature) paired with a
+/// version, as returned by the OpenCL 3.0 `*_WITH_VERSION` queries.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NameVersion {
+    name: String,
+    version: NumericVersion,
+}
+
+impl NameVersion {
+    pub fn new<S: Into<String>>(name: S, version: NumericVersion) -> NameVersion {
+        NameVersion { name: name.into(), version }
+    }
+
+    /// Converts a `cl_name_version`, reading its name up to the first nul.
+    pub fn from_raw(raw: &cl_name_version) -> NameVersion {
+        let name: Vec<u8> = raw.name.iter()
+            .take_while(|&&c| c != 0)
+            .map(|&c| c as u8)
+            .collect();
+
+        NameVersion {
+            name: String::from_utf8_lossy(&name).into_owned(),
+            version: NumericVersion::from_raw(raw.version),
+        }
+    }
+
+    /// Parses the result of a `*_WITH_VERSION` info query, an array of
+    /// `cl_name_version`.
+    pub fn list_from_bytes(bytes: &[u8]) -> OclCoreResult<Vec<NameVersion>> {
+        let raw = unsafe { crate::util::bytes_to_vec::<cl_name_version>(bytes)? };
+        Ok(raw.iter().map(NameVersion::from_raw).collect())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> NumericVersion {
+        self.version
+    }
+}
+
+impl std::fmt::Display for NameVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.name, self.version)
+    }
+}
+
 
 // cl_context_properties enum  Property value  Description
 //
@@ -732,6 +875,430 @@ impl<T: OclPrm> BufferRegion<T> {
 }
 
 
+/// Specifies how a device is to be partitioned into sub-devices.
+///
+/// ### Info (from [SDK](https://www.khronos.org/registry/cl/sdk/1.2/docs/man/xhtml/clCreateSubDevices.html))
+///
+/// * `Equally(n)`: Split the aggregate device into as many smaller aggregate
+///   devices as can be created, each containing `n` compute units.
+/// * `ByCounts(counts)`: Create one sub-device for each entry in `counts`,
+///   each containing the specified number of compute units.
+/// * `ByAffinityDomain(domain)`: Split the device along the outermost cache
+///   line (or NUMA node) boundary of the specified affinity domain.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DevicePartition {
+    Equally(u32),
+    ByCounts(Vec<u32>),
+    ByAffinityDomain(DeviceAffinityDomain),
+}
+
+impl DevicePartition {
+    /// Returns a null-terminated property list suitable for passing to
+    /// `clCreateSubDevices`.
+    pub fn to_raw(&self) -> Vec<cl_device_partition_property> {
+        let mut props = Vec::with_capacity(4);
+
+        match *self {
+            DevicePartition::Equally(n) => {
+                props.push(ffi::CL_DEVICE_PARTITION_EQUALLY as cl_device_partition_property);
+                props.push(n as cl_device_partition_property);
+            },
+            DevicePartition::ByCounts(ref counts) => {
+                props.push(ffi::CL_DEVICE_PARTITION_BY_COUNTS as cl_device_partition_property);
+                props.extend(counts.iter().map(|&c| c as cl_device_partition_property));
+                props.push(ffi::CL_DEVICE_PARTITION_BY_COUNTS_LIST_END as
+                    cl_device_partition_property);
+            },
+            DevicePartition::ByAffinityDomain(domain) => {
+                props.push(ffi::CL_DEVICE_PARTITION_BY_AFFINITY_DOMAIN as
+                    cl_device_partition_property);
+                props.push(domain.bits() as cl_device_partition_property);
+            },
+        }
+
+        props.push(0);
+        props
+    }
+}
+
+
+/// Command queue properties list.
+///
+/// Used with `create_command_queue_with_properties`. Properties other than
+/// the `CommandQueueProperties` flags (and the on-device flags in
+/// particular) require a device supporting OpenCL 2.0+.
+///
+/// ### Example
+///
+/// ```rust, ignore
+/// let props = QueueProperties::new().profiling().priority(QueuePriority::High);
+/// let queue = core::create_command_queue_with_properties(&context, &device, &props, None)?;
+/// ```
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct QueueProperties {
+    flags: CommandQueueProperties,
+    size: Option<u32>,
+    priority: Option<QueuePriority>,
+    throttle: Option<QueueThrottle>,
+}
+
+impl QueueProperties {
+    /// Returns an empty new list of queue properties.
+    pub fn new() -> QueueProperties {
+        QueueProperties::default()
+    }
+
+    /// Specifies the command queue properties bitfield (builder-style).
+    ///
+    /// Replaces any flags previously set.
+    pub fn flags(mut self, flags: CommandQueueProperties) -> QueueProperties {
+        self.flags = flags;
+        self
+    }
+
+    /// Enables out of order execution (builder-style).
+    pub fn out_of_order(mut self) -> QueueProperties {
+        self.flags = self.flags.out_of_order();
+        self
+    }
+
+    /// Enables profiling (builder-style).
+    pub fn profiling(mut self) -> QueueProperties {
+        self.flags = self.flags.profiling();
+        self
+    }
+
+    /// Specifies that the queue is an on-device queue, for use by kernels
+    /// enqueuing other kernels (builder-style).
+    ///
+    /// On-device queues must also be out of order. Set `default` to make
+    /// the queue the default on-device queue.
+    pub fn on_device(mut self, default: bool) -> QueueProperties {
+        self.flags = self.flags.out_of_order().on_device();
+        if default { self.flags = self.flags.on_device_default(); }
+        self
+    }
+
+    /// Specifies the size (in bytes) of an on-device queue (builder-style).
+    pub fn size(mut self, size: u32) -> QueueProperties {
+        self.size = Some(size);
+        self
+    }
+
+    /// Specifies the priority hint (builder-style).
+    ///
+    /// Requires the `cl_khr_priority_hints` extension.
+    pub fn priority(mut self, priority: QueuePriority) -> QueueProperties {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Specifies the throttle hint (builder-style).
+    ///
+    /// Requires the `cl_khr_throttle_hints` extension.
+    pub fn throttle(mut self, throttle: QueueThrottle) -> QueueProperties {
+        self.throttle = Some(throttle);
+        self
+    }
+
+    /// Returns the command queue properties bitfield.
+    pub fn get_flags(&self) -> CommandQueueProperties {
+        self.flags
+    }
+
+    /// Returns the on-device queue size, if specified.
+    pub fn get_size(&self) -> Option<u32> {
+        self.size
+    }
+
+    /// Returns the priority hint, if specified.
+    pub fn get_priority(&self) -> Option<QueuePriority> {
+        self.priority
+    }
+
+    /// Returns the throttle hint, if specified.
+    pub fn get_throttle(&self) -> Option<QueueThrottle> {
+        self.throttle
+    }
+
+    /// Returns true if these properties can be expressed using only a
+    /// `CommandQueueProperties` bitfield (as used by OpenCL 1.x).
+    pub fn is_bitfield_only(&self) -> bool {
+        !self.flags.intersects(CommandQueueProperties::ON_DEVICE |
+                CommandQueueProperties::ON_DEVICE_DEFAULT) &&
+            self.size.is_none() && self.priority.is_none() && self.throttle.is_none()
+    }
+
+    /// Returns a zero-terminated property list suitable for passing to
+    /// `clCreateCommandQueueWithProperties`.
+    pub fn to_raw(&self) -> Vec<cl_queue_properties> {
+        let mut props = Vec::with_capacity(9);
+
+        if !self.flags.is_empty() {
+            props.push(ffi::CL_QUEUE_PROPERTIES as cl_queue_properties);
+            props.push(self.flags.bits() as cl_queue_properties);
+        }
+        if let Some(size) = self.size {
+            props.push(ffi::CL_QUEUE_SIZE as cl_queue_properties);
+            props.push(size as cl_queue_properties);
+        }
+        if let Some(priority) = self.priority {
+            props.push(ffi::CL_QUEUE_PRIORITY_KHR);
+            props.push(priority as cl_queue_properties);
+        }
+        if let Some(throttle) = self.throttle {
+            props.push(ffi::CL_QUEUE_THROTTLE_KHR);
+            props.push(throttle as cl_queue_properties);
+        }
+
+        props.push(0);
+        props
+    }
+}
+
+impl From<CommandQueueProperties> for QueueProperties {
+    fn from(flags: CommandQueueProperties) -> QueueProperties {
+        QueueProperties::new().flags(flags)
+    }
+}
+
+
+/// A list of sampler properties.
+///
+/// Used with `create_sampler_with_properties`. Properties which are not
+/// specified take on their default values (normalized coordinates,
+/// `AddressingMode::Clamp` and `FilterMode::Nearest`).
+///
+/// The mip filter mode and level of detail range require a device
+/// supporting OpenCL 2.0+ and the `cl_khr_mipmap_image` extension.
+///
+/// ### Example
+///
+/// ```rust, ignore
+/// let props = SamplerProperties::new()
+///     .filter_mode(FilterMode::Linear)
+///     .mip_filter_mode(FilterMode::Linear)
+///     .lod(0.0, 4.0);
+/// let sampler = core::create_sampler_with_properties(&context, &props, None)?;
+/// ```
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SamplerProperties {
+    normalized_coords: Option<bool>,
+    addressing_mode: Option<AddressingMode>,
+    filter_mode: Option<FilterMode>,
+    mip_filter_mode: Option<FilterMode>,
+    lod_min: Option<f32>,
+    lod_max: Option<f32>,
+}
+
+impl SamplerProperties {
+    /// Returns an empty new list of sampler properties.
+    pub fn new() -> SamplerProperties {
+        SamplerProperties::default()
+    }
+
+    /// Specifies whether image coordinates are normalized (builder-style).
+    pub fn normalized_coords(mut self, normalized_coords: bool) -> SamplerProperties {
+        self.normalized_coords = Some(normalized_coords);
+        self
+    }
+
+    /// Specifies how out of range image coordinates are handled
+    /// (builder-style).
+    pub fn addressing_mode(mut self, addressing_mode: AddressingMode) -> SamplerProperties {
+        self.addressing_mode = Some(addressing_mode);
+        self
+    }
+
+    /// Specifies the filter used when reading an image (builder-style).
+    pub fn filter_mode(mut self, filter_mode: FilterMode) -> SamplerProperties {
+        self.filter_mode = Some(filter_mode);
+        self
+    }
+
+    /// Specifies the filter used between mip-levels when reading a
+    /// mipmapped image (builder-style).
+    pub fn mip_filter_mode(mut self, mip_filter_mode: FilterMode) -> SamplerProperties {
+        self.mip_filter_mode = Some(mip_filter_mode);
+        self
+    }
+
+    /// Specifies the minimum level of detail used when reading a mipmapped
+    /// image (builder-style).
+    pub fn lod_min(mut self, lod_min: f32) -> SamplerProperties {
+        self.lod_min = Some(lod_min);
+        self
+    }
+
+    /// Specifies the maximum level of detail used when reading a mipmapped
+    /// image (builder-style).
+    pub fn lod_max(mut self, lod_max: f32) -> SamplerProperties {
+        self.lod_max = Some(lod_max);
+        self
+    }
+
+    /// Specifies both the minimum and maximum level of detail (builder-style).
+    pub fn lod(self, lod_min: f32, lod_max: f32) -> SamplerProperties {
+        self.lod_min(lod_min).lod_max(lod_max)
+    }
+
+    /// Returns whether image coordinates are normalized.
+    pub fn get_normalized_coords(&self) -> bool {
+        self.normalized_coords.unwrap_or(true)
+    }
+
+    /// Returns the addressing mode.
+    pub fn get_addressing_mode(&self) -> AddressingMode {
+        self.addressing_mode.unwrap_or(AddressingMode::Clamp)
+    }
+
+    /// Returns the filter mode.
+    pub fn get_filter_mode(&self) -> FilterMode {
+        self.filter_mode.unwrap_or(FilterMode::Nearest)
+    }
+
+    /// Returns the mip filter mode, if specified.
+    pub fn get_mip_filter_mode(&self) -> Option<FilterMode> {
+        self.mip_filter_mode
+    }
+
+    /// Returns the minimum level of detail, if specified.
+    pub fn get_lod_min(&self) -> Option<f32> {
+        self.lod_min
+    }
+
+    /// Returns the maximum level of detail, if specified.
+    pub fn get_lod_max(&self) -> Option<f32> {
+        self.lod_max
+    }
+
+    /// Returns true if these properties can be passed to `clCreateSampler`
+    /// (as used by OpenCL 1.x).
+    pub fn is_basic(&self) -> bool {
+        self.mip_filter_mode.is_none() && self.lod_min.is_none() && self.lod_max.is_none()
+    }
+
+    /// Returns a zero-terminated property list suitable for passing to
+    /// `clCreateSamplerWithProperties`.
+    ///
+    /// Level of detail values are stored as the bits of a `cl_float` in the
+    /// low bytes of their property value.
+    pub fn to_raw(&self) -> Vec<cl_sampler_properties> {
+        let mut props = Vec::with_capacity(13);
+
+        if let Some(normalized_coords) = self.normalized_coords {
+            props.push(ffi::CL_SAMPLER_NORMALIZED_COORDS as cl_sampler_properties);
+            props.push(normalized_coords as cl_sampler_properties);
+        }
+        if let Some(addressing_mode) = self.addressing_mode {
+            props.push(ffi::CL_SAMPLER_ADDRESSING_MODE as cl_sampler_properties);
+            props.push(addressing_mode as cl_sampler_properties);
+        }
+        if let Some(filter_mode) = self.filter_mode {
+            props.push(ffi::CL_SAMPLER_FILTER_MODE as cl_sampler_properties);
+            props.push(filter_mode as cl_sampler_properties);
+        }
+        if let Some(mip_filter_mode) = self.mip_filter_mode {
+            props.push(ffi::CL_SAMPLER_MIP_FILTER_MODE as cl_sampler_properties);
+            props.push(mip_filter_mode as cl_sampler_properties);
+        }
+        if let Some(lod_min) = self.lod_min {
+            props.push(ffi::CL_SAMPLER_LOD_MIN as cl_sampler_properties);
+            props.push(lod_min.to_bits() as cl_sampler_properties);
+        }
+        if let Some(lod_max) = self.lod_max {
+            props.push(ffi::CL_SAMPLER_LOD_MAX as cl_sampler_properties);
+            props.push(lod_max.to_bits() as cl_sampler_properties);
+        }
+
+        props.push(0);
+        props
+    }
+}
+
+/// A list of memory object properties.
+///
+/// Used with `create_buffer_with_properties` and
+/// `create_image_with_properties`. OpenCL 3.0 does not define any properties
+/// of its own; extensions (such as `cl_khr_external_memory`) define the
+/// property names and values accepted by a platform.
+///
+/// ### Example
+///
+/// ```rust, ignore
+/// let props = MemProperties::new().property(CL_EXTERNAL_MEMORY_HANDLE_OPAQUE_FD_KHR, fd as u64);
+/// let buffer = core::create_buffer_with_properties::<_, f32>(&context, &props,
+///     MemFlags::new().read_write(), 1024, None, None)?;
+/// ```
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MemProperties {
+    props: Vec<(cl_mem_properties, cl_mem_properties)>,
+}
+
+impl MemProperties {
+    /// Returns an empty new list of memory object properties.
+    pub fn new() -> MemProperties {
+        MemProperties::default()
+    }
+
+    /// Adds a property (builder-style).
+    ///
+    /// Replaces the value of `name` if it has already been set.
+    pub fn property(mut self, name: cl_mem_properties, value: cl_mem_properties) -> MemProperties {
+        match self.props.iter_mut().find(|p| p.0 == name) {
+            Some(prop) => prop.1 = value,
+            None => self.props.push((name, value)),
+        }
+        self
+    }
+
+    /// Returns the value of the property `name`, if set.
+    pub fn get(&self, name: cl_mem_properties) -> Option<cl_mem_properties> {
+        self.props.iter().find(|p| p.0 == name).map(|p| p.1)
+    }
+
+    /// Returns true if no properties have been set.
+    pub fn is_empty(&self) -> bool {
+        self.props.is_empty()
+    }
+
+    /// Returns a zero-terminated property list suitable for passing to
+    /// `clCreateBufferWithProperties` or `clCreateImageWithProperties`.
+    pub fn to_raw(&self) -> Vec<cl_mem_properties> {
+        let mut props = Vec::with_capacity(self.props.len() * 2 + 1);
+
+        for &(name, value) in &self.props {
+            props.push(name);
+            props.push(value);
+        }
+
+        props.push(0);
+        props
+    }
+}
+
+/// Additional information passed to a kernel before it is enqueued.
+///
+/// * `SvmPtrs(ptrs)`: Shared virtual memory pointers (or pointers into SVM
+///   allocations) which will be accessed by the kernel but which are not
+///   passed to it directly as arguments (for example: pointers stored within
+///   other SVM allocations).
+/// * `SvmFineGrainSystem(enabled)`: Whether or not the kernel will access
+///   host memory allocated by the system (rather than by `svm_alloc`). Only
+///   meaningful on devices which support fine-grained system SVM.
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum KernelExecInfo<'a> {
+    SvmPtrs(&'a [*const c_void]),
+    SvmFineGrainSystem(bool),
+}
+
+
 pub enum ImageFormatParseError {
     UnknownImageChannelOrder(ffi::cl_channel_order),
     UnknownImageChannelDataType(ffi::cl_channel_type),
@@ -0,0 +1,692 @@
+//! A tree-walking interpreter for parsed kernels.
+//!
+//! Work-items are executed one after another, in order, on the calling
+//! thread. Barriers are therefore no-ops and kernels which depend on
+//! work-items within a group running concurrently will not behave correctly.
+
+use std::ptr;
+use super::parser::{Function, Ty, Elem, Scalar, Expr, Stmt, Lit, UnOp, BinOp, Builtin};
+use super::Module;
+
+
+/// An argument passed to a kernel.
+#[derive(Clone, Debug)]
+pub enum Arg {
+    /// A by-value argument.
+    Bytes(Vec<u8>),
+    /// A memory object (pointer and length in bytes).
+    Mem(*mut u8, usize),
+    /// A null memory object.
+    Null,
+    /// A local memory allocation of the given size in bytes.
+    Local(usize),
+}
+
+/// The global and local dimensions of an enqueued kernel.
+#[derive(Clone, Copy, Debug)]
+pub struct NdRange {
+    pub work_dim: u32,
+    pub offset: [usize; 3],
+    pub global: [usize; 3],
+    pub local: [usize; 3],
+}
+
+/// A pointer into a buffer.
+#[derive(Clone, Copy, Debug)]
+struct Ptr {
+    base: *mut u8,
+    len: usize,
+    /// The element offset from `base`.
+    offset: i64,
+    elem: Elem,
+}
+
+/// A runtime value.
+#[derive(Clone, Copy, Debug)]
+enum Value {
+    Int(i64),
+    Float(f64),
+    Ptr(Ptr),
+    /// A value of a type which is not supported in expressions (vectors,
+    /// images, samplers).
+    Opaque,
+}
+
+/// The local variables of an executing function.
+struct Frame<'f> {
+    vals: Vec<Value>,
+    tys: &'f [Ty],
+}
+
+impl<'f> Frame<'f> {
+    fn new(func: &'f Function) -> Frame<'f> {
+        Frame { vals: vec![Value::Int(0); func.slots.len()], tys: &func.slots }
+    }
+}
+
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return(Option<Value>),
+}
+
+enum Place {
+    Slot(usize),
+    Mem(Ptr),
+}
+
+struct WorkItem {
+    work_dim: u32,
+    global_id: [usize; 3],
+    global_size: [usize; 3],
+    local_id: [usize; 3],
+    local_size: [usize; 3],
+    group_id: [usize; 3],
+    num_groups: [usize; 3],
+    offset: [usize; 3],
+}
+
+type ExecResult<T> = Result<T, String>;
+
+
+/// Runs the kernel at `kernel_idx` within `module` over `range`.
+pub fn run_kernel(module: &Module, kernel_idx: usize, args: &[Arg], range: &NdRange)
+        -> ExecResult<()>
+{
+    let func = &module.functions[kernel_idx];
+    if args.len() != func.params.len() {
+        return Err(format!("kernel '{}' expects {} arguments", func.name, func.params.len()));
+    }
+
+    let num_groups: [usize; 3] =
+        ::std::array::from_fn(|d| range.global[d] / range.local[d].max(1));
+
+    let machine = Machine { module };
+
+    for gz in 0..num_groups[2] {
+        for gy in 0..num_groups[1] {
+            for gx in 0..num_groups[0] {
+                // Local memory is allocated once per work-group:
+                let mut locals: Vec<Vec<u64>> = Vec::new();
+                let mut params = Vec::with_capacity(args.len());
+
+                for (param, arg) in func.params.iter().zip(args.iter()) {
+                    params.push(arg_value(&param.ty, arg, &mut locals)?);
+                }
+
+                for lz in 0..range.local[2].max(1) {
+                    for ly in 0..range.local[1].max(1) {
+                        for lx in 0..range.local[0].max(1) {
+                            let group_id = [gx, gy, gz];
+                            let local_id = [lx, ly, lz];
+                            let mut global_id = [0; 3];
+                            for d in 0..3 {
+                                global_id[d] = range.offset[d] + group_id[d] * range.local[d]
+                                    + local_id[d];
+                            }
+
+                            let wi = WorkItem {
+                                work_dim: range.work_dim,
+                                global_id,
+                                global_size: range.global,
+                                local_id,
+                                local_size: range.local,
+                                group_id,
+                                num_groups,
+                                offset: range.offset,
+                            };
+
+                            let mut frame = Frame::new(func);
+                            frame.vals[..params.len()].copy_from_slice(&params);
+                            machine.exec_fn(func, &mut frame, &wi)
+                                .map_err(|e| format!("kernel '{}': {}", func.name, e))?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn arg_value(ty: &Ty, arg: &Arg, locals: &mut Vec<Vec<u64>>) -> ExecResult<Value> {
+    Ok(match (ty, arg) {
+        (&Ty::Val(elem), Arg::Bytes(bytes)) if elem.lanes == 1 => {
+            if bytes.len() < elem.size() {
+                return Err("argument too small".to_owned());
+            }
+            let p = Ptr { base: bytes.as_ptr() as *mut u8, len: bytes.len(), offset: 0, elem };
+            load(p)?
+        },
+        (&Ty::Ptr(elem, _), &Arg::Mem(base, len)) => Value::Ptr(Ptr { base, len, offset: 0, elem }),
+        (&Ty::Ptr(elem, _), &Arg::Null) => Value::Ptr(Ptr { base: ptr::null_mut(), len: 0,
+            offset: 0, elem }),
+        (&Ty::Ptr(elem, _), &Arg::Local(size)) => {
+            let mut storage = vec![0u64; size.div_ceil(8)];
+            let base = storage.as_mut_ptr() as *mut u8;
+            locals.push(storage);
+            Value::Ptr(Ptr { base, len: size, offset: 0, elem })
+        },
+        _ => Value::Opaque,
+    })
+}
+
+struct Machine<'m> {
+    module: &'m Module,
+}
+
+impl<'m> Machine<'m> {
+    fn exec_fn(&self, func: &Function, frame: &mut Frame, wi: &WorkItem)
+            -> ExecResult<Option<Value>>
+    {
+        for stmt in func.body.iter() {
+            match self.stmt(stmt, frame, wi)? {
+                Flow::Return(val) => return Ok(val),
+                Flow::Normal => (),
+                Flow::Break | Flow::Continue => {
+                    return Err("'break' or 'continue' outside of a loop".to_owned());
+                },
+            }
+        }
+        Ok(None)
+    }
+
+    fn block(&self, stmts: &[Stmt], frame: &mut Frame, wi: &WorkItem) -> ExecResult<Flow> {
+        for stmt in stmts {
+            match self.stmt(stmt, frame, wi)? {
+                Flow::Normal => (),
+                other => return Ok(other),
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn stmt(&self, stmt: &Stmt, frame: &mut Frame, wi: &WorkItem) -> ExecResult<Flow> {
+        match *stmt {
+            Stmt::Empty => (),
+            Stmt::Expr(ref e) => { self.expr(e, frame, wi)?; },
+            Stmt::Decl(ref decls) => {
+                for &(slot, ref init) in decls.iter() {
+                    if let Some(ref init) = *init {
+                        let val = self.expr(init, frame, wi)?;
+                        frame.vals[slot] = convert(val, &frame.tys[slot])?;
+                    } else {
+                        frame.vals[slot] = Value::Int(0);
+                    }
+                }
+            },
+            Stmt::Block(ref stmts) => return self.block(stmts, frame, wi),
+            Stmt::If(ref cond, ref then, ref els) => {
+                if truthy(self.expr(cond, frame, wi)?)? {
+                    return self.stmt(then, frame, wi);
+                } else if let Some(ref els) = *els {
+                    return self.stmt(els, frame, wi);
+                }
+            },
+            Stmt::For(ref init, ref cond, ref step, ref body) => {
+                if let Some(ref init) = *init {
+                    self.stmt(init, frame, wi)?;
+                }
+                loop {
+                    if let Some(ref cond) = *cond {
+                        if !truthy(self.expr(cond, frame, wi)?)? { break; }
+                    }
+                    match self.stmt(body, frame, wi)? {
+                        Flow::Break => break,
+                        Flow::Return(v) => return Ok(Flow::Return(v)),
+                        Flow::Normal | Flow::Continue => (),
+                    }
+                    if let Some(ref step) = *step {
+                        self.expr(step, frame, wi)?;
+                    }
+                }
+            },
+            Stmt::While(ref cond, ref body) => {
+                while truthy(self.expr(cond, frame, wi)?)? {
+                    match self.stmt(body, frame, wi)? {
+                        Flow::Break => break,
+                        Flow::Return(v) => return Ok(Flow::Return(v)),
+                        Flow::Normal | Flow::Continue => (),
+                    }
+                }
+            },
+            Stmt::DoWhile(ref body, ref cond) => {
+                loop {
+                    match self.stmt(body, frame, wi)? {
+                        Flow::Break => break,
+                        Flow::Return(v) => return Ok(Flow::Return(v)),
+                        Flow::Normal | Flow::Continue => (),
+                    }
+                    if !truthy(self.expr(cond, frame, wi)?)? { break; }
+                }
+            },
+            Stmt::Return(ref val) => {
+                let val = match *val {
+                    Some(ref e) => Some(self.expr(e, frame, wi)?),
+                    None => None,
+                };
+                return Ok(Flow::Return(val));
+            },
+            Stmt::Break => return Ok(Flow::Break),
+            Stmt::Continue => return Ok(Flow::Continue),
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn expr(&self, expr: &Expr, frame: &mut Frame, wi: &WorkItem) -> ExecResult<Value> {
+        Ok(match *expr {
+            Expr::Lit(Lit::Int(v)) => Value::Int(v),
+            Expr::Lit(Lit::Float(v)) => Value::Float(v),
+            Expr::Var(slot) => frame.vals[slot],
+            Expr::Index(..) | Expr::Deref(_) => {
+                match self.place(expr, frame, wi)? {
+                    Place::Mem(p) => load(p)?,
+                    Place::Slot(slot) => frame.vals[slot],
+                }
+            },
+            Expr::Unary(op, ref e) => {
+                let v = self.expr(e, frame, wi)?;
+                match (op, v) {
+                    (UnOp::Neg, Value::Int(i)) => Value::Int(i.wrapping_neg()),
+                    (UnOp::Neg, Value::Float(f)) => Value::Float(-f),
+                    (UnOp::Not, v) => Value::Int(!truthy(v)? as i64),
+                    (UnOp::BitNot, Value::Int(i)) => Value::Int(!i),
+                    _ => return Err(format!("invalid operand for unary operator {:?}", op)),
+                }
+            },
+            Expr::Binary(op, ref a, ref b) => {
+                let a = self.expr(a, frame, wi)?;
+                let b = self.expr(b, frame, wi)?;
+                binary(op, a, b)?
+            },
+            Expr::And(ref a, ref b) => {
+                let v = truthy(self.expr(a, frame, wi)?)? && truthy(self.expr(b, frame, wi)?)?;
+                Value::Int(v as i64)
+            },
+            Expr::Or(ref a, ref b) => {
+                let v = truthy(self.expr(a, frame, wi)?)? || truthy(self.expr(b, frame, wi)?)?;
+                Value::Int(v as i64)
+            },
+            Expr::Assign(op, ref target, ref val) => {
+                let place = self.place(target, frame, wi)?;
+                let mut v = self.expr(val, frame, wi)?;
+                if let Some(op) = op {
+                    let cur = self.read_place(&place, frame)?;
+                    v = binary(op, cur, v)?;
+                }
+                self.write_place(&place, v, frame)?
+            },
+            Expr::IncDec { ref target, inc, prefix } => {
+                let place = self.place(target, frame, wi)?;
+                let cur = self.read_place(&place, frame)?;
+                let new = binary(if inc { BinOp::Add } else { BinOp::Sub }, cur, Value::Int(1))?;
+                let new = self.write_place(&place, new, frame)?;
+                if prefix { new } else { cur }
+            },
+            Expr::Ternary(ref c, ref a, ref b) => {
+                if truthy(self.expr(c, frame, wi)?)? {
+                    self.expr(a, frame, wi)?
+                } else {
+                    self.expr(b, frame, wi)?
+                }
+            },
+            Expr::Cast(scalar, ref e) => {
+                let v = self.expr(e, frame, wi)?;
+                convert(v, &Ty::Val(Elem::scalar(scalar)))?
+            },
+            Expr::Comma(ref a, ref b) => {
+                self.expr(a, frame, wi)?;
+                self.expr(b, frame, wi)?
+            },
+            Expr::Builtin(builtin, ref args) => {
+                let mut vals = Vec::with_capacity(args.len());
+                for a in args.iter() {
+                    vals.push(self.expr(a, frame, wi)?);
+                }
+                call_builtin(builtin, &vals, wi)?
+            },
+            Expr::Call(fn_idx, ref args) => {
+                let callee = &self.module.functions[fn_idx];
+                let mut callee_frame = Frame::new(callee);
+                for (i, a) in args.iter().enumerate() {
+                    let v = self.expr(a, frame, wi)?;
+                    callee_frame.vals[i] = convert(v, &callee.slots[i])?;
+                }
+
+                match (self.exec_fn(callee, &mut callee_frame, wi)?, &callee.ret) {
+                    (_, &Ty::Void) => Value::Int(0),
+                    (Some(v), ty) => convert(v, ty)?,
+                    (None, _) => return Err(format!("'{}' did not return a value",
+                        callee.name)),
+                }
+            },
+        })
+    }
+
+    fn place(&self, expr: &Expr, frame: &mut Frame, wi: &WorkItem) -> ExecResult<Place> {
+        match *expr {
+            Expr::Var(slot) => Ok(Place::Slot(slot)),
+            Expr::Index(ref base, ref idx) => {
+                let base = self.expr(base, frame, wi)?;
+                let idx = self.expr(idx, frame, wi)?;
+                match (base, idx) {
+                    (Value::Ptr(p), Value::Int(i)) => Ok(Place::Mem(Ptr { offset: p.offset + i, ..p })),
+                    _ => Err("invalid subscript".to_owned()),
+                }
+            },
+            Expr::Deref(ref e) => {
+                match self.expr(e, frame, wi)? {
+                    Value::Ptr(p) => Ok(Place::Mem(p)),
+                    _ => Err("dereference of a non-pointer".to_owned()),
+                }
+            },
+            _ => Err("expression is not assignable".to_owned()),
+        }
+    }
+
+    fn read_place(&self, place: &Place, frame: &Frame) -> ExecResult<Value> {
+        match *place {
+            Place::Slot(slot) => Ok(frame.vals[slot]),
+            Place::Mem(p) => load(p),
+        }
+    }
+
+    fn write_place(&self, place: &Place, val: Value, frame: &mut Frame) -> ExecResult<Value> {
+        match *place {
+            Place::Slot(slot) => {
+                let val = convert(val, &frame.tys[slot])?;
+                frame.vals[slot] = val;
+                Ok(val)
+            },
+            Place::Mem(p) => store(p, val),
+        }
+    }
+}
+
+
+fn truthy(v: Value) -> ExecResult<bool> {
+    match v {
+        Value::Int(i) => Ok(i != 0),
+        Value::Float(f) => Ok(f != 0.0),
+        Value::Ptr(p) => Ok(!p.base.is_null()),
+        Value::Opaque => Err("unsupported value type (vectors, images and samplers can not \
+            be used in expressions)".to_owned()),
+    }
+}
+
+fn binary(op: BinOp, a: Value, b: Value) -> ExecResult<Value> {
+    use self::BinOp::*;
+
+    Ok(match (a, b) {
+        (Value::Int(a), Value::Int(b)) => Value::Int(match op {
+            Add => a.wrapping_add(b),
+            Sub => a.wrapping_sub(b),
+            Mul => a.wrapping_mul(b),
+            Div => {
+                if b == 0 { return Err("integer division by zero".to_owned()); }
+                a.wrapping_div(b)
+            },
+            Rem => {
+                if b == 0 { return Err("integer division by zero".to_owned()); }
+                a.wrapping_rem(b)
+            },
+            Shl => a.wrapping_shl((b & 63) as u32),
+            Shr => a.wrapping_shr((b & 63) as u32),
+            BitAnd => a & b,
+            BitOr => a | b,
+            BitXor => a ^ b,
+            Eq => (a == b) as i64,
+            Ne => (a != b) as i64,
+            Lt => (a < b) as i64,
+            Le => (a <= b) as i64,
+            Gt => (a > b) as i64,
+            Ge => (a >= b) as i64,
+        }),
+        (Value::Ptr(p), Value::Int(i)) if op == Add => Value::Ptr(Ptr { offset: p.offset + i, ..p }),
+        (Value::Int(i), Value::Ptr(p)) if op == Add => Value::Ptr(Ptr { offset: p.offset + i, ..p }),
+        (Value::Ptr(p), Value::Int(i)) if op == Sub => Value::Ptr(Ptr { offset: p.offset - i, ..p }),
+        (Value::Ptr(a), Value::Ptr(b)) => {
+            let (a, b) = ((a.base as i64) + a.offset * a.elem.size() as i64,
+                (b.base as i64) + b.offset * b.elem.size() as i64);
+            match op {
+                Sub => Value::Int(a - b),
+                Eq => Value::Int((a == b) as i64),
+                Ne => Value::Int((a != b) as i64),
+                Lt => Value::Int((a < b) as i64),
+                Le => Value::Int((a <= b) as i64),
+                Gt => Value::Int((a > b) as i64),
+                Ge => Value::Int((a >= b) as i64),
+                _ => return Err(format!("invalid pointer operation {:?}", op)),
+            }
+        },
+        (Value::Int(_), Value::Float(_)) | (Value::Float(_), Value::Int(_)) |
+        (Value::Float(_), Value::Float(_)) => {
+            let (a, b) = (as_f64(a)?, as_f64(b)?);
+            match op {
+                Add => Value::Float(a + b),
+                Sub => Value::Float(a - b),
+                Mul => Value::Float(a * b),
+                Div => Value::Float(a / b),
+                Rem => Value::Float(a % b),
+                Eq => Value::Int((a == b) as i64),
+                Ne => Value::Int((a != b) as i64),
+                Lt => Value::Int((a < b) as i64),
+                Le => Value::Int((a <= b) as i64),
+                Gt => Value::Int((a > b) as i64),
+                Ge => Value::Int((a >= b) as i64),
+                _ => return Err(format!("invalid floating point operation {:?}", op)),
+            }
+        },
+        _ => return Err(format!("invalid operands for {:?}", op)),
+    })
+}
+
+fn as_f64(v: Value) -> ExecResult<f64> {
+    match v {
+        Value::Int(i) => Ok(i as f64),
+        Value::Float(f) => Ok(f),
+        _ => Err("expected a numeric value".to_owned()),
+    }
+}
+
+fn as_i64(v: Value) -> ExecResult<i64> {
+    match v {
+        Value::Int(i) => Ok(i),
+        Value::Float(f) => Ok(f as i64),
+        _ => Err("expected a numeric value".to_owned()),
+    }
+}
+
+/// Converts `v` to type `ty`, truncating or rounding as a store would.
+fn convert(v: Value, ty: &Ty) -> ExecResult<Value> {
+    match *ty {
+        Ty::Val(elem) if elem.lanes == 1 => {
+            if let Value::Ptr(_) = v {
+                return Err("can not convert a pointer to a scalar".to_owned());
+            }
+            Ok(match elem.scalar {
+                Scalar::Float | Scalar::Half => Value::Float(as_f64(v)? as f32 as f64),
+                Scalar::Double => Value::Float(as_f64(v)?),
+                Scalar::Bool => Value::Int(truthy(v)? as i64),
+                Scalar::Char => Value::Int(as_i64(v)? as i8 as i64),
+                Scalar::UChar => Value::Int(as_i64(v)? as u8 as i64),
+                Scalar::Short => Value::Int(as_i64(v)? as i16 as i64),
+                Scalar::UShort => Value::Int(as_i64(v)? as u16 as i64),
+                Scalar::Int => Value::Int(as_i64(v)? as i32 as i64),
+                Scalar::UInt => Value::Int(as_i64(v)? as u32 as i64),
+                Scalar::Long | Scalar::ULong => match v {
+                    // Preserve the full range of unsigned values:
+                    Value::Float(f) if elem.scalar == Scalar::ULong && f >= 0.0 =>
+                        Value::Int(f as u64 as i64),
+                    _ => Value::Int(as_i64(v)?),
+                },
+            })
+        },
+        Ty::Ptr(elem, _) => match v {
+            Value::Ptr(p) => Ok(Value::Ptr(Ptr { elem, ..p })),
+            Value::Int(0) => Ok(Value::Ptr(Ptr { base: ptr::null_mut(), len: 0, offset: 0, elem })),
+            _ => Err("can not convert a value to a pointer".to_owned()),
+        },
+        _ => Ok(Value::Opaque),
+    }
+}
+
+fn check_bounds(p: &Ptr) -> ExecResult<usize> {
+    if p.elem.lanes != 1 {
+        return Err("vector loads and stores are not supported".to_owned());
+    }
+    if p.base.is_null() {
+        return Err("null pointer dereference".to_owned());
+    }
+    let size = p.elem.size() as i64;
+    let ofs = p.offset.checked_mul(size).ok_or_else(|| "pointer overflow".to_owned())?;
+    if ofs < 0 || ofs + size > p.len as i64 {
+        return Err(format!("out of bounds access (element {} of a {} byte buffer)",
+            p.offset, p.len));
+    }
+    Ok(ofs as usize)
+}
+
+fn load(p: Ptr) -> ExecResult<Value> {
+    let ofs = check_bounds(&p)?;
+
+    unsafe {
+        let src = p.base.add(ofs);
+        Ok(match p.elem.scalar {
+            Scalar::Bool => Value::Int((ptr::read_unaligned(src) != 0) as i64),
+            Scalar::Char => Value::Int(ptr::read_unaligned(src as *const i8) as i64),
+            Scalar::UChar => Value::Int(ptr::read_unaligned(src as *const u8) as i64),
+            Scalar::Short => Value::Int(ptr::read_unaligned(src as *const i16) as i64),
+            Scalar::UShort => Value::Int(ptr::read_unaligned(src as *const u16) as i64),
+            Scalar::Int => Value::Int(ptr::read_unaligned(src as *const i32) as i64),
+            Scalar::UInt => Value::Int(ptr::read_unaligned(src as *const u32) as i64),
+            Scalar::Long | Scalar::ULong => Value::Int(ptr::read_unaligned(src as *const i64)),
+            Scalar::Half => Value::Float(half_to_f32(ptr::read_unaligned(src as *const u16)) as f64),
+            Scalar::Float => Value::Float(ptr::read_unaligned(src as *const f32) as f64),
+            Scalar::Double => Value::Float(ptr::read_unaligned(src as *const f64)),
+        })
+    }
+}
+
+fn store(p: Ptr, v: Value) -> ExecResult<Value> {
+    let ofs = check_bounds(&p)?;
+    let v = convert(v, &Ty::Val(p.elem))?;
+
+    unsafe {
+        let dst = p.base.add(ofs);
+        match p.elem.scalar {
+            Scalar::Bool | Scalar::Char | Scalar::UChar => ptr::write_unaligned(dst, as_i64(v)? as u8),
+            Scalar::Short | Scalar::UShort => ptr::write_unaligned(dst as *mut u16, as_i64(v)? as u16),
+            Scalar::Int | Scalar::UInt => ptr::write_unaligned(dst as *mut u32, as_i64(v)? as u32),
+            Scalar::Long | Scalar::ULong => ptr::write_unaligned(dst as *mut i64, as_i64(v)?),
+            Scalar::Half => ptr::write_unaligned(dst as *mut u16, f32_to_half(as_f64(v)? as f32)),
+            Scalar::Float => ptr::write_unaligned(dst as *mut f32, as_f64(v)? as f32),
+            Scalar::Double => ptr::write_unaligned(dst as *mut f64, as_f64(v)?),
+        }
+    }
+    Ok(v)
+}
+
+fn half_to_f32(h: u16) -> f32 {
+    let sign = if h & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exp = ((h >> 10) & 0x1f) as i32;
+    let frac = (h & 0x3ff) as f32;
+
+    sign * match exp {
+        0 => frac * 2f32.powi(-24),
+        31 => if frac == 0.0 { f32::INFINITY } else { f32::NAN },
+        _ => (1.0 + frac / 1024.0) * 2f32.powi(exp - 15),
+    }
+}
+
+fn f32_to_half(f: f32) -> u16 {
+    let bits = f.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32 - 127 + 15;
+    let frac = bits & 0x7f_ffff;
+
+    if f.is_nan() {
+        sign | 0x7e00
+    } else if exp >= 31 {
+        sign | 0x7c00
+    } else if exp <= 0 {
+        // Subnormal (or zero):
+        if exp < -10 { return sign; }
+        let frac = (frac | 0x80_0000) >> (14 - exp);
+        sign | frac as u16
+    } else {
+        sign | ((exp as u16) << 10) | (frac >> 13) as u16
+    }
+}
+
+fn call_builtin(builtin: Builtin, args: &[Value], wi: &WorkItem) -> ExecResult<Value> {
+    use self::Builtin::*;
+
+    let dim = |default: usize, vals: &[usize; 3]| -> ExecResult<Value> {
+        let d = as_i64(args[0])?;
+        Ok(Value::Int(if d >= 0 && (d as u32) < wi.work_dim { vals[d as usize] } else { default }
+            as i64))
+    };
+
+    let f1 = |f: fn(f64) -> f64| -> ExecResult<Value> { Ok(Value::Float(f(as_f64(args[0])?))) };
+    let f2 = |f: fn(f64, f64) -> f64| -> ExecResult<Value> {
+        Ok(Value::Float(f(as_f64(args[0])?, as_f64(args[1])?)))
+    };
+    let all_int = args.iter().all(|a| matches!(*a, Value::Int(_)));
+
+    match builtin {
+        GlobalId => dim(0, &wi.global_id),
+        GlobalSize => dim(1, &wi.global_size),
+        LocalId => dim(0, &wi.local_id),
+        LocalSize => dim(1, &wi.local_size),
+        GroupId => dim(0, &wi.group_id),
+        NumGroups => dim(1, &wi.num_groups),
+        GlobalOffset => dim(0, &wi.offset),
+        WorkDim => Ok(Value::Int(wi.work_dim as i64)),
+        Barrier => Ok(Value::Int(0)),
+        Sqrt => f1(f64::sqrt),
+        Rsqrt => f1(|x| 1.0 / x.sqrt()),
+        Fabs => f1(f64::abs),
+        Exp => f1(f64::exp),
+        Exp2 => f1(f64::exp2),
+        Log => f1(f64::ln),
+        Log2 => f1(f64::log2),
+        Log10 => f1(f64::log10),
+        Sin => f1(f64::sin),
+        Cos => f1(f64::cos),
+        Tan => f1(f64::tan),
+        Asin => f1(f64::asin),
+        Acos => f1(f64::acos),
+        Atan => f1(f64::atan),
+        Sinh => f1(f64::sinh),
+        Cosh => f1(f64::cosh),
+        Tanh => f1(f64::tanh),
+        Floor => f1(f64::floor),
+        Ceil => f1(f64::ceil),
+        Round => f1(f64::round),
+        Trunc => f1(f64::trunc),
+        Pow => f2(f64::powf),
+        Atan2 => f2(f64::atan2),
+        Fmod => f2(|a, b| a % b),
+        Fmin => f2(f64::min),
+        Fmax => f2(f64::max),
+        Hypot => f2(f64::hypot),
+        Min if all_int => Ok(Value::Int(as_i64(args[0])?.min(as_i64(args[1])?))),
+        Min => f2(f64::min),
+        Max if all_int => Ok(Value::Int(as_i64(args[0])?.max(as_i64(args[1])?))),
+        Max => f2(f64::max),
+        Abs if all_int => Ok(Value::Int(as_i64(args[0])?.wrapping_abs())),
+        Abs => f1(f64::abs),
+        Clamp if all_int => {
+            let (x, lo, hi) = (as_i64(args[0])?, as_i64(args[1])?, as_i64(args[2])?);
+            Ok(Value::Int(x.max(lo).min(hi)))
+        },
+        Clamp => {
+            let (x, lo, hi) = (as_f64(args[0])?, as_f64(args[1])?, as_f64(args[2])?);
+            Ok(Value::Float(x.max(lo).min(hi)))
+        },
+        Mad => Ok(Value::Float(as_f64(args[0])? * as_f64(args[1])? + as_f64(args[2])?)),
+    }
+}
@@ -0,0 +1,319 @@
+//! Tokenizer and (very) minimal preprocessor.
+
+use std::collections::HashMap;
+
+
+/// A source token.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    Ident(String),
+    Int(i64),
+    Float(f64),
+    Punct(&'static str),
+}
+
+/// A token and the source line on which it begins.
+#[derive(Clone, Debug)]
+pub struct Spanned {
+    pub tok: Token,
+    pub line: usize,
+}
+
+// Longest first so that greedy matching works:
+static PUNCTS: &[&str] = &[
+    "<<=", ">>=", "...",
+    "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "++", "--", "&&", "||", "==", "!=",
+    "<=", ">=", "<<", ">>", "->",
+    "+", "-", "*", "/", "%", "&", "|", "^", "!", "~", "<", ">", "=", "?", ":", ";", ",",
+    ".", "(", ")", "[", "]", "{", "}",
+];
+
+
+/// Parses a list of compiler options, returning any macros defined with
+/// `-D`. All other options are ignored.
+pub fn option_defines(options: &str) -> Result<Vec<(String, String)>, String> {
+    let mut defines = Vec::new();
+    let mut words = options.split_whitespace();
+
+    while let Some(word) = words.next() {
+        let def = if word == "-D" {
+            match words.next() {
+                Some(d) => d,
+                None => return Err("missing macro name after '-D'".to_owned()),
+            }
+        } else if let Some(name) = word.strip_prefix("-D") {
+            name
+        } else {
+            continue;
+        };
+
+        let mut parts = def.splitn(2, '=');
+        let name = parts.next().unwrap_or("").to_owned();
+        let value = parts.next().unwrap_or("1").to_owned();
+        defines.push((name, value));
+    }
+
+    Ok(defines)
+}
+
+/// Tokenizes `src`, expanding object-like macros defined either within the
+/// source or by `defines`. The messages of `#warning` directives are added to
+/// `warnings`.
+pub fn tokenize(src: &str, defines: &[(String, String)], warnings: &mut Vec<String>)
+        -> Result<Vec<Spanned>, String>
+{
+    let mut macros: HashMap<String, Vec<Token>> = HashMap::new();
+
+    for (name, value) in defines {
+        let toks = lex(value, 0)?.into_iter().map(|s| s.tok).collect();
+        macros.insert(name.clone(), toks);
+    }
+
+    // Join continued lines:
+    let src = src.replace("\\\r\n", " ").replace("\\\n", " ");
+    let mut out = Vec::new();
+    let mut in_block_comment = false;
+    let mut conditions: Vec<Condition> = Vec::new();
+
+    for (line_idx, line) in src.lines().enumerate() {
+        let line_no = line_idx + 1;
+        let stripped = strip_comments(line, &mut in_block_comment);
+        let trimmed = stripped.trim_start();
+        let active = conditions.iter().all(|c| c.active);
+
+        if let Some(directive_line) = trimmed.strip_prefix('#') {
+            if !conditional(directive_line, line_no, &macros, &mut conditions)? && active {
+                directive(directive_line, line_no, &mut macros, warnings)?;
+            }
+        } else if active {
+            out.extend(expand(lex(&stripped, line_no)?, &macros)?);
+        }
+    }
+
+    if !conditions.is_empty() {
+        return Err("unterminated conditional directive".to_owned());
+    }
+
+    Ok(out)
+}
+
+/// A `#ifdef` or `#ifndef` block.
+struct Condition {
+    /// Whether lines within the current branch are used.
+    active: bool,
+    /// Whether any branch has been used (or the enclosing block is inactive).
+    taken: bool,
+}
+
+/// Handles `#ifdef`, `#ifndef`, `#else` and `#endif`, returning false for any
+/// other directive.
+fn conditional(text: &str, line: usize, macros: &HashMap<String, Vec<Token>>,
+        conditions: &mut Vec<Condition>) -> Result<bool, String>
+{
+    let text = text.trim();
+    let (name, rest) = match text.find(char::is_whitespace) {
+        Some(idx) => (&text[..idx], text[idx..].trim()),
+        None => (text, ""),
+    };
+    let enclosing_active = |conditions: &[Condition]| conditions.iter().all(|c| c.active);
+
+    match name {
+        "ifdef" | "ifndef" => {
+            let active = enclosing_active(conditions)
+                && macros.contains_key(rest) == (name == "ifdef");
+            let taken = active || !enclosing_active(conditions);
+            conditions.push(Condition { active, taken });
+        },
+        "else" => {
+            let condition = conditions.pop()
+                .ok_or_else(|| format!("line {}: '#else' without '#ifdef'", line))?;
+            let active = !condition.taken && enclosing_active(conditions);
+            conditions.push(Condition { active, taken: true });
+        },
+        "endif" => {
+            conditions.pop().ok_or_else(|| format!("line {}: '#endif' without '#ifdef'", line))?;
+        },
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn strip_comments(line: &str, in_block_comment: &mut bool) -> String {
+    let mut out = String::with_capacity(line.len());
+    let bytes = line.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if *in_block_comment {
+            if bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/') {
+                *in_block_comment = false;
+                out.push(' ');
+                i += 2;
+            } else {
+                i += 1;
+            }
+        } else if bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'/') {
+            break;
+        } else if bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'*') {
+            *in_block_comment = true;
+            i += 2;
+        } else {
+            let ch = line[i..].chars().next().unwrap();
+            out.push(ch);
+            i += ch.len_utf8();
+        }
+    }
+
+    out
+}
+
+fn directive(text: &str, line: usize, macros: &mut HashMap<String, Vec<Token>>,
+        warnings: &mut Vec<String>) -> Result<(), String>
+{
+    let text = text.trim();
+    let (name, rest) = match text.find(char::is_whitespace) {
+        Some(idx) => (&text[..idx], text[idx..].trim()),
+        None => (text, ""),
+    };
+
+    match name {
+        "define" => {
+            let name_end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let (macro_name, value) = rest.split_at(name_end);
+            if macro_name.is_empty() {
+                return Err(format!("line {}: invalid macro definition", line));
+            }
+            if value.starts_with('(') {
+                return Err(format!("line {}: function-like macros are not supported", line));
+            }
+            let toks = lex(value, line)?.into_iter().map(|s| s.tok).collect();
+            macros.insert(macro_name.to_owned(), toks);
+            Ok(())
+        },
+        "undef" => {
+            macros.remove(rest);
+            Ok(())
+        },
+        "warning" => {
+            warnings.push(format!("line {}: {}", line, rest));
+            Ok(())
+        },
+        "error" => Err(format!("line {}: {}", line, rest)),
+        // Line numbers are always reported within the combined source.
+        "pragma" | "line" | "" => Ok(()),
+        other => Err(format!("line {}: unsupported preprocessor directive '#{}'", line, other)),
+    }
+}
+
+fn expand(toks: Vec<Spanned>, macros: &HashMap<String, Vec<Token>>) -> Result<Vec<Spanned>, String> {
+    if macros.is_empty() { return Ok(toks); }
+
+    let mut out = Vec::with_capacity(toks.len());
+    for spanned in toks {
+        expand_into(spanned, macros, &mut out, 0)?;
+    }
+    Ok(out)
+}
+
+fn expand_into(spanned: Spanned, macros: &HashMap<String, Vec<Token>>, out: &mut Vec<Spanned>,
+        depth: usize) -> Result<(), String>
+{
+    if let Token::Ident(ref name) = spanned.tok {
+        if let Some(replacement) = macros.get(name) {
+            if depth > 16 {
+                return Err(format!("line {}: macro expansion too deep for '{}'",
+                    spanned.line, name));
+            }
+            for tok in replacement.iter() {
+                expand_into(Spanned { tok: tok.clone(), line: spanned.line }, macros, out,
+                    depth + 1)?;
+            }
+            return Ok(());
+        }
+    }
+    out.push(spanned);
+    Ok(())
+}
+
+fn lex(src: &str, first_line: usize) -> Result<Vec<Spanned>, String> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut toks = Vec::new();
+    let mut line = first_line;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') { i += 1; }
+            toks.push(Spanned { tok: Token::Ident(chars[start..i].iter().collect()), line });
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())) {
+            let (tok, len) = number(&chars[i..]).map_err(|e| format!("line {}: {}", line, e))?;
+            toks.push(Spanned { tok, line });
+            i += len;
+        } else if c == '"' || c == '\'' {
+            return Err(format!("line {}: string and character literals are not supported", line));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 3)].iter().collect();
+            match PUNCTS.iter().find(|p| rest.starts_with(*p)) {
+                Some(p) => {
+                    toks.push(Spanned { tok: Token::Punct(p), line });
+                    i += p.len();
+                },
+                None => return Err(format!("line {}: unexpected character '{}'", line, c)),
+            }
+        }
+    }
+
+    Ok(toks)
+}
+
+fn number(chars: &[char]) -> Result<(Token, usize), String> {
+    let mut i = 0;
+
+    // Hexadecimal:
+    if chars.len() > 2 && chars[0] == '0' && (chars[1] == 'x' || chars[1] == 'X') {
+        i = 2;
+        while i < chars.len() && chars[i].is_ascii_hexdigit() { i += 1; }
+        let digits: String = chars[2..i].iter().collect();
+        let val = u64::from_str_radix(&digits, 16).map_err(|e| e.to_string())?;
+        while i < chars.len() && "uUlL".contains(chars[i]) { i += 1; }
+        return Ok((Token::Int(val as i64), i));
+    }
+
+    let mut is_float = false;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii_digit() {
+            i += 1;
+        } else if c == '.' {
+            is_float = true;
+            i += 1;
+        } else if (c == 'e' || c == 'E') && i > 0 {
+            is_float = true;
+            i += 1;
+            if i < chars.len() && (chars[i] == '+' || chars[i] == '-') { i += 1; }
+        } else {
+            break;
+        }
+    }
+
+    let text: String = chars[..i].iter().collect();
+
+    if is_float || (i < chars.len() && (chars[i] == 'f' || chars[i] == 'F')) {
+        let val = text.parse::<f64>().map_err(|e| format!("invalid number '{}': {}", text, e))?;
+        if i < chars.len() && "fFhHlL".contains(chars[i]) { i += 1; }
+        Ok((Token::Float(val), i))
+    } else {
+        let val = text.parse::<u64>().map_err(|e| format!("invalid number '{}': {}", text, e))?;
+        while i < chars.len() && "uUlL".contains(chars[i]) { i += 1; }
+        Ok((Token::Int(val as i64), i))
+    }
+}
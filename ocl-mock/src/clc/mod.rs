@@ -0,0 +1,71 @@
+//! A small interpreter for a subset of OpenCL C.
+//!
+//! Supported: scalar types and pointers to scalars, helper functions,
+//! the usual statements and operators, object-like macros (including those
+//! passed with `-D`), `#ifdef`/`#ifndef` conditionals, `#warning` and
+//! `#error`, work-item functions and common math built-ins.
+//!
+//! Not supported: vectors (other than as kernel parameters, which can not
+//! be loaded from or stored to), string and character literals (and so
+//! `printf`), structs, arrays, images, atomics, `__local` variables,
+//! function-like macros, `#if` expressions and `#include`. Programs using
+//! them will fail to build with a descriptive build log.
+//!
+//! Errors found while running a kernel, such as out-of-bounds accesses,
+//! are returned from [`run_kernel`].
+
+mod lexer;
+mod parser;
+mod exec;
+
+pub use self::parser::{Function, Param, Ty, Elem, Scalar, AddrSpace, Access};
+pub use self::exec::{Arg, NdRange, run_kernel};
+
+
+/// A parsed program.
+#[derive(Clone, Debug)]
+pub struct Module {
+    pub functions: Vec<Function>,
+    /// Messages of `#warning` directives, formatted as `line N: message`.
+    pub warnings: Vec<String>,
+}
+
+impl Module {
+    /// Parses `src`, using any `-D` macro definitions within `options`.
+    pub fn compile(src: &str, options: &str) -> Result<Module, String> {
+        let defines = lexer::option_defines(options)?;
+        let mut warnings = Vec::new();
+        let toks = lexer::tokenize(src, &defines, &mut warnings)?;
+        let functions = parser::parse(toks)?;
+        Ok(Module { functions, warnings })
+    }
+
+    /// Preprocesses `src` as a separately compiled object, returning its
+    /// source, preceded by definitions of any `-D` macros within `options` so
+    /// that they still apply once linked, and the messages of `#warning`
+    /// directives.
+    ///
+    /// The source is only parsed when linked as functions defined by other
+    /// objects may not be known.
+    pub fn compile_object(src: &str, options: &str) -> Result<(String, Vec<String>), String> {
+        let defines = lexer::option_defines(options)?;
+        let mut warnings = Vec::new();
+        lexer::tokenize(src, &defines, &mut warnings)?;
+
+        let mut object: String = defines.iter()
+            .map(|(name, value)| format!("#define {} {}\n", name, value))
+            .collect();
+        object.push_str(src);
+        Ok((object, warnings))
+    }
+
+    /// Returns the index of the kernel named `name`.
+    pub fn kernel_idx(&self, name: &str) -> Option<usize> {
+        self.functions.iter().position(|f| f.is_kernel && f.name == name)
+    }
+
+    /// Returns the indexes of all kernels.
+    pub fn kernel_idxs(&self) -> Vec<usize> {
+        self.functions.iter().enumerate().filter(|&(_, f)| f.is_kernel).map(|(i, _)| i).collect()
+    }
+}
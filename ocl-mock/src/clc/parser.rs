@@ -0,0 +1,1166 @@
+//! Syntax tree and recursive descent parser for the supported subset of
+//! OpenCL C.
+
+use std::collections::HashMap;
+use super::lexer::{Token, Spanned};
+
+
+/// A scalar (or vector element) type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scalar {
+    Bool,
+    Char,
+    UChar,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Long,
+    ULong,
+    Half,
+    Float,
+    Double,
+}
+
+impl Scalar {
+    /// Returns the size of this type in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            Scalar::Bool | Scalar::Char | Scalar::UChar => 1,
+            Scalar::Short | Scalar::UShort | Scalar::Half => 2,
+            Scalar::Int | Scalar::UInt | Scalar::Float => 4,
+            Scalar::Long | Scalar::ULong | Scalar::Double => 8,
+        }
+    }
+
+    /// Returns true if this is a floating point type.
+    pub fn is_float(self) -> bool {
+        matches!(self, Scalar::Half | Scalar::Float | Scalar::Double)
+    }
+
+    /// Returns the OpenCL C name of this type.
+    pub fn name(self) -> &'static str {
+        match self {
+            Scalar::Bool => "bool",
+            Scalar::Char => "char",
+            Scalar::UChar => "uchar",
+            Scalar::Short => "short",
+            Scalar::UShort => "ushort",
+            Scalar::Int => "int",
+            Scalar::UInt => "uint",
+            Scalar::Long => "long",
+            Scalar::ULong => "ulong",
+            Scalar::Half => "half",
+            Scalar::Float => "float",
+            Scalar::Double => "double",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Scalar> {
+        Some(match name {
+            "bool" => Scalar::Bool,
+            "char" => Scalar::Char,
+            "uchar" => Scalar::UChar,
+            "short" => Scalar::Short,
+            "ushort" => Scalar::UShort,
+            "int" => Scalar::Int,
+            "uint" => Scalar::UInt,
+            "long" | "ptrdiff_t" | "intptr_t" => Scalar::Long,
+            "ulong" | "size_t" | "uintptr_t" => Scalar::ULong,
+            "half" => Scalar::Half,
+            "float" => Scalar::Float,
+            "double" => Scalar::Double,
+            _ => return None,
+        })
+    }
+}
+
+/// An element type: a scalar or a vector of scalars.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Elem {
+    pub scalar: Scalar,
+    pub lanes: u8,
+}
+
+impl Elem {
+    pub fn scalar(scalar: Scalar) -> Elem {
+        Elem { scalar, lanes: 1 }
+    }
+
+    /// Returns the size of this type in bytes (three component vectors are
+    /// sized as four).
+    pub fn size(self) -> usize {
+        let lanes = if self.lanes == 3 { 4 } else { self.lanes as usize };
+        self.scalar.size() * lanes
+    }
+
+    pub fn name(self) -> String {
+        if self.lanes == 1 {
+            self.scalar.name().to_owned()
+        } else {
+            format!("{}{}", self.scalar.name(), self.lanes)
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Elem> {
+        if let Some(scalar) = Scalar::from_name(name) {
+            return Some(Elem::scalar(scalar));
+        }
+
+        let digits_at = name.find(|c: char| c.is_ascii_digit())?;
+        let (base, lanes) = name.split_at(digits_at);
+        let lanes = match lanes {
+            "2" => 2, "3" => 3, "4" => 4, "8" => 8, "16" => 16,
+            _ => return None,
+        };
+        match Scalar::from_name(base) {
+            Some(Scalar::Bool) | None => None,
+            Some(scalar) => Some(Elem { scalar, lanes }),
+        }
+    }
+}
+
+/// An address space qualifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddrSpace {
+    Private,
+    Global,
+    Constant,
+    Local,
+}
+
+/// An image access qualifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    None,
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+}
+
+/// A type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Ty {
+    Void,
+    Val(Elem),
+    Ptr(Elem, AddrSpace),
+    Image(&'static str),
+    Sampler,
+}
+
+/// A function parameter.
+#[derive(Clone, Debug)]
+pub struct Param {
+    pub name: String,
+    pub ty: Ty,
+    pub access: Access,
+    pub is_const: bool,
+    pub is_restrict: bool,
+    pub is_volatile: bool,
+    /// The type name as reported by `clGetKernelArgInfo`.
+    pub type_name: String,
+}
+
+impl Param {
+    /// Returns the address space of the memory pointed to by this parameter.
+    pub fn addr_space(&self) -> AddrSpace {
+        match self.ty {
+            Ty::Ptr(_, addr) => addr,
+            Ty::Image(_) => AddrSpace::Global,
+            _ => AddrSpace::Private,
+        }
+    }
+}
+
+/// A function (kernel or otherwise).
+#[derive(Clone, Debug)]
+pub struct Function {
+    pub name: String,
+    pub is_kernel: bool,
+    pub attributes: String,
+    pub ret: Ty,
+    pub params: Vec<Param>,
+    /// The type of each local variable slot (parameters first).
+    pub slots: Vec<Ty>,
+    pub body: Vec<Stmt>,
+}
+
+/// Unary operators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnOp {
+    Neg,
+    Not,
+    BitNot,
+}
+
+/// Binary operators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinOp {
+    Add, Sub, Mul, Div, Rem,
+    Shl, Shr, BitAnd, BitOr, BitXor,
+    Eq, Ne, Lt, Le, Gt, Ge,
+}
+
+/// Built-in functions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Builtin {
+    GlobalId, GlobalSize, LocalId, LocalSize, GroupId, NumGroups, GlobalOffset, WorkDim,
+    Barrier,
+    Sqrt, Rsqrt, Fabs, Exp, Exp2, Log, Log2, Log10, Sin, Cos, Tan, Asin, Acos, Atan,
+    Sinh, Cosh, Tanh, Floor, Ceil, Round, Trunc,
+    Pow, Atan2, Fmod, Fmin, Fmax, Hypot,
+    Min, Max, Abs, Clamp, Mad,
+}
+
+impl Builtin {
+    fn from_name(name: &str) -> Option<(Builtin, usize)> {
+        let name = name.strip_prefix("native_")
+            .or_else(|| name.strip_prefix("half_"))
+            .unwrap_or(name);
+
+        Some(match name {
+            "get_global_id" => (Builtin::GlobalId, 1),
+            "get_global_size" => (Builtin::GlobalSize, 1),
+            "get_local_id" => (Builtin::LocalId, 1),
+            "get_local_size" => (Builtin::LocalSize, 1),
+            "get_group_id" => (Builtin::GroupId, 1),
+            "get_num_groups" => (Builtin::NumGroups, 1),
+            "get_global_offset" => (Builtin::GlobalOffset, 1),
+            "get_work_dim" => (Builtin::WorkDim, 0),
+            "barrier" | "mem_fence" | "read_mem_fence" | "write_mem_fence" => (Builtin::Barrier, 1),
+            "sqrt" => (Builtin::Sqrt, 1),
+            "rsqrt" => (Builtin::Rsqrt, 1),
+            "fabs" => (Builtin::Fabs, 1),
+            "exp" => (Builtin::Exp, 1),
+            "exp2" => (Builtin::Exp2, 1),
+            "log" => (Builtin::Log, 1),
+            "log2" => (Builtin::Log2, 1),
+            "log10" => (Builtin::Log10, 1),
+            "sin" => (Builtin::Sin, 1),
+            "cos" => (Builtin::Cos, 1),
+            "tan" => (Builtin::Tan, 1),
+            "asin" => (Builtin::Asin, 1),
+            "acos" => (Builtin::Acos, 1),
+            "atan" => (Builtin::Atan, 1),
+            "sinh" => (Builtin::Sinh, 1),
+            "cosh" => (Builtin::Cosh, 1),
+            "tanh" => (Builtin::Tanh, 1),
+            "floor" => (Builtin::Floor, 1),
+            "ceil" => (Builtin::Ceil, 1),
+            "round" | "rint" => (Builtin::Round, 1),
+            "trunc" => (Builtin::Trunc, 1),
+            "pow" | "powr" => (Builtin::Pow, 2),
+            "atan2" => (Builtin::Atan2, 2),
+            "fmod" => (Builtin::Fmod, 2),
+            "fmin" => (Builtin::Fmin, 2),
+            "fmax" => (Builtin::Fmax, 2),
+            "hypot" => (Builtin::Hypot, 2),
+            "min" => (Builtin::Min, 2),
+            "max" => (Builtin::Max, 2),
+            "abs" => (Builtin::Abs, 1),
+            "clamp" => (Builtin::Clamp, 3),
+            "mad" | "fma" => (Builtin::Mad, 3),
+            _ => return None,
+        })
+    }
+}
+
+/// A literal value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Lit {
+    Int(i64),
+    Float(f64),
+}
+
+/// An expression.
+#[derive(Clone, Debug)]
+pub enum Expr {
+    Lit(Lit),
+    Var(usize),
+    Index(Box<Expr>, Box<Expr>),
+    Deref(Box<Expr>),
+    Unary(UnOp, Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Assign(Option<BinOp>, Box<Expr>, Box<Expr>),
+    IncDec { target: Box<Expr>, inc: bool, prefix: bool },
+    Ternary(Box<Expr>, Box<Expr>, Box<Expr>),
+    Cast(Scalar, Box<Expr>),
+    Builtin(Builtin, Vec<Expr>),
+    Call(usize, Vec<Expr>),
+    Comma(Box<Expr>, Box<Expr>),
+}
+
+/// A statement.
+#[derive(Clone, Debug)]
+pub enum Stmt {
+    Empty,
+    Expr(Expr),
+    Decl(Vec<(usize, Option<Expr>)>),
+    Block(Vec<Stmt>),
+    If(Expr, Box<Stmt>, Option<Box<Stmt>>),
+    For(Option<Box<Stmt>>, Option<Expr>, Option<Expr>, Box<Stmt>),
+    While(Expr, Box<Stmt>),
+    DoWhile(Box<Stmt>, Expr),
+    Return(Option<Expr>),
+    Break,
+    Continue,
+}
+
+
+/// Parses a list of tokens into a list of functions.
+pub fn parse(toks: Vec<Spanned>) -> Result<Vec<Function>, String> {
+    let mut parser = Parser { toks, pos: 0, functions: Vec::new(), fn_idxs: HashMap::new(),
+        scopes: Vec::new(), slots: Vec::new() };
+
+    while !parser.at_end() {
+        parser.top_level()?;
+    }
+
+    Ok(parser.functions)
+}
+
+
+#[derive(Default)]
+struct Qualifiers {
+    addr: Option<AddrSpace>,
+    access: Option<Access>,
+    is_const: bool,
+    is_restrict: bool,
+    is_volatile: bool,
+    is_kernel: bool,
+    attributes: Vec<String>,
+}
+
+struct Parser {
+    toks: Vec<Spanned>,
+    pos: usize,
+    functions: Vec<Function>,
+    fn_idxs: HashMap<String, usize>,
+    scopes: Vec<HashMap<String, usize>>,
+    slots: Vec<Ty>,
+}
+
+impl Parser {
+    fn at_end(&self) -> bool {
+        self.pos >= self.toks.len()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.toks.get(self.pos).map(|s| &s.tok)
+    }
+
+    fn peek_at(&self, ofs: usize) -> Option<&Token> {
+        self.toks.get(self.pos + ofs).map(|s| &s.tok)
+    }
+
+    fn line(&self) -> usize {
+        self.toks.get(self.pos).or_else(|| self.toks.last()).map(|s| s.line).unwrap_or(0)
+    }
+
+    fn err<T>(&self, msg: String) -> Result<T, String> {
+        Err(format!("line {}: {}", self.line(), msg))
+    }
+
+    fn is_punct(&self, p: &str) -> bool {
+        match self.peek() {
+            Some(&Token::Punct(q)) => q == p,
+            _ => false,
+        }
+    }
+
+    fn is_ident(&self, name: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(s)) => s == name,
+            _ => false,
+        }
+    }
+
+    fn eat_punct(&mut self, p: &str) -> bool {
+        if self.is_punct(p) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, p: &str) -> Result<(), String> {
+        if self.eat_punct(p) {
+            Ok(())
+        } else {
+            let found = self.describe();
+            self.err(format!("expected '{}', found {}", p, found))
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self.peek() {
+            Some(Token::Ident(s)) => format!("'{}'", s),
+            Some(&Token::Int(v)) => format!("'{}'", v),
+            Some(&Token::Float(v)) => format!("'{}'", v),
+            Some(&Token::Punct(p)) => format!("'{}'", p),
+            None => "end of input".to_owned(),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.peek().cloned() {
+            Some(Token::Ident(s)) => {
+                self.pos += 1;
+                Ok(s)
+            },
+            _ => {
+                let found = self.describe();
+                self.err(format!("expected identifier, found {}", found))
+            },
+        }
+    }
+
+    //========================================================================
+    //============================== Types ===================================
+    //========================================================================
+
+    /// Consumes any qualifiers and attributes.
+    fn qualifiers(&mut self, quals: &mut Qualifiers) -> Result<(), String> {
+        loop {
+            let name = match self.peek() {
+                Some(Token::Ident(s)) => s.clone(),
+                _ => return Ok(()),
+            };
+
+            match name.as_str() {
+                "__kernel" | "kernel" => quals.is_kernel = true,
+                "__global" | "global" => quals.addr = Some(AddrSpace::Global),
+                "__constant" | "constant" => quals.addr = Some(AddrSpace::Constant),
+                "__local" | "local" => quals.addr = Some(AddrSpace::Local),
+                "__private" | "private" => quals.addr = Some(AddrSpace::Private),
+                "__read_only" | "read_only" => quals.access = Some(Access::ReadOnly),
+                "__write_only" | "write_only" => quals.access = Some(Access::WriteOnly),
+                "__read_write" | "read_write" => quals.access = Some(Access::ReadWrite),
+                "const" => quals.is_const = true,
+                "restrict" | "__restrict" => quals.is_restrict = true,
+                "volatile" => quals.is_volatile = true,
+                "inline" | "__inline" | "static" | "signed" => (),
+                "__attribute__" => {
+                    self.pos += 1;
+                    let attr = self.balanced_text()?;
+                    quals.attributes.push(attr);
+                    continue;
+                },
+                _ => return Ok(()),
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes a parenthesized group, returning its contents as text.
+    fn balanced_text(&mut self) -> Result<String, String> {
+        self.expect_punct("(")?;
+        let mut depth = 1;
+        let mut text = String::new();
+
+        while depth > 0 {
+            let tok = match self.peek().cloned() {
+                Some(t) => t,
+                None => return self.err("unterminated attribute".to_owned()),
+            };
+            self.pos += 1;
+
+            match tok {
+                Token::Punct("(") => depth += 1,
+                Token::Punct(")") => depth -= 1,
+                _ => (),
+            }
+            if depth > 0 {
+                match tok {
+                    Token::Ident(s) => text.push_str(&s),
+                    Token::Int(v) => text.push_str(&v.to_string()),
+                    Token::Float(v) => text.push_str(&v.to_string()),
+                    Token::Punct(p) => text.push_str(p),
+                }
+            }
+        }
+
+        // Strip the extra set of parentheses from `__attribute__((...))`:
+        if text.starts_with('(') && text.ends_with(')') {
+            text = text[1..text.len() - 1].to_owned();
+        }
+        Ok(text)
+    }
+
+    /// Returns true if the current token begins a type.
+    fn at_type(&self) -> bool {
+        match self.peek() {
+            Some(Token::Ident(s)) => match s.as_str() {
+                "__global" | "global" | "__constant" | "constant" | "__local" | "local" |
+                "__private" | "private" | "const" | "volatile" | "restrict" | "unsigned" |
+                "signed" | "void" | "sampler_t" | "struct" | "union" | "event_t" => true,
+                other => Elem::from_name(other).is_some() || image_type(other).is_some(),
+            },
+            _ => false,
+        }
+    }
+
+    /// Parses a base type (after qualifiers).
+    fn base_type(&mut self) -> Result<Ty, String> {
+        let name = self.ident()?;
+
+        match name.as_str() {
+            "void" => Ok(Ty::Void),
+            "sampler_t" => Ok(Ty::Sampler),
+            "unsigned" => {
+                let scalar = match self.peek() {
+                    Some(Token::Ident(s)) if s == "char" => Some(Scalar::UChar),
+                    Some(Token::Ident(s)) if s == "short" => Some(Scalar::UShort),
+                    Some(Token::Ident(s)) if s == "int" => Some(Scalar::UInt),
+                    Some(Token::Ident(s)) if s == "long" => Some(Scalar::ULong),
+                    _ => None,
+                };
+                match scalar {
+                    Some(s) => {
+                        self.pos += 1;
+                        Ok(Ty::Val(Elem::scalar(s)))
+                    },
+                    None => Ok(Ty::Val(Elem::scalar(Scalar::UInt))),
+                }
+            },
+            "struct" | "union" | "event_t" => {
+                self.err(format!("'{}' types are not supported", name))
+            },
+            other => {
+                if let Some(elem) = Elem::from_name(other) {
+                    Ok(Ty::Val(elem))
+                } else if let Some(img) = image_type(other) {
+                    Ok(Ty::Image(img))
+                } else {
+                    self.err(format!("unknown type '{}'", other))
+                }
+            },
+        }
+    }
+
+    /// Parses any trailing `*` and qualifiers after a base type.
+    fn pointer_suffix(&mut self, base: Ty, quals: &mut Qualifiers) -> Result<Ty, String> {
+        let mut ty = base;
+        while self.eat_punct("*") {
+            ty = match ty {
+                Ty::Val(elem) => Ty::Ptr(elem, quals.addr.unwrap_or(AddrSpace::Private)),
+                Ty::Void => Ty::Ptr(Elem::scalar(Scalar::UChar),
+                    quals.addr.unwrap_or(AddrSpace::Private)),
+                _ => return self.err("unsupported pointer type".to_owned()),
+            };
+            // Qualifiers following the `*` apply to the pointer itself:
+            let mut ptr_quals = Qualifiers::default();
+            self.qualifiers(&mut ptr_quals)?;
+            quals.is_restrict |= ptr_quals.is_restrict;
+            if self.is_punct("*") {
+                return self.err("pointers to pointers are not supported".to_owned());
+            }
+        }
+        Ok(ty)
+    }
+
+    //========================================================================
+    //============================ Top Level =================================
+    //========================================================================
+
+    fn top_level(&mut self) -> Result<(), String> {
+        if self.eat_punct(";") { return Ok(()); }
+
+        if self.is_ident("typedef") {
+            return self.err("'typedef' is not supported".to_owned());
+        }
+
+        let mut quals = Qualifiers::default();
+        self.qualifiers(&mut quals)?;
+        let base = self.base_type()?;
+        let ret = self.pointer_suffix(base, &mut quals)?;
+        self.qualifiers(&mut quals)?;
+        let name = self.ident()?;
+
+        if !self.is_punct("(") {
+            return self.err(format!("program scope variables ('{}') are not supported", name));
+        }
+
+        self.scopes = vec![HashMap::new()];
+        self.slots = Vec::new();
+
+        let params = self.params()?;
+        self.qualifiers(&mut quals)?;
+
+        // Prototype:
+        if self.eat_punct(";") { return Ok(()); }
+
+        if quals.is_kernel && ret != Ty::Void {
+            return self.err(format!("kernel '{}' must return void", name));
+        }
+
+        let fn_idx = self.functions.len();
+        if self.fn_idxs.contains_key(&name) {
+            return self.err(format!("redefinition of '{}'", name));
+        }
+
+        self.expect_punct("{")?;
+        let body = self.block_body()?;
+
+        self.functions.push(Function {
+            name: name.clone(),
+            is_kernel: quals.is_kernel,
+            attributes: quals.attributes.join(" "),
+            ret,
+            params,
+            slots: std::mem::take(&mut self.slots),
+            body,
+        });
+        self.fn_idxs.insert(name, fn_idx);
+        Ok(())
+    }
+
+    fn params(&mut self) -> Result<Vec<Param>, String> {
+        self.expect_punct("(")?;
+        let mut params = Vec::new();
+
+        if self.eat_punct(")") { return Ok(params); }
+        if self.is_ident("void") && self.peek_at(1) == Some(&Token::Punct(")")) {
+            self.pos += 2;
+            return Ok(params);
+        }
+
+        loop {
+            let mut quals = Qualifiers::default();
+            self.qualifiers(&mut quals)?;
+            let base = self.base_type()?;
+            self.qualifiers(&mut quals)?;
+            let ty = self.pointer_suffix(base, &mut quals)?;
+            let name = self.ident()?;
+
+            if self.is_punct("[") {
+                return self.err("array parameters are not supported".to_owned());
+            }
+
+            let type_name = match ty {
+                Ty::Val(elem) => elem.name(),
+                Ty::Ptr(elem, _) => format!("{}*", elem.name()),
+                Ty::Image(name) => name.to_owned(),
+                Ty::Sampler => "sampler_t".to_owned(),
+                Ty::Void => return self.err("parameters can not be 'void'".to_owned()),
+            };
+
+            let access = match ty {
+                Ty::Image(_) => quals.access.unwrap_or(Access::ReadOnly),
+                _ => Access::None,
+            };
+
+            self.declare(&name, ty.clone())?;
+            params.push(Param {
+                name,
+                ty,
+                access,
+                is_const: quals.is_const || quals.addr == Some(AddrSpace::Constant),
+                is_restrict: quals.is_restrict,
+                is_volatile: quals.is_volatile,
+                type_name,
+            });
+
+            if self.eat_punct(")") { break; }
+            self.expect_punct(",")?;
+        }
+
+        Ok(params)
+    }
+
+    fn declare(&mut self, name: &str, ty: Ty) -> Result<usize, String> {
+        let slot = self.slots.len();
+        let scope = self.scopes.last_mut().expect("no scope");
+        if scope.contains_key(name) {
+            return Err(format!("line {}: redeclaration of '{}'", self.toks.get(self.pos)
+                .map(|s| s.line).unwrap_or(0), name));
+        }
+        scope.insert(name.to_owned(), slot);
+        self.slots.push(ty);
+        Ok(slot)
+    }
+
+    fn lookup(&self, name: &str) -> Option<usize> {
+        self.scopes.iter().rev().filter_map(|s| s.get(name)).next().cloned()
+    }
+
+    //========================================================================
+    //============================ Statements ================================
+    //========================================================================
+
+    /// Parses statements up to and including a closing brace.
+    fn block_body(&mut self) -> Result<Vec<Stmt>, String> {
+        self.scopes.push(HashMap::new());
+        let mut stmts = Vec::new();
+
+        while !self.eat_punct("}") {
+            if self.at_end() {
+                return self.err("unexpected end of input (missing '}')".to_owned());
+            }
+            stmts.push(self.stmt()?);
+        }
+
+        self.scopes.pop();
+        Ok(stmts)
+    }
+
+    fn stmt(&mut self) -> Result<Stmt, String> {
+        if self.eat_punct(";") { return Ok(Stmt::Empty); }
+        if self.eat_punct("{") { return Ok(Stmt::Block(self.block_body()?)); }
+
+        let keyword = match self.peek() {
+            Some(Token::Ident(s)) => s.clone(),
+            _ => String::new(),
+        };
+
+        match keyword.as_str() {
+            "if" => {
+                self.pos += 1;
+                self.expect_punct("(")?;
+                let cond = self.expr()?;
+                self.expect_punct(")")?;
+                let then = self.scoped_stmt()?;
+                let els = if self.is_ident("else") {
+                    self.pos += 1;
+                    Some(Box::new(self.scoped_stmt()?))
+                } else {
+                    None
+                };
+                Ok(Stmt::If(cond, Box::new(then), els))
+            },
+            "for" => {
+                self.pos += 1;
+                self.expect_punct("(")?;
+                self.scopes.push(HashMap::new());
+                let init = if self.eat_punct(";") {
+                    None
+                } else if self.at_type() {
+                    Some(Box::new(self.decl()?))
+                } else {
+                    let e = self.expr()?;
+                    self.expect_punct(";")?;
+                    Some(Box::new(Stmt::Expr(e)))
+                };
+                let cond = if self.is_punct(";") { None } else { Some(self.expr()?) };
+                self.expect_punct(";")?;
+                let step = if self.is_punct(")") { None } else { Some(self.expr()?) };
+                self.expect_punct(")")?;
+                let body = self.scoped_stmt()?;
+                self.scopes.pop();
+                Ok(Stmt::For(init, cond, step, Box::new(body)))
+            },
+            "while" => {
+                self.pos += 1;
+                self.expect_punct("(")?;
+                let cond = self.expr()?;
+                self.expect_punct(")")?;
+                Ok(Stmt::While(cond, Box::new(self.scoped_stmt()?)))
+            },
+            "do" => {
+                self.pos += 1;
+                let body = self.scoped_stmt()?;
+                if !self.is_ident("while") {
+                    return self.err("expected 'while'".to_owned());
+                }
+                self.pos += 1;
+                self.expect_punct("(")?;
+                let cond = self.expr()?;
+                self.expect_punct(")")?;
+                self.expect_punct(";")?;
+                Ok(Stmt::DoWhile(Box::new(body), cond))
+            },
+            "return" => {
+                self.pos += 1;
+                let val = if self.is_punct(";") { None } else { Some(self.expr()?) };
+                self.expect_punct(";")?;
+                Ok(Stmt::Return(val))
+            },
+            "break" => {
+                self.pos += 1;
+                self.expect_punct(";")?;
+                Ok(Stmt::Break)
+            },
+            "continue" => {
+                self.pos += 1;
+                self.expect_punct(";")?;
+                Ok(Stmt::Continue)
+            },
+            "switch" | "goto" => self.err(format!("'{}' is not supported", keyword)),
+            _ => {
+                if self.at_type() {
+                    self.decl()
+                } else {
+                    let e = self.expr()?;
+                    self.expect_punct(";")?;
+                    Ok(Stmt::Expr(e))
+                }
+            },
+        }
+    }
+
+    /// Parses a statement within its own scope.
+    fn scoped_stmt(&mut self) -> Result<Stmt, String> {
+        self.scopes.push(HashMap::new());
+        let stmt = self.stmt();
+        self.scopes.pop();
+        stmt
+    }
+
+    fn decl(&mut self) -> Result<Stmt, String> {
+        let mut quals = Qualifiers::default();
+        self.qualifiers(&mut quals)?;
+        let base = self.base_type()?;
+        self.qualifiers(&mut quals)?;
+
+        if quals.addr == Some(AddrSpace::Local) {
+            return self.err("'__local' variables are not supported".to_owned());
+        }
+
+        let mut decls = Vec::new();
+
+        loop {
+            let ty = self.pointer_suffix(base.clone(), &mut quals)?;
+            let name = self.ident()?;
+
+            match ty {
+                Ty::Val(elem) if elem.lanes > 1 => {
+                    return self.err("vector types are not supported".to_owned());
+                },
+                Ty::Val(_) | Ty::Ptr(..) => (),
+                _ => return self.err(format!("unsupported type for variable '{}'", name)),
+            }
+
+            if self.is_punct("[") {
+                return self.err("arrays are not supported".to_owned());
+            }
+
+            let init = if self.eat_punct("=") { Some(self.assign_expr()?) } else { None };
+            // Declare after the initializer so it can not refer to itself:
+            let slot = self.declare(&name, ty)?;
+            decls.push((slot, init));
+
+            if self.eat_punct(";") { break; }
+            self.expect_punct(",")?;
+        }
+
+        Ok(Stmt::Decl(decls))
+    }
+
+    //========================================================================
+    //=========================== Expressions ================================
+    //========================================================================
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.assign_expr()?;
+        while self.eat_punct(",") {
+            let rhs = self.assign_expr()?;
+            lhs = Expr::Comma(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn assign_expr(&mut self) -> Result<Expr, String> {
+        let lhs = self.ternary()?;
+
+        let op = match self.peek() {
+            Some(&Token::Punct(p)) => match p {
+                "=" => Some(None),
+                "+=" => Some(Some(BinOp::Add)),
+                "-=" => Some(Some(BinOp::Sub)),
+                "*=" => Some(Some(BinOp::Mul)),
+                "/=" => Some(Some(BinOp::Div)),
+                "%=" => Some(Some(BinOp::Rem)),
+                "&=" => Some(Some(BinOp::BitAnd)),
+                "|=" => Some(Some(BinOp::BitOr)),
+                "^=" => Some(Some(BinOp::BitXor)),
+                "<<=" => Some(Some(BinOp::Shl)),
+                ">>=" => Some(Some(BinOp::Shr)),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        match op {
+            Some(op) => {
+                self.check_lvalue(&lhs)?;
+                self.pos += 1;
+                let rhs = self.assign_expr()?;
+                Ok(Expr::Assign(op, Box::new(lhs), Box::new(rhs)))
+            },
+            None => Ok(lhs),
+        }
+    }
+
+    fn check_lvalue(&self, expr: &Expr) -> Result<(), String> {
+        match *expr {
+            Expr::Var(_) | Expr::Index(..) | Expr::Deref(_) => Ok(()),
+            _ => self.err("expression is not assignable".to_owned()),
+        }
+    }
+
+    fn ternary(&mut self) -> Result<Expr, String> {
+        let cond = self.binary(0)?;
+        if self.eat_punct("?") {
+            let a = self.expr()?;
+            self.expect_punct(":")?;
+            let b = self.ternary()?;
+            Ok(Expr::Ternary(Box::new(cond), Box::new(a), Box::new(b)))
+        } else {
+            Ok(cond)
+        }
+    }
+
+    fn binary(&mut self, min_prec: u8) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+
+        while let Some(&Token::Punct(p)) = self.peek() {
+            let (prec, op) = match p {
+                "||" => (1, None),
+                "&&" => (2, None),
+                "|" => (3, Some(BinOp::BitOr)),
+                "^" => (4, Some(BinOp::BitXor)),
+                "&" => (5, Some(BinOp::BitAnd)),
+                "==" => (6, Some(BinOp::Eq)),
+                "!=" => (6, Some(BinOp::Ne)),
+                "<" => (7, Some(BinOp::Lt)),
+                "<=" => (7, Some(BinOp::Le)),
+                ">" => (7, Some(BinOp::Gt)),
+                ">=" => (7, Some(BinOp::Ge)),
+                "<<" => (8, Some(BinOp::Shl)),
+                ">>" => (8, Some(BinOp::Shr)),
+                "+" => (9, Some(BinOp::Add)),
+                "-" => (9, Some(BinOp::Sub)),
+                "*" => (10, Some(BinOp::Mul)),
+                "/" => (10, Some(BinOp::Div)),
+                "%" => (10, Some(BinOp::Rem)),
+                _ => break,
+            };
+
+            if prec < min_prec.max(1) { break; }
+            let is_or = self.is_punct("||");
+            self.pos += 1;
+            let rhs = self.binary(prec + 1)?;
+
+            lhs = match op {
+                Some(op) => Expr::Binary(op, Box::new(lhs), Box::new(rhs)),
+                None if is_or => Expr::Or(Box::new(lhs), Box::new(rhs)),
+                None => Expr::And(Box::new(lhs), Box::new(rhs)),
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        let p = match self.peek() {
+            Some(&Token::Punct(p)) => p,
+            _ => return self.postfix(),
+        };
+
+        match p {
+            "-" => {
+                self.pos += 1;
+                Ok(Expr::Unary(UnOp::Neg, Box::new(self.unary()?)))
+            },
+            "+" => {
+                self.pos += 1;
+                self.unary()
+            },
+            "!" => {
+                self.pos += 1;
+                Ok(Expr::Unary(UnOp::Not, Box::new(self.unary()?)))
+            },
+            "~" => {
+                self.pos += 1;
+                Ok(Expr::Unary(UnOp::BitNot, Box::new(self.unary()?)))
+            },
+            "*" => {
+                self.pos += 1;
+                let ptr = self.unary()?;
+                self.check_scalar_access(&ptr)?;
+                Ok(Expr::Deref(Box::new(ptr)))
+            },
+            "&" => self.err("the address-of operator is not supported".to_owned()),
+            "++" | "--" => {
+                self.pos += 1;
+                let target = self.unary()?;
+                self.check_lvalue(&target)?;
+                Ok(Expr::IncDec { target: Box::new(target), inc: p == "++", prefix: true })
+            },
+            "(" => {
+                // Cast:
+                let is_cast = {
+                    self.pos += 1;
+                    let at = self.at_type();
+                    self.pos -= 1;
+                    at
+                };
+
+                if is_cast {
+                    self.pos += 1;
+                    let mut quals = Qualifiers::default();
+                    self.qualifiers(&mut quals)?;
+                    let ty = self.base_type()?;
+                    if self.is_punct("*") {
+                        return self.err("pointer casts are not supported".to_owned());
+                    }
+                    self.expect_punct(")")?;
+                    let scalar = match ty {
+                        Ty::Val(elem) if elem.lanes == 1 => elem.scalar,
+                        _ => return self.err("unsupported cast".to_owned()),
+                    };
+                    Ok(Expr::Cast(scalar, Box::new(self.unary()?)))
+                } else {
+                    self.postfix()
+                }
+            },
+            _ => self.postfix(),
+        }
+    }
+
+    /// Returns an error if `ptr` is a pointer to a vector, which can not be
+    /// loaded from or stored to.
+    fn check_scalar_access(&self, ptr: &Expr) -> Result<(), String> {
+        match *ptr {
+            Expr::Var(slot) => match self.slots[slot] {
+                Ty::Ptr(elem, _) if elem.lanes != 1 => {
+                    self.err("vector loads and stores are not supported".to_owned())
+                },
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Expr, String> {
+        let mut expr = self.primary()?;
+
+        loop {
+            if self.eat_punct("[") {
+                self.check_scalar_access(&expr)?;
+                let idx = self.expr()?;
+                self.expect_punct("]")?;
+                expr = Expr::Index(Box::new(expr), Box::new(idx));
+            } else if self.is_punct("++") || self.is_punct("--") {
+                self.check_lvalue(&expr)?;
+                let inc = self.is_punct("++");
+                self.pos += 1;
+                expr = Expr::IncDec { target: Box::new(expr), inc, prefix: false };
+            } else if self.is_punct(".") || self.is_punct("->") {
+                return self.err("member access is not supported".to_owned());
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let tok = match self.peek().cloned() {
+            Some(t) => t,
+            None => return self.err("unexpected end of input".to_owned()),
+        };
+
+        match tok {
+            Token::Int(v) => {
+                self.pos += 1;
+                Ok(Expr::Lit(Lit::Int(v)))
+            },
+            Token::Float(v) => {
+                self.pos += 1;
+                Ok(Expr::Lit(Lit::Float(v)))
+            },
+            Token::Punct("(") => {
+                self.pos += 1;
+                let e = self.expr()?;
+                self.expect_punct(")")?;
+                Ok(e)
+            },
+            Token::Ident(name) => {
+                self.pos += 1;
+
+                if self.is_punct("(") {
+                    return self.call(&name);
+                }
+
+                match name.as_str() {
+                    "true" => return Ok(Expr::Lit(Lit::Int(1))),
+                    "false" => return Ok(Expr::Lit(Lit::Int(0))),
+                    "CLK_LOCAL_MEM_FENCE" => return Ok(Expr::Lit(Lit::Int(1))),
+                    "CLK_GLOBAL_MEM_FENCE" => return Ok(Expr::Lit(Lit::Int(2))),
+                    "M_PI" | "M_PI_F" => return Ok(Expr::Lit(Lit::Float(::std::f64::consts::PI))),
+                    "M_E" | "M_E_F" => return Ok(Expr::Lit(Lit::Float(::std::f64::consts::E))),
+                    "INFINITY" => return Ok(Expr::Lit(Lit::Float(f64::INFINITY))),
+                    "NAN" => return Ok(Expr::Lit(Lit::Float(f64::NAN))),
+                    _ => (),
+                }
+
+                match self.lookup(&name) {
+                    Some(slot) => Ok(Expr::Var(slot)),
+                    None => {
+                        self.pos -= 1;
+                        self.err(format!("use of undeclared identifier '{}'", name))
+                    },
+                }
+            },
+            Token::Punct(p) => self.err(format!("unexpected '{}'", p)),
+        }
+    }
+
+    fn call(&mut self, name: &str) -> Result<Expr, String> {
+        self.expect_punct("(")?;
+        let mut args = Vec::new();
+
+        if !self.eat_punct(")") {
+            loop {
+                args.push(self.assign_expr()?);
+                if self.eat_punct(")") { break; }
+                self.expect_punct(",")?;
+            }
+        }
+
+        // Conversions, e.g. `convert_float(x)` or `convert_int_sat(x)`:
+        if let Some(conversion) = name.strip_prefix("convert_") {
+            let ty_name = conversion.split('_').next().unwrap_or("");
+            return match Scalar::from_name(ty_name) {
+                Some(scalar) if args.len() == 1 => Ok(Expr::Cast(scalar, Box::new(args.remove(0)))),
+                _ => self.err(format!("unsupported conversion '{}'", name)),
+            };
+        }
+
+        if let Some(&idx) = self.fn_idxs.get(name) {
+            let expected = self.functions[idx].params.len();
+            if args.len() != expected {
+                return self.err(format!("'{}' takes {} arguments but {} were given",
+                    name, expected, args.len()));
+            }
+            return Ok(Expr::Call(idx, args));
+        }
+
+        match Builtin::from_name(name) {
+            Some((builtin, arity)) => {
+                if args.len() != arity {
+                    return self.err(format!("'{}' takes {} arguments but {} were given",
+                        name, arity, args.len()));
+                }
+                Ok(Expr::Builtin(builtin, args))
+            },
+            None => self.err(format!("call to unknown or unsupported function '{}'", name)),
+        }
+    }
+}
+
+
+fn image_type(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "image1d_t" => "image1d_t",
+        "image1d_array_t" => "image1d_array_t",
+        "image1d_buffer_t" => "image1d_buffer_t",
+        "image2d_t" => "image2d_t",
+        "image2d_array_t" => "image2d_array_t",
+        "image3d_t" => "image3d_t",
+        _ => return None,
+    })
+}
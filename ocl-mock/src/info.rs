@@ -0,0 +1,90 @@
+//! Helpers for the `clGet*Info` family of functions.
+
+use std::{mem, ptr, slice};
+use crate::cl::*;
+
+
+/// The raw bytes of an info result.
+pub type InfoBytes = Result<Vec<u8>, cl_int>;
+
+/// Copies `bytes` into `param_value` (if non-null) and writes its size to
+/// `param_value_size_ret` (if non-null), validating `param_value_size`.
+pub unsafe fn write_info(bytes: InfoBytes, param_value_size: size_t, param_value: *mut c_void,
+        param_value_size_ret: *mut size_t) -> cl_int
+{
+    let bytes = match bytes {
+        Ok(b) => b,
+        Err(err) => return err,
+    };
+
+    if !param_value.is_null() {
+        if param_value_size < bytes.len() {
+            return CL_INVALID_VALUE;
+        }
+        ptr::copy_nonoverlapping(bytes.as_ptr(), param_value as *mut u8, bytes.len());
+    }
+
+    if !param_value_size_ret.is_null() {
+        *param_value_size_ret = bytes.len();
+    }
+
+    CL_SUCCESS
+}
+
+/// Returns the bytes of a plain value.
+pub fn val<T: Copy>(v: T) -> InfoBytes {
+    vals(&[v])
+}
+
+/// Returns the bytes of a slice of plain values.
+pub fn vals<T: Copy>(v: &[T]) -> InfoBytes {
+    let bytes = unsafe { slice::from_raw_parts(v.as_ptr() as *const u8, mem::size_of_val(v)) };
+    Ok(bytes.to_vec())
+}
+
+/// Returns a null terminated string.
+pub fn string(s: &str) -> InfoBytes {
+    let mut bytes = Vec::with_capacity(s.len() + 1);
+    bytes.extend_from_slice(s.as_bytes());
+    bytes.push(0);
+    Ok(bytes)
+}
+
+/// Returns a handle.
+pub fn handle(h: usize) -> InfoBytes {
+    val(h as *mut c_void)
+}
+
+/// Returns a boolean.
+pub fn boolean(b: bool) -> InfoBytes {
+    val(if b { CL_TRUE } else { CL_FALSE })
+}
+
+/// Writes `count` to `count_ret` (if non-null), and as many `items` as fit
+/// within `num_entries` to `out` (if non-null).
+pub unsafe fn write_list<T: Copy>(items: &[T], num_entries: cl_uint, out: *mut T,
+        count_ret: *mut cl_uint) -> cl_int
+{
+    if out.is_null() && count_ret.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    if !out.is_null() {
+        if num_entries == 0 {
+            return CL_INVALID_VALUE;
+        }
+        for (i, item) in items.iter().take(num_entries as usize).enumerate() {
+            *out.add(i) = *item;
+        }
+    }
+    if !count_ret.is_null() {
+        *count_ret = items.len() as cl_uint;
+    }
+    CL_SUCCESS
+}
+
+/// Writes `err` to `errcode_ret` if non-null.
+pub unsafe fn set_errcode(errcode_ret: *mut cl_int, err: cl_int) {
+    if !errcode_ret.is_null() {
+        *errcode_ret = err;
+    }
+}
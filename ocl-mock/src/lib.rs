@@ -0,0 +1,70 @@
+//! # ocl-mock
+//!
+//! A host-only implementation of the OpenCL API, for running `ocl` and
+//! `ocl-core` code (and their test suites) on machines without an OpenCL
+//! platform.
+//!
+//! The library exports the same `cl*` symbols as an OpenCL ICD loader and
+//! presents a single platform ("ocl-mock") with a single CPU device (which
+//! has four compute units, can be partitioned into sub-devices and provides
+//! a couple of trivial built-in kernels).
+//! Buffers, sub-buffers, queues, events (including user events and
+//! callbacks), programs and kernels are all implemented on the host.
+//! Kernels are executed by a small interpreter for a subset of OpenCL C
+//! (see the [`clc`] module for details). Native kernels are run with the
+//! mock's internal state locked and must not call back into the API.
+//! Images, samplers, pipes and shared virtual memory are not supported.
+//!
+//! ## Selecting the mock at load time
+//!
+//! Build the dependent crate with the `dynamic_loading` feature and point
+//! `OCL_LIBRARY_PATH` at the built library:
+//!
+//! ```text
+//! cargo build -p ocl-mock
+//! OCL_LIBRARY_PATH=$PWD/target/debug/libocl_mock.so \
+//!     cargo test -p ocl --features dynamic_loading
+//! ```
+//!
+//! ## Selecting the mock at link time
+//!
+//! Alternatively, link against it directly in place of the OpenCL library:
+//!
+//! ```text
+//! cargo build -p ocl-mock
+//! ln -sf libocl_mock.so target/debug/libOpenCL.so
+//! RUSTFLAGS="-L $PWD/target/debug" LD_LIBRARY_PATH=$PWD/target/debug \
+//!     cargo test -p ocl
+//! ```
+//!
+//! Functions introduced after OpenCL 1.1 are then resolved from the linked
+//! mock as well (`OCL_LIBRARY_PATH` is only used with `dynamic_loading`).
+//!
+//! ## Limitations
+//!
+//! Commands execute on the thread which enqueues them (or which completes
+//! the user event they wait on) and work-items run one after another, so
+//! kernels relying on barriers for anything other than memory ordering
+//! will behave differently than on real hardware.
+//!
+//! Programs using unsupported OpenCL C features (vector arithmetic, loads
+//! and stores, string literals, ...) fail to build with the reason in the
+//! build log. A kernel which fails while running completes its event with
+//! `CL_OUT_OF_RESOURCES` and reports the error to the notification callback
+//! of the context.
+//!
+//! [`clc`]: clc/index.html
+
+#![allow(non_camel_case_types, non_upper_case_globals, non_snake_case, clippy::missing_safety_doc)]
+
+mod cl;
+mod info;
+mod state;
+mod platform;
+mod mem;
+mod program;
+mod queue;
+mod unsupported;
+pub mod clc;
+
+#[cfg(test)] mod tests;
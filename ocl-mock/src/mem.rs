@@ -0,0 +1,277 @@
+//! Buffer functions.
+//!
+//! Images are not supported (devices report no image support).
+
+use std::ptr;
+use crate::cl::*;
+use crate::info::{self, write_info, set_errcode};
+use crate::state::{self, State, Object, SendPtr};
+
+
+/// The alignment of buffer storage (matches `CL_DEVICE_MEM_BASE_ADDR_ALIGN`).
+pub const ALIGN: usize = 128;
+
+#[repr(C, align(128))]
+#[derive(Clone, Copy)]
+struct Chunk([u8; ALIGN]);
+
+enum Storage {
+    /// Memory allocated by us.
+    Owned(Vec<Chunk>),
+    /// Memory owned by the caller (`CL_MEM_USE_HOST_PTR`).
+    Host(SendPtr),
+    /// A region of the parent buffer.
+    Sub,
+}
+
+pub type DestructorCallback = extern "C" fn(cl_mem, *mut c_void);
+
+/// A buffer.
+pub struct Mem {
+    pub context: usize,
+    pub flags: cl_mem_flags,
+    pub size: usize,
+    pub host_ptr: SendPtr,
+    /// The parent buffer and the origin within it, for sub-buffers.
+    pub parent: Option<(usize, usize)>,
+    pub map_count: cl_uint,
+    pub destructor_callbacks: Vec<(DestructorCallback, SendPtr)>,
+    storage: Storage,
+}
+
+impl State {
+    /// Returns a pointer to the storage of the buffer `handle` and its size
+    /// in bytes.
+    pub fn mem_region(&self, handle: usize) -> Result<(*mut u8, usize), cl_int> {
+        let mem = self.mem(handle)?;
+        let base = match mem.storage {
+            Storage::Owned(ref chunks) => chunks.as_ptr() as *mut u8,
+            Storage::Host(ptr) => ptr.0 as *mut u8,
+            Storage::Sub => {
+                let (parent, origin) = mem.parent.expect("sub-buffer without parent");
+                let (base, _) = self.mem_region(parent)?;
+                unsafe { base.add(origin) }
+            },
+        };
+        Ok((base, mem.size))
+    }
+}
+
+fn access_flags() -> cl_mem_flags {
+    CL_MEM_READ_WRITE | CL_MEM_WRITE_ONLY | CL_MEM_READ_ONLY
+}
+
+fn host_access_flags() -> cl_mem_flags {
+    CL_MEM_HOST_WRITE_ONLY | CL_MEM_HOST_READ_ONLY | CL_MEM_HOST_NO_ACCESS
+}
+
+fn validate_flags(flags: cl_mem_flags) -> Result<(), cl_int> {
+    let valid = access_flags() | host_access_flags() | CL_MEM_USE_HOST_PTR |
+        CL_MEM_ALLOC_HOST_PTR | CL_MEM_COPY_HOST_PTR;
+
+    if flags & !valid != 0 ||
+        (flags & access_flags()).count_ones() > 1 ||
+        (flags & host_access_flags()).count_ones() > 1 ||
+        (flags & CL_MEM_USE_HOST_PTR != 0 &&
+            flags & (CL_MEM_ALLOC_HOST_PTR | CL_MEM_COPY_HOST_PTR) != 0)
+    {
+        return Err(CL_INVALID_VALUE);
+    }
+    Ok(())
+}
+
+unsafe fn create_buffer(context: usize, flags: cl_mem_flags, size: size_t, host_ptr: *mut c_void)
+        -> Result<usize, cl_int>
+{
+    validate_flags(flags)?;
+
+    let uses_host_ptr = flags & (CL_MEM_USE_HOST_PTR | CL_MEM_COPY_HOST_PTR) != 0;
+    if uses_host_ptr == host_ptr.is_null() {
+        return Err(CL_INVALID_HOST_PTR);
+    }
+
+    if size == 0 || size as u64 > (1u64 << 30) {
+        return Err(CL_INVALID_BUFFER_SIZE);
+    }
+
+    let flags = if flags & access_flags() == 0 { flags | CL_MEM_READ_WRITE } else { flags };
+
+    let storage = if flags & CL_MEM_USE_HOST_PTR != 0 {
+        Storage::Host(SendPtr(host_ptr))
+    } else {
+        let mut chunks = vec![Chunk([0; ALIGN]); size.div_ceil(ALIGN)];
+        if flags & CL_MEM_COPY_HOST_PTR != 0 {
+            ptr::copy_nonoverlapping(host_ptr as *const u8, chunks.as_mut_ptr() as *mut u8, size);
+        }
+        Storage::Owned(chunks)
+    };
+
+    let host_ptr = if flags & CL_MEM_USE_HOST_PTR != 0 { host_ptr } else { ptr::null_mut() };
+
+    state::with(|s| {
+        s.context(context)?;
+        Ok(s.insert(Object::Mem(Mem {
+            context,
+            flags,
+            size,
+            host_ptr: SendPtr(host_ptr),
+            parent: None,
+            map_count: 0,
+            destructor_callbacks: Vec::new(),
+            storage,
+        })))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateBuffer(context: cl_context, flags: cl_mem_flags, size: size_t,
+        host_ptr: *mut c_void, errcode_ret: *mut cl_int) -> cl_mem
+{
+    match create_buffer(context as usize, flags, size, host_ptr) {
+        Ok(handle) => {
+            set_errcode(errcode_ret, CL_SUCCESS);
+            handle as cl_mem
+        },
+        Err(err) => {
+            set_errcode(errcode_ret, err);
+            ptr::null_mut()
+        },
+    }
+}
+
+unsafe fn create_sub_buffer(buffer: usize, flags: cl_mem_flags,
+        buffer_create_type: cl_buffer_create_type, buffer_create_info: *const c_void)
+        -> Result<usize, cl_int>
+{
+    if buffer_create_type != CL_BUFFER_CREATE_TYPE_REGION || buffer_create_info.is_null() {
+        return Err(CL_INVALID_VALUE);
+    }
+    let region = &*(buffer_create_info as *const cl_buffer_region);
+
+    validate_flags(flags)?;
+    if flags & (CL_MEM_USE_HOST_PTR | CL_MEM_ALLOC_HOST_PTR | CL_MEM_COPY_HOST_PTR) != 0 {
+        return Err(CL_INVALID_VALUE);
+    }
+
+    state::with(|s| {
+        let (context, parent_flags, parent_size, parent_host_ptr) = {
+            let parent = s.mem(buffer)?;
+            if parent.parent.is_some() { return Err(CL_INVALID_MEM_OBJECT); }
+            (parent.context, parent.flags, parent.size, parent.host_ptr)
+        };
+
+        if region.size == 0 {
+            return Err(CL_INVALID_BUFFER_SIZE);
+        }
+        if region.origin.checked_add(region.size).is_none_or(|end| end > parent_size) {
+            return Err(CL_INVALID_VALUE);
+        }
+        if !region.origin.is_multiple_of(ALIGN) {
+            return Err(CL_MISALIGNED_SUB_BUFFER_OFFSET);
+        }
+
+        // Access flags not specified are inherited from the parent:
+        let mut flags = flags;
+        if flags & access_flags() == 0 {
+            flags |= parent_flags & access_flags();
+        }
+        if flags & host_access_flags() == 0 {
+            flags |= parent_flags & host_access_flags();
+        }
+        flags |= parent_flags & (CL_MEM_USE_HOST_PTR | CL_MEM_ALLOC_HOST_PTR | CL_MEM_COPY_HOST_PTR);
+
+        let host_ptr = if parent_host_ptr.0.is_null() {
+            ptr::null_mut()
+        } else {
+            (parent_host_ptr.0 as *mut u8).add(region.origin) as *mut c_void
+        };
+
+        Ok(s.insert(Object::Mem(Mem {
+            context,
+            flags,
+            size: region.size,
+            host_ptr: SendPtr(host_ptr),
+            parent: Some((buffer, region.origin)),
+            map_count: 0,
+            destructor_callbacks: Vec::new(),
+            storage: Storage::Sub,
+        })))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateSubBuffer(buffer: cl_mem, flags: cl_mem_flags,
+        buffer_create_type: cl_buffer_create_type, buffer_create_info: *const c_void,
+        errcode_ret: *mut cl_int) -> cl_mem
+{
+    match create_sub_buffer(buffer as usize, flags, buffer_create_type, buffer_create_info) {
+        Ok(handle) => {
+            set_errcode(errcode_ret, CL_SUCCESS);
+            handle as cl_mem
+        },
+        Err(err) => {
+            set_errcode(errcode_ret, err);
+            ptr::null_mut()
+        },
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clRetainMemObject(memobj: cl_mem) -> cl_int {
+    state::with(|s| {
+        s.mem(memobj as usize)?;
+        s.retain(memobj as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clReleaseMemObject(memobj: cl_mem) -> cl_int {
+    state::with(|s| {
+        s.mem(memobj as usize)?;
+        s.release(memobj as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetMemObjectInfo(memobj: cl_mem, param_name: cl_mem_info,
+        param_value_size: size_t, param_value: *mut c_void, param_value_size_ret: *mut size_t)
+        -> cl_int
+{
+    let bytes = state::with(|s| {
+        let refs = s.ref_count(memobj as usize);
+        let mem = s.mem(memobj as usize)?;
+
+        match param_name {
+            CL_MEM_TYPE => info::val(CL_MEM_OBJECT_BUFFER),
+            CL_MEM_FLAGS => info::val(mem.flags),
+            CL_MEM_SIZE => info::val(mem.size),
+            CL_MEM_HOST_PTR => info::val(mem.host_ptr.0),
+            CL_MEM_MAP_COUNT => info::val(mem.map_count),
+            CL_MEM_REFERENCE_COUNT => info::val(refs),
+            CL_MEM_CONTEXT => info::handle(mem.context),
+            CL_MEM_ASSOCIATED_MEMOBJECT => info::handle(mem.parent.map(|p| p.0).unwrap_or(0)),
+            CL_MEM_OFFSET => info::val(mem.parent.map(|p| p.1).unwrap_or(0)),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+
+    write_info(bytes, param_value_size, param_value, param_value_size_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clSetMemObjectDestructorCallback(memobj: cl_mem,
+        pfn_notify: Option<DestructorCallback>, user_data: *mut c_void) -> cl_int
+{
+    let pfn_notify = match pfn_notify {
+        Some(f) => f,
+        None => return CL_INVALID_VALUE,
+    };
+
+    state::with(|s| {
+        let mem = s.mem_mut(memobj as usize)?;
+        mem.destructor_callbacks.push((pfn_notify, SendPtr(user_data)));
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
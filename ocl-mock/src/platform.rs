@@ -0,0 +1,505 @@
+//! Platform, device and context functions.
+
+use std::ptr;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+use crate::cl::*;
+use crate::info::{self, InfoBytes, write_info, write_list, set_errcode};
+use crate::program::BUILT_IN_KERNELS;
+use crate::state::{self, Object, SendPtr, PLATFORM, DEVICE};
+
+
+const PLATFORM_NAME: &str = "ocl-mock";
+const PLATFORM_VERSION: &str = concat!("OpenCL 1.2 ocl-mock ", env!("CARGO_PKG_VERSION"));
+const DEVICE_NAME: &str = "ocl-mock CPU";
+const DEVICE_VERSION: &str = "OpenCL 1.2 ocl-mock";
+const DEVICE_EXTENSIONS: &str = "cl_khr_fp64 cl_khr_byte_addressable_store";
+
+/// The number of compute units of the (root) device.
+const COMPUTE_UNITS: cl_uint = 4;
+
+const FIRST_SUB_DEVICE: usize = 0x0c1_1000;
+
+
+/// The error notification callback of a context.
+pub type ContextCallback = extern "C" fn(*const c_char, *const c_void, size_t, *mut c_void);
+
+/// A context.
+pub struct Context {
+    pub properties: Vec<cl_context_properties>,
+    pub devices: Vec<usize>,
+    /// Notified of errors which occur at runtime (e.g. failed kernels).
+    pub notify: Option<(ContextCallback, SendPtr)>,
+}
+
+/// A device created by partitioning the device (or another sub-device).
+struct SubDevice {
+    parent: usize,
+    compute_units: cl_uint,
+    /// The properties the device was partitioned with (null terminated).
+    partition_type: Vec<cl_device_partition_property>,
+    refs: cl_uint,
+}
+
+/// Sub-devices are kept in their own registry (rather than in `state`) so
+/// that devices can be validated while the main registry is locked.
+struct SubDevices {
+    next_handle: usize,
+    devices: BTreeMap<usize, SubDevice>,
+}
+
+static SUB_DEVICES: Mutex<SubDevices> = Mutex::new(SubDevices {
+    next_handle: FIRST_SUB_DEVICE,
+    devices: BTreeMap::new(),
+});
+
+fn sub_devices() -> MutexGuard<'static, SubDevices> {
+    SUB_DEVICES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Returns true if `device` is a valid device handle.
+pub fn is_device(device: cl_device_id) -> bool {
+    device as usize == DEVICE || sub_devices().devices.contains_key(&(device as usize))
+}
+
+fn device_types() -> cl_device_type {
+    CL_DEVICE_TYPE_CPU | CL_DEVICE_TYPE_DEFAULT
+}
+
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetPlatformIDs(num_entries: cl_uint, platforms: *mut cl_platform_id,
+        num_platforms: *mut cl_uint) -> cl_int
+{
+    write_list(&[PLATFORM as cl_platform_id], num_entries, platforms, num_platforms)
+}
+
+/// The `cl_khr_icd` entry point, allowing this library to be listed within
+/// an `.icd` file (for ICD diagnostics). Mock objects have no dispatch table
+/// so the library can not be used through an actual ICD loader.
+#[no_mangle]
+pub unsafe extern "system" fn clIcdGetPlatformIDsKHR(num_entries: cl_uint,
+        platforms: *mut cl_platform_id, num_platforms: *mut cl_uint) -> cl_int
+{
+    clGetPlatformIDs(num_entries, platforms, num_platforms)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetPlatformInfo(platform: cl_platform_id, param_name: cl_platform_info,
+        param_value_size: size_t, param_value: *mut c_void, param_value_size_ret: *mut size_t)
+        -> cl_int
+{
+    if !platform.is_null() && platform as usize != PLATFORM {
+        return CL_INVALID_PLATFORM;
+    }
+
+    let bytes = match param_name {
+        CL_PLATFORM_PROFILE => info::string("FULL_PROFILE"),
+        CL_PLATFORM_VERSION => info::string(PLATFORM_VERSION),
+        CL_PLATFORM_NAME => info::string(PLATFORM_NAME),
+        CL_PLATFORM_VENDOR => info::string(PLATFORM_NAME),
+        CL_PLATFORM_EXTENSIONS => info::string(""),
+        _ => Err(CL_INVALID_VALUE),
+    };
+
+    write_info(bytes, param_value_size, param_value, param_value_size_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetDeviceIDs(platform: cl_platform_id, device_type: cl_device_type,
+        num_entries: cl_uint, devices: *mut cl_device_id, num_devices: *mut cl_uint) -> cl_int
+{
+    if !platform.is_null() && platform as usize != PLATFORM {
+        return CL_INVALID_PLATFORM;
+    }
+
+    let valid_types = CL_DEVICE_TYPE_DEFAULT | CL_DEVICE_TYPE_CPU | CL_DEVICE_TYPE_GPU |
+        CL_DEVICE_TYPE_ACCELERATOR | CL_DEVICE_TYPE_CUSTOM;
+    if device_type != CL_DEVICE_TYPE_ALL && (device_type & !valid_types != 0 || device_type == 0) {
+        return CL_INVALID_DEVICE_TYPE;
+    }
+
+    if device_type & device_types() == 0 {
+        return CL_DEVICE_NOT_FOUND;
+    }
+
+    write_list(&[DEVICE as cl_device_id], num_entries, devices, num_devices)
+}
+
+fn device_info(device: usize, param_name: cl_device_info) -> InfoBytes {
+    let fp_config = CL_FP_DENORM | CL_FP_INF_NAN | CL_FP_ROUND_TO_NEAREST | CL_FP_FMA;
+
+    let (compute_units, parent, partition_type, refs) = match sub_devices().devices.get(&device) {
+        Some(sd) => (sd.compute_units, sd.parent, sd.partition_type.clone(), sd.refs),
+        None => (COMPUTE_UNITS, 0, vec![0], 1),
+    };
+    let max_sub_devices = if compute_units > 1 { compute_units } else { 0 };
+
+    match param_name {
+        CL_DEVICE_TYPE => info::val(CL_DEVICE_TYPE_CPU),
+        CL_DEVICE_VENDOR_ID => info::val(0 as cl_uint),
+        CL_DEVICE_MAX_COMPUTE_UNITS => info::val(compute_units),
+        CL_DEVICE_MAX_WORK_ITEM_DIMENSIONS => info::val(3 as cl_uint),
+        CL_DEVICE_MAX_WORK_GROUP_SIZE => info::val(1024 as size_t),
+        CL_DEVICE_MAX_WORK_ITEM_SIZES => info::vals(&[1024 as size_t, 1024, 1024]),
+        CL_DEVICE_PREFERRED_VECTOR_WIDTH_CHAR | CL_DEVICE_PREFERRED_VECTOR_WIDTH_SHORT |
+        CL_DEVICE_PREFERRED_VECTOR_WIDTH_INT | CL_DEVICE_PREFERRED_VECTOR_WIDTH_LONG |
+        CL_DEVICE_PREFERRED_VECTOR_WIDTH_FLOAT | CL_DEVICE_PREFERRED_VECTOR_WIDTH_DOUBLE |
+        CL_DEVICE_NATIVE_VECTOR_WIDTH_CHAR | CL_DEVICE_NATIVE_VECTOR_WIDTH_SHORT |
+        CL_DEVICE_NATIVE_VECTOR_WIDTH_INT | CL_DEVICE_NATIVE_VECTOR_WIDTH_LONG |
+        CL_DEVICE_NATIVE_VECTOR_WIDTH_FLOAT | CL_DEVICE_NATIVE_VECTOR_WIDTH_DOUBLE =>
+            info::val(1 as cl_uint),
+        CL_DEVICE_PREFERRED_VECTOR_WIDTH_HALF | CL_DEVICE_NATIVE_VECTOR_WIDTH_HALF =>
+            info::val(0 as cl_uint),
+        CL_DEVICE_MAX_CLOCK_FREQUENCY => info::val(1000 as cl_uint),
+        CL_DEVICE_ADDRESS_BITS => info::val(64 as cl_uint),
+        CL_DEVICE_MAX_READ_IMAGE_ARGS | CL_DEVICE_MAX_WRITE_IMAGE_ARGS |
+        CL_DEVICE_MAX_SAMPLERS => info::val(0 as cl_uint),
+        CL_DEVICE_MAX_MEM_ALLOC_SIZE => info::val(1u64 << 30),
+        CL_DEVICE_IMAGE2D_MAX_WIDTH | CL_DEVICE_IMAGE2D_MAX_HEIGHT |
+        CL_DEVICE_IMAGE3D_MAX_WIDTH | CL_DEVICE_IMAGE3D_MAX_HEIGHT |
+        CL_DEVICE_IMAGE3D_MAX_DEPTH | CL_DEVICE_IMAGE_MAX_BUFFER_SIZE |
+        CL_DEVICE_IMAGE_MAX_ARRAY_SIZE => info::val(0 as size_t),
+        CL_DEVICE_IMAGE_SUPPORT => info::boolean(false),
+        CL_DEVICE_MAX_PARAMETER_SIZE => info::val(1024 as size_t),
+        CL_DEVICE_MEM_BASE_ADDR_ALIGN => info::val(1024 as cl_uint),
+        CL_DEVICE_MIN_DATA_TYPE_ALIGN_SIZE => info::val(128 as cl_uint),
+        CL_DEVICE_SINGLE_FP_CONFIG => info::val(fp_config),
+        CL_DEVICE_DOUBLE_FP_CONFIG => info::val(fp_config | CL_FP_ROUND_TO_ZERO | CL_FP_ROUND_TO_INF),
+        CL_DEVICE_HALF_FP_CONFIG => info::val(0 as cl_device_fp_config),
+        CL_DEVICE_GLOBAL_MEM_CACHE_TYPE => info::val(CL_NONE),
+        CL_DEVICE_GLOBAL_MEM_CACHELINE_SIZE => info::val(64 as cl_uint),
+        CL_DEVICE_GLOBAL_MEM_CACHE_SIZE => info::val(0 as cl_ulong),
+        CL_DEVICE_GLOBAL_MEM_SIZE => info::val(1u64 << 32),
+        CL_DEVICE_MAX_CONSTANT_BUFFER_SIZE => info::val(1u64 << 16),
+        CL_DEVICE_MAX_CONSTANT_ARGS => info::val(8 as cl_uint),
+        CL_DEVICE_LOCAL_MEM_TYPE => info::val(CL_GLOBAL),
+        CL_DEVICE_LOCAL_MEM_SIZE => info::val(1u64 << 15),
+        CL_DEVICE_ERROR_CORRECTION_SUPPORT => info::boolean(false),
+        CL_DEVICE_PROFILING_TIMER_RESOLUTION => info::val(1 as size_t),
+        CL_DEVICE_ENDIAN_LITTLE => info::boolean(cfg!(target_endian = "little")),
+        CL_DEVICE_AVAILABLE | CL_DEVICE_COMPILER_AVAILABLE | CL_DEVICE_LINKER_AVAILABLE |
+        CL_DEVICE_HOST_UNIFIED_MEMORY | CL_DEVICE_PREFERRED_INTEROP_USER_SYNC =>
+            info::boolean(true),
+        CL_DEVICE_EXECUTION_CAPABILITIES => info::val(CL_EXEC_KERNEL | CL_EXEC_NATIVE_KERNEL),
+        CL_DEVICE_QUEUE_PROPERTIES => info::val(CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE |
+            CL_QUEUE_PROFILING_ENABLE),
+        CL_DEVICE_NAME => info::string(DEVICE_NAME),
+        CL_DEVICE_VENDOR => info::string(PLATFORM_NAME),
+        CL_DRIVER_VERSION => info::string(env!("CARGO_PKG_VERSION")),
+        CL_DEVICE_PROFILE => info::string("FULL_PROFILE"),
+        CL_DEVICE_VERSION => info::string(DEVICE_VERSION),
+        CL_DEVICE_EXTENSIONS => info::string(DEVICE_EXTENSIONS),
+        CL_DEVICE_PLATFORM => info::handle(PLATFORM),
+        CL_DEVICE_OPENCL_C_VERSION => info::string("OpenCL C 1.2 "),
+        CL_DEVICE_BUILT_IN_KERNELS => info::string(&BUILT_IN_KERNELS.iter()
+            .map(|&(name, _)| name).collect::<Vec<_>>().join(";")),
+        CL_DEVICE_PARENT_DEVICE => info::handle(parent),
+        CL_DEVICE_PARTITION_MAX_SUB_DEVICES => info::val(max_sub_devices),
+        CL_DEVICE_PARTITION_PROPERTIES => if max_sub_devices > 0 {
+            info::vals(&[CL_DEVICE_PARTITION_EQUALLY as cl_device_partition_property,
+                CL_DEVICE_PARTITION_BY_COUNTS as cl_device_partition_property])
+        } else {
+            info::val(0 as cl_device_partition_property)
+        },
+        CL_DEVICE_PARTITION_TYPE => info::vals(&partition_type),
+        CL_DEVICE_PARTITION_AFFINITY_DOMAIN => info::val(0 as cl_device_affinity_domain),
+        CL_DEVICE_REFERENCE_COUNT => info::val(refs),
+        CL_DEVICE_PRINTF_BUFFER_SIZE => info::val(0 as size_t),
+        CL_DEVICE_IMAGE_PITCH_ALIGNMENT | CL_DEVICE_IMAGE_BASE_ADDRESS_ALIGNMENT =>
+            info::val(0 as cl_uint),
+        _ => Err(CL_INVALID_VALUE),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetDeviceInfo(device: cl_device_id, param_name: cl_device_info,
+        param_value_size: size_t, param_value: *mut c_void, param_value_size_ret: *mut size_t)
+        -> cl_int
+{
+    if !is_device(device) {
+        return CL_INVALID_DEVICE;
+    }
+    write_info(device_info(device as usize, param_name), param_value_size, param_value,
+        param_value_size_ret)
+}
+
+/// Returns the compute unit counts of the sub-devices described by the
+/// partition property list `properties`.
+unsafe fn partition_counts(properties: *const cl_device_partition_property,
+        compute_units: cl_uint) -> Result<(Vec<cl_uint>, Vec<cl_device_partition_property>), cl_int>
+{
+    if properties.is_null() {
+        return Err(CL_INVALID_VALUE);
+    }
+    if compute_units < 2 {
+        return Err(CL_DEVICE_PARTITION_FAILED);
+    }
+
+    let mut props = vec![*properties];
+    let counts = match *properties as cl_uint {
+        CL_DEVICE_PARTITION_EQUALLY => {
+            let n = *properties.add(1);
+            props.push(n);
+            if n <= 0 || n as cl_uint > compute_units {
+                return Err(CL_INVALID_VALUE);
+            }
+            vec![n as cl_uint; (compute_units / n as cl_uint) as usize]
+        },
+        CL_DEVICE_PARTITION_BY_COUNTS => {
+            let mut counts = Vec::new();
+            let mut i = 1;
+            loop {
+                let c = *properties.add(i);
+                props.push(c);
+                if c == CL_DEVICE_PARTITION_BY_COUNTS_LIST_END as cl_device_partition_property {
+                    break;
+                }
+                if c < 0 {
+                    return Err(CL_INVALID_VALUE);
+                }
+                counts.push(c as cl_uint);
+                i += 1;
+            }
+            if counts.is_empty() || counts.len() > compute_units as usize ||
+                counts.iter().sum::<cl_uint>() > compute_units
+            {
+                return Err(CL_INVALID_DEVICE_PARTITION_COUNT);
+            }
+            counts
+        },
+        _ => return Err(CL_INVALID_VALUE),
+    };
+
+    props.push(0);
+    Ok((counts, props))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateSubDevices(in_device: cl_device_id,
+        properties: *const cl_device_partition_property, num_devices: cl_uint,
+        out_devices: *mut cl_device_id, num_devices_ret: *mut cl_uint) -> cl_int
+{
+    let mut sds = sub_devices();
+    let parent = in_device as usize;
+
+    let compute_units = match sds.devices.get(&parent) {
+        Some(sd) => sd.compute_units,
+        None if parent == DEVICE => COMPUTE_UNITS,
+        None => return CL_INVALID_DEVICE,
+    };
+
+    let (counts, partition_type) = match partition_counts(properties, compute_units) {
+        Ok(r) => r,
+        Err(err) => return err,
+    };
+
+    if !out_devices.is_null() {
+        if (num_devices as usize) < counts.len() {
+            return CL_INVALID_VALUE;
+        }
+        for (i, &compute_units) in counts.iter().enumerate() {
+            let handle = sds.next_handle;
+            sds.next_handle += 0x40;
+            sds.devices.insert(handle, SubDevice {
+                parent,
+                compute_units,
+                partition_type: partition_type.clone(),
+                refs: 1,
+            });
+            *out_devices.add(i) = handle as cl_device_id;
+        }
+    }
+
+    if !num_devices_ret.is_null() {
+        *num_devices_ret = counts.len() as cl_uint;
+    }
+    CL_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clRetainDevice(device: cl_device_id) -> cl_int {
+    if device as usize == DEVICE {
+        return CL_SUCCESS;
+    }
+    match sub_devices().devices.get_mut(&(device as usize)) {
+        Some(sd) => {
+            sd.refs += 1;
+            CL_SUCCESS
+        },
+        None => CL_INVALID_DEVICE,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clReleaseDevice(device: cl_device_id) -> cl_int {
+    if device as usize == DEVICE {
+        return CL_SUCCESS;
+    }
+    let mut sds = sub_devices();
+    let destroy = match sds.devices.get_mut(&(device as usize)) {
+        Some(sd) => {
+            sd.refs -= 1;
+            sd.refs == 0
+        },
+        None => return CL_INVALID_DEVICE,
+    };
+    if destroy {
+        sds.devices.remove(&(device as usize));
+    }
+    CL_SUCCESS
+}
+
+/// Validates a null terminated context property list, returning a copy
+/// (including the terminator).
+unsafe fn context_properties(properties: *const cl_context_properties)
+        -> Result<Vec<cl_context_properties>, cl_int>
+{
+    let mut props = Vec::new();
+    if properties.is_null() { return Ok(props); }
+
+    let mut i = 0;
+    loop {
+        let name = *properties.add(i);
+        props.push(name);
+        if name == 0 { break; }
+        let value = *properties.add(i + 1);
+        props.push(value);
+
+        match name as cl_uint {
+            CL_CONTEXT_PLATFORM => {
+                if value as usize != PLATFORM { return Err(CL_INVALID_PLATFORM); }
+            },
+            CL_CONTEXT_INTEROP_USER_SYNC => (),
+            _ => return Err(CL_INVALID_PROPERTY),
+        }
+        i += 2;
+    }
+
+    Ok(props)
+}
+
+unsafe fn create_context(properties: *const cl_context_properties, devices: Vec<usize>,
+        pfn_notify: Option<ContextCallback>, user_data: *mut c_void, errcode_ret: *mut cl_int)
+        -> cl_context
+{
+    if pfn_notify.is_none() && !user_data.is_null() {
+        set_errcode(errcode_ret, CL_INVALID_VALUE);
+        return ptr::null_mut();
+    }
+
+    let properties = match context_properties(properties) {
+        Ok(p) => p,
+        Err(err) => {
+            set_errcode(errcode_ret, err);
+            return ptr::null_mut();
+        },
+    };
+
+    let notify = pfn_notify.map(|pfn| (pfn, SendPtr(user_data)));
+    let handle = state::with(|s| s.insert(Object::Context(Context { properties, devices,
+        notify })));
+    set_errcode(errcode_ret, CL_SUCCESS);
+    handle as cl_context
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateContext(properties: *const cl_context_properties,
+        num_devices: cl_uint, devices: *const cl_device_id,
+        pfn_notify: Option<ContextCallback>, user_data: *mut c_void, errcode_ret: *mut cl_int)
+        -> cl_context
+{
+    if num_devices == 0 || devices.is_null() {
+        set_errcode(errcode_ret, CL_INVALID_VALUE);
+        return ptr::null_mut();
+    }
+
+    let mut device_list = Vec::with_capacity(num_devices as usize);
+    for i in 0..num_devices as usize {
+        let device = *devices.add(i);
+        if !is_device(device) {
+            set_errcode(errcode_ret, CL_INVALID_DEVICE);
+            return ptr::null_mut();
+        }
+        device_list.push(device as usize);
+    }
+
+    create_context(properties, device_list, pfn_notify, user_data, errcode_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateContextFromType(properties: *const cl_context_properties,
+        device_type: cl_device_type,
+        pfn_notify: Option<ContextCallback>, user_data: *mut c_void, errcode_ret: *mut cl_int)
+        -> cl_context
+{
+    if device_type != CL_DEVICE_TYPE_ALL && device_type & device_types() == 0 {
+        set_errcode(errcode_ret, CL_DEVICE_NOT_FOUND);
+        return ptr::null_mut();
+    }
+
+    create_context(properties, vec![DEVICE], pfn_notify, user_data, errcode_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clRetainContext(context: cl_context) -> cl_int {
+    state::with(|s| {
+        s.context(context as usize)?;
+        s.retain(context as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clReleaseContext(context: cl_context) -> cl_int {
+    state::with(|s| {
+        s.context(context as usize)?;
+        s.release(context as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetContextInfo(context: cl_context, param_name: cl_context_info,
+        param_value_size: size_t, param_value: *mut c_void, param_value_size_ret: *mut size_t)
+        -> cl_int
+{
+    let bytes = state::with(|s| {
+        let refs = s.ref_count(context as usize);
+        let ctx = s.context(context as usize)?;
+
+        match param_name {
+            CL_CONTEXT_REFERENCE_COUNT => info::val(refs),
+            CL_CONTEXT_DEVICES => info::vals(&ctx.devices),
+            CL_CONTEXT_NUM_DEVICES => info::val(ctx.devices.len() as cl_uint),
+            CL_CONTEXT_PROPERTIES => info::vals(&ctx.properties),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+
+    write_info(bytes, param_value_size, param_value, param_value_size_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clUnloadCompiler() -> cl_int {
+    CL_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clUnloadPlatformCompiler(platform: cl_platform_id) -> cl_int {
+    if platform as usize == PLATFORM { CL_SUCCESS } else { CL_INVALID_PLATFORM }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetExtensionFunctionAddress(_func_name: *const c_char)
+        -> *mut c_void
+{
+    ptr::null_mut()
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetExtensionFunctionAddressForPlatform(_platform: cl_platform_id,
+        _func_name: *const c_char) -> *mut c_void
+{
+    ptr::null_mut()
+}
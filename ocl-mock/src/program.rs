@@ -0,0 +1,805 @@
+//! Program and kernel functions.
+//!
+//! Programs are built by parsing their source with the interpreter in
+//! `clc`. Program binaries consist of `BINARY_MAGIC` followed by the program
+//! source and are rebuilt from source when loaded. Built-in kernels are
+//! ordinary OpenCL C compiled when their program is created.
+//!
+//! Compiling a program only preprocesses it, replacing `#include`s of
+//! embedded headers with their source. Linking concatenates the objects and
+//! libraries in the order given and parses the result, so functions must be
+//! defined by an earlier input than the one calling them.
+
+use std::ffi::CStr;
+use std::{ptr, slice};
+use std::sync::Arc;
+use crate::cl::*;
+use crate::clc::{Module, Ty, AddrSpace, Access};
+use crate::info::{self, InfoBytes, write_info, set_errcode};
+use crate::platform::is_device;
+use crate::state::{self, State, Object, SendPtr, DEVICE};
+
+
+/// The prefix of all program binaries.
+pub const BINARY_MAGIC: &[u8] = b"OCLMOCK1\n";
+
+/// The names and source of the device's built-in kernels.
+pub const BUILT_IN_KERNELS: &[(&str, &str)] = &[
+    ("ocl_mock_fill_float", "__kernel void ocl_mock_fill_float(__global float* buffer, \
+        float value) { buffer[get_global_id(0)] = value; }"),
+    ("ocl_mock_scale_float", "__kernel void ocl_mock_scale_float(__global float* buffer, \
+        float factor) { buffer[get_global_id(0)] *= factor; }"),
+];
+
+
+/// A program.
+pub struct Program {
+    pub context: usize,
+    pub source: String,
+    pub options: String,
+    pub status: cl_build_status,
+    pub log: String,
+    pub module: Option<Arc<Module>>,
+    pub built_in: bool,
+    pub binary_type: cl_program_binary_type,
+    /// The preprocessed source of a compiled object or library.
+    pub object: Option<String>,
+}
+
+/// A kernel argument value.
+#[derive(Clone, Debug)]
+pub enum KernelArg {
+    Bytes(Vec<u8>),
+    Mem(usize),
+    Null,
+    Local(usize),
+}
+
+/// A kernel.
+pub struct Kernel {
+    pub program: usize,
+    pub module: Arc<Module>,
+    pub idx: usize,
+    pub args: Vec<Option<KernelArg>>,
+}
+
+impl Kernel {
+    fn name(&self) -> &str {
+        &self.module.functions[self.idx].name
+    }
+}
+
+
+unsafe fn insert_program(program: Program, errcode_ret: *mut cl_int) -> cl_program {
+    let result = state::with(|s| {
+        s.context(program.context)?;
+        Ok(s.insert(Object::Program(program)))
+    });
+
+    match result {
+        Ok(handle) => {
+            set_errcode(errcode_ret, CL_SUCCESS);
+            handle as cl_program
+        },
+        Err(err) => {
+            set_errcode(errcode_ret, err);
+            ptr::null_mut()
+        },
+    }
+}
+
+unsafe fn new_program(context: usize, source: String, errcode_ret: *mut cl_int) -> cl_program {
+    insert_program(Program {
+        context,
+        source,
+        options: String::new(),
+        status: CL_BUILD_NONE,
+        log: String::new(),
+        module: None,
+        built_in: false,
+        binary_type: CL_PROGRAM_BINARY_TYPE_NONE as cl_program_binary_type,
+        object: None,
+    }, errcode_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateProgramWithSource(context: cl_context, count: cl_uint,
+        strings: *const *const c_char, lengths: *const size_t, errcode_ret: *mut cl_int)
+        -> cl_program
+{
+    if count == 0 || strings.is_null() {
+        set_errcode(errcode_ret, CL_INVALID_VALUE);
+        return ptr::null_mut();
+    }
+
+    let mut source = Vec::new();
+    for i in 0..count as usize {
+        let string = *strings.add(i);
+        if string.is_null() {
+            set_errcode(errcode_ret, CL_INVALID_VALUE);
+            return ptr::null_mut();
+        }
+        let len = if lengths.is_null() { 0 } else { *lengths.add(i) };
+        if len == 0 {
+            source.extend_from_slice(CStr::from_ptr(string).to_bytes());
+        } else {
+            source.extend_from_slice(slice::from_raw_parts(string as *const u8, len));
+        }
+    }
+
+    new_program(context as usize, String::from_utf8_lossy(&source).into_owned(), errcode_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateProgramWithBinary(context: cl_context, num_devices: cl_uint,
+        device_list: *const cl_device_id, lengths: *const size_t,
+        binaries: *const *const u8, binary_status: *mut cl_int, errcode_ret: *mut cl_int)
+        -> cl_program
+{
+    if num_devices != 1 || device_list.is_null() || lengths.is_null() || binaries.is_null() {
+        set_errcode(errcode_ret, CL_INVALID_VALUE);
+        return ptr::null_mut();
+    }
+    if !is_device(*device_list) {
+        set_errcode(errcode_ret, CL_INVALID_DEVICE);
+        return ptr::null_mut();
+    }
+
+    let (len, binary) = (*lengths, *binaries);
+    if len == 0 || binary.is_null() {
+        set_errcode(errcode_ret, CL_INVALID_VALUE);
+        return ptr::null_mut();
+    }
+
+    let bytes = slice::from_raw_parts(binary, len);
+    let status = if bytes.starts_with(BINARY_MAGIC) { CL_SUCCESS } else { CL_INVALID_BINARY };
+    if !binary_status.is_null() {
+        *binary_status = status;
+    }
+    if status != CL_SUCCESS {
+        set_errcode(errcode_ret, status);
+        return ptr::null_mut();
+    }
+
+    let source = String::from_utf8_lossy(&bytes[BINARY_MAGIC.len()..]).into_owned();
+    new_program(context as usize, source, errcode_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateProgramWithBuiltInKernels(context: cl_context,
+        num_devices: cl_uint, device_list: *const cl_device_id, kernel_names: *const c_char,
+        errcode_ret: *mut cl_int) -> cl_program
+{
+    if num_devices == 0 || kernel_names.is_null() {
+        set_errcode(errcode_ret, CL_INVALID_VALUE);
+        return ptr::null_mut();
+    }
+    if let Err(err) = validate_devices(num_devices, device_list) {
+        set_errcode(errcode_ret, err);
+        return ptr::null_mut();
+    }
+
+    let mut source = String::new();
+    for name in CStr::from_ptr(kernel_names).to_string_lossy().split(';') {
+        match BUILT_IN_KERNELS.iter().find(|&&(n, _)| n == name.trim()) {
+            Some(&(_, src)) => { source.push_str(src); source.push('\n'); },
+            None => {
+                set_errcode(errcode_ret, CL_INVALID_VALUE);
+                return ptr::null_mut();
+            },
+        }
+    }
+
+    let module = Module::compile(&source, "").expect("ocl-mock: invalid built-in kernel source");
+
+    insert_program(Program {
+        context: context as usize,
+        source,
+        options: String::new(),
+        status: CL_BUILD_SUCCESS,
+        log: String::new(),
+        module: Some(Arc::new(module)),
+        built_in: true,
+        binary_type: CL_PROGRAM_BINARY_TYPE_EXECUTABLE as cl_program_binary_type,
+        object: None,
+    }, errcode_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clRetainProgram(program: cl_program) -> cl_int {
+    state::with(|s| {
+        s.program(program as usize)?;
+        s.retain(program as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clReleaseProgram(program: cl_program) -> cl_int {
+    state::with(|s| {
+        s.program(program as usize)?;
+        s.release(program as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+/// Returns the handles of all kernels created from `program`.
+fn program_kernels(s: &State, program: usize) -> Vec<usize> {
+    s.handles(|obj| match *obj {
+        Object::Kernel(ref k) => k.program == program,
+        _ => false,
+    })
+}
+
+unsafe fn validate_devices(num_devices: cl_uint, device_list: *const cl_device_id)
+        -> Result<(), cl_int>
+{
+    if (num_devices == 0) != device_list.is_null() {
+        return Err(CL_INVALID_VALUE);
+    }
+    for i in 0..num_devices as usize {
+        if !is_device(*device_list.add(i)) {
+            return Err(CL_INVALID_DEVICE);
+        }
+    }
+    Ok(())
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clBuildProgram(program: cl_program, num_devices: cl_uint,
+        device_list: *const cl_device_id, options: *const c_char,
+        pfn_notify: Option<extern "C" fn(cl_program, *mut c_void)>, user_data: *mut c_void)
+        -> cl_int
+{
+    if let Err(err) = validate_devices(num_devices, device_list) {
+        return err;
+    }
+    if pfn_notify.is_none() && !user_data.is_null() {
+        return CL_INVALID_VALUE;
+    }
+
+    let options = if options.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(options).to_string_lossy().into_owned()
+    };
+
+    let user_data = SendPtr(user_data);
+
+    state::with(|s| {
+        if s.program(program as usize)?.built_in
+                || !program_kernels(s, program as usize).is_empty() {
+            return Err(CL_INVALID_OPERATION);
+        }
+
+        let prog = s.program_mut(program as usize)?;
+        let result = Module::compile(&prog.source, &options);
+        prog.options = options;
+
+        let status = match result {
+            Ok(module) => {
+                prog.status = CL_BUILD_SUCCESS;
+                prog.log = module.warnings.iter().map(|msg| diagnostic("warning", msg)).collect();
+                prog.module = Some(Arc::new(module));
+                prog.binary_type = CL_PROGRAM_BINARY_TYPE_EXECUTABLE as cl_program_binary_type;
+                CL_SUCCESS
+            },
+            Err(msg) => {
+                prog.status = CL_BUILD_ERROR;
+                prog.log = diagnostic("error", &msg);
+                prog.module = None;
+                prog.binary_type = CL_PROGRAM_BINARY_TYPE_NONE as cl_program_binary_type;
+                CL_BUILD_PROGRAM_FAILURE
+            },
+        };
+
+        if let Some(pfn_notify) = pfn_notify {
+            s.defer(move || pfn_notify(program, user_data.0));
+        }
+
+        if status == CL_SUCCESS { Ok(()) } else { Err(status) }
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCompileProgram(program: cl_program, num_devices: cl_uint,
+        device_list: *const cl_device_id, options: *const c_char, num_input_headers: cl_uint,
+        input_headers: *const cl_program, header_include_names: *const *const c_char,
+        pfn_notify: Option<extern "C" fn(cl_program, *mut c_void)>, user_data: *mut c_void)
+        -> cl_int
+{
+    if let Err(err) = validate_devices(num_devices, device_list) {
+        return err;
+    }
+    if (pfn_notify.is_none() && !user_data.is_null())
+            || (num_input_headers == 0) != input_headers.is_null()
+            || (num_input_headers == 0) != header_include_names.is_null() {
+        return CL_INVALID_VALUE;
+    }
+
+    let options = if options.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(options).to_string_lossy().into_owned()
+    };
+    let names: Vec<String> = (0..num_input_headers as usize)
+        .map(|i| CStr::from_ptr(*header_include_names.add(i)).to_string_lossy().into_owned())
+        .collect();
+
+    let user_data = SendPtr(user_data);
+
+    state::with(|s| {
+        let mut headers = Vec::with_capacity(names.len());
+        for (i, name) in names.into_iter().enumerate() {
+            let header = s.program(*input_headers.add(i) as usize)?;
+            headers.push((name, header.source.clone()));
+        }
+
+        if s.program(program as usize)?.built_in
+                || !program_kernels(s, program as usize).is_empty() {
+            return Err(CL_INVALID_OPERATION);
+        }
+
+        let prog = s.program_mut(program as usize)?;
+        let result = include_headers(&prog.source, &headers, 0)
+            .and_then(|src| Module::compile_object(&src, &options));
+        prog.options = options;
+        prog.module = None;
+
+        let status = match result {
+            Ok((object, warnings)) => {
+                prog.status = CL_BUILD_SUCCESS;
+                prog.log = warnings.iter().map(|msg| diagnostic("warning", msg)).collect();
+                prog.binary_type = CL_PROGRAM_BINARY_TYPE_COMPILED_OBJECT as cl_program_binary_type;
+                prog.object = Some(object);
+                CL_SUCCESS
+            },
+            Err(msg) => {
+                prog.status = CL_BUILD_ERROR;
+                prog.log = diagnostic("error", &msg);
+                prog.binary_type = CL_PROGRAM_BINARY_TYPE_NONE as cl_program_binary_type;
+                prog.object = None;
+                CL_COMPILE_PROGRAM_FAILURE
+            },
+        };
+
+        if let Some(pfn_notify) = pfn_notify {
+            s.defer(move || pfn_notify(program, user_data.0));
+        }
+
+        if status == CL_SUCCESS { Ok(()) } else { Err(status) }
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clLinkProgram(context: cl_context, num_devices: cl_uint,
+        device_list: *const cl_device_id, options: *const c_char, num_input_programs: cl_uint,
+        input_programs: *const cl_program,
+        pfn_notify: Option<extern "C" fn(cl_program, *mut c_void)>, user_data: *mut c_void,
+        errcode_ret: *mut cl_int) -> cl_program
+{
+    if let Err(err) = validate_devices(num_devices, device_list) {
+        set_errcode(errcode_ret, err);
+        return ptr::null_mut();
+    }
+    if (pfn_notify.is_none() && !user_data.is_null()) || num_input_programs == 0
+            || input_programs.is_null() {
+        set_errcode(errcode_ret, CL_INVALID_VALUE);
+        return ptr::null_mut();
+    }
+
+    let options = if options.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(options).to_string_lossy().into_owned()
+    };
+    let create_library = options.split_whitespace().any(|opt| opt == "-create-library");
+
+    let objects = state::with(|s| {
+        let mut objects = Vec::with_capacity(num_input_programs as usize);
+        for i in 0..num_input_programs as usize {
+            let input = s.program(*input_programs.add(i) as usize)?;
+            if input.context != context as usize {
+                return Err(CL_INVALID_CONTEXT);
+            }
+            objects.push(input.object.clone().ok_or(CL_INVALID_PROGRAM)?);
+        }
+        Ok(objects)
+    });
+    let source = match objects {
+        Ok(objects) => objects.join("\n"),
+        Err(err) => {
+            set_errcode(errcode_ret, err);
+            return ptr::null_mut();
+        },
+    };
+
+    let mut prog = Program {
+        context: context as usize,
+        source: String::new(),
+        options,
+        status: CL_BUILD_SUCCESS,
+        log: String::new(),
+        module: None,
+        built_in: false,
+        binary_type: CL_PROGRAM_BINARY_TYPE_LIBRARY as cl_program_binary_type,
+        object: None,
+    };
+
+    // Libraries may call functions defined by later inputs and so are not
+    // parsed until linked into an executable:
+    let status = if create_library {
+        prog.object = Some(source);
+        CL_SUCCESS
+    } else {
+        match Module::compile(&source, "") {
+            Ok(module) => {
+                prog.log = module.warnings.iter().map(|msg| diagnostic("warning", msg)).collect();
+                prog.module = Some(Arc::new(module));
+                prog.binary_type = CL_PROGRAM_BINARY_TYPE_EXECUTABLE as cl_program_binary_type;
+                CL_SUCCESS
+            },
+            Err(msg) => {
+                prog.status = CL_BUILD_ERROR;
+                prog.log = diagnostic("error", &msg);
+                prog.binary_type = CL_PROGRAM_BINARY_TYPE_NONE as cl_program_binary_type;
+                CL_LINK_PROGRAM_FAILURE
+            },
+        }
+    };
+
+    // A failed link still returns a program from which to read the log:
+    let program = insert_program(prog, errcode_ret);
+    if program.is_null() { return program; }
+    set_errcode(errcode_ret, status);
+
+    if let Some(pfn_notify) = pfn_notify {
+        let user_data = SendPtr(user_data);
+        state::with(|s| -> Result<(), cl_int> {
+            s.defer(move || pfn_notify(program, user_data.0));
+            Ok(())
+        }).ok();
+    }
+    program
+}
+
+/// Replaces `#include` directives naming one of `headers`, a list of
+/// `(include_name, source)`, with the source of the header.
+fn include_headers(src: &str, headers: &[(String, String)], depth: usize)
+        -> Result<String, String>
+{
+    if depth > 64 {
+        return Err("'#include' nested too deeply".to_owned());
+    }
+
+    let mut out = String::with_capacity(src.len());
+    for line in src.lines() {
+        let include = line.trim_start().strip_prefix('#')
+            .and_then(|rest| rest.trim_start().strip_prefix("include"))
+            .map(|name| name.trim().trim_matches(|c| c == '"' || c == '<' || c == '>'));
+
+        match include.and_then(|name| headers.iter().find(|(n, _)| n == name)) {
+            Some((_, header)) => out.push_str(&include_headers(header, headers, depth + 1)?),
+            None => out.push_str(line),
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Formats a compiler message (`line N: message` or `message`) as a
+/// Clang-style build log line.
+fn diagnostic(severity: &str, msg: &str) -> String {
+    let located = msg.strip_prefix("line ").and_then(|rest| {
+        let (line, msg) = rest.split_at(rest.find(": ")?);
+        line.parse::<usize>().ok().map(|line| (line, &msg[2..]))
+    });
+
+    match located {
+        Some((line, msg)) => format!("<source>:{}: {}: {}\n", line, severity, msg),
+        None => format!("{}: {}\n", severity, msg),
+    }
+}
+
+/// Writes a program's binaries to the array of pointers at `param_value`.
+unsafe fn write_binaries(binary: &[u8], param_value_size: size_t, param_value: *mut c_void,
+        param_value_size_ret: *mut size_t) -> cl_int
+{
+    let ptr_size = ::std::mem::size_of::<*mut u8>();
+
+    if !param_value.is_null() {
+        if param_value_size < ptr_size {
+            return CL_INVALID_VALUE;
+        }
+        let dst = *(param_value as *mut *mut u8);
+        if !dst.is_null() {
+            ptr::copy_nonoverlapping(binary.as_ptr(), dst, binary.len());
+        }
+    }
+    if !param_value_size_ret.is_null() {
+        *param_value_size_ret = ptr_size;
+    }
+    CL_SUCCESS
+}
+
+fn binary(prog: &Program) -> Vec<u8> {
+    if prog.module.is_none() {
+        return Vec::new();
+    }
+    let mut binary = BINARY_MAGIC.to_vec();
+    binary.extend_from_slice(prog.source.as_bytes());
+    binary
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetProgramInfo(program: cl_program, param_name: cl_program_info,
+        param_value_size: size_t, param_value: *mut c_void, param_value_size_ret: *mut size_t)
+        -> cl_int
+{
+    let bytes = state::with(|s| -> InfoBytes {
+        let refs = s.ref_count(program as usize);
+        let prog = s.program(program as usize)?;
+
+        let kernel_names = || -> Result<Vec<&str>, cl_int> {
+            let module = prog.module.as_ref().ok_or(CL_INVALID_PROGRAM_EXECUTABLE)?;
+            Ok(module.kernel_idxs().into_iter().map(|i| module.functions[i].name.as_str()).collect())
+        };
+
+        match param_name {
+            CL_PROGRAM_REFERENCE_COUNT => info::val(refs),
+            CL_PROGRAM_CONTEXT => info::handle(prog.context),
+            CL_PROGRAM_NUM_DEVICES => info::val(1 as cl_uint),
+            CL_PROGRAM_DEVICES => info::handle(DEVICE),
+            CL_PROGRAM_SOURCE => info::string(&prog.source),
+            CL_PROGRAM_BINARY_SIZES => info::val(binary(prog).len()),
+            // Handled below:
+            CL_PROGRAM_BINARIES => Ok(binary(prog)),
+            CL_PROGRAM_NUM_KERNELS => info::val(kernel_names()?.len()),
+            CL_PROGRAM_KERNEL_NAMES => info::string(&kernel_names()?.join(";")),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+
+    match (param_name, bytes) {
+        (CL_PROGRAM_BINARIES, Ok(binary)) => write_binaries(&binary, param_value_size,
+            param_value, param_value_size_ret),
+        (_, bytes) => write_info(bytes, param_value_size, param_value, param_value_size_ret),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetProgramBuildInfo(program: cl_program, device: cl_device_id,
+        param_name: cl_program_build_info, param_value_size: size_t, param_value: *mut c_void,
+        param_value_size_ret: *mut size_t) -> cl_int
+{
+    if !is_device(device) {
+        return CL_INVALID_DEVICE;
+    }
+
+    let bytes = state::with(|s| {
+        let prog = s.program(program as usize)?;
+
+        match param_name {
+            CL_PROGRAM_BUILD_STATUS => info::val(prog.status),
+            CL_PROGRAM_BUILD_OPTIONS => info::string(&prog.options),
+            CL_PROGRAM_BUILD_LOG => info::string(&prog.log),
+            CL_PROGRAM_BINARY_TYPE => info::val(prog.binary_type),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+
+    write_info(bytes, param_value_size, param_value, param_value_size_ret)
+}
+
+fn new_kernel(s: &mut State, program: usize, idx: usize) -> Result<usize, cl_int> {
+    let module = s.program(program)?.module.clone().ok_or(CL_INVALID_PROGRAM_EXECUTABLE)?;
+    let num_args = module.functions[idx].params.len();
+    Ok(s.insert(Object::Kernel(Kernel { program, module, idx, args: vec![None; num_args] })))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateKernel(program: cl_program, kernel_name: *const c_char,
+        errcode_ret: *mut cl_int) -> cl_kernel
+{
+    let name = if kernel_name.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(kernel_name).to_string_lossy().into_owned())
+    };
+
+    let result = state::with(|s| {
+        let module = s.program(program as usize)?.module.clone()
+            .ok_or(CL_INVALID_PROGRAM_EXECUTABLE)?;
+        let name = name.ok_or(CL_INVALID_VALUE)?;
+        let idx = module.kernel_idx(&name).ok_or(CL_INVALID_KERNEL_NAME)?;
+        new_kernel(s, program as usize, idx)
+    });
+
+    match result {
+        Ok(handle) => {
+            set_errcode(errcode_ret, CL_SUCCESS);
+            handle as cl_kernel
+        },
+        Err(err) => {
+            set_errcode(errcode_ret, err);
+            ptr::null_mut()
+        },
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateKernelsInProgram(program: cl_program, num_kernels: cl_uint,
+        kernels: *mut cl_kernel, num_kernels_ret: *mut cl_uint) -> cl_int
+{
+    state::with(|s| {
+        let module = s.program(program as usize)?.module.clone()
+            .ok_or(CL_INVALID_PROGRAM_EXECUTABLE)?;
+        let idxs = module.kernel_idxs();
+
+        if !kernels.is_null() {
+            if (num_kernels as usize) < idxs.len() {
+                return Err(CL_INVALID_VALUE);
+            }
+            for (i, &idx) in idxs.iter().enumerate() {
+                *kernels.add(i) = new_kernel(s, program as usize, idx)? as cl_kernel;
+            }
+        }
+        if !num_kernels_ret.is_null() {
+            *num_kernels_ret = idxs.len() as cl_uint;
+        }
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clRetainKernel(kernel: cl_kernel) -> cl_int {
+    state::with(|s| {
+        s.kernel(kernel as usize)?;
+        s.retain(kernel as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clReleaseKernel(kernel: cl_kernel) -> cl_int {
+    state::with(|s| {
+        s.kernel(kernel as usize)?;
+        s.release(kernel as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+unsafe fn kernel_arg(s: &State, ty: &Ty, arg_size: size_t, arg_value: *const c_void)
+        -> Result<KernelArg, cl_int>
+{
+    let handle_size = ::std::mem::size_of::<cl_mem>();
+
+    match *ty {
+        Ty::Ptr(_, AddrSpace::Local) => {
+            if !arg_value.is_null() { return Err(CL_INVALID_ARG_VALUE); }
+            if arg_size == 0 { return Err(CL_INVALID_ARG_SIZE); }
+            Ok(KernelArg::Local(arg_size))
+        },
+        Ty::Ptr(..) | Ty::Image(_) => {
+            if arg_size != handle_size { return Err(CL_INVALID_ARG_SIZE); }
+            let handle = if arg_value.is_null() { 0 } else { *(arg_value as *const usize) };
+            if handle == 0 {
+                if let Ty::Image(_) = *ty { return Err(CL_INVALID_MEM_OBJECT); }
+                return Ok(KernelArg::Null);
+            }
+            s.mem(handle).map_err(|_| CL_INVALID_MEM_OBJECT)?;
+            Ok(KernelArg::Mem(handle))
+        },
+        Ty::Sampler => Err(CL_INVALID_SAMPLER),
+        Ty::Val(elem) => {
+            if arg_size != elem.size() { return Err(CL_INVALID_ARG_SIZE); }
+            if arg_value.is_null() { return Err(CL_INVALID_ARG_VALUE); }
+            Ok(KernelArg::Bytes(slice::from_raw_parts(arg_value as *const u8, arg_size).to_vec()))
+        },
+        Ty::Void => Err(CL_INVALID_ARG_VALUE),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clSetKernelArg(kernel: cl_kernel, arg_index: cl_uint,
+        arg_size: size_t, arg_value: *const c_void) -> cl_int
+{
+    state::with(|s| {
+        let (module, idx) = {
+            let k = s.kernel(kernel as usize)?;
+            (k.module.clone(), k.idx)
+        };
+        let param = module.functions[idx].params.get(arg_index as usize)
+            .ok_or(CL_INVALID_ARG_INDEX)?;
+        let arg = kernel_arg(s, &param.ty, arg_size, arg_value)?;
+        s.kernel_mut(kernel as usize)?.args[arg_index as usize] = Some(arg);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetKernelInfo(kernel: cl_kernel, param_name: cl_kernel_info,
+        param_value_size: size_t, param_value: *mut c_void, param_value_size_ret: *mut size_t)
+        -> cl_int
+{
+    let bytes = state::with(|s| {
+        let refs = s.ref_count(kernel as usize);
+        let k = s.kernel(kernel as usize)?;
+        let func = &k.module.functions[k.idx];
+
+        match param_name {
+            CL_KERNEL_FUNCTION_NAME => info::string(k.name()),
+            CL_KERNEL_NUM_ARGS => info::val(func.params.len() as cl_uint),
+            CL_KERNEL_REFERENCE_COUNT => info::val(refs),
+            CL_KERNEL_CONTEXT => info::handle(s.program(k.program)?.context),
+            CL_KERNEL_PROGRAM => info::handle(k.program),
+            CL_KERNEL_ATTRIBUTES => info::string(&func.attributes),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+
+    write_info(bytes, param_value_size, param_value, param_value_size_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetKernelArgInfo(kernel: cl_kernel, arg_indx: cl_uint,
+        param_name: cl_kernel_arg_info, param_value_size: size_t, param_value: *mut c_void,
+        param_value_size_ret: *mut size_t) -> cl_int
+{
+    let bytes = state::with(|s| {
+        let k = s.kernel(kernel as usize)?;
+        let param = k.module.functions[k.idx].params.get(arg_indx as usize)
+            .ok_or(CL_INVALID_ARG_INDEX)?;
+
+        match param_name {
+            CL_KERNEL_ARG_ADDRESS_QUALIFIER => info::val(match param.addr_space() {
+                AddrSpace::Global => CL_KERNEL_ARG_ADDRESS_GLOBAL,
+                AddrSpace::Local => CL_KERNEL_ARG_ADDRESS_LOCAL,
+                AddrSpace::Constant => CL_KERNEL_ARG_ADDRESS_CONSTANT,
+                AddrSpace::Private => CL_KERNEL_ARG_ADDRESS_PRIVATE,
+            }),
+            CL_KERNEL_ARG_ACCESS_QUALIFIER => info::val(match param.access {
+                Access::ReadOnly => CL_KERNEL_ARG_ACCESS_READ_ONLY,
+                Access::WriteOnly => CL_KERNEL_ARG_ACCESS_WRITE_ONLY,
+                Access::ReadWrite => CL_KERNEL_ARG_ACCESS_READ_WRITE,
+                Access::None => CL_KERNEL_ARG_ACCESS_NONE,
+            }),
+            CL_KERNEL_ARG_TYPE_NAME => info::string(&param.type_name),
+            CL_KERNEL_ARG_TYPE_QUALIFIER => {
+                let mut quals = CL_KERNEL_ARG_TYPE_NONE;
+                if param.is_const { quals |= CL_KERNEL_ARG_TYPE_CONST; }
+                if param.is_restrict { quals |= CL_KERNEL_ARG_TYPE_RESTRICT; }
+                if param.is_volatile { quals |= CL_KERNEL_ARG_TYPE_VOLATILE; }
+                info::val(quals)
+            },
+            CL_KERNEL_ARG_NAME => info::string(&param.name),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+
+    write_info(bytes, param_value_size, param_value, param_value_size_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetKernelWorkGroupInfo(kernel: cl_kernel, device: cl_device_id,
+        param_name: cl_kernel_work_group_info, param_value_size: size_t,
+        param_value: *mut c_void, param_value_size_ret: *mut size_t) -> cl_int
+{
+    if !device.is_null() && !is_device(device) {
+        return CL_INVALID_DEVICE;
+    }
+
+    let bytes = state::with(|s| {
+        s.kernel(kernel as usize)?;
+
+        match param_name {
+            CL_KERNEL_WORK_GROUP_SIZE => info::val(1024 as size_t),
+            CL_KERNEL_COMPILE_WORK_GROUP_SIZE => info::vals(&[0 as size_t; 3]),
+            CL_KERNEL_LOCAL_MEM_SIZE | CL_KERNEL_PRIVATE_MEM_SIZE => info::val(0 as cl_ulong),
+            CL_KERNEL_PREFERRED_WORK_GROUP_SIZE_MULTIPLE => info::val(1 as size_t),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+
+    write_info(bytes, param_value_size, param_value, param_value_size_ret)
+}
@@ -0,0 +1,1119 @@
+//! Command queue, event and enqueue functions.
+//!
+//! Every queue executes its commands in order. A command runs as soon as
+//! all of the events in its wait list have completed, which is usually
+//! immediately (on the enqueuing thread). Commands waiting on incomplete
+//! user events remain pending (along with every command enqueued after
+//! them) until those events are completed from another thread or callback.
+
+use std::collections::VecDeque;
+use std::ffi::CString;
+use std::{mem, ptr, slice};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+use crate::cl::*;
+use crate::clc::{self, Module, NdRange};
+use crate::info::{self, write_info, set_errcode};
+use crate::platform::is_device;
+use crate::program::KernelArg;
+use crate::state::{self, State, Object, SendPtr};
+
+
+pub type EventCallback = extern "C" fn(cl_event, cl_int, *mut c_void);
+pub type NativeKernelFn = extern "C" fn(*mut c_void);
+
+/// A command queue.
+pub struct Queue {
+    pub context: usize,
+    pub device: usize,
+    pub properties: cl_command_queue_properties,
+    pub pending: VecDeque<Command>,
+}
+
+/// An event.
+pub struct Event {
+    pub context: usize,
+    /// The queue the command was enqueued on (zero for user events).
+    pub queue: usize,
+    pub command_type: cl_command_type,
+    pub status: cl_int,
+    pub profiling: bool,
+    /// Queued, submit, start and end times.
+    pub times: [cl_ulong; 4],
+    pub callbacks: Vec<(cl_int, EventCallback, SendPtr)>,
+}
+
+/// A rectangular region within two buffers (all values in bytes).
+#[derive(Clone, Copy, Debug)]
+pub struct Rect {
+    pub src_origin: [usize; 3],
+    pub dst_origin: [usize; 3],
+    pub region: [usize; 3],
+    pub src_row_pitch: usize,
+    pub src_slice_pitch: usize,
+    pub dst_row_pitch: usize,
+    pub dst_slice_pitch: usize,
+}
+
+impl Rect {
+    fn src_offset(&self, y: usize, z: usize) -> usize {
+        self.src_origin[0] + (self.src_origin[1] + y) * self.src_row_pitch +
+            (self.src_origin[2] + z) * self.src_slice_pitch
+    }
+
+    fn dst_offset(&self, y: usize, z: usize) -> usize {
+        self.dst_origin[0] + (self.dst_origin[1] + y) * self.dst_row_pitch +
+            (self.dst_origin[2] + z) * self.dst_slice_pitch
+    }
+
+    /// Copies the region from `src` to `dst`.
+    unsafe fn copy(&self, src: *const u8, dst: *mut u8) {
+        for z in 0..self.region[2] {
+            for y in 0..self.region[1] {
+                ptr::copy(src.add(self.src_offset(y, z)), dst.add(self.dst_offset(y, z)),
+                    self.region[0]);
+            }
+        }
+    }
+}
+
+/// An operation to be performed by a command.
+pub enum Op {
+    Marker,
+    Read { mem: usize, offset: usize, size: usize, dst: SendPtr },
+    Write { mem: usize, offset: usize, size: usize, src: SendPtr },
+    ReadRect { mem: usize, rect: Rect, dst: SendPtr },
+    WriteRect { mem: usize, rect: Rect, src: SendPtr },
+    Copy { src: usize, dst: usize, src_offset: usize, dst_offset: usize, size: usize },
+    CopyRect { src: usize, dst: usize, rect: Rect },
+    Fill { mem: usize, pattern: Vec<u8>, offset: usize, size: usize },
+    Unmap { mem: usize },
+    Kernel { module: Arc<Module>, idx: usize, args: Vec<KernelArg>, range: NdRange },
+    /// A native kernel, with the memory objects to substitute into `args`
+    /// and their byte offsets within it.
+    Native { func: NativeKernelFn, args: Vec<u8>, mems: Vec<(usize, usize)> },
+}
+
+/// An enqueued command.
+pub struct Command {
+    event: usize,
+    wait_list: Vec<usize>,
+    op: Op,
+    /// Objects retained until the command completes.
+    retained: Vec<usize>,
+}
+
+
+/// Returns the current time in nanoseconds.
+pub fn now() -> cl_ulong {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_nanos() as cl_ulong
+}
+
+impl State {
+    /// Sets the status of `event`, queuing any callbacks which are due.
+    pub fn set_event_status(&mut self, event: usize, status: cl_int) {
+        let ev = match self.event_mut(event) {
+            Ok(ev) => ev,
+            Err(_) => return,
+        };
+
+        ev.status = status;
+        let time = now();
+        match status {
+            CL_SUBMITTED => ev.times[1] = time,
+            CL_RUNNING => ev.times[2] = time,
+            CL_COMPLETE => ev.times[3] = time,
+            _ => (),
+        }
+
+        let (due, waiting) = ev.callbacks.drain(..).partition(|cb| status <= cb.0);
+        ev.callbacks = waiting;
+
+        for (_, pfn, user_data) in due as Vec<(cl_int, EventCallback, SendPtr)> {
+            self.defer(move || pfn(event as cl_event, status, user_data.0));
+        }
+    }
+
+    /// Returns the status of `event` (events which no longer exist are
+    /// complete).
+    fn event_status(&self, event: usize) -> cl_int {
+        self.event(event).map(|ev| ev.status).unwrap_or(CL_COMPLETE)
+    }
+
+    /// Executes every command which is ready to run, on every queue.
+    pub fn process_queues(&mut self) {
+        let queues = self.handles(|obj| matches!(*obj, Object::Queue(_)));
+
+        loop {
+            let mut progress = false;
+
+            for &queue in queues.iter() {
+                while let Some(cmd) = self.next_ready(queue) {
+                    self.execute(queue, cmd);
+                    progress = true;
+                }
+                self.destroy_if_unused(queue);
+            }
+
+            if !progress { break; }
+        }
+    }
+
+    /// Removes and returns the command at the front of `queue` if all of
+    /// the events it waits on have completed (or failed).
+    fn next_ready(&mut self, queue: usize) -> Option<Command> {
+        let ready = {
+            let q = self.queue(queue).ok()?;
+            let cmd = q.pending.front()?;
+            cmd.wait_list.iter().all(|&ev| self.event_status(ev) <= CL_COMPLETE)
+        };
+
+        if ready {
+            self.queue_mut(queue).ok()?.pending.pop_front()
+        } else {
+            None
+        }
+    }
+
+    fn execute(&mut self, queue: usize, cmd: Command) {
+        let failed_wait = cmd.wait_list.iter().any(|&ev| self.event_status(ev) < 0);
+
+        let status = if failed_wait {
+            CL_EXEC_STATUS_ERROR_FOR_EVENTS_IN_WAIT_LIST
+        } else {
+            self.set_event_status(cmd.event, CL_SUBMITTED);
+            self.set_event_status(cmd.event, CL_RUNNING);
+
+            match unsafe { self.run(queue, &cmd.op) } {
+                Ok(()) => CL_COMPLETE,
+                Err(err) => err,
+            }
+        };
+
+        self.set_event_status(cmd.event, status);
+
+        for handle in cmd.retained.iter().chain(cmd.wait_list.iter()) {
+            self.release(*handle);
+        }
+        self.release(cmd.event);
+    }
+
+    /// Reports `msg` to the error notification callback (if any) of the
+    /// context of `queue`.
+    fn notify_context(&mut self, queue: usize, msg: &str) {
+        let notify = self.queue(queue).ok()
+            .and_then(|q| self.context(q.context).ok())
+            .and_then(|ctx| ctx.notify);
+
+        if let Some((pfn, user_data)) = notify {
+            let msg = CString::new(msg.replace('\0', "")).unwrap_or_default();
+            self.defer(move || pfn(msg.as_ptr(), ptr::null(), 0, user_data.0));
+        }
+    }
+
+    unsafe fn run(&mut self, queue: usize, op: &Op) -> Result<(), cl_int> {
+        match *op {
+            Op::Marker => (),
+            Op::Read { mem, offset, size, dst } => {
+                let (base, _) = self.mem_region(mem)?;
+                ptr::copy(base.add(offset), dst.0 as *mut u8, size);
+            },
+            Op::Write { mem, offset, size, src } => {
+                let (base, _) = self.mem_region(mem)?;
+                ptr::copy(src.0 as *const u8, base.add(offset), size);
+            },
+            Op::ReadRect { mem, ref rect, dst } => {
+                let (base, _) = self.mem_region(mem)?;
+                rect.copy(base, dst.0 as *mut u8);
+            },
+            Op::WriteRect { mem, ref rect, src } => {
+                let (base, _) = self.mem_region(mem)?;
+                rect.copy(src.0 as *const u8, base);
+            },
+            Op::Copy { src, dst, src_offset, dst_offset, size } => {
+                let (src_base, _) = self.mem_region(src)?;
+                let (dst_base, _) = self.mem_region(dst)?;
+                ptr::copy(src_base.add(src_offset), dst_base.add(dst_offset), size);
+            },
+            Op::CopyRect { src, dst, ref rect } => {
+                let (src_base, _) = self.mem_region(src)?;
+                let (dst_base, _) = self.mem_region(dst)?;
+                rect.copy(src_base, dst_base);
+            },
+            Op::Fill { mem, ref pattern, offset, size } => {
+                let (base, _) = self.mem_region(mem)?;
+                let dst = base.add(offset);
+                for i in (0..size).step_by(pattern.len()) {
+                    ptr::copy_nonoverlapping(pattern.as_ptr(), dst.add(i), pattern.len());
+                }
+            },
+            Op::Unmap { mem } => {
+                let m = self.mem_mut(mem)?;
+                m.map_count = m.map_count.saturating_sub(1);
+            },
+            Op::Kernel { ref module, idx, ref args, ref range } => {
+                let mut kernel_args = Vec::with_capacity(args.len());
+                for arg in args.iter() {
+                    kernel_args.push(match *arg {
+                        KernelArg::Bytes(ref b) => clc::Arg::Bytes(b.clone()),
+                        KernelArg::Mem(h) => {
+                            let (base, size) = self.mem_region(h)?;
+                            clc::Arg::Mem(base, size)
+                        },
+                        KernelArg::Null => clc::Arg::Null,
+                        KernelArg::Local(size) => clc::Arg::Local(size),
+                    });
+                }
+
+                if let Err(err) = clc::run_kernel(module, idx, &kernel_args, range) {
+                    self.notify_context(queue, &format!("kernel execution failed: {}", err));
+                    return Err(CL_OUT_OF_RESOURCES);
+                }
+            },
+            Op::Native { func, ref args, ref mems } => {
+                let mut args = args.clone();
+                for &(mem, offset) in mems.iter() {
+                    let (base, _) = self.mem_region(mem)?;
+                    ptr::write_unaligned(args.as_mut_ptr().add(offset) as *mut *mut u8, base);
+                }
+                func(args.as_mut_ptr() as *mut c_void);
+            },
+        }
+        Ok(())
+    }
+}
+
+
+//============================================================================
+//================================ Queues ====================================
+//============================================================================
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateCommandQueue(context: cl_context, device: cl_device_id,
+        properties: cl_command_queue_properties, errcode_ret: *mut cl_int) -> cl_command_queue
+{
+    let result = state::with(|s| {
+        let ctx = s.context(context as usize)?;
+        if !is_device(device) || !ctx.devices.contains(&(device as usize)) {
+            return Err(CL_INVALID_DEVICE);
+        }
+        if properties & !(CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE | CL_QUEUE_PROFILING_ENABLE) != 0 {
+            return Err(CL_INVALID_VALUE);
+        }
+
+        Ok(s.insert(Object::Queue(Queue {
+            context: context as usize,
+            device: device as usize,
+            properties,
+            pending: VecDeque::new(),
+        })))
+    });
+
+    match result {
+        Ok(handle) => {
+            set_errcode(errcode_ret, CL_SUCCESS);
+            handle as cl_command_queue
+        },
+        Err(err) => {
+            set_errcode(errcode_ret, err);
+            ptr::null_mut()
+        },
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clRetainCommandQueue(command_queue: cl_command_queue) -> cl_int {
+    state::with(|s| {
+        s.queue(command_queue as usize)?;
+        s.retain(command_queue as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clReleaseCommandQueue(command_queue: cl_command_queue) -> cl_int {
+    state::with(|s| {
+        s.queue(command_queue as usize)?;
+        s.release(command_queue as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetCommandQueueInfo(command_queue: cl_command_queue,
+        param_name: cl_command_queue_info, param_value_size: size_t, param_value: *mut c_void,
+        param_value_size_ret: *mut size_t) -> cl_int
+{
+    let bytes = state::with(|s| {
+        let refs = s.ref_count(command_queue as usize);
+        let q = s.queue(command_queue as usize)?;
+
+        match param_name {
+            CL_QUEUE_CONTEXT => info::handle(q.context),
+            CL_QUEUE_DEVICE => info::handle(q.device),
+            CL_QUEUE_REFERENCE_COUNT => info::val(refs),
+            CL_QUEUE_PROPERTIES => info::val(q.properties),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+
+    write_info(bytes, param_value_size, param_value, param_value_size_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clFlush(command_queue: cl_command_queue) -> cl_int {
+    state::with(|s| {
+        s.queue(command_queue as usize)?;
+        s.process_queues();
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clFinish(command_queue: cl_command_queue) -> cl_int {
+    let queue = command_queue as usize;
+
+    let result = state::with(|s| {
+        s.queue(queue)?;
+        s.process_queues();
+        Ok(())
+    });
+    if let Err(err) = result {
+        return err;
+    }
+
+    state::wait_for(|s| match s.queue(queue) {
+        Ok(q) if !q.pending.is_empty() => None,
+        _ => Some(CL_SUCCESS),
+    })
+}
+
+
+//============================================================================
+//================================ Events ====================================
+//============================================================================
+
+/// Validates an event wait list, returning the event handles.
+unsafe fn wait_list(s: &State, context: usize, num_events: cl_uint, event_list: *const cl_event)
+        -> Result<Vec<usize>, cl_int>
+{
+    if (num_events == 0) != event_list.is_null() {
+        return Err(CL_INVALID_EVENT_WAIT_LIST);
+    }
+
+    let mut events = Vec::with_capacity(num_events as usize);
+    for i in 0..num_events as usize {
+        let event = *event_list.add(i) as usize;
+        let ev = s.event(event).map_err(|_| CL_INVALID_EVENT_WAIT_LIST)?;
+        if ev.context != context {
+            return Err(CL_INVALID_CONTEXT);
+        }
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Enqueues a command.
+///
+/// `prepare` validates the command's arguments and returns the operation
+/// to perform and the objects it uses (which are retained until it
+/// completes).
+unsafe fn enqueue<F>(command_queue: cl_command_queue, command_type: cl_command_type,
+        blocking: bool, num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event, prepare: F) -> cl_int
+    where F: FnOnce(&mut State, usize) -> Result<(Op, Vec<usize>), cl_int>
+{
+    let queue = command_queue as usize;
+
+    let result = state::with(|s| {
+        let (context, profiling) = {
+            let q = s.queue(queue)?;
+            (q.context, q.properties & CL_QUEUE_PROFILING_ENABLE != 0)
+        };
+        let wait_list = wait_list(s, context, num_events_in_wait_list, event_wait_list)?;
+        let (op, retained) = prepare(s, context)?;
+
+        for &handle in retained.iter().chain(wait_list.iter()) {
+            s.retain(handle);
+        }
+
+        let mut times = [0; 4];
+        times[0] = now();
+        let ev = s.insert(Object::Event(Event {
+            context,
+            queue,
+            command_type,
+            status: CL_QUEUED,
+            profiling,
+            times,
+            callbacks: Vec::new(),
+        }));
+
+        // One reference for the caller, one for the duration of a blocking
+        // call:
+        if !event.is_null() {
+            s.retain(ev);
+            *event = ev as cl_event;
+        }
+        if blocking {
+            s.retain(ev);
+        }
+
+        s.queue_mut(queue)?.pending.push_back(Command { event: ev, wait_list, op, retained });
+        s.process_queues();
+        Ok(ev)
+    });
+
+    let ev = match result {
+        Ok(ev) => ev,
+        Err(err) => return err,
+    };
+
+    if blocking {
+        let status = state::wait_for(|s| {
+            let status = s.event_status(ev);
+            if status <= CL_COMPLETE { Some(status) } else { None }
+        });
+        state::with(|s| s.release(ev));
+
+        if status < 0 {
+            return CL_EXEC_STATUS_ERROR_FOR_EVENTS_IN_WAIT_LIST;
+        }
+    }
+
+    CL_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clWaitForEvents(num_events: cl_uint, event_list: *const cl_event)
+        -> cl_int
+{
+    if num_events == 0 || event_list.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    let events: Vec<usize> = (0..num_events as usize).map(|i| *event_list.add(i) as usize)
+        .collect();
+
+    let result = state::with(|s| {
+        let context = s.event(events[0])?.context;
+        for &ev in events.iter() {
+            if s.event(ev)?.context != context {
+                return Err(CL_INVALID_CONTEXT);
+            }
+        }
+        s.process_queues();
+        Ok(())
+    });
+    if let Err(err) = result {
+        return err;
+    }
+
+    state::wait_for(|s| {
+        let statuses: Vec<cl_int> = events.iter().map(|&ev| s.event_status(ev)).collect();
+        if statuses.iter().any(|&st| st > CL_COMPLETE) {
+            None
+        } else if statuses.iter().any(|&st| st < 0) {
+            Some(CL_EXEC_STATUS_ERROR_FOR_EVENTS_IN_WAIT_LIST)
+        } else {
+            Some(CL_SUCCESS)
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetEventInfo(event: cl_event, param_name: cl_event_info,
+        param_value_size: size_t, param_value: *mut c_void, param_value_size_ret: *mut size_t)
+        -> cl_int
+{
+    let bytes = state::with(|s| {
+        let refs = s.ref_count(event as usize);
+        let ev = s.event(event as usize)?;
+
+        match param_name {
+            CL_EVENT_COMMAND_QUEUE => info::handle(ev.queue),
+            CL_EVENT_COMMAND_TYPE => info::val(ev.command_type),
+            CL_EVENT_REFERENCE_COUNT => info::val(refs),
+            CL_EVENT_COMMAND_EXECUTION_STATUS => info::val(ev.status),
+            CL_EVENT_CONTEXT => info::handle(ev.context),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+
+    write_info(bytes, param_value_size, param_value, param_value_size_ret)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clCreateUserEvent(context: cl_context, errcode_ret: *mut cl_int)
+        -> cl_event
+{
+    let result = state::with(|s| {
+        s.context(context as usize)?;
+        let mut times = [0; 4];
+        times[0] = now();
+        Ok(s.insert(Object::Event(Event {
+            context: context as usize,
+            queue: 0,
+            command_type: CL_COMMAND_USER,
+            status: CL_SUBMITTED,
+            profiling: false,
+            times,
+            callbacks: Vec::new(),
+        })))
+    });
+
+    match result {
+        Ok(handle) => {
+            set_errcode(errcode_ret, CL_SUCCESS);
+            handle as cl_event
+        },
+        Err(err) => {
+            set_errcode(errcode_ret, err);
+            ptr::null_mut()
+        },
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clRetainEvent(event: cl_event) -> cl_int {
+    state::with(|s| {
+        s.event(event as usize)?;
+        s.retain(event as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clReleaseEvent(event: cl_event) -> cl_int {
+    state::with(|s| {
+        s.event(event as usize)?;
+        s.release(event as usize);
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clSetUserEventStatus(event: cl_event, execution_status: cl_int)
+        -> cl_int
+{
+    if execution_status > CL_COMPLETE {
+        return CL_INVALID_VALUE;
+    }
+
+    state::with(|s| {
+        let ev = s.event(event as usize)?;
+        if ev.command_type != CL_COMMAND_USER {
+            return Err(CL_INVALID_EVENT);
+        }
+        if ev.status <= CL_COMPLETE {
+            return Err(CL_INVALID_OPERATION);
+        }
+        s.set_event_status(event as usize, execution_status);
+        s.process_queues();
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clSetEventCallback(event: cl_event, command_exec_callback_type: cl_int,
+        pfn_notify: Option<EventCallback>, user_data: *mut c_void) -> cl_int
+{
+    let pfn_notify = match pfn_notify {
+        Some(f) => f,
+        None => return CL_INVALID_VALUE,
+    };
+    match command_exec_callback_type {
+        CL_COMPLETE | CL_RUNNING | CL_SUBMITTED => (),
+        _ => return CL_INVALID_VALUE,
+    }
+
+    let user_data = SendPtr(user_data);
+
+    state::with(|s| {
+        let handle = event as usize;
+        let ev = s.event_mut(handle)?;
+
+        if ev.status <= command_exec_callback_type {
+            let status = ev.status;
+            s.defer(move || pfn_notify(handle as cl_event, status, user_data.0));
+        } else {
+            ev.callbacks.push((command_exec_callback_type, pfn_notify, user_data));
+        }
+        Ok(())
+    }).err().unwrap_or(CL_SUCCESS)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clGetEventProfilingInfo(event: cl_event, param_name: cl_profiling_info,
+        param_value_size: size_t, param_value: *mut c_void, param_value_size_ret: *mut size_t)
+        -> cl_int
+{
+    let bytes = state::with(|s| {
+        let ev = s.event(event as usize)?;
+        if !ev.profiling || ev.status != CL_COMPLETE {
+            return Err(CL_PROFILING_INFO_NOT_AVAILABLE);
+        }
+
+        match param_name {
+            CL_PROFILING_COMMAND_QUEUED => info::val(ev.times[0]),
+            CL_PROFILING_COMMAND_SUBMIT => info::val(ev.times[1]),
+            CL_PROFILING_COMMAND_START => info::val(ev.times[2]),
+            CL_PROFILING_COMMAND_END => info::val(ev.times[3]),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+
+    write_info(bytes, param_value_size, param_value, param_value_size_ret)
+}
+
+
+//============================================================================
+//=============================== Enqueue ====================================
+//============================================================================
+
+/// Checks that `mem` belongs to `context` and that `offset..offset + size`
+/// lies within it.
+fn check_range(s: &State, context: usize, mem: usize, offset: usize, size: usize)
+        -> Result<(), cl_int>
+{
+    let m = s.mem(mem)?;
+    if m.context != context {
+        return Err(CL_INVALID_CONTEXT);
+    }
+    if offset.checked_add(size).is_none_or(|end| end > m.size) {
+        return Err(CL_INVALID_VALUE);
+    }
+    Ok(())
+}
+
+/// Checks that the host may access `mem` (`read` or write).
+fn check_host_access(s: &State, mem: usize, read: bool) -> Result<(), cl_int> {
+    let flags = s.mem(mem)?.flags;
+    let denied = if read {
+        CL_MEM_HOST_WRITE_ONLY | CL_MEM_HOST_NO_ACCESS
+    } else {
+        CL_MEM_HOST_READ_ONLY | CL_MEM_HOST_NO_ACCESS
+    };
+    if flags & denied != 0 { Err(CL_INVALID_OPERATION) } else { Ok(()) }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueReadBuffer(command_queue: cl_command_queue, buffer: cl_mem,
+        blocking_read: cl_bool, offset: size_t, cb: size_t, ptr: *mut c_void,
+        num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event, event: *mut cl_event)
+        -> cl_int
+{
+    let mem = buffer as usize;
+    enqueue(command_queue, CL_COMMAND_READ_BUFFER, blocking_read != CL_FALSE,
+            num_events_in_wait_list, event_wait_list, event, |s, context| {
+        check_range(s, context, mem, offset, cb)?;
+        check_host_access(s, mem, true)?;
+        if ptr.is_null() || cb == 0 { return Err(CL_INVALID_VALUE); }
+        Ok((Op::Read { mem, offset, size: cb, dst: SendPtr(ptr) }, vec![mem]))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueWriteBuffer(command_queue: cl_command_queue, buffer: cl_mem,
+        blocking_write: cl_bool, offset: size_t, cb: size_t, ptr: *const c_void,
+        num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event, event: *mut cl_event)
+        -> cl_int
+{
+    let mem = buffer as usize;
+    enqueue(command_queue, CL_COMMAND_WRITE_BUFFER, blocking_write != CL_FALSE,
+            num_events_in_wait_list, event_wait_list, event, |s, context| {
+        check_range(s, context, mem, offset, cb)?;
+        check_host_access(s, mem, false)?;
+        if ptr.is_null() || cb == 0 { return Err(CL_INVALID_VALUE); }
+        Ok((Op::Write { mem, offset, size: cb, src: SendPtr(ptr as *mut c_void) }, vec![mem]))
+    })
+}
+
+/// Returns the row and slice pitches to use, substituting defaults for
+/// zeros.
+fn pitches(region: [usize; 3], row_pitch: usize, slice_pitch: usize)
+        -> Result<(usize, usize), cl_int>
+{
+    let row_pitch = if row_pitch == 0 { region[0] } else { row_pitch };
+    let slice_pitch = if slice_pitch == 0 { region[1] * row_pitch } else { slice_pitch };
+
+    if row_pitch < region[0] || slice_pitch < region[1] * row_pitch ||
+        (row_pitch != 0 && slice_pitch % row_pitch != 0)
+    {
+        return Err(CL_INVALID_VALUE);
+    }
+    Ok((row_pitch, slice_pitch))
+}
+
+unsafe fn read_3(p: *const size_t) -> Result<[usize; 3], cl_int> {
+    if p.is_null() { return Err(CL_INVALID_VALUE); }
+    Ok([*p, *p.add(1), *p.add(2)])
+}
+
+/// Returns the number of bytes spanned by a region at `origin`.
+fn rect_extent(origin: [usize; 3], region: [usize; 3], row_pitch: usize, slice_pitch: usize)
+        -> usize
+{
+    origin[0] + region[0] + (origin[1] + region[1] - 1) * row_pitch +
+        (origin[2] + region[2] - 1) * slice_pitch
+}
+
+/// Builds a `Rect` for a transfer between a buffer and host memory (or
+/// another buffer). Returns the rect and the extents of the source and
+/// destination.
+unsafe fn rect(src_origin: *const size_t, dst_origin: *const size_t, region: *const size_t,
+        src_row_pitch: size_t, src_slice_pitch: size_t, dst_row_pitch: size_t,
+        dst_slice_pitch: size_t) -> Result<(Rect, usize, usize), cl_int>
+{
+    let src_origin = read_3(src_origin)?;
+    let dst_origin = read_3(dst_origin)?;
+    let region = read_3(region)?;
+    if region.contains(&0) {
+        return Err(CL_INVALID_VALUE);
+    }
+
+    let (src_row_pitch, src_slice_pitch) = pitches(region, src_row_pitch, src_slice_pitch)?;
+    let (dst_row_pitch, dst_slice_pitch) = pitches(region, dst_row_pitch, dst_slice_pitch)?;
+
+    let rect = Rect { src_origin, dst_origin, region, src_row_pitch, src_slice_pitch,
+        dst_row_pitch, dst_slice_pitch };
+    let src_extent = rect_extent(src_origin, region, src_row_pitch, src_slice_pitch);
+    let dst_extent = rect_extent(dst_origin, region, dst_row_pitch, dst_slice_pitch);
+    Ok((rect, src_extent, dst_extent))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueReadBufferRect(command_queue: cl_command_queue,
+        buffer: cl_mem, blocking_read: cl_bool, buffer_origin: *const size_t,
+        host_origin: *const size_t, region: *const size_t, buffer_row_pitch: size_t,
+        buffer_slice_pitch: size_t, host_row_pitch: size_t, host_slice_pitch: size_t,
+        ptr: *mut c_void, num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event) -> cl_int
+{
+    let mem = buffer as usize;
+    enqueue(command_queue, CL_COMMAND_READ_BUFFER_RECT, blocking_read != CL_FALSE,
+            num_events_in_wait_list, event_wait_list, event, |s, context| {
+        let (rect, buffer_extent, _) = rect(buffer_origin, host_origin, region,
+            buffer_row_pitch, buffer_slice_pitch, host_row_pitch, host_slice_pitch)?;
+        check_range(s, context, mem, 0, buffer_extent)?;
+        check_host_access(s, mem, true)?;
+        if ptr.is_null() { return Err(CL_INVALID_VALUE); }
+        Ok((Op::ReadRect { mem, rect, dst: SendPtr(ptr) }, vec![mem]))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueWriteBufferRect(command_queue: cl_command_queue,
+        buffer: cl_mem, blocking_write: cl_bool, buffer_origin: *const size_t,
+        host_origin: *const size_t, region: *const size_t, buffer_row_pitch: size_t,
+        buffer_slice_pitch: size_t, host_row_pitch: size_t, host_slice_pitch: size_t,
+        ptr: *const c_void, num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event) -> cl_int
+{
+    let mem = buffer as usize;
+    enqueue(command_queue, CL_COMMAND_WRITE_BUFFER_RECT, blocking_write != CL_FALSE,
+            num_events_in_wait_list, event_wait_list, event, |s, context| {
+        let (rect, _, buffer_extent) = rect(host_origin, buffer_origin, region,
+            host_row_pitch, host_slice_pitch, buffer_row_pitch, buffer_slice_pitch)?;
+        check_range(s, context, mem, 0, buffer_extent)?;
+        check_host_access(s, mem, false)?;
+        if ptr.is_null() { return Err(CL_INVALID_VALUE); }
+        Ok((Op::WriteRect { mem, rect, src: SendPtr(ptr as *mut c_void) }, vec![mem]))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueCopyBuffer(command_queue: cl_command_queue,
+        src_buffer: cl_mem, dst_buffer: cl_mem, src_offset: size_t, dst_offset: size_t,
+        cb: size_t, num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event) -> cl_int
+{
+    let (src, dst) = (src_buffer as usize, dst_buffer as usize);
+    enqueue(command_queue, CL_COMMAND_COPY_BUFFER, false, num_events_in_wait_list,
+            event_wait_list, event, |s, context| {
+        check_range(s, context, src, src_offset, cb)?;
+        check_range(s, context, dst, dst_offset, cb)?;
+        if cb == 0 { return Err(CL_INVALID_VALUE); }
+        if src == dst && src_offset < dst_offset + cb && dst_offset < src_offset + cb {
+            return Err(CL_MEM_COPY_OVERLAP);
+        }
+        Ok((Op::Copy { src, dst, src_offset, dst_offset, size: cb }, vec![src, dst]))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueCopyBufferRect(command_queue: cl_command_queue,
+        src_buffer: cl_mem, dst_buffer: cl_mem, src_origin: *const size_t,
+        dst_origin: *const size_t, region: *const size_t, src_row_pitch: size_t,
+        src_slice_pitch: size_t, dst_row_pitch: size_t, dst_slice_pitch: size_t,
+        num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event) -> cl_int
+{
+    let (src, dst) = (src_buffer as usize, dst_buffer as usize);
+    enqueue(command_queue, CL_COMMAND_COPY_BUFFER_RECT, false, num_events_in_wait_list,
+            event_wait_list, event, |s, context| {
+        let (rect, src_extent, dst_extent) = rect(src_origin, dst_origin, region,
+            src_row_pitch, src_slice_pitch, dst_row_pitch, dst_slice_pitch)?;
+        check_range(s, context, src, 0, src_extent)?;
+        check_range(s, context, dst, 0, dst_extent)?;
+        Ok((Op::CopyRect { src, dst, rect }, vec![src, dst]))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueFillBuffer(command_queue: cl_command_queue, buffer: cl_mem,
+        pattern: *const c_void, pattern_size: size_t, offset: size_t, size: size_t,
+        num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event) -> cl_int
+{
+    let mem = buffer as usize;
+    enqueue(command_queue, CL_COMMAND_FILL_BUFFER, false, num_events_in_wait_list,
+            event_wait_list, event, |s, context| {
+        check_range(s, context, mem, offset, size)?;
+        if pattern.is_null() || !pattern_size.is_power_of_two() || pattern_size > 128 ||
+            !offset.is_multiple_of(pattern_size) || !size.is_multiple_of(pattern_size)
+        {
+            return Err(CL_INVALID_VALUE);
+        }
+        let pattern = ::std::slice::from_raw_parts(pattern as *const u8, pattern_size).to_vec();
+        Ok((Op::Fill { mem, pattern, offset, size }, vec![mem]))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueMapBuffer(command_queue: cl_command_queue, buffer: cl_mem,
+        blocking_map: cl_bool, map_flags: cl_map_flags, offset: size_t, cb: size_t,
+        num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event, errcode_ret: *mut cl_int) -> *mut c_void
+{
+    let mem = buffer as usize;
+    let mut mapped = ptr::null_mut();
+
+    let err = enqueue(command_queue, CL_COMMAND_MAP_BUFFER, blocking_map != CL_FALSE,
+            num_events_in_wait_list, event_wait_list, event, |s, context| {
+        check_range(s, context, mem, offset, cb)?;
+        if cb == 0 || map_flags & !(CL_MAP_READ | CL_MAP_WRITE |
+            CL_MAP_WRITE_INVALIDATE_REGION) != 0
+        {
+            return Err(CL_INVALID_VALUE);
+        }
+        if map_flags & CL_MAP_READ != 0 {
+            check_host_access(s, mem, true)?;
+        }
+        if map_flags & (CL_MAP_WRITE | CL_MAP_WRITE_INVALIDATE_REGION) != 0 {
+            check_host_access(s, mem, false)?;
+        }
+
+        // Buffers are always host accessible so mapping is a no-op:
+        let (base, _) = s.mem_region(mem)?;
+        mapped = base.add(offset) as *mut c_void;
+        s.mem_mut(mem)?.map_count += 1;
+        Ok((Op::Marker, vec![mem]))
+    });
+
+    set_errcode(errcode_ret, err);
+    if err == CL_SUCCESS { mapped } else { ptr::null_mut() }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueUnmapMemObject(command_queue: cl_command_queue,
+        memobj: cl_mem, mapped_ptr: *mut c_void, num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event, event: *mut cl_event) -> cl_int
+{
+    let mem = memobj as usize;
+    enqueue(command_queue, CL_COMMAND_UNMAP_MEM_OBJECT, false, num_events_in_wait_list,
+            event_wait_list, event, |s, context| {
+        let m = s.mem(mem)?;
+        if m.context != context {
+            return Err(CL_INVALID_CONTEXT);
+        }
+        if mapped_ptr.is_null() || m.map_count == 0 {
+            return Err(CL_INVALID_VALUE);
+        }
+        Ok((Op::Unmap { mem }, vec![mem]))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueMigrateMemObjects(command_queue: cl_command_queue,
+        num_mem_objects: cl_uint, mem_objects: *const cl_mem, flags: cl_mem_migration_flags,
+        num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event) -> cl_int
+{
+    enqueue(command_queue, CL_COMMAND_MIGRATE_MEM_OBJECTS, false, num_events_in_wait_list,
+            event_wait_list, event, |s, context| {
+        if num_mem_objects == 0 || mem_objects.is_null() || flags & !(CL_MIGRATE_MEM_OBJECT_HOST |
+            CL_MIGRATE_MEM_OBJECT_CONTENT_UNDEFINED) != 0
+        {
+            return Err(CL_INVALID_VALUE);
+        }
+        for i in 0..num_mem_objects as usize {
+            if s.mem(*mem_objects.add(i) as usize)?.context != context {
+                return Err(CL_INVALID_CONTEXT);
+            }
+        }
+        Ok((Op::Marker, Vec::new()))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueNDRangeKernel(command_queue: cl_command_queue,
+        kernel: cl_kernel, work_dim: cl_uint, global_work_offset: *const size_t,
+        global_work_size: *const size_t, local_work_size: *const size_t,
+        num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event) -> cl_int
+{
+    enqueue(command_queue, CL_COMMAND_NDRANGE_KERNEL, false, num_events_in_wait_list,
+            event_wait_list, event, |s, context| {
+        let (module, idx, args, program) = {
+            let k = s.kernel(kernel as usize)?;
+            (k.module.clone(), k.idx, k.args.clone(), k.program)
+        };
+        if s.program(program)?.context != context {
+            return Err(CL_INVALID_CONTEXT);
+        }
+        if !(1..=3).contains(&work_dim) {
+            return Err(CL_INVALID_WORK_DIMENSION);
+        }
+        if global_work_size.is_null() {
+            return Err(CL_INVALID_VALUE);
+        }
+
+        let mut range = NdRange { work_dim, offset: [0; 3], global: [1; 3], local: [1; 3] };
+        for d in 0..work_dim as usize {
+            range.global[d] = *global_work_size.add(d);
+            if range.global[d] == 0 {
+                return Err(CL_INVALID_GLOBAL_WORK_SIZE);
+            }
+            if !global_work_offset.is_null() {
+                range.offset[d] = *global_work_offset.add(d);
+            }
+            if !local_work_size.is_null() {
+                range.local[d] = *local_work_size.add(d);
+                if range.local[d] == 0 || range.local[d] > 1024 {
+                    return Err(CL_INVALID_WORK_ITEM_SIZE);
+                }
+                if !range.global[d].is_multiple_of(range.local[d]) {
+                    return Err(CL_INVALID_WORK_GROUP_SIZE);
+                }
+            }
+        }
+        if range.local.iter().product::<usize>() > 1024 {
+            return Err(CL_INVALID_WORK_GROUP_SIZE);
+        }
+
+        let mut kernel_args = Vec::with_capacity(args.len());
+        let mut retained = vec![kernel as usize];
+        for arg in args.into_iter() {
+            let arg = arg.ok_or(CL_INVALID_KERNEL_ARGS)?;
+            if let KernelArg::Mem(h) = arg {
+                s.mem(h).map_err(|_| CL_INVALID_KERNEL_ARGS)?;
+                retained.push(h);
+            }
+            kernel_args.push(arg);
+        }
+
+        Ok((Op::Kernel { module, idx, args: kernel_args, range }, retained))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueTask(command_queue: cl_command_queue, kernel: cl_kernel,
+        num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event) -> cl_int
+{
+    let one = [1 as size_t];
+    clEnqueueNDRangeKernel(command_queue, kernel, 1, ptr::null(), one.as_ptr(), one.as_ptr(),
+        num_events_in_wait_list, event_wait_list, event)
+}
+
+/// Runs `user_func` on the enqueuing thread (or whichever thread completes
+/// the events it waits on) with the mock's state locked, so it must not call
+/// any OpenCL functions.
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueNativeKernel(command_queue: cl_command_queue,
+        user_func: Option<NativeKernelFn>, args: *mut c_void, cb_args: size_t,
+        num_mem_objects: cl_uint, mem_list: *const cl_mem, args_mem_loc: *const *const c_void,
+        num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event) -> cl_int
+{
+    let func = match user_func {
+        Some(f) => f,
+        None => return CL_INVALID_VALUE,
+    };
+    if args.is_null() != (cb_args == 0)
+            || (num_mem_objects > 0 && (args.is_null() || mem_list.is_null()
+                || args_mem_loc.is_null()))
+            || (num_mem_objects == 0 && (!mem_list.is_null() || !args_mem_loc.is_null())) {
+        return CL_INVALID_VALUE;
+    }
+
+    let args_bytes = if args.is_null() {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(args as *const u8, cb_args).to_vec()
+    };
+
+    enqueue(command_queue, CL_COMMAND_NATIVE_KERNEL, false, num_events_in_wait_list,
+            event_wait_list, event, |s, context| {
+        let mut mems = Vec::with_capacity(num_mem_objects as usize);
+        for i in 0..num_mem_objects as usize {
+            let mem = *mem_list.add(i) as usize;
+            check_range(s, context, mem, 0, 0)?;
+
+            let offset = (*args_mem_loc.add(i) as usize).wrapping_sub(args as usize);
+            if offset.checked_add(mem::size_of::<*mut c_void>()).is_none_or(|end| end > cb_args) {
+                return Err(CL_INVALID_VALUE);
+            }
+            mems.push((mem, offset));
+        }
+        let retained = mems.iter().map(|&(mem, _)| mem).collect();
+        Ok((Op::Native { func, args: args_bytes, mems }, retained))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueMarkerWithWaitList(command_queue: cl_command_queue,
+        num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event) -> cl_int
+{
+    enqueue(command_queue, CL_COMMAND_MARKER, false, num_events_in_wait_list, event_wait_list,
+        event, |_, _| Ok((Op::Marker, Vec::new())))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueBarrierWithWaitList(command_queue: cl_command_queue,
+        num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+        event: *mut cl_event) -> cl_int
+{
+    enqueue(command_queue, CL_COMMAND_BARRIER, false, num_events_in_wait_list, event_wait_list,
+        event, |_, _| Ok((Op::Marker, Vec::new())))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueMarker(command_queue: cl_command_queue,
+        event: *mut cl_event) -> cl_int
+{
+    if event.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    clEnqueueMarkerWithWaitList(command_queue, 0, ptr::null(), event)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueWaitForEvents(command_queue: cl_command_queue,
+        num_events: cl_uint, event_list: *const cl_event) -> cl_int
+{
+    if num_events == 0 || event_list.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    match clEnqueueBarrierWithWaitList(command_queue, num_events, event_list, ptr::null_mut()) {
+        CL_INVALID_EVENT_WAIT_LIST => CL_INVALID_EVENT,
+        err => err,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn clEnqueueBarrier(command_queue: cl_command_queue) -> cl_int {
+    clEnqueueBarrierWithWaitList(command_queue, 0, ptr::null(), ptr::null_mut())
+}
@@ -0,0 +1,248 @@
+//! The global object registry.
+//!
+//! Every OpenCL object (other than the platform and device, which are
+//! permanent) is stored in a single, mutex protected registry keyed by its
+//! handle. Handles are never reused and are never dereferenced: they only
+//! need to be unique, non-null pointer-sized values.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard, Condvar};
+use crate::cl::*;
+use crate::platform::Context;
+use crate::queue::{Queue, Event};
+use crate::mem::Mem;
+use crate::program::{Program, Kernel};
+
+
+/// The handle of the one and only platform.
+pub const PLATFORM: usize = 0x0c1_0000;
+
+/// The handle of the one and only device.
+pub const DEVICE: usize = 0x0c1_0040;
+
+const FIRST_HANDLE: usize = 0x0c2_0000;
+const HANDLE_STRIDE: usize = 0x40;
+
+
+/// A raw pointer which may be moved between threads.
+///
+/// Used for host pointers and callback user data, which are owned by the
+/// caller and only ever passed back to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SendPtr(pub *mut c_void);
+
+unsafe impl Send for SendPtr {}
+
+/// A callback to be invoked once the registry lock has been released.
+pub struct Deferred(Box<dyn FnOnce()>);
+
+unsafe impl Send for Deferred {}
+
+/// A registered object.
+pub enum Object {
+    Context(Context),
+    Queue(Queue),
+    Mem(Mem),
+    Program(Program),
+    Kernel(Kernel),
+    Event(Event),
+}
+
+impl Object {
+    /// Returns the handles of the objects retained by this object, to be
+    /// released when it is destroyed.
+    fn retained(&self) -> Vec<usize> {
+        match *self {
+            Object::Context(_) => Vec::new(),
+            Object::Queue(ref q) => vec![q.context],
+            Object::Mem(ref m) => {
+                let mut r = vec![m.context];
+                if let Some((parent, _)) = m.parent { r.push(parent); }
+                r
+            },
+            Object::Program(ref p) => vec![p.context],
+            Object::Kernel(ref k) => vec![k.program],
+            Object::Event(ref e) => vec![e.context],
+        }
+    }
+}
+
+struct Entry {
+    refs: u32,
+    obj: Object,
+}
+
+/// The registry.
+pub struct State {
+    next_handle: usize,
+    objects: BTreeMap<usize, Entry>,
+    deferred: Vec<Deferred>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    next_handle: FIRST_HANDLE,
+    objects: BTreeMap::new(),
+    deferred: Vec::new(),
+});
+
+/// Signalled whenever the registry is modified.
+static CHANGED: Condvar = Condvar::new();
+
+
+fn lock() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn run_deferred(deferred: Vec<Deferred>) {
+    for d in deferred {
+        (d.0)();
+    }
+}
+
+/// Runs `f` with exclusive access to the registry, then invokes any
+/// callbacks queued by `f` after the lock has been released.
+pub fn with<R, F: FnOnce(&mut State) -> R>(f: F) -> R {
+    let (result, deferred) = {
+        let mut state = lock();
+        let result = f(&mut state);
+        (result, std::mem::take(&mut state.deferred))
+    };
+    CHANGED.notify_all();
+    run_deferred(deferred);
+    result
+}
+
+/// Blocks until `f` returns `Some`.
+///
+/// `f` is re-evaluated each time the registry is modified.
+pub fn wait_for<R, F: FnMut(&mut State) -> Option<R>>(mut f: F) -> R {
+    let mut state = lock();
+    loop {
+        if let Some(result) = f(&mut state) {
+            let deferred = std::mem::take(&mut state.deferred);
+            drop(state);
+            run_deferred(deferred);
+            return result;
+        }
+        state = CHANGED.wait(state).unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
+}
+
+
+macro_rules! accessors {
+    ($( $get:ident, $get_mut:ident, $variant:ident, $ty:ty, $err:expr; )*) => {
+        $(
+            pub fn $get(&self, handle: usize) -> Result<&$ty, cl_int> {
+                match self.objects.get(&handle) {
+                    Some(&Entry { obj: Object::$variant(ref o), .. }) => Ok(o),
+                    _ => Err($err),
+                }
+            }
+
+            #[allow(dead_code)]
+            pub fn $get_mut(&mut self, handle: usize) -> Result<&mut $ty, cl_int> {
+                match self.objects.get_mut(&handle) {
+                    Some(&mut Entry { obj: Object::$variant(ref mut o), .. }) => Ok(o),
+                    _ => Err($err),
+                }
+            }
+        )*
+    };
+}
+
+impl State {
+    accessors! {
+        context, context_mut, Context, Context, CL_INVALID_CONTEXT;
+        queue, queue_mut, Queue, Queue, CL_INVALID_COMMAND_QUEUE;
+        mem, mem_mut, Mem, Mem, CL_INVALID_MEM_OBJECT;
+        program, program_mut, Program, Program, CL_INVALID_PROGRAM;
+        kernel, kernel_mut, Kernel, Kernel, CL_INVALID_KERNEL;
+        event, event_mut, Event, Event, CL_INVALID_EVENT;
+    }
+
+    /// Registers `obj`, returning its handle. The object begins with a
+    /// reference count of one.
+    pub fn insert(&mut self, obj: Object) -> usize {
+        for handle in obj.retained() {
+            self.retain(handle);
+        }
+        let handle = self.next_handle;
+        self.next_handle += HANDLE_STRIDE;
+        self.objects.insert(handle, Entry { refs: 1, obj });
+        handle
+    }
+
+    /// Returns the handles of all registered objects for which `f` returns
+    /// true.
+    pub fn handles<F: Fn(&Object) -> bool>(&self, f: F) -> Vec<usize> {
+        self.objects.iter().filter(|&(_, e)| f(&e.obj)).map(|(&h, _)| h).collect()
+    }
+
+    /// Returns the reference count of `handle`.
+    pub fn ref_count(&self, handle: usize) -> cl_uint {
+        self.objects.get(&handle).map(|e| e.refs).unwrap_or(0)
+    }
+
+    /// Increments the reference count of `handle`. Returns false if no such
+    /// object exists.
+    pub fn retain(&mut self, handle: usize) -> bool {
+        match self.objects.get_mut(&handle) {
+            Some(entry) => {
+                entry.refs += 1;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Decrements the reference count of `handle`, destroying the object
+    /// (and releasing any objects it retains) when it reaches zero. Returns
+    /// false if no such object exists.
+    pub fn release(&mut self, handle: usize) -> bool {
+        let destroy = match self.objects.get_mut(&handle) {
+            Some(entry) => {
+                if entry.refs == 0 { return false; }
+                entry.refs -= 1;
+                entry.refs == 0
+            },
+            None => return false,
+        };
+
+        if destroy {
+            self.destroy_if_unused(handle);
+        }
+        true
+    }
+
+    /// Destroys `handle` if it is no longer referenced and is not in use.
+    pub fn destroy_if_unused(&mut self, handle: usize) {
+        let in_use = match self.objects.get(&handle) {
+            Some(entry) => entry.refs > 0 || match entry.obj {
+                // Queues live until all of their commands have completed:
+                Object::Queue(ref q) => !q.pending.is_empty(),
+                _ => false,
+            },
+            None => return,
+        };
+        if in_use { return; }
+
+        let entry = self.objects.remove(&handle).expect("object vanished");
+
+        if let Object::Mem(ref m) = entry.obj {
+            // Destructor callbacks are called in the reverse order of
+            // registration:
+            for &(pfn, user_data) in m.destructor_callbacks.iter().rev() {
+                self.defer(move || pfn(handle as cl_mem, user_data.0));
+            }
+        }
+
+        for retained in entry.obj.retained() {
+            self.release(retained);
+        }
+    }
+
+    /// Queues `f` to be called once the registry lock has been released.
+    pub fn defer<F: FnOnce() + 'static>(&mut self, f: F) {
+        self.deferred.push(Deferred(Box::new(f)));
+    }
+}
@@ -0,0 +1,261 @@
+//! Tests of the interpreter and of the API as seen through its entry points.
+
+use std::ffi::CString;
+use std::ptr;
+use crate::cl::*;
+use crate::clc::{Module, Arg, NdRange};
+use crate::platform::*;
+use crate::mem::*;
+use crate::program::*;
+use crate::queue::*;
+
+
+fn range_1d(global: usize, local: usize) -> NdRange {
+    NdRange { work_dim: 1, offset: [0; 3], global: [global, 1, 1], local: [local, 1, 1] }
+}
+
+fn run(src: &str, kernel: &str, args: &[Arg], range: NdRange) -> Result<(), String> {
+    let module = Module::compile(src, "")?;
+    let idx = module.kernel_idx(kernel).expect("kernel not found");
+    crate::clc::run_kernel(&module, idx, args, &range)
+}
+
+#[test]
+fn clc_add() {
+    let src = r#"
+        #define SCALE 2.0f
+        float twice(float x) { return x * SCALE; }
+
+        __kernel void add(__global float* buf, float val) {
+            size_t i = get_global_id(0);
+            buf[i] = twice(buf[i]) + val;
+        }
+    "#;
+
+    let mut buf = vec![1.0f32; 64];
+    let args = [
+        Arg::Mem(buf.as_mut_ptr() as *mut u8, buf.len() * 4),
+        Arg::Bytes(10.0f32.to_ne_bytes().to_vec()),
+    ];
+    run(src, "add", &args, range_1d(64, 8)).unwrap();
+    assert!(buf.iter().all(|&v| v == 12.0));
+}
+
+#[test]
+fn clc_control_flow() {
+    let src = r#"
+        __kernel void collatz(__global uint* steps) {
+            uint n = get_global_id(0) + 1;
+            uint count = 0;
+            while (n != 1) {
+                n = (n % 2 == 0) ? n / 2 : 3 * n + 1;
+                ++count;
+            }
+            steps[get_global_id(0)] = count;
+        }
+    "#;
+
+    let mut steps = vec![0u32; 8];
+    let args = [Arg::Mem(steps.as_mut_ptr() as *mut u8, steps.len() * 4)];
+    run(src, "collatz", &args, range_1d(8, 1)).unwrap();
+    assert_eq!(steps, [0, 1, 7, 2, 5, 8, 16, 3]);
+}
+
+#[test]
+fn clc_out_of_bounds() {
+    let src = "__kernel void oob(__global int* buf) { buf[get_global_id(0) + 1] = 0; }";
+    let mut buf = vec![0i32; 4];
+    let args = [Arg::Mem(buf.as_mut_ptr() as *mut u8, buf.len() * 4)];
+    assert!(run(src, "oob", &args, range_1d(4, 1)).is_err());
+}
+
+#[test]
+fn clc_conditionals() {
+    let src = "#ifndef GUARD\n#define GUARD\n#line 1 \"a.cl\"\n#ifdef GUARD\n#define VAL 3\n\
+        #else\n#define VAL oops\n#endif\n#endif\n\
+        __kernel void k(__global int* buf) { buf[get_global_id(0)] = VAL; }";
+    let mut buf = vec![0i32; 2];
+    let args = [Arg::Mem(buf.as_mut_ptr() as *mut u8, buf.len() * 4)];
+    run(src, "k", &args, range_1d(2, 1)).unwrap();
+    assert_eq!(buf, [3, 3]);
+
+    assert!(Module::compile("#ifdef A\n__kernel void k() {}", "").is_err());
+    assert!(Module::compile("#endif\n__kernel void k() {}", "").is_err());
+}
+
+#[test]
+fn clc_diagnostics() {
+    let module = Module::compile("\n#warning slow path\n__kernel void k() {}", "").unwrap();
+    assert_eq!(module.warnings, vec!["line 2: slow path".to_owned()]);
+
+    let err = Module::compile("#ifdef A\n#error A is set\n#endif\n#error not set\n", "")
+        .unwrap_err();
+    assert_eq!(err, "line 4: not set");
+}
+
+#[test]
+fn clc_unsupported() {
+    assert!(Module::compile("__kernel void k() { int a[4]; }", "").is_err());
+    assert!(Module::compile("#include <foo.h>\n__kernel void k() {}", "").is_err());
+    assert_eq!(Module::compile("__kernel void k(__global float4* v) {\n v[0] = v[1]; }", "")
+        .unwrap_err(), "line 2: vector loads and stores are not supported");
+    assert!(Module::compile("__kernel void k(__global int2* v) { *v = *v; }", "").is_err());
+    assert!(Module::compile("__kernel void k() { printf(\"%d\", 1); }", "").is_err());
+}
+
+#[test]
+fn api_round_trip() {
+    unsafe {
+        let mut platform = ptr::null_mut();
+        assert_eq!(clGetPlatformIDs(1, &mut platform, ptr::null_mut()), CL_SUCCESS);
+        let mut device = ptr::null_mut();
+        assert_eq!(clGetDeviceIDs(platform, CL_DEVICE_TYPE_ALL, 1, &mut device, ptr::null_mut()),
+            CL_SUCCESS);
+
+        let mut err = 0;
+        let context = clCreateContext(ptr::null(), 1, &device, None, ptr::null_mut(), &mut err);
+        assert_eq!(err, CL_SUCCESS);
+        let queue = clCreateCommandQueue(context, device, 0, &mut err);
+        assert_eq!(err, CL_SUCCESS);
+
+        let mut data: Vec<i32> = (0..256).collect();
+        let buffer = clCreateBuffer(context, CL_MEM_READ_WRITE | CL_MEM_COPY_HOST_PTR,
+            data.len() * 4, data.as_mut_ptr() as *mut c_void, &mut err);
+        assert_eq!(err, CL_SUCCESS);
+
+        let src = CString::new("__kernel void inc(__global int* b, int v) \
+            { b[get_global_id(0)] += v; }").unwrap();
+        let src_ptr = src.as_ptr();
+        let program = clCreateProgramWithSource(context, 1, &src_ptr, ptr::null(), &mut err);
+        assert_eq!(err, CL_SUCCESS);
+        assert_eq!(clBuildProgram(program, 0, ptr::null(), ptr::null(), None, ptr::null_mut()),
+            CL_SUCCESS);
+
+        let name = CString::new("inc").unwrap();
+        let kernel = clCreateKernel(program, name.as_ptr(), &mut err);
+        assert_eq!(err, CL_SUCCESS);
+        let v = 5i32;
+        assert_eq!(clSetKernelArg(kernel, 0, ::std::mem::size_of::<cl_mem>(),
+            &buffer as *const _ as *const c_void), CL_SUCCESS);
+
+        // Launching without every argument set fails:
+        let global = 256usize;
+        assert_eq!(clEnqueueNDRangeKernel(queue, kernel, 1, ptr::null(), &global, ptr::null(),
+            0, ptr::null(), ptr::null_mut()), CL_INVALID_KERNEL_ARGS);
+
+        assert_eq!(clSetKernelArg(kernel, 1, 4, &v as *const _ as *const c_void), CL_SUCCESS);
+
+        // Gate the kernel on a user event:
+        let user_event = clCreateUserEvent(context, &mut err);
+        assert_eq!(err, CL_SUCCESS);
+        let mut kernel_event = ptr::null_mut();
+        assert_eq!(clEnqueueNDRangeKernel(queue, kernel, 1, ptr::null(), &global, ptr::null(),
+            1, &user_event, &mut kernel_event), CL_SUCCESS);
+
+        let mut status: cl_int = 0;
+        assert_eq!(clGetEventInfo(kernel_event, CL_EVENT_COMMAND_EXECUTION_STATUS, 4,
+            &mut status as *mut _ as *mut c_void, ptr::null_mut()), CL_SUCCESS);
+        assert_eq!(status, CL_QUEUED);
+
+        assert_eq!(clSetUserEventStatus(user_event, CL_COMPLETE), CL_SUCCESS);
+        assert_eq!(clWaitForEvents(1, &kernel_event), CL_SUCCESS);
+
+        let mut out = vec![0i32; 256];
+        assert_eq!(clEnqueueReadBuffer(queue, buffer, CL_TRUE, 0, out.len() * 4,
+            out.as_mut_ptr() as *mut c_void, 0, ptr::null(), ptr::null_mut()), CL_SUCCESS);
+        assert!(out.iter().enumerate().all(|(i, &v)| v == i as i32 + 5));
+
+        assert_eq!(clFinish(queue), CL_SUCCESS);
+        for &ev in [user_event, kernel_event].iter() {
+            assert_eq!(clReleaseEvent(ev), CL_SUCCESS);
+        }
+        assert_eq!(clReleaseKernel(kernel), CL_SUCCESS);
+        assert_eq!(clReleaseProgram(program), CL_SUCCESS);
+        assert_eq!(clReleaseMemObject(buffer), CL_SUCCESS);
+        assert_eq!(clReleaseCommandQueue(queue), CL_SUCCESS);
+        assert_eq!(clReleaseContext(context), CL_SUCCESS);
+        assert_eq!(clReleaseContext(context), CL_INVALID_CONTEXT);
+    }
+}
+
+#[test]
+fn api_build_failure() {
+    unsafe {
+        let mut platform = ptr::null_mut();
+        clGetPlatformIDs(1, &mut platform, ptr::null_mut());
+        let mut device = ptr::null_mut();
+        clGetDeviceIDs(platform, CL_DEVICE_TYPE_ALL, 1, &mut device, ptr::null_mut());
+        let mut err = 0;
+        let context = clCreateContext(ptr::null(), 1, &device, None, ptr::null_mut(), &mut err);
+
+        let src = CString::new("__kernel void broken( {").unwrap();
+        let src_ptr = src.as_ptr();
+        let program = clCreateProgramWithSource(context, 1, &src_ptr, ptr::null(), &mut err);
+        assert_eq!(clBuildProgram(program, 0, ptr::null(), ptr::null(), None, ptr::null_mut()),
+            CL_BUILD_PROGRAM_FAILURE);
+
+        let mut size = 0;
+        assert_eq!(clGetProgramBuildInfo(program, device, CL_PROGRAM_BUILD_LOG, 0,
+            ptr::null_mut(), &mut size), CL_SUCCESS);
+        assert!(size > 1);
+
+        clReleaseProgram(program);
+        clReleaseContext(context);
+    }
+}
+
+extern "C" fn record_error(errinfo: *const c_char, _: *const c_void, _: size_t,
+        user_data: *mut c_void) {
+    let errors = unsafe { &mut *(user_data as *mut Vec<String>) };
+    errors.push(unsafe { ::std::ffi::CStr::from_ptr(errinfo) }.to_string_lossy().into_owned());
+}
+
+#[test]
+fn api_kernel_failure() {
+    unsafe {
+        let mut platform = ptr::null_mut();
+        clGetPlatformIDs(1, &mut platform, ptr::null_mut());
+        let mut device = ptr::null_mut();
+        clGetDeviceIDs(platform, CL_DEVICE_TYPE_ALL, 1, &mut device, ptr::null_mut());
+        let mut errors: Vec<String> = Vec::new();
+        let mut err = 0;
+        let context = clCreateContext(ptr::null(), 1, &device, Some(record_error),
+            &mut errors as *mut _ as *mut c_void, &mut err);
+        assert_eq!(err, CL_SUCCESS);
+        let queue = clCreateCommandQueue(context, device, 0, &mut err);
+
+        let mut data = vec![0i32; 4];
+        let buffer = clCreateBuffer(context, CL_MEM_READ_WRITE | CL_MEM_COPY_HOST_PTR,
+            data.len() * 4, data.as_mut_ptr() as *mut c_void, &mut err);
+        let src = CString::new("__kernel void oob(__global int* b) \
+            { b[get_global_id(0) + 1] = 0; }").unwrap();
+        let src_ptr = src.as_ptr();
+        let program = clCreateProgramWithSource(context, 1, &src_ptr, ptr::null(), &mut err);
+        assert_eq!(clBuildProgram(program, 0, ptr::null(), ptr::null(), None, ptr::null_mut()),
+            CL_SUCCESS);
+        let name = CString::new("oob").unwrap();
+        let kernel = clCreateKernel(program, name.as_ptr(), &mut err);
+        clSetKernelArg(kernel, 0, ::std::mem::size_of::<cl_mem>(),
+            &buffer as *const _ as *const c_void);
+
+        let global = data.len();
+        let mut event = ptr::null_mut();
+        assert_eq!(clEnqueueNDRangeKernel(queue, kernel, 1, ptr::null(), &global, ptr::null(),
+            0, ptr::null(), &mut event), CL_SUCCESS);
+        assert_eq!(clFinish(queue), CL_SUCCESS);
+
+        let mut status: cl_int = 0;
+        assert_eq!(clGetEventInfo(event, CL_EVENT_COMMAND_EXECUTION_STATUS, 4,
+            &mut status as *mut _ as *mut c_void, ptr::null_mut()), CL_SUCCESS);
+        assert_eq!(status, CL_OUT_OF_RESOURCES);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("kernel execution failed"));
+
+        clReleaseEvent(event);
+        clReleaseKernel(kernel);
+        clReleaseProgram(program);
+        clReleaseMemObject(buffer);
+        clReleaseCommandQueue(queue);
+        clReleaseContext(context);
+    }
+}
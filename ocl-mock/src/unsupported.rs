@@ -0,0 +1,419 @@
+//! Entry points for unsupported features.
+//!
+//! The mock device reports no support for images, samplers, pipes, shared
+//! virtual memory, native kernels, IL programs or OpenGL interoperability. The corresponding functions are nonetheless exported
+//! (so that binaries linked against the full API load successfully) and
+//! simply fail.
+
+use std::ptr;
+use crate::cl::*;
+use crate::info::set_errcode;
+
+
+pub type cl_GLuint = u32;
+pub type cl_GLint = i32;
+pub type cl_GLenum = u32;
+pub type cl_gl_object_type = cl_uint;
+pub type cl_gl_texture_info = cl_uint;
+pub type cl_gl_context_info = cl_uint;
+
+type ContextCallback = Option<extern "C" fn(context: cl_context, user_data: *mut c_void)>;
+
+type ProgramCallback = Option<extern "C" fn(program: cl_program, user_data: *mut c_void)>;
+
+type SvmFreeCallback = Option<extern "C" fn(queue: cl_command_queue, num_svm_pointers: cl_uint,
+    svm_pointers: *const *const c_void, user_data: *mut c_void)>;
+
+
+/// Defines exported functions which do nothing but return the given value.
+macro_rules! unsupported {
+    ($( pub fn $name:ident($($arg:ident: $arg_ty:ty),* $(,)*) -> $ret:ty $body:block )*) => {
+        $(
+            #[no_mangle]
+            #[allow(unused_variables)]
+            pub unsafe extern "system" fn $name($($arg: $arg_ty),*) -> $ret $body
+        )*
+    };
+}
+
+/// Sets `errcode_ret` to `CL_INVALID_OPERATION` and returns null.
+unsafe fn fail<T>(errcode_ret: *mut cl_int) -> *mut T {
+    set_errcode(errcode_ret, CL_INVALID_OPERATION);
+    ptr::null_mut()
+}
+
+
+// Devices:
+unsupported! {
+    pub fn clGetDeviceAndHostTimer(device: cl_device_id, device_timestamp: *mut cl_ulong,
+            host_timestamp: *mut cl_ulong) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+
+    pub fn clGetHostTimer(device: cl_device_id, host_timestamp: *mut cl_ulong) -> cl_int {
+        CL_INVALID_OPERATION
+    }
+}
+
+// Contexts:
+unsupported! {
+    pub fn clSetContextDestructorCallback(context: cl_context, pfn_notify: ContextCallback,
+            user_data: *mut c_void) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+}
+
+// Queues:
+unsupported! {
+    pub fn clCreateCommandQueueWithProperties(context: cl_context, device: cl_device_id,
+            properties: *const cl_queue_properties, errcode_ret: *mut cl_int) -> cl_command_queue
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clSetDefaultDeviceCommandQueue(context: cl_context, device: cl_device_id,
+            command_queue: cl_command_queue) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+}
+
+// Buffers:
+unsupported! {
+    pub fn clCreateBufferWithProperties(context: cl_context, properties: *const cl_mem_properties,
+            flags: cl_mem_flags, size: size_t, host_ptr: *mut c_void, errcode_ret: *mut cl_int)
+            -> cl_mem
+    {
+        fail(errcode_ret)
+    }
+}
+
+// Images:
+unsupported! {
+    pub fn clCreateImageWithProperties(context: cl_context, properties: *const cl_mem_properties,
+            flags: cl_mem_flags, image_format: *const cl_image_format,
+            image_desc: *const cl_image_desc, host_ptr: *mut c_void, errcode_ret: *mut cl_int)
+            -> cl_mem
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clCreateImage(context: cl_context, flags: cl_mem_flags,
+            image_format: *const cl_image_format, image_desc: *const cl_image_desc,
+            host_ptr: *mut c_void, errcode_ret: *mut cl_int) -> cl_mem
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clCreateImage2D(context: cl_context, flags: cl_mem_flags,
+            image_format: *mut cl_image_format, image_width: size_t, image_depth: size_t,
+            image_slc_pitch: size_t, host_ptr: *mut c_void, errcode_ret: *mut cl_int) -> cl_mem
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clCreateImage3D(context: cl_context, flags: cl_mem_flags,
+            image_format: *mut cl_image_format, image_width: size_t, image_height: size_t,
+            image_depth: size_t, image_row_pitch: size_t, image_slc_pitch: size_t,
+            host_ptr: *mut c_void, errcode_ret: *mut cl_int) -> cl_mem
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clGetSupportedImageFormats(context: cl_context, flags: cl_mem_flags,
+            image_type: cl_mem_object_type, num_entries: cl_uint,
+            image_formats: *mut cl_image_format, num_image_formats: *mut cl_uint) -> cl_int
+    {
+        if !num_image_formats.is_null() { *num_image_formats = 0; }
+        CL_SUCCESS
+    }
+
+    pub fn clGetImageInfo(image: cl_mem, param_name: cl_image_info, param_value_size: size_t,
+            param_value: *mut c_void, param_value_size_ret: *mut size_t) -> cl_int
+    {
+        CL_INVALID_MEM_OBJECT
+    }
+
+    pub fn clEnqueueReadImage(command_queue: cl_command_queue, image: cl_mem,
+            blocking_read: cl_bool, origin: *const size_t, region: *const size_t,
+            row_pitch: size_t, slc_pitch: size_t, ptr: *mut c_void,
+            num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+            event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_MEM_OBJECT
+    }
+
+    pub fn clEnqueueWriteImage(command_queue: cl_command_queue, image: cl_mem,
+            blocking_write: cl_bool, origin: *const size_t, region: *const size_t,
+            input_row_pitch: size_t, input_slc_pitch: size_t, ptr: *const c_void,
+            num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+            event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_MEM_OBJECT
+    }
+
+    pub fn clEnqueueFillImage(command_queue: cl_command_queue, image: cl_mem,
+            fill_color: *const c_void, origin: *const size_t, region: *const size_t,
+            num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+            event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_MEM_OBJECT
+    }
+
+    pub fn clEnqueueCopyImage(command_queue: cl_command_queue, src_image: cl_mem,
+            dst_image: cl_mem, src_origin: *const size_t, dst_origin: *const size_t,
+            region: *const size_t, num_events_in_wait_list: cl_uint,
+            event_wait_list: *const cl_event, event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_MEM_OBJECT
+    }
+
+    pub fn clEnqueueCopyImageToBuffer(command_queue: cl_command_queue, src_image: cl_mem,
+            dst_buffer: cl_mem, src_origin: *const size_t, region: *const size_t,
+            dst_offset: size_t, num_events_in_wait_list: cl_uint,
+            event_wait_list: *const cl_event, event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_MEM_OBJECT
+    }
+
+    pub fn clEnqueueCopyBufferToImage(command_queue: cl_command_queue, src_buffer: cl_mem,
+            dst_image: cl_mem, src_offset: size_t, dst_origin: *const size_t,
+            region: *const size_t, num_events_in_wait_list: cl_uint,
+            event_wait_list: *const cl_event, event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_MEM_OBJECT
+    }
+
+    pub fn clEnqueueMapImage(command_queue: cl_command_queue, image: cl_mem,
+            blocking_map: cl_bool, map_flags: cl_map_flags, origin: *const size_t,
+            region: *const size_t, image_row_pitch: *mut size_t, image_slc_pitch: *mut size_t,
+            num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+            event: *mut cl_event, errcode_ret: *mut cl_int) -> *mut c_void
+    {
+        set_errcode(errcode_ret, CL_INVALID_MEM_OBJECT);
+        ptr::null_mut()
+    }
+}
+
+// Samplers:
+unsupported! {
+    pub fn clCreateSampler(context: cl_context, normalize_coords: cl_bool,
+            addressing_mode: cl_addressing_mode, filter_mode: cl_filter_mode,
+            errcode_ret: *mut cl_int) -> cl_sampler
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clCreateSamplerWithProperties(context: cl_context,
+            normalized_coords: *const cl_sampler_properties, errcode_ret: *mut cl_int)
+            -> cl_sampler
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clRetainSampler(sampler: cl_sampler) -> cl_int {
+        CL_INVALID_SAMPLER
+    }
+
+    pub fn clReleaseSampler(sampler: cl_sampler) -> cl_int {
+        CL_INVALID_SAMPLER
+    }
+
+    pub fn clGetSamplerInfo(sampler: cl_sampler, param_name: cl_sampler_info,
+            param_value_size: size_t, param_value: *mut c_void,
+            param_value_size_ret: *mut size_t) -> cl_int
+    {
+        CL_INVALID_SAMPLER
+    }
+}
+
+// Pipes:
+unsupported! {
+    pub fn clCreatePipe(context: cl_context, flags: cl_mem_flags, pipe_packet_size: cl_uint,
+            pipe_max_packets: cl_uint, properties: *const cl_pipe_properties,
+            errcode_ret: *mut cl_int) -> cl_mem
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clGetPipeInfo(pipe: cl_mem, param_name: cl_pipe_info, param_value_size: size_t,
+            param_value: *mut c_void, param_value_size_ret: *mut size_t) -> cl_int
+    {
+        CL_INVALID_MEM_OBJECT
+    }
+}
+
+// Shared virtual memory:
+unsupported! {
+    pub fn clSVMAlloc(context: cl_context, flags: cl_svm_mem_flags, size: size_t,
+            alignment: cl_uint) -> *mut c_void
+    {
+        ptr::null_mut()
+    }
+
+    pub fn clSVMFree(context: cl_context, svm_pointer: *mut c_void) -> () {}
+
+    pub fn clSetKernelArgSVMPointer(kernel: cl_kernel, arg_index: cl_uint,
+            arg_value: *const c_void) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+
+    pub fn clSetKernelExecInfo(kernel: cl_kernel, param_name: cl_kernel_exec_info,
+            param_value_size: size_t, param_value: *const c_void) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+
+    pub fn clEnqueueSVMFree(command_queue: cl_command_queue, num_svm_pointers: cl_uint,
+            svm_pointers: *const *const c_void, pfn_free_func: SvmFreeCallback,
+            user_data: *mut c_void, num_events_in_wait_list: cl_uint,
+            event_wait_list: *const cl_event, event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+
+    pub fn clEnqueueSVMMemcpy(command_queue: cl_command_queue, blocking_copy: cl_bool,
+            dst_ptr: *mut c_void, src_ptr: *const c_void, size: size_t,
+            num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+            event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+
+    pub fn clEnqueueSVMMemFill(command_queue: cl_command_queue, svm_ptr: *mut c_void,
+            pattern: *const c_void, pattern_size: size_t, size: size_t,
+            num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+            event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+
+    pub fn clEnqueueSVMMap(command_queue: cl_command_queue, blocking_map: cl_bool,
+            flags: cl_map_flags, svm_ptr: *mut c_void, size: size_t,
+            num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+            event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+
+    pub fn clEnqueueSVMUnmap(command_queue: cl_command_queue, svm_ptr: *mut c_void,
+            num_events_in_wait_list: cl_uint, event_wait_list: *const cl_event,
+            event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+
+    pub fn clEnqueueSVMMigrateMem(command_queue: cl_command_queue, num_svm_pointers: cl_uint,
+            svm_pointers: *const *const c_void, sizes: *const size_t,
+            flags: cl_mem_migration_flags, num_events_in_wait_list: cl_uint,
+            event_wait_list: *const cl_event, event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+}
+
+// Programs and kernels:
+unsupported! {
+    pub fn clCreateProgramWithIL(context: cl_context, il: *const c_void, length: size_t,
+            errcode_ret: *mut cl_int) -> cl_program
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clSetProgramReleaseCallback(program: cl_program, pfn_notify: ProgramCallback,
+            user_data: *mut c_void) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+
+    pub fn clSetProgramSpecializationConstant(program: cl_program, spec_id: cl_uint,
+            spec_size: size_t, spec_value: *const c_void) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+
+    pub fn clCloneKernel(source_kernel: cl_kernel, errcode_ret: *mut cl_int) -> cl_kernel {
+        fail(errcode_ret)
+    }
+
+    pub fn clGetKernelSubGroupInfo(kernel: cl_kernel, device: cl_device_id,
+            param_name: cl_kernel_sub_group_info, input_value_size: size_t,
+            input_value: *const c_void, param_value_size: size_t, param_value: *mut c_void,
+            param_value_size_ret: *mut size_t) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+}
+
+// OpenGL interoperability:
+unsupported! {
+    pub fn clGetGLContextInfoKHR(properties: *const cl_context_properties,
+            param_name: cl_gl_context_info, param_value_size: size_t, param_value: *mut c_void,
+            param_value_size_ret: *mut size_t) -> cl_int
+    {
+        CL_INVALID_OPERATION
+    }
+
+    pub fn clCreateFromGLBuffer(context: cl_context, flags: cl_mem_flags, bufobj: cl_GLuint,
+            errcode_ret: *mut cl_int) -> cl_mem
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clCreateFromGLRenderbuffer(context: cl_context, flags: cl_mem_flags,
+            renderbuffer: cl_GLuint, errcode_ret: *mut cl_int) -> cl_mem
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clCreateFromGLTexture(context: cl_context, flags: cl_mem_flags,
+            texture_target: cl_GLenum, miplevel: cl_GLint, texture: cl_GLuint,
+            errcode_ret: *mut cl_int) -> cl_mem
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clCreateFromGLTexture2D(context: cl_context, flags: cl_mem_flags,
+            texture_target: cl_GLenum, miplevel: cl_GLint, texture: cl_GLuint,
+            errcode_ret: *mut cl_int) -> cl_mem
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clCreateFromGLTexture3D(context: cl_context, flags: cl_mem_flags,
+            texture_target: cl_GLenum, miplevel: cl_GLint, texture: cl_GLuint,
+            errcode_ret: *mut cl_int) -> cl_mem
+    {
+        fail(errcode_ret)
+    }
+
+    pub fn clGetGLObjectInfo(memobj: cl_mem, gl_object_type: *mut cl_gl_object_type,
+            gl_object_name: *mut cl_GLuint) -> cl_int
+    {
+        CL_INVALID_GL_OBJECT
+    }
+
+    pub fn clGetGLTextureInfo(memobj: cl_mem, param_name: cl_gl_texture_info,
+            param_value_size: size_t, param_value: *mut c_void,
+            param_value_size_ret: *mut size_t) -> cl_int
+    {
+        CL_INVALID_GL_OBJECT
+    }
+
+    pub fn clEnqueueAcquireGLObjects(command_queue: cl_command_queue, num_objects: cl_uint,
+            mem_objects: *const cl_mem, num_events_in_wait_list: cl_uint,
+            event_wait_list: *const cl_event, event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_GL_OBJECT
+    }
+
+    pub fn clEnqueueReleaseGLObjects(command_queue: cl_command_queue, num_objects: cl_uint,
+            mem_objects: *const cl_mem, num_events_in_wait_list: cl_uint,
+            event_wait_list: *const cl_event, event: *mut cl_event) -> cl_int
+    {
+        CL_INVALID_GL_OBJECT
+    }
+}
@@ -8,7 +8,8 @@ use futures::sync::oneshot::Canceled as OneshotCanceled;
 use futures::sync::mpsc::SendError;
 use crate::core::error::{Error as OclCoreError};
 use crate::core::Status;
-use crate::standard::{DeviceError, PlatformError, KernelError};
+use crate::standard::{DeviceError, PlatformError, KernelError, QueueError};
+use crate::standard::{SvmError, PipeError};
 
 use crate::BufferCmdError;
 
@@ -38,6 +39,12 @@ pub enum ErrorKind {
     Platform(PlatformError),
     #[fail(display = "{}", _0)]
     Kernel(KernelError),
+    #[fail(display = "{}", _0)]
+    Queue(QueueError),
+    #[fail(display = "{}", _0)]
+    Svm(SvmError),
+    #[fail(display = "{}", _0)]
+    Pipe(PipeError),
 }
 
 
@@ -175,6 +182,24 @@ impl From<KernelError> for Error {
     }
 }
 
+impl From<QueueError> for Error {
+    fn from(err: QueueError) -> Error {
+        Error { inner: Context::new(ErrorKind::Queue(err)) }
+    }
+}
+
+impl From<SvmError> for Error {
+    fn from(err: SvmError) -> Error {
+        Error { inner: Context::new(ErrorKind::Svm(err)) }
+    }
+}
+
+impl From<PipeError> for Error {
+    fn from(err: PipeError) -> Error {
+        Error { inner: Context::new(ErrorKind::Pipe(err)) }
+    }
+}
+
 impl From<Error> for String {
     fn from(err: Error) -> String {
         err.to_string()
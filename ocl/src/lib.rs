@@ -50,6 +50,7 @@
 
 extern crate num_traits;
 extern crate futures;
+extern crate regex;
 #[macro_use]
 extern crate failure;
 pub extern crate ocl_core as core;
@@ -61,8 +62,15 @@ mod standard;
 pub mod error;
 pub mod r#async;
 
-pub use self::standard::{Platform, Extensions, Device, Context, Program, Queue, Kernel, Buffer, Image, Event,
-    EventList, EventArray, Sampler, SpatialDims, ProQue, BufferCmdError};
+pub use self::standard::{Platform, Extensions, Device, SubDevice, Context, Program, Queue, Kernel, Buffer, Image, Event,
+    EventList, EventArray, Sampler, SpatialDims, ProQue, BufferCmdError, NativeMemArg, NativeArgs};
+pub use self::standard::{SvmVec, SvmMap, SvmError, Pipe, PipeError};
+pub use self::standard::DeviceClock;
+pub use self::standard::{DeviceExtension, DeviceExtensions, DeviceSelector, DevicePreference,
+    DEVICE_SELECTOR_ENV_VAR};
+pub use self::standard::{ProgramCache, PROGRAM_CACHE_DIR_ENV_VAR};
+pub use self::standard::{SourceMap, SourceLocation};
+pub use self::standard::FutureProgram;
 pub use self::r#async::{MemMap, FutureMemMap, RwVec, ReadGuard, WriteGuard,
     FutureReadGuard, FutureWriteGuard};
 pub use crate::error::{Error, Result};
@@ -135,9 +143,12 @@ pub mod builders {
     pub use crate::standard::{ContextBuilder, BuildOpt, ProgramBuilder, ImageBuilder, ProQueBuilder,
         DeviceSpecifier, BufferCmdKind, BufferCmdDataShape, BufferCmd, BufferReadCmd,
         BufferWriteCmd, BufferMapCmd, ImageCmdKind, ImageCmd, KernelCmd, BufferBuilder,
-        KernelBuilder};
+        KernelBuilder, SamplerBuilder};
     pub use crate::standard::{ClNullEventPtrEnum, ClWaitListPtrEnum};
-    pub use crate::core::{ImageFormat, ImageDescriptor, ContextProperties};
+    pub use crate::core::{ImageFormat, ImageDescriptor, ContextProperties, QueueProperties,
+        SamplerProperties, MemProperties};
+    pub use crate::standard::{SvmCmdKind, SvmCmd, SvmMapCmd, SvmUnmapCmd, SvmVecBuilder,
+        PipeBuilder};
     // #[cfg(not(release))] pub use standard::BufferTest;
 }
 
@@ -153,8 +164,12 @@ pub mod flags {
             FP_ROUND_TO_INF, FP_FMA, FP_SOFT_FLOAT, FP_CORRECTLY_ROUNDED_DIVIDE_SQRT,
         // cl_device_exec_capabilities - bitfield
         DeviceExecCapabilities, EXEC_KERNEL, EXEC_NATIVE_KERNEL,
+        // cl_device_svm_capabilities - bitfield
+        DeviceSvmCapabilities, DEVICE_SVM_COARSE_GRAIN_BUFFER, DEVICE_SVM_FINE_GRAIN_BUFFER,
+            DEVICE_SVM_FINE_GRAIN_SYSTEM, DEVICE_SVM_ATOMICS,
         // cl_command_queue_properties - bitfield
         CommandQueueProperties, QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE, QUEUE_PROFILING_ENABLE,
+            QUEUE_ON_DEVICE, QUEUE_ON_DEVICE_DEFAULT,
         // cl_device_affinity_domain
         DeviceAffinityDomain, DEVICE_AFFINITY_DOMAIN_NUMA, DEVICE_AFFINITY_DOMAIN_L4_CACHE,
             DEVICE_AFFINITY_DOMAIN_L3_CACHE, DEVICE_AFFINITY_DOMAIN_L2_CACHE,
@@ -163,6 +178,8 @@ pub mod flags {
         MemFlags, MEM_READ_WRITE, MEM_WRITE_ONLY, MEM_READ_ONLY, MEM_USE_HOST_PTR,
             MEM_ALLOC_HOST_PTR, MEM_COPY_HOST_PTR, MEM_HOST_WRITE_ONLY, MEM_HOST_READ_ONLY,
             MEM_HOST_NO_ACCESS,
+        // cl_svm_mem_flags - bitfield
+        SvmMemFlags, MEM_SVM_FINE_GRAIN_BUFFER, MEM_SVM_ATOMICS,
         // cl_mem_migration_flags - bitfield
         MemMigrationFlags, MIGRATE_MEM_OBJECT_HOST, MIGRATE_MEM_OBJECT_CONTENT_UNDEFINED,
         // cl_map_flags - bitfield
@@ -172,7 +189,7 @@ pub mod flags {
             PROGRAM_BINARY_TYPE_LIBRARY, PROGRAM_BINARY_TYPE_EXECUTABLE,
         // cl_kernel_arg_type_qualifer
         KernelArgTypeQualifier, KERNEL_ARG_TYPE_NONE, KERNEL_ARG_TYPE_CONST,
-            KERNEL_ARG_TYPE_RESTRICT, KERNEL_ARG_TYPE_VOLATILE,
+            KERNEL_ARG_TYPE_RESTRICT, KERNEL_ARG_TYPE_VOLATILE, KERNEL_ARG_TYPE_PIPE,
     };
 }
 
@@ -185,16 +202,18 @@ pub mod enums {
     pub use crate::core::{ImageChannelOrder, ImageChannelDataType, Cbool, Polling, PlatformInfo,
         DeviceInfo, DeviceMemCacheType, DeviceLocalMemType, ContextInfo, ContextProperty,
         ContextInfoOrPropertiesPointerType, DevicePartitionProperty, CommandQueueInfo, ChannelType,
-        MemObjectType, MemInfo, ImageInfo, AddressingMode, FilterMode, SamplerInfo, ProgramInfo,
-        ProgramBuildInfo, ProgramBuildStatus, KernelInfo, KernelArgInfo, KernelArgAddressQualifier,
-        KernelArgAccessQualifier, KernelWorkGroupInfo, EventInfo, CommandType,
-        CommandExecutionStatus, BufferCreateType, ProfilingInfo};
+        MemObjectType, MemInfo, ImageInfo, PipeInfo, AddressingMode, FilterMode, SamplerInfo,
+        ProgramInfo, ProgramBuildInfo, ProgramBuildStatus, KernelInfo, KernelArgInfo,
+        KernelArgAddressQualifier, KernelArgAccessQualifier, KernelWorkGroupInfo,
+        KernelSubGroupInfo, EventInfo, CommandType, CommandExecutionStatus, BufferCreateType,
+        ProfilingInfo, QueuePriority, QueueThrottle};
 
     // Custom enums.
-    pub use crate::core::{ArgVal, ContextPropertyValue, PlatformInfoResult, DeviceInfoResult,
-        ContextInfoResult, CommandQueueInfoResult, MemInfoResult, ImageInfoResult,
-        SamplerInfoResult, ProgramInfoResult, ProgramBuildInfoResult, KernelInfoResult,
-        KernelArgInfoResult, KernelWorkGroupInfoResult, EventInfoResult, ProfilingInfoResult};
+    pub use crate::core::{ArgVal, ContextPropertyValue, DevicePartition, KernelExecInfo,
+        PlatformInfoResult, DeviceInfoResult, ContextInfoResult, CommandQueueInfoResult,
+        MemInfoResult, ImageInfoResult, PipeInfoResult, SamplerInfoResult, ProgramInfoResult,
+        ProgramBuildInfoResult, KernelInfoResult, KernelArgInfoResult, KernelWorkGroupInfoResult,
+        KernelSubGroupInfoResult, EventInfoResult, ProfilingInfoResult};
 
     // Error status.
     pub use crate::core::Status;
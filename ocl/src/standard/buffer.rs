@@ -6,6 +6,7 @@ use std::ops::{Deref, DerefMut, Range};
 use crate::core::{self, Error as OclCoreError, Result as OclCoreResult, OclPrm, Mem as MemCore,
     MemFlags, MemInfo, MemInfoResult, BufferRegion, MapFlags, AsMem, MemCmdRw, MemCmdAll,
     ClNullEventPtr};
+use crate::core::MemProperties;
 use crate::{Context, Queue, FutureMemMap, MemMap, Event, RwVec, FutureReadGuard, FutureWriteGuard,
     SpatialDims};
 use crate::standard::{ClNullEventPtrEnum, ClWaitListPtrEnum, HostSlice};
@@ -1721,6 +1722,46 @@ impl<T: OclPrm> Buffer<T> {
         Ok(buf)
     }
 
+    /// Creates a new buffer with a list of memory object properties.
+    ///
+    /// See [`::new`] and the [`BufferBuilder`] documentation for argument
+    /// details.
+    ///
+    /// ### Safety
+    ///
+    /// Incorrectly using properties, flags and/or host_slice is unsafe.
+    ///
+    /// [Version Controlled: OpenCL 3.0+]
+    ///
+    /// [`::new`]: struct.Buffer.html#method.new
+    /// [`BufferBuilder`]: builders/struct.BufferBuilder.html
+    pub unsafe fn with_properties<'e, 'o, Q, D>(que_ctx: Q, properties: &MemProperties,
+            flags: MemFlags, len: D, host_slice: Option<&[T]>) -> OclResult<Buffer<T>>
+            where Q: Into<QueCtx<'o>>, D: Into<SpatialDims> {
+        let len = len.into().to_len();
+        let que_ctx = que_ctx.into();
+
+        let ctx_owned;
+        let ctx_ref = match que_ctx {
+            QueCtx::Queue(ref q) => {
+                ctx_owned = q.context();
+                &ctx_owned
+            },
+            QueCtx::Context(c) => c,
+        };
+
+        let obj_core = core::create_buffer_with_properties(ctx_ref, properties, flags, len,
+            host_slice, None)?;
+
+        Ok(Buffer {
+            obj_core,
+            queue: que_ctx.into(),
+            len,
+            offset: None,
+            _data: PhantomData,
+        })
+    }
+
     /// Creates a buffer linked to a previously created OpenGL buffer object.
     ///
     /// [UNTESTED]
@@ -1901,6 +1942,28 @@ impl<T: OclPrm> Buffer<T> {
         core::get_mem_object_info(&self.obj_core, info_kind)
     }
 
+    /// Registers a callback to be run once the underlying memory object has
+    /// been destroyed.
+    ///
+    /// The memory object is destroyed only after every clone of this buffer
+    /// (along with any kernel, sub-buffer, or mapping referring to it) has
+    /// been dropped and all commands using it have completed. Once the
+    /// callback runs, the host memory region of a buffer created with
+    /// `MEM_USE_HOST_PTR` (see `BufferBuilder::use_host_slice`) is no longer
+    /// in use and may be freed or reused.
+    ///
+    /// The callback may be run from a thread belonging to the OpenCL runtime.
+    /// The `&MemCore` it receives is only useful for identification (e.g. by
+    /// comparing pointers) and must not be used in any OpenCL call. See
+    /// [`core::set_mem_object_destructor_callback`] for details.
+    ///
+    /// [`core::set_mem_object_destructor_callback`]: ../core/fn.set_mem_object_destructor_callback.html
+    pub fn set_destructor_callback<F>(&self, callback: F) -> OclResult<()>
+            where F: FnOnce(&MemCore) + Send + 'static {
+        core::set_mem_object_destructor_callback(&self.obj_core, Box::new(callback))
+            .map_err(OclError::from)
+    }
+
     /// Changes the default queue used by this buffer for all subsequent
     /// command enqueue operations (reads, writes, etc.).
     ///
@@ -2136,7 +2199,8 @@ pub struct BufferBuilder<'a, T> where T: OclPrm {
     flags: Option<MemFlags>,
     host_slice: HostSlice<'a, T>,
     len: usize,
-    fill_val: Option<(T, Option<ClNullEventPtrEnum<'a>>)>
+    fill_val: Option<(T, Option<ClNullEventPtrEnum<'a>>)>,
+    properties: Option<MemProperties>,
 }
 
 impl<'a, T> BufferBuilder<'a, T> where T: 'a + OclPrm {
@@ -2148,6 +2212,7 @@ impl<'a, T> BufferBuilder<'a, T> where T: 'a + OclPrm {
             host_slice: HostSlice::None,
             len: 0,
             fill_val: None,
+            properties: None,
         }
     }
 
@@ -2214,6 +2279,18 @@ impl<'a, T> BufferBuilder<'a, T> where T: 'a + OclPrm {
         self
     }
 
+    /// Specifies a list of memory object properties to create the buffer
+    /// with.
+    ///
+    /// OpenCL 3.0 does not define any properties of its own. Properties are
+    /// defined by extensions and are platform specific.
+    ///
+    /// [Version Controlled: OpenCL 3.0+]
+    pub fn properties(mut self, properties: MemProperties) -> BufferBuilder<'a, T> {
+        self.properties = Some(properties);
+        self
+    }
+
     /// Specifies a region of host memory to use as storage for the buffer.
     ///
     /// OpenCL implementations are allowed to cache the buffer contents
@@ -2374,7 +2451,10 @@ impl<'a, T> BufferBuilder<'a, T> where T: 'a + OclPrm {
             QueCtx::Context(_) => None,
         };
 
-        let buf = unsafe { Buffer::new(qc, flags, len, host_slice)? };
+        let buf = match self.properties {
+            Some(ref props) => unsafe { Buffer::with_properties(qc, props, flags, len, host_slice)? },
+            None => unsafe { Buffer::new(qc, flags, len, host_slice)? },
+        };
 
         // Fill buffer if `fill_val` and a queue have been specified,
         // blocking if the `fill_event` is `None`.
@@ -8,7 +8,7 @@ use crate::core::{self, Context as ContextCore, ContextProperties, ContextProper
     CreateContextCallbackFn, UserDataPtr, OpenclVersion, ClContextPtr, ClVersions};
 use crate::core::error::{Result as OclCoreResult};
 use crate::error::{Error as OclError, Result as OclResult};
-use crate::standard::{Platform, Device, DeviceSpecifier};
+use crate::standard::{Platform, Device, DeviceSpecifier, DeviceSelector};
 
 
 
@@ -150,6 +150,19 @@ impl Context {
         self.0.platform().map(|opt| opt.map(Platform::from)).map_err(OclError::from)
     }
 
+    /// Registers a callback to be run when this context is destroyed (when
+    /// the last clone of it, and every object created within it, is
+    /// dropped).
+    ///
+    /// The callback may be run from a thread belonging to the OpenCL runtime.
+    ///
+    /// [Version Controlled: OpenCL 3.0+]
+    pub fn set_destructor_callback<F>(&self, callback: F) -> OclResult<()>
+            where F: FnOnce() + Send + 'static {
+        core::set_context_destructor_callback(&self.0, Box::new(callback), None)
+            .map_err(OclError::from)
+    }
+
     fn fmt_info(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         f.debug_struct("Context")
             .field("ReferenceCount", &self.info(ContextInfo::ReferenceCount))
@@ -226,6 +239,10 @@ impl ContextBuilder {
     /// * All devices associated with the first available platform
     /// * No notify callback function or user data.
     ///
+    /// If the `OCL_DEVICE` environment variable is set and no devices are
+    /// specified, the devices it selects (see `DeviceSelector`) are used
+    /// instead, along with their platform if none is specified.
+    ///
     pub fn new() -> ContextBuilder {
         // Default platform will be set within `::build` if unspecified by that time.
         let properties = ContextProperties::new();
@@ -314,10 +331,19 @@ impl ContextBuilder {
     pub fn build(&self) -> OclResult<Context> {
         let mut props = self.properties.clone();
 
+        let device_spec = match self.device_spec {
+            Some(ref ds) => Some(ds.clone()),
+            None => DeviceSelector::from_env()?.map(DeviceSpecifier::from),
+        };
+
         if props.get_platform().is_none() {
-            props.set_platform(Platform::default());
+            let platform = match device_spec {
+                Some(ref ds) => ds.platform()?,
+                None => Platform::default(),
+            };
+            props.set_platform(platform);
         }
 
-        Context::new(Some(props), self.device_spec.clone(), None, None)
+        Context::new(Some(props), device_spec, None, None)
     }
 }
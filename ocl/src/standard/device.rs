@@ -3,10 +3,14 @@
 use std;
 use std::ops::{Deref, DerefMut};
 use std::borrow::Borrow;
+use std::sync::Arc;
 use crate::ffi::cl_device_id;
-use crate::core::{self, util, DeviceId as DeviceIdCore, DeviceType, DeviceInfo, DeviceInfoResult, ClDeviceIdPtr};
+use crate::core::{self, util, DeviceId as DeviceIdCore, DeviceType, DeviceInfo, DeviceInfoResult,
+    ClDeviceIdPtr, DevicePartition, NumericVersion, NameVersion};
 use crate::error::{Error as OclError, Result as OclResult};
 use crate::standard::Platform;
+use crate::standard::DeviceClock;
+use crate::standard::{DeviceExtension, DeviceExtensions, DeviceSelector};
 
 
 /// A device related error.
@@ -19,6 +23,13 @@ pub enum DeviceError {
     #[fail(display = "An index in the resolve list is out of range (index: {}, max: {})",
         idx, max)]
     ResolveIdxsInvalidIndex { idx: usize, max: usize },
+    #[fail(display = "No devices found supporting all of the required extensions: [{}].",
+        _0)]
+    NoDevicesWithExtensions(String),
+    #[fail(display = "Invalid device selector '{}': {}.", selector, reason)]
+    InvalidSelector { selector: String, reason: String },
+    #[fail(display = "No devices match the device selector '{}'.", _0)]
+    NoMatchingDevices(String),
 }
 
 // Perhaps add something like this to the `DeviceSpecifier`.
@@ -59,6 +70,14 @@ pub enum DeviceError {
 /// The `TypeFlags` variant is used for specifying a list of devices using a
 /// bitfield (`DeviceType`) and is the most robust / portable.
 ///
+/// The `WithExtensions` variant narrows another specifier down to the
+/// devices supporting every one of a list of extensions (see
+/// `::with_extensions`).
+///
+/// The `Selector` variant specifies devices by name, vendor, memory size,
+/// version and so on (see `DeviceSelector`), searching every platform when
+/// none has been specified.
+///
 ///
 /// [FIXME: Add some links to the SDK]
 ///
@@ -71,6 +90,8 @@ pub enum DeviceSpecifier {
     Indices(Vec<usize>),
     WrappingIndices(Vec<usize>),
     TypeFlags(DeviceType),
+    WithExtensions(Box<DeviceSpecifier>, Vec<DeviceExtension>),
+    Selector(DeviceSelector),
 }
 
 impl DeviceSpecifier {
@@ -139,6 +160,52 @@ impl DeviceSpecifier {
         DeviceSpecifier::TypeFlags(flags)
     }
 
+    /// Returns a `DeviceSpecifier::WithExtensions` variant which specifies
+    /// only those devices, out of the ones specified by `self`, which support
+    /// every one of `extensions`.
+    ///
+    /// Resolving the list returns an error if no device supports all of the
+    /// required extensions.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore
+    /// let devices = DeviceSpecifier::TypeFlags(DeviceType::GPU)
+    ///     .with_extensions(&[DeviceExtension::Fp64, DeviceExtension::GlSharing]);
+    /// ```
+    ///
+    pub fn with_extensions<I, E>(self, extensions: I) -> DeviceSpecifier
+            where I: IntoIterator<Item = E>, E: Into<DeviceExtension> {
+        let extensions = extensions.into_iter().map(Into::into).collect();
+        DeviceSpecifier::WithExtensions(Box::new(self), extensions)
+    }
+
+    /// Returns a `DeviceSpecifier::Selector` variant which specifies the
+    /// devices matching `selector`, best match first.
+    ///
+    pub fn selector(self, selector: DeviceSelector) -> DeviceSpecifier {
+        DeviceSpecifier::Selector(selector)
+    }
+
+    /// Returns true if this is a `DeviceSpecifier::Selector` variant.
+    pub fn is_selector(&self) -> bool {
+        match *self {
+            DeviceSpecifier::Selector(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the platform to use when none has been specified: the platform
+    /// of the best matching device for selectors, otherwise the default
+    /// platform.
+    pub(crate) fn platform(&self) -> OclResult<Platform> {
+        match *self {
+            DeviceSpecifier::Selector(ref selector) => selector.best_platform(),
+            DeviceSpecifier::WithExtensions(ref specifier, _) => specifier.platform(),
+            _ => Ok(Platform::default()),
+        }
+    }
+
     /// Returns the list of devices matching the parameters specified by this
     /// `DeviceSpecifier`
     ///
@@ -149,8 +216,12 @@ impl DeviceSpecifier {
     /// `Platform`. If no `platform` has been specified, this behaviour is
     /// undefined and could end up using any platform at all.
     ///
+    /// If no `platform` is specified, the `Selector` variant returns the
+    /// matching devices on the platform of the best match.
+    ///
     pub fn to_device_list<P: Borrow<Platform>>(&self, platform: Option<P>) -> OclResult<Vec<Device>> {
-        let platform = platform.map(|p| *p.borrow()).unwrap_or_default();
+        let platform_opt = platform.map(|p| *p.borrow());
+        let platform = platform_opt.unwrap_or_default();
 
         match *self {
             DeviceSpecifier::All => {
@@ -174,6 +245,24 @@ impl DeviceSpecifier {
             DeviceSpecifier::TypeFlags(flags) => {
                 Device::list(&platform, Some(flags)).map_err(OclError::from)
             },
+            DeviceSpecifier::WithExtensions(ref specifier, ref extensions) => {
+                let mut devices = Vec::new();
+                for device in specifier.to_device_list(Some(platform))? {
+                    if device.extensions()?.has_all(extensions) {
+                        devices.push(device);
+                    }
+                }
+
+                if devices.is_empty() {
+                    let names = extensions.iter().map(DeviceExtension::name)
+                        .collect::<Vec<_>>().join(", ");
+                    return Err(DeviceError::NoDevicesWithExtensions(names).into());
+                }
+                Ok(devices)
+            },
+            DeviceSpecifier::Selector(ref selector) => {
+                selector.to_device_list(platform_opt)
+            },
         }
     }
 }
@@ -238,6 +327,12 @@ impl<'a> From<&'a DeviceIdCore> for DeviceSpecifier {
     }
 }
 
+impl From<DeviceSelector> for DeviceSpecifier {
+    fn from(selector: DeviceSelector) -> DeviceSpecifier {
+        DeviceSpecifier::Selector(selector)
+    }
+}
+
 impl From<DeviceType> for DeviceSpecifier {
     fn from(flags: DeviceType) -> DeviceSpecifier {
         DeviceSpecifier::TypeFlags(flags)
@@ -416,6 +511,15 @@ impl Device {
         }
     }
 
+    /// Returns the platform the device belongs to.
+    pub fn platform(&self) -> OclResult<Platform> {
+        match self.info(DeviceInfo::Platform) {
+            Ok(DeviceInfoResult::Platform(r)) => Ok(Platform::new(r)),
+            Err(err) => Err(err),
+            _ => panic!("Device::platform: Unexpected 'DeviceInfoResult' variant."),
+        }
+    }
+
     /// Returns whether or not the device is available for use.
     pub fn is_available(&self) -> OclResult<bool> {
         match self.info(DeviceInfo::Available) {
@@ -425,6 +529,69 @@ impl Device {
         }
     }
 
+    /// Returns the set of extensions supported by the device.
+    pub fn extensions(&self) -> OclResult<DeviceExtensions> {
+        match self.info(DeviceInfo::Extensions) {
+            Ok(DeviceInfoResult::Extensions(r)) => Ok(DeviceExtensions::from_names(&r)),
+            Err(err) => Err(err),
+            _ => panic!("Device::extensions: Unexpected 'DeviceInfoResult' variant."),
+        }
+    }
+
+    /// Returns the names of the built-in kernels supported by the device.
+    ///
+    /// Requires OpenCL 1.2+. The list is empty for devices without built-in
+    /// kernels.
+    pub fn built_in_kernels(&self) -> OclResult<Vec<String>> {
+        match self.info(DeviceInfo::BuiltInKernels) {
+            Ok(DeviceInfoResult::BuiltInKernels(names)) => {
+                Ok(names.split(';')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(String::from)
+                    .collect())
+            },
+            Err(err) => Err(err),
+            _ => panic!("Device::built_in_kernels: Unexpected 'DeviceInfoResult' variant."),
+        }
+    }
+
+    /// Returns the OpenCL version supported by the device, including the
+    /// patch version.
+    ///
+    /// Requires an OpenCL 3.0+ platform.
+    pub fn numeric_version(&self) -> OclResult<NumericVersion> {
+        match self.info(DeviceInfo::NumericVersion) {
+            Ok(DeviceInfoResult::NumericVersion(r)) => Ok(r),
+            Err(err) => Err(err),
+            _ => panic!("Device::numeric_version: Unexpected 'DeviceInfoResult' variant."),
+        }
+    }
+
+    /// Returns the extensions supported by the device along with their
+    /// versions.
+    ///
+    /// Requires an OpenCL 3.0+ platform.
+    pub fn extensions_with_version(&self) -> OclResult<Vec<NameVersion>> {
+        match self.info(DeviceInfo::ExtensionsWithVersion) {
+            Ok(DeviceInfoResult::ExtensionsWithVersion(r)) => Ok(r),
+            Err(err) => Err(err),
+            _ => panic!("Device::extensions_with_version: Unexpected 'DeviceInfoResult' variant."),
+        }
+    }
+
+    /// Returns the optional OpenCL C features supported by the device (such
+    /// as `__opencl_c_fp64` or `__opencl_c_images`).
+    ///
+    /// Requires an OpenCL 3.0+ platform.
+    pub fn opencl_c_features(&self) -> OclResult<Vec<NameVersion>> {
+        match self.info(DeviceInfo::OpenclCFeatures) {
+            Ok(DeviceInfoResult::OpenclCFeatures(r)) => Ok(r),
+            Err(err) => Err(err),
+            _ => panic!("Device::opencl_c_features: Unexpected 'DeviceInfoResult' variant."),
+        }
+    }
+
     /// Returns raw info about the device, as a vector of bytes. Intended for use with non-standard
     /// OpenCL extensions.
     pub fn info_raw(&self, info_kind: u32) -> OclResult<Vec<u8>> {
@@ -441,6 +608,54 @@ impl Device {
         self.clone().into()
     }
 
+    /// Partitions this device into sub-devices.
+    ///
+    /// Each returned `SubDevice` holds a reference to its underlying device,
+    /// released when its last clone is dropped. Requires OpenCL 1.2+.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore
+    /// // Create sub-devices containing two compute units each:
+    /// let sub_devices = device.partition(DevicePartition::Equally(2))?;
+    /// let context = Context::builder().devices(&sub_devices[0]).build()?;
+    /// ```
+    pub fn partition(&self, partition: DevicePartition) -> OclResult<Vec<SubDevice>> {
+        let sub_devices = core::create_sub_devices(&self.0, &partition, None)?;
+        Ok(sub_devices.into_iter()
+            .map(|d| SubDevice(Arc::new(SubDeviceInner { device: Device(d), parent: *self })))
+            .collect())
+    }
+
+    /// Returns a pair of timestamps, `(device_timestamp, host_timestamp)`,
+    /// read at approximately the same time from the device timer and the
+    /// host timer (in nanoseconds).
+    ///
+    /// Device timestamps share a time base with event profiling info. The
+    /// host timer is implementation defined and is not directly comparable
+    /// with `std::time::Instant` (see `::clock`).
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    pub fn device_and_host_timer(&self) -> OclResult<(u64, u64)> {
+        core::get_device_and_host_timer(self.0, None).map_err(OclError::from)
+    }
+
+    /// Returns the current value of the host timer as seen by this device
+    /// (in nanoseconds).
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    pub fn host_timer(&self) -> OclResult<u64> {
+        core::get_host_timer(self.0, None).map_err(OclError::from)
+    }
+
+    /// Returns a new `DeviceClock`, used to convert device timestamps (such
+    /// as event profiling info) into `std::time::Instant`s.
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    pub fn clock(&self) -> OclResult<DeviceClock> {
+        DeviceClock::new(*self)
+    }
+
     /// Returns the underlying `DeviceIdCore`.
     pub fn as_core(&self) -> &DeviceIdCore {
         &self.0
@@ -583,3 +798,99 @@ impl DerefMut for Device {
         &mut self.0
     }
 }
+
+
+/// A device created by partitioning another device.
+///
+/// Unlike root-level devices, sub-devices are reference counted: the
+/// underlying device is released when the last clone of a `SubDevice` is
+/// dropped. A `SubDevice` dereferences to `Device` and can be used wherever
+/// a `Device` is accepted, including `Context::builder().devices(..)` and
+/// `Queue::new`.
+///
+/// ## Lifetime
+///
+/// `Device` is `Copy` and does not hold a reference. A `Device` copied from
+/// a `SubDevice` (e.g. `*sub_device`) must not be used after the last clone
+/// of the `SubDevice` has been dropped unless a context or queue created
+/// with it still exists (which keeps the sub-device alive).
+#[derive(Clone, Debug)]
+pub struct SubDevice(Arc<SubDeviceInner>);
+
+#[derive(Debug)]
+struct SubDeviceInner {
+    device: Device,
+    parent: Device,
+}
+
+impl Drop for SubDeviceInner {
+    fn drop(&mut self) {
+        // Errors cannot be handled here and releasing a valid sub-device
+        // does not fail:
+        let _ = unsafe { core::release_device(&self.device.0, None) };
+    }
+}
+
+impl SubDevice {
+    /// Returns the device this sub-device was partitioned from.
+    pub fn parent(&self) -> Device {
+        self.0.parent
+    }
+
+    /// Returns the sub-device as a `Device`.
+    ///
+    /// See the type level documentation regarding the lifetime of the
+    /// returned device.
+    pub fn as_device(&self) -> &Device {
+        &self.0.device
+    }
+
+    /// Partitions this sub-device further.
+    pub fn partition(&self, partition: DevicePartition) -> OclResult<Vec<SubDevice>> {
+        self.0.device.partition(partition)
+    }
+}
+
+unsafe impl<'a> ClDeviceIdPtr for &'a SubDevice {
+    fn as_ptr(&self) -> cl_device_id {
+        self.0.device.as_ptr()
+    }
+}
+
+impl AsRef<Device> for SubDevice {
+    fn as_ref(&self) -> &Device {
+        &self.0.device
+    }
+}
+
+impl Deref for SubDevice {
+    type Target = Device;
+
+    fn deref(&self) -> &Device {
+        &self.0.device
+    }
+}
+
+impl<'a> From<&'a SubDevice> for Device {
+    fn from(sub_device: &'a SubDevice) -> Device {
+        sub_device.0.device
+    }
+}
+
+impl<'a> From<&'a SubDevice> for DeviceSpecifier {
+    fn from(sub_device: &'a SubDevice) -> DeviceSpecifier {
+        DeviceSpecifier::Single(sub_device.0.device)
+    }
+}
+
+impl<'a> From<&'a [SubDevice]> for DeviceSpecifier {
+    fn from(sub_devices: &'a [SubDevice]) -> DeviceSpecifier {
+        DeviceSpecifier::List(sub_devices.iter().map(|d| d.0.device).collect())
+    }
+}
+
+impl std::fmt::Display for SubDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.device.fmt_info(f)
+    }
+}
@@ -0,0 +1,212 @@
+//! Correlation between device and host timers.
+
+use std::time::{Duration, Instant};
+use crate::core::{self, OpenclVersion, ProfilingInfoResult};
+use crate::error::Result as OclResult;
+use crate::standard::Device;
+
+
+/// The number of timer pairs read when taking a single sample. The pair read
+/// within the shortest interval is kept.
+const READS_PER_SAMPLE: usize = 4;
+
+/// The number of samples taken by `DeviceClock::new`.
+const INITIAL_SAMPLES: usize = 8;
+
+/// The minimum span of host time covered by samples before the drift
+/// between the device and host clocks is estimated.
+const MIN_DRIFT_SPAN: Duration = Duration::from_millis(100);
+
+
+/// Converts device timestamps, such as those returned by
+/// `Event::profiling_info`, into `std::time::Instant`s.
+///
+/// A `DeviceClock` samples the device timer (using
+/// `clGetDeviceAndHostTimer`) alongside `Instant::now` and fits a line
+/// through the samples. Samples taken when the clock is created determine
+/// the offset between the two clocks. Call `::sample` periodically (once per
+/// frame, for example) to refine the offset and to estimate the rate at which
+/// the device clock drifts relative to the host clock. Drift is estimated
+/// once the samples span at least 100ms.
+///
+/// ### Example
+///
+/// ```rust,ignore
+/// let mut clock = device.clock()?;
+/// // ...
+/// let start = clock.profiling_instant(&event.profiling_info(ProfilingInfo::Start)?);
+/// let end = clock.profiling_instant(&event.profiling_info(ProfilingInfo::End)?);
+/// clock.sample()?;
+/// ```
+///
+/// [Version Controlled: OpenCL 2.1+]
+#[derive(Clone, Debug)]
+pub struct DeviceClock {
+    device: Device,
+    device_version: OpenclVersion,
+    base_instant: Instant,
+    base_device_ns: u64,
+    // Sums used to fit `device = offset + rate * host` by least squares,
+    // where both are nanoseconds since `base_*`:
+    count: f64,
+    sum_host: f64,
+    sum_device: f64,
+    sum_host_sq: f64,
+    sum_host_device: f64,
+    max_host_ns: f64,
+    offset_ns: f64,
+    rate: f64,
+    uncertainty: Duration,
+}
+
+impl DeviceClock {
+    /// Returns a new clock for `device`, calibrated by taking several
+    /// samples.
+    ///
+    /// Returns an error if `device` does not support OpenCL 2.1+.
+    pub fn new(device: Device) -> OclResult<DeviceClock> {
+        let device_version = device.version()?;
+        let (base_instant, base_device_ns, uncertainty) = Self::read(&device, &device_version)?;
+
+        let mut clock = DeviceClock {
+            device,
+            device_version,
+            base_instant,
+            base_device_ns,
+            count: 0.0,
+            sum_host: 0.0,
+            sum_device: 0.0,
+            sum_host_sq: 0.0,
+            sum_host_device: 0.0,
+            max_host_ns: 0.0,
+            offset_ns: 0.0,
+            rate: 1.0,
+            uncertainty,
+        };
+
+        clock.add_sample(base_instant, base_device_ns, uncertainty);
+        for _ in 1..INITIAL_SAMPLES {
+            clock.sample()?;
+        }
+
+        Ok(clock)
+    }
+
+    /// Reads the device timer, bracketed by `Instant::now`, several times and
+    /// returns the read made within the shortest interval as `(instant,
+    /// device_timestamp, interval)`.
+    fn read(device: &Device, device_version: &OpenclVersion)
+            -> OclResult<(Instant, u64, Duration)> {
+        let mut best: Option<(Instant, u64, Duration)> = None;
+
+        for _ in 0..READS_PER_SAMPLE {
+            let before = Instant::now();
+            let (device_ns, _) = core::get_device_and_host_timer(*device.as_core(),
+                Some(device_version))?;
+            let after = Instant::now();
+
+            let interval = after - before;
+            if best.map(|(_, _, i)| interval < i).unwrap_or(true) {
+                best = Some((before + interval / 2, device_ns, interval));
+            }
+        }
+
+        Ok(best.expect("DeviceClock::read: No reads made."))
+    }
+
+    /// Takes a new sample, refining the offset and drift estimates.
+    pub fn sample(&mut self) -> OclResult<()> {
+        let (instant, device_ns, interval) = Self::read(&self.device, &self.device_version)?;
+        self.add_sample(instant, device_ns, interval);
+        Ok(())
+    }
+
+    fn add_sample(&mut self, instant: Instant, device_ns: u64, interval: Duration) {
+        let host = signed_nanos(self.base_instant, instant);
+        let device = device_ns as f64 - self.base_device_ns as f64;
+
+        self.count += 1.0;
+        self.sum_host += host;
+        self.sum_device += device;
+        self.sum_host_sq += host * host;
+        self.sum_host_device += host * device;
+        self.max_host_ns = self.max_host_ns.max(host);
+        self.uncertainty = self.uncertainty.min(interval);
+
+        let mean_host = self.sum_host / self.count;
+        let mean_device = self.sum_device / self.count;
+        let var_host = self.sum_host_sq / self.count - mean_host * mean_host;
+
+        self.rate = if self.max_host_ns >= MIN_DRIFT_SPAN.as_nanos() as f64 && var_host > 0.0 {
+            (self.sum_host_device / self.count - mean_host * mean_device) / var_host
+        } else {
+            1.0
+        };
+        self.offset_ns = mean_device - self.rate * mean_host;
+    }
+
+    /// Returns the host `Instant` corresponding to a device timestamp (in
+    /// nanoseconds).
+    pub fn to_instant(&self, device_ns: u64) -> Instant {
+        let device = device_ns as f64 - self.base_device_ns as f64;
+        let host = (device - self.offset_ns) / self.rate;
+
+        if host >= 0.0 {
+            self.base_instant + Duration::from_nanos(host as u64)
+        } else {
+            self.base_instant - Duration::from_nanos((-host) as u64)
+        }
+    }
+
+    /// Returns the device timestamp (in nanoseconds) corresponding to a host
+    /// `Instant`.
+    pub fn to_device_ns(&self, instant: Instant) -> u64 {
+        let host = signed_nanos(self.base_instant, instant);
+        let device = self.offset_ns + self.rate * host + self.base_device_ns as f64;
+        device.max(0.0) as u64
+    }
+
+    /// Returns the host `Instant` corresponding to an event profiling
+    /// timestamp.
+    pub fn profiling_instant(&self, result: &ProfilingInfoResult) -> Instant {
+        match *result {
+            ProfilingInfoResult::Queued(ns) | ProfilingInfoResult::Submit(ns) |
+            ProfilingInfoResult::Start(ns) | ProfilingInfoResult::End(ns) => self.to_instant(ns),
+        }
+    }
+
+    /// Returns the estimated drift of the device clock relative to the host
+    /// clock, in parts per million (positive if the device clock runs fast).
+    ///
+    /// Zero until the samples span at least 100ms.
+    pub fn drift_ppm(&self) -> f64 {
+        (self.rate - 1.0) * 1e6
+    }
+
+    /// Returns the shortest interval within which a sample was taken, an
+    /// approximate bound on the error of converted timestamps (excluding
+    /// drift).
+    pub fn uncertainty(&self) -> Duration {
+        self.uncertainty
+    }
+
+    /// Returns the number of samples taken.
+    pub fn sample_count(&self) -> usize {
+        self.count as usize
+    }
+
+    /// Returns the device.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+}
+
+
+/// Returns the signed number of nanoseconds from `base` to `instant`.
+fn signed_nanos(base: Instant, instant: Instant) -> f64 {
+    if instant >= base {
+        (instant - base).as_nanos() as f64
+    } else {
+        -((base - instant).as_nanos() as f64)
+    }
+}
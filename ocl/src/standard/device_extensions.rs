@@ -0,0 +1,223 @@
+//! Typed device extensions.
+
+use std::fmt;
+use std::slice;
+
+
+/// Declares the `DeviceExtension` enum along with the name of each known
+/// extension.
+macro_rules! device_extensions {
+    ($( $(#[$attr:meta])* $variant:ident => $name:literal, )*) => {
+        /// An extension supported by a device.
+        ///
+        /// Extensions not otherwise listed are represented by `Unknown`.
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum DeviceExtension {
+            $( $(#[$attr])* $variant, )*
+            /// An extension not otherwise listed, containing its name.
+            Unknown(String),
+        }
+
+        impl DeviceExtension {
+            /// Returns the extension named `name`.
+            pub fn from_name(name: &str) -> DeviceExtension {
+                match name {
+                    $( $name => DeviceExtension::$variant, )*
+                    other => DeviceExtension::Unknown(other.to_owned()),
+                }
+            }
+
+            /// Returns the name of the extension (e.g. `cl_khr_fp64`).
+            pub fn name(&self) -> &str {
+                match *self {
+                    $( DeviceExtension::$variant => $name, )*
+                    DeviceExtension::Unknown(ref name) => name,
+                }
+            }
+        }
+    };
+}
+
+device_extensions! {
+    /// `cl_khr_fp64`: Double precision floating point.
+    Fp64 => "cl_khr_fp64",
+    /// `cl_khr_fp16`: Half precision floating point.
+    Fp16 => "cl_khr_fp16",
+    /// `cl_khr_global_int32_base_atomics`
+    GlobalInt32BaseAtomics => "cl_khr_global_int32_base_atomics",
+    /// `cl_khr_global_int32_extended_atomics`
+    GlobalInt32ExtendedAtomics => "cl_khr_global_int32_extended_atomics",
+    /// `cl_khr_local_int32_base_atomics`
+    LocalInt32BaseAtomics => "cl_khr_local_int32_base_atomics",
+    /// `cl_khr_local_int32_extended_atomics`
+    LocalInt32ExtendedAtomics => "cl_khr_local_int32_extended_atomics",
+    /// `cl_khr_int64_base_atomics`: 64-bit integer atomics.
+    Int64BaseAtomics => "cl_khr_int64_base_atomics",
+    /// `cl_khr_int64_extended_atomics`: 64-bit integer min, max and bitwise
+    /// atomics.
+    Int64ExtendedAtomics => "cl_khr_int64_extended_atomics",
+    /// `cl_khr_byte_addressable_store`
+    ByteAddressableStore => "cl_khr_byte_addressable_store",
+    /// `cl_khr_3d_image_writes`
+    ImageWrites3d => "cl_khr_3d_image_writes",
+    /// `cl_khr_image2d_from_buffer`
+    Image2dFromBuffer => "cl_khr_image2d_from_buffer",
+    /// `cl_khr_depth_images`
+    DepthImages => "cl_khr_depth_images",
+    /// `cl_khr_mipmap_image`
+    MipmapImage => "cl_khr_mipmap_image",
+    /// `cl_khr_mipmap_image_writes`
+    MipmapImageWrites => "cl_khr_mipmap_image_writes",
+    /// `cl_khr_srgb_image_writes`
+    SrgbImageWrites => "cl_khr_srgb_image_writes",
+    /// `cl_khr_gl_sharing`: OpenGL buffer and texture sharing.
+    GlSharing => "cl_khr_gl_sharing",
+    /// `cl_khr_gl_event`
+    GlEvent => "cl_khr_gl_event",
+    /// `cl_khr_gl_depth_images`
+    GlDepthImages => "cl_khr_gl_depth_images",
+    /// `cl_khr_gl_msaa_sharing`
+    GlMsaaSharing => "cl_khr_gl_msaa_sharing",
+    /// `cl_khr_egl_image`
+    EglImage => "cl_khr_egl_image",
+    /// `cl_khr_egl_event`
+    EglEvent => "cl_khr_egl_event",
+    /// `cl_khr_d3d10_sharing`
+    D3d10Sharing => "cl_khr_d3d10_sharing",
+    /// `cl_khr_d3d11_sharing`
+    D3d11Sharing => "cl_khr_d3d11_sharing",
+    /// `cl_khr_dx9_media_sharing`
+    Dx9MediaSharing => "cl_khr_dx9_media_sharing",
+    /// `cl_khr_icd`
+    Icd => "cl_khr_icd",
+    /// `cl_khr_subgroups`: Sub-groups on OpenCL 2.0 devices.
+    Subgroups => "cl_khr_subgroups",
+    /// `cl_khr_il_program`: Programs created from SPIR-V.
+    IlProgram => "cl_khr_il_program",
+    /// `cl_khr_spir`
+    Spir => "cl_khr_spir",
+    /// `cl_khr_create_command_queue`
+    CreateCommandQueue => "cl_khr_create_command_queue",
+    /// `cl_khr_terminate_context`
+    TerminateContext => "cl_khr_terminate_context",
+    /// `cl_khr_priority_hints`
+    PriorityHints => "cl_khr_priority_hints",
+    /// `cl_khr_throttle_hints`
+    ThrottleHints => "cl_khr_throttle_hints",
+    /// `cl_khr_device_uuid`
+    DeviceUuid => "cl_khr_device_uuid",
+    /// `cl_khr_pci_bus_info`
+    PciBusInfo => "cl_khr_pci_bus_info",
+    /// `cl_intel_subgroups`
+    IntelSubgroups => "cl_intel_subgroups",
+    /// `cl_intel_unified_shared_memory`
+    IntelUnifiedSharedMemory => "cl_intel_unified_shared_memory",
+    /// `cl_amd_device_attribute_query`
+    AmdDeviceAttributeQuery => "cl_amd_device_attribute_query",
+    /// `cl_nv_device_attribute_query`
+    NvDeviceAttributeQuery => "cl_nv_device_attribute_query",
+    /// `cl_arm_printf`
+    ArmPrintf => "cl_arm_printf",
+}
+
+impl<'a> From<&'a str> for DeviceExtension {
+    fn from(name: &'a str) -> DeviceExtension {
+        DeviceExtension::from_name(name)
+    }
+}
+
+impl From<String> for DeviceExtension {
+    fn from(name: String) -> DeviceExtension {
+        DeviceExtension::from_name(&name)
+    }
+}
+
+impl<'a> From<&'a DeviceExtension> for DeviceExtension {
+    fn from(extension: &'a DeviceExtension) -> DeviceExtension {
+        extension.clone()
+    }
+}
+
+impl fmt::Display for DeviceExtension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+
+/// The set of extensions supported by a device.
+///
+/// ### Example
+///
+/// ```rust,ignore
+/// let extensions = device.extensions()?;
+/// if extensions.has(DeviceExtension::Fp64) {
+///     // ...
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceExtensions {
+    extensions: Vec<DeviceExtension>,
+}
+
+impl DeviceExtensions {
+    /// Parses a whitespace-separated list of extension names (as returned by
+    /// `DeviceInfo::Extensions`).
+    pub fn from_names(names: &str) -> DeviceExtensions {
+        DeviceExtensions {
+            extensions: names.split_whitespace().map(DeviceExtension::from_name).collect(),
+        }
+    }
+
+    /// Returns true if `extension` (a `DeviceExtension` or extension name)
+    /// is supported.
+    pub fn has<E: Into<DeviceExtension>>(&self, extension: E) -> bool {
+        self.extensions.contains(&extension.into())
+    }
+
+    /// Returns true if every one of `extensions` is supported.
+    pub fn has_all<I, E>(&self, extensions: I) -> bool
+            where I: IntoIterator<Item = E>, E: Into<DeviceExtension> {
+        extensions.into_iter().all(|ext| self.has(ext))
+    }
+
+    /// Returns true if any one of `extensions` is supported.
+    pub fn has_any<I, E>(&self, extensions: I) -> bool
+            where I: IntoIterator<Item = E>, E: Into<DeviceExtension> {
+        extensions.into_iter().any(|ext| self.has(ext))
+    }
+
+    /// Returns an iterator over the supported extensions.
+    pub fn iter(&self) -> slice::Iter<'_, DeviceExtension> {
+        self.extensions.iter()
+    }
+
+    /// Returns the number of supported extensions.
+    pub fn len(&self) -> usize {
+        self.extensions.len()
+    }
+
+    /// Returns true if no extensions are supported.
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a DeviceExtensions {
+    type Item = &'a DeviceExtension;
+    type IntoIter = slice::Iter<'a, DeviceExtension>;
+
+    fn into_iter(self) -> slice::Iter<'a, DeviceExtension> {
+        self.extensions.iter()
+    }
+}
+
+impl fmt::Display for DeviceExtensions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, ext) in self.extensions.iter().enumerate() {
+            if i > 0 { f.write_str(" ")?; }
+            f.write_str(ext.name())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,414 @@
+//! Device selection by name, vendor, memory size, version and extensions.
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use regex::{Regex, RegexBuilder};
+use crate::core::{self, DeviceType, DeviceInfo, DeviceInfoResult, OpenclVersion};
+use crate::error::Result as OclResult;
+use crate::standard::{Platform, Device, DeviceError, DeviceExtension};
+
+
+/// The environment variable read by `DeviceSelector::from_env` and used by
+/// `ContextBuilder` and `ProQueBuilder` when no devices are specified.
+pub const DEVICE_SELECTOR_ENV_VAR: &str = "OCL_DEVICE";
+
+
+/// The kind of device to prefer when several match a `DeviceSelector`.
+///
+/// Devices are classified using `DeviceInfo::HostUnifiedMemory`: integrated
+/// devices share memory with the host, discrete devices do not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DevicePreference {
+    Discrete,
+    Integrated,
+}
+
+
+/// Selects devices matching a set of criteria, searching every platform.
+///
+/// Criteria are combined: a device must satisfy all of them. Name, vendor
+/// and platform patterns are regular expressions matched case-insensitively
+/// anywhere within the name. Matching devices are ordered with preferred
+/// devices (see `::prefer`) first, then by platform and device order.
+///
+/// Selectors can also be parsed from a string of semicolon-separated
+/// `key=value` pairs (the syntax of the `OCL_DEVICE` environment variable):
+///
+/// * `platform=<regex>`: The platform name.
+/// * `name=<regex>`: The device name.
+/// * `vendor=<regex>`: The device vendor.
+/// * `type=<CPU|GPU|ACCELERATOR|CUSTOM|DEFAULT|ALL>`: The device type.
+///   Several may be separated by `|`.
+/// * `min_global_mem=<bytes>`: The minimum global memory size. A `K`, `M`,
+///   `G` or `T` suffix multiplies by powers of 1024.
+/// * `min_version=<major.minor>`: The minimum OpenCL version.
+/// * `extensions=<names>`: Required extensions, separated by commas.
+/// * `prefer=<discrete|integrated>`: The kind of device to list first.
+///
+/// ### Example
+///
+/// ```rust,ignore
+/// // Equivalent to "vendor=nvidia|amd;min_global_mem=4G;extensions=cl_khr_fp64":
+/// let selector = DeviceSelector::new()
+///     .vendor("nvidia|amd")
+///     .min_global_mem(4 << 30)
+///     .extensions(&[DeviceExtension::Fp64])
+///     .prefer(DevicePreference::Discrete);
+///
+/// let context = Context::builder().devices(selector).build()?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceSelector {
+    platform: Option<String>,
+    name: Option<String>,
+    vendor: Option<String>,
+    device_type: Option<DeviceType>,
+    min_global_mem: Option<u64>,
+    min_version: Option<OpenclVersion>,
+    extensions: Vec<DeviceExtension>,
+    prefer: Option<DevicePreference>,
+}
+
+impl DeviceSelector {
+    /// Returns a new selector matching every device.
+    pub fn new() -> DeviceSelector {
+        DeviceSelector::default()
+    }
+
+    /// Returns the selector specified by the `OCL_DEVICE` environment
+    /// variable, if set.
+    pub fn from_env() -> OclResult<Option<DeviceSelector>> {
+        match env::var(DEVICE_SELECTOR_ENV_VAR) {
+            Ok(ref s) if !s.trim().is_empty() => s.parse().map(Some).map_err(Into::into),
+            _ => Ok(None),
+        }
+    }
+
+    /// Only selects devices on platforms with a name matching `pattern`.
+    pub fn platform(mut self, pattern: &str) -> DeviceSelector {
+        self.platform = Some(pattern.to_owned());
+        self
+    }
+
+    /// Only selects devices with a name matching `pattern`.
+    pub fn name(mut self, pattern: &str) -> DeviceSelector {
+        self.name = Some(pattern.to_owned());
+        self
+    }
+
+    /// Only selects devices with a vendor matching `pattern`.
+    pub fn vendor(mut self, pattern: &str) -> DeviceSelector {
+        self.vendor = Some(pattern.to_owned());
+        self
+    }
+
+    /// Only selects devices of the types specified by `flags`.
+    pub fn device_type(mut self, flags: DeviceType) -> DeviceSelector {
+        self.device_type = Some(flags);
+        self
+    }
+
+    /// Only selects devices with at least `bytes` of global memory.
+    pub fn min_global_mem(mut self, bytes: u64) -> DeviceSelector {
+        self.min_global_mem = Some(bytes);
+        self
+    }
+
+    /// Only selects devices supporting at least OpenCL `version`.
+    pub fn min_version<V: Into<OpenclVersion>>(mut self, version: V) -> DeviceSelector {
+        self.min_version = Some(version.into());
+        self
+    }
+
+    /// Only selects devices supporting every one of `extensions`.
+    pub fn extensions<I, E>(mut self, extensions: I) -> DeviceSelector
+            where I: IntoIterator<Item = E>, E: Into<DeviceExtension> {
+        self.extensions.extend(extensions.into_iter().map(Into::into));
+        self
+    }
+
+    /// Lists devices of the `preference` kind before any others.
+    pub fn prefer(mut self, preference: DevicePreference) -> DeviceSelector {
+        self.prefer = Some(preference);
+        self
+    }
+
+    /// Returns every matching device on every platform, best match first.
+    pub fn select(&self) -> OclResult<Vec<Device>> {
+        let platforms = core::get_platform_ids()?.into_iter().map(Platform::new).collect();
+        self.select_from(platforms)
+    }
+
+    /// Returns every matching device on `platform`, best match first.
+    pub fn select_on(&self, platform: Platform) -> OclResult<Vec<Device>> {
+        self.select_from(vec![platform])
+    }
+
+    /// Returns true if `device` satisfies every criteria of this selector
+    /// (except those regarding its platform).
+    pub fn matches(&self, device: &Device) -> OclResult<bool> {
+        let patterns = self.patterns()?;
+        self.matches_device(&patterns, device)
+    }
+
+    /// Returns the matching devices on a single platform: `platform` if
+    /// specified, otherwise the platform of the best match.
+    ///
+    /// Returns an error if no devices match.
+    pub(crate) fn to_device_list(&self, platform: Option<Platform>) -> OclResult<Vec<Device>> {
+        let devices = match platform {
+            Some(platform) => self.select_on(platform)?,
+            None => {
+                let devices = self.select()?;
+                match devices.first() {
+                    Some(best) => {
+                        let platform = best.platform()?;
+                        let mut on_platform = Vec::with_capacity(devices.len());
+                        for device in devices {
+                            if device.platform()?.as_core() == platform.as_core() {
+                                on_platform.push(device);
+                            }
+                        }
+                        on_platform
+                    },
+                    None => devices,
+                }
+            },
+        };
+
+        if devices.is_empty() {
+            return Err(DeviceError::NoMatchingDevices(self.to_string()).into());
+        }
+        Ok(devices)
+    }
+
+    /// Returns the platform of the best matching device.
+    pub(crate) fn best_platform(&self) -> OclResult<Platform> {
+        match self.select()?.first() {
+            Some(device) => device.platform(),
+            None => Err(DeviceError::NoMatchingDevices(self.to_string()).into()),
+        }
+    }
+
+    fn select_from(&self, platforms: Vec<Platform>) -> OclResult<Vec<Device>> {
+        let patterns = self.patterns()?;
+        let mut devices = Vec::new();
+
+        for platform in platforms {
+            if let Some(ref re) = patterns[0] {
+                if !re.is_match(&platform.name()?) { continue; }
+            }
+
+            for device in Device::list(platform, self.device_type)? {
+                if self.matches_device(&patterns, &device)? {
+                    devices.push(device);
+                }
+            }
+        }
+
+        if let Some(preference) = self.prefer {
+            let mut preferred = Vec::with_capacity(devices.len());
+            for &device in &devices {
+                preferred.push(is_preferred(&device, preference));
+            }
+            let mut devices_pref: Vec<_> = devices.into_iter().zip(preferred).collect();
+            // A stable sort keeps platform and device order among equals:
+            devices_pref.sort_by_key(|&(_, pref)| !pref);
+            devices = devices_pref.into_iter().map(|(device, _)| device).collect();
+        }
+
+        Ok(devices)
+    }
+
+    fn matches_device(&self, patterns: &[Option<Regex>; 3], device: &Device) -> OclResult<bool> {
+        if let Some(ref re) = patterns[1] {
+            if !re.is_match(&device.name()?) { return Ok(false); }
+        }
+        if let Some(ref re) = patterns[2] {
+            if !re.is_match(&device.vendor()?) { return Ok(false); }
+        }
+        if let Some(min_global_mem) = self.min_global_mem {
+            match device.info(DeviceInfo::GlobalMemSize)? {
+                DeviceInfoResult::GlobalMemSize(size) => {
+                    if size < min_global_mem { return Ok(false); }
+                },
+                _ => panic!("DeviceSelector::matches: Unexpected 'DeviceInfoResult' variant."),
+            }
+        }
+        if let Some(min_version) = self.min_version {
+            if device.version()? < min_version { return Ok(false); }
+        }
+        if !self.extensions.is_empty() && !device.extensions()?.has_all(&self.extensions) {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Compiles the platform, name and vendor patterns.
+    fn patterns(&self) -> Result<[Option<Regex>; 3], DeviceError> {
+        Ok([
+            compile_pattern(self.platform.as_ref())?,
+            compile_pattern(self.name.as_ref())?,
+            compile_pattern(self.vendor.as_ref())?,
+        ])
+    }
+}
+
+impl FromStr for DeviceSelector {
+    type Err = DeviceError;
+
+    fn from_str(s: &str) -> Result<DeviceSelector, DeviceError> {
+        let invalid = |reason: String| DeviceError::InvalidSelector {
+            selector: s.to_owned(),
+            reason,
+        };
+        let mut selector = DeviceSelector::new();
+
+        for criterion in s.split(';').map(str::trim).filter(|c| !c.is_empty()) {
+            let mut kv = criterion.splitn(2, '=');
+            let key = kv.next().unwrap_or("").trim();
+            let value = match kv.next() {
+                Some(value) => value.trim(),
+                None => return Err(invalid(format!("'{}' is not a 'key=value' pair", criterion))),
+            };
+
+            match key {
+                "platform" => selector.platform = Some(value.to_owned()),
+                "name" => selector.name = Some(value.to_owned()),
+                "vendor" => selector.vendor = Some(value.to_owned()),
+                "type" => {
+                    let mut flags = DeviceType::empty();
+                    for name in value.split('|') {
+                        flags |= parse_device_type(name.trim())
+                            .ok_or_else(|| invalid(format!("unknown device type '{}'", name)))?;
+                    }
+                    selector.device_type = Some(flags);
+                },
+                "min_global_mem" => {
+                    selector.min_global_mem = Some(parse_mem_size(value)
+                        .ok_or_else(|| invalid(format!("invalid memory size '{}'", value)))?);
+                },
+                "min_version" => {
+                    selector.min_version = Some(parse_version(value)
+                        .ok_or_else(|| invalid(format!("invalid version '{}'", value)))?);
+                },
+                "extensions" => {
+                    selector.extensions.extend(value.split(',').map(str::trim)
+                        .filter(|name| !name.is_empty()).map(DeviceExtension::from_name));
+                },
+                "prefer" => {
+                    selector.prefer = Some(match value.to_lowercase().as_str() {
+                        "discrete" => DevicePreference::Discrete,
+                        "integrated" => DevicePreference::Integrated,
+                        _ => return Err(invalid(format!("unknown preference '{}'", value))),
+                    });
+                },
+                _ => return Err(invalid(format!("unknown key '{}'", key))),
+            }
+        }
+
+        // Reject invalid patterns now rather than when selecting:
+        selector.patterns().map_err(|err| match err {
+            DeviceError::InvalidSelector { reason, .. } => invalid(reason),
+            err => err,
+        })?;
+        Ok(selector)
+    }
+}
+
+impl fmt::Display for DeviceSelector {
+    /// Formats the selector using the `OCL_DEVICE` syntax.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut criteria = Vec::new();
+        if let Some(ref p) = self.platform { criteria.push(format!("platform={}", p)); }
+        if let Some(ref p) = self.name { criteria.push(format!("name={}", p)); }
+        if let Some(ref p) = self.vendor { criteria.push(format!("vendor={}", p)); }
+        if let Some(flags) = self.device_type {
+            criteria.push(format!("type={}", device_type_names(flags)));
+        }
+        if let Some(bytes) = self.min_global_mem {
+            criteria.push(format!("min_global_mem={}", bytes));
+        }
+        if let Some(version) = self.min_version {
+            criteria.push(format!("min_version={}", version));
+        }
+        if !self.extensions.is_empty() {
+            let names: Vec<_> = self.extensions.iter().map(DeviceExtension::name).collect();
+            criteria.push(format!("extensions={}", names.join(",")));
+        }
+        match self.prefer {
+            Some(DevicePreference::Discrete) => criteria.push("prefer=discrete".to_owned()),
+            Some(DevicePreference::Integrated) => criteria.push("prefer=integrated".to_owned()),
+            None => (),
+        }
+        f.write_str(&criteria.join(";"))
+    }
+}
+
+
+fn compile_pattern(pattern: Option<&String>) -> Result<Option<Regex>, DeviceError> {
+    match pattern {
+        Some(pattern) => RegexBuilder::new(pattern).case_insensitive(true).build()
+            .map(Some)
+            .map_err(|err| DeviceError::InvalidSelector {
+                selector: pattern.clone(),
+                reason: err.to_string(),
+            }),
+        None => Ok(None),
+    }
+}
+
+fn is_preferred(device: &Device, preference: DevicePreference) -> bool {
+    // Devices which do not report this (deprecated) query are never preferred.
+    match device.info(DeviceInfo::HostUnifiedMemory) {
+        Ok(DeviceInfoResult::HostUnifiedMemory(unified)) => {
+            unified == (preference == DevicePreference::Integrated)
+        },
+        _ => false,
+    }
+}
+
+const DEVICE_TYPES: [(&str, DeviceType); 6] = [
+    ("DEFAULT", DeviceType::DEFAULT),
+    ("CPU", DeviceType::CPU),
+    ("GPU", DeviceType::GPU),
+    ("ACCELERATOR", DeviceType::ACCELERATOR),
+    ("CUSTOM", DeviceType::CUSTOM),
+    ("ALL", DeviceType::ALL),
+];
+
+fn parse_device_type(name: &str) -> Option<DeviceType> {
+    DEVICE_TYPES.iter().find(|&&(n, _)| n.eq_ignore_ascii_case(name)).map(|&(_, flags)| flags)
+}
+
+fn device_type_names(flags: DeviceType) -> String {
+    if flags.contains(DeviceType::ALL) { return "ALL".to_owned(); }
+    let names: Vec<_> = DEVICE_TYPES.iter()
+        .filter(|&&(_, f)| f != DeviceType::ALL && flags.contains(f))
+        .map(|&(n, _)| n)
+        .collect();
+    names.join("|")
+}
+
+fn parse_mem_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let (digits, shift) = match s.chars().last()?.to_ascii_uppercase() {
+        'K' => (&s[..s.len() - 1], 10),
+        'M' => (&s[..s.len() - 1], 20),
+        'G' => (&s[..s.len() - 1], 30),
+        'T' => (&s[..s.len() - 1], 40),
+        _ => (s, 0),
+    };
+    digits.trim().parse::<u64>().ok()?.checked_mul(1 << shift)
+}
+
+fn parse_version(s: &str) -> Option<OpenclVersion> {
+    let mut parts = s.trim().splitn(2, '.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = match parts.next() {
+        Some(minor) => minor.parse().ok()?,
+        None => 0,
+    };
+    Some(OpenclVersion::new(major, minor))
+}
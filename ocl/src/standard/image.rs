@@ -14,6 +14,7 @@ use crate::core::{self, OclPrm, Mem as MemCore, MemFlags, MemObjectType, ImageFo
     ImageFormat, ImageDescriptor, ImageInfo, ImageInfoResult, MemInfo, MemInfoResult,
     ImageChannelOrder, ImageChannelDataType, AsMem, MemCmdRw, MemCmdAll,
     MapFlags};
+use crate::core::MemProperties;
 use crate::standard::{Context, Queue, SpatialDims, ClNullEventPtrEnum, ClWaitListPtrEnum,
     QueCtx, HostSlice};
 use crate::MemMap;
@@ -696,6 +697,39 @@ impl<T: OclPrm> Image<T> {
             Some(&device_versions),
         )?;
 
+        Image::from_core_desc(obj_core, que_ctx, &image_desc)
+    }
+
+    /// Returns a new `Image` created with a list of memory object
+    /// properties.
+    ///
+    /// Prefer `::builder` to create a new image.
+    ///
+    /// [Version Controlled: OpenCL 3.0+]
+    pub unsafe fn with_properties<'o, Q>(que_ctx: Q, properties: &MemProperties, flags: MemFlags,
+            image_format: ImageFormat, image_desc: ImageDescriptor, host_data: Option<&[T]>)
+            -> OclResult<Image<T>>
+            where Q: Into<QueCtx<'o>> {
+        let que_ctx = que_ctx.into();
+        let context = que_ctx.context_cloned();
+        let device_versions = context.device_versions()?;
+
+        let obj_core = core::create_image_with_properties(
+            &context,
+            properties,
+            flags,
+            &image_format,
+            &image_desc,
+            host_data,
+            Some(&device_versions),
+        )?;
+
+        Image::from_core_desc(obj_core, que_ctx, &image_desc)
+    }
+
+    /// Wraps a newly created image.
+    fn from_core_desc(obj_core: MemCore, que_ctx: QueCtx, image_desc: &ImageDescriptor)
+            -> OclResult<Image<T>> {
         let pixel_element_len = match core::get_image_info(&obj_core, ImageInfo::ElementSize)? {
             ImageInfoResult::ElementSize(s) => s / mem::size_of::<T>(),
             _ => return Err("ocl::Image::element_len(): \
@@ -932,6 +966,20 @@ impl<T: OclPrm> Image<T> {
     }
 
 
+    /// Registers a callback to be run once the underlying memory object has
+    /// been destroyed (after every clone of this image has been dropped and
+    /// all commands using it have completed).
+    ///
+    /// See [`Buffer::set_destructor_callback`] for details.
+    ///
+    /// [`Buffer::set_destructor_callback`]: struct.Buffer.html#method.set_destructor_callback
+    pub fn set_destructor_callback<F>(&self, callback: F) -> OclResult<()>
+            where F: FnOnce(&MemCore) + Send + 'static {
+        core::set_mem_object_destructor_callback(&self.obj_core, Box::new(callback))
+            .map_err(OclError::from)
+    }
+
+
     /// Format image info.
     fn fmt_info(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         f.debug_struct("Image")
@@ -1009,6 +1057,7 @@ pub struct ImageBuilder<'a, T> where T: 'a {
     host_slice: HostSlice<'a, T>,
     image_format: ImageFormat,
     image_desc: ImageDescriptor,
+    properties: Option<MemProperties>,
     _pixel: PhantomData<T>,
 }
 
@@ -1053,6 +1102,7 @@ impl<'a, T> ImageBuilder<'a, T> where T: 'a + OclPrm {
             host_slice: HostSlice::None,
             image_format: ImageFormat::new_rgba(),
             image_desc: ImageDescriptor::new(MemObjectType::Image1d, 0, 0, 0, 0, 0, 0, None),
+            properties: None,
             _pixel: PhantomData,
         }
     }
@@ -1331,6 +1381,18 @@ impl<'a, T> ImageBuilder<'a, T> where T: 'a + OclPrm {
         self
     }
 
+    /// Specifies a list of memory object properties to create the image
+    /// with.
+    ///
+    /// OpenCL 3.0 does not define any properties of its own. Properties are
+    /// defined by extensions and are platform specific.
+    ///
+    /// [Version Controlled: OpenCL 3.0+]
+    pub fn properties(mut self, properties: MemProperties) -> ImageBuilder<'a, T> {
+        self.properties = Some(properties);
+        self
+    }
+
     /// Builds with no host side image data memory specified and returns a
     /// new `Image`.
     pub fn build(mut self) -> OclResult<Image<T>> {
@@ -1346,14 +1408,19 @@ impl<'a, T> ImageBuilder<'a, T> where T: 'a + OclPrm {
             HostSlice::None => None,
         };
 
-        match self.queue_option {
-            Some(qo) => {
-                unsafe { Image::new(qo, self.flags, self.image_format.clone(),
-                    self.image_desc.clone(), host_slice) }
-            },
+        let qo = match self.queue_option {
+            Some(qo) => qo,
             None => panic!("ocl::ImageBuilder::build: A context or default queue must be set \
                 with '.context(...)' or '.queue(...)'."),
+        };
+
+        if let Some(ref props) = self.properties {
+            return unsafe { Image::with_properties(qo, props, self.flags,
+                self.image_format.clone(), self.image_desc.clone(), host_slice) };
         }
 
+        unsafe { Image::new(qo, self.flags, self.image_format.clone(),
+            self.image_desc.clone(), host_slice) }
+
     }
 }
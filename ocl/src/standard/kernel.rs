@@ -17,6 +17,9 @@ use crate::core::error::{ErrorKind as OclCoreErrorKind};
 use crate::error::{Error as OclError, Result as OclResult, ErrorKind as OclErrorKind};
 use crate::standard::{SpatialDims, Program, Queue, WorkDims, Sampler, Device, ClNullEventPtrEnum,
     ClWaitListPtrEnum, Buffer, Image};
+use crate::core::KernelExecInfo;
+use crate::standard::{SvmVec, Pipe};
+use crate::core::{KernelSubGroupInfo, KernelSubGroupInfoResult};
 pub use self::arg_type::{BaseType, Cardinality, ArgType};
 
 
@@ -47,6 +50,9 @@ pub enum KernelError {
         (required: {}, specified: {}). Use named arguments with 'None' or zero values to \
         declare arguments you plan to assign a value to at a later time.", required, specified)]
     BuilderWrongArgCount { required: u32, specified: u32 },
+    #[fail(display = "Local Work Size cannot be left unspecified when querying sub-group \
+        info. Set a default for the kernel or specify one.")]
+    SubGroupInfoNoLws,
 }
 
 
@@ -413,6 +419,42 @@ impl<'b, T> From<Image<T>> for ArgValConverter<'b, T> where T: OclPrm {
     }
 }
 
+impl<'b, T> From<&'b Pipe<T>> for ArgValConverter<'b, T> where T: OclPrm {
+    /// Converts from a `Pipe`.
+    fn from(pipe: &'b Pipe<T>) -> ArgValConverter<'b, T> {
+        ArgValConverter {
+            val: ArgValKeeper::Shared(ArgVal::mem(pipe)),
+            type_id: Some(TypeId::of::<T>()),
+            mem: Some(pipe.as_mem().clone()),
+            _ty: PhantomData,
+        }
+    }
+}
+
+impl<'b, T> From<&'b mut Pipe<T>> for ArgValConverter<'b, T> where T: OclPrm {
+    fn from(pipe: &'b mut Pipe<T>) -> ArgValConverter<'b, T> {
+        ArgValConverter::from(&*pipe)
+    }
+}
+
+impl<'b, T> From<&'b SvmVec<T>> for ArgValConverter<'b, T> where T: OclPrm {
+    /// Converts from an `SvmVec`.
+    fn from(svm: &'b SvmVec<T>) -> ArgValConverter<'b, T> {
+        ArgValConverter {
+            val: ArgValKeeper::Shared(ArgVal::svm_pointer(svm.as_ptr())),
+            type_id: Some(TypeId::of::<T>()),
+            mem: None,
+            _ty: PhantomData,
+        }
+    }
+}
+
+impl<'b, T> From<&'b mut SvmVec<T>> for ArgValConverter<'b, T> where T: OclPrm {
+    fn from(svm: &'b mut SvmVec<T>) -> ArgValConverter<'b, T> {
+        ArgValConverter::from(&*svm)
+    }
+}
+
 impl<'b, T> From<&'b T> for ArgValConverter<'b, T> where T: OclPrm {
     /// Converts from a scalar or vector value.
     fn from(prm: &'b T) -> ArgValConverter<'b, T> {
@@ -515,7 +557,8 @@ impl MemArgs {
 /// that no two threads create a race condition by attempting to set an
 /// argument and enqueue a kernel at the same time. Use the `KernelBuilder` to
 /// create multiple identical kernels (`KernelBuilder` is clonable and
-/// re-usable).
+/// re-usable) or, on devices supporting OpenCL 2.1+, use `::try_clone` to
+/// create an independent copy of a kernel along with its current arguments.
 #[derive(Debug)]
 pub struct Kernel {
     obj_core: KernelCore,
@@ -534,6 +577,42 @@ impl Kernel {
         KernelBuilder::new()
     }
 
+    /// Wraps a newly created core kernel which has no arguments set.
+    ///
+    /// Argument types are cached (unless unavailable on the platform) and, if
+    /// argument names can be queried, each argument is registered as a named
+    /// argument under the name used in the kernel source.
+    pub(crate) fn from_core_unset(obj_core: KernelCore) -> OclResult<Kernel> {
+        let num_args = match core::get_kernel_info(&obj_core, KernelInfo::NumArgs) {
+            Ok(KernelInfoResult::NumArgs(num)) => num,
+            Err(err) => return Err(OclError::from(err)),
+            _=> unreachable!(),
+        };
+
+        let arg_types = cache_arg_types(&obj_core, num_args)?;
+        let mut named_args = NamedArgs(None);
+
+        if arg_types.is_some() {
+            for arg_idx in 0..num_args {
+                match arg_name(&obj_core, arg_idx) {
+                    Ok(name) => named_args.insert(name.into(), arg_idx),
+                    Err(_) => { named_args = NamedArgs(None); break; },
+                }
+            }
+        }
+
+        Ok(Kernel {
+            obj_core,
+            named_args,
+            mem_args: MemArgs(Some(RefCell::new(BTreeMap::new()))),
+            queue: None,
+            gwo: SpatialDims::Unspecified,
+            gws: SpatialDims::Unspecified,
+            lws: SpatialDims::Unspecified,
+            arg_types,
+        })
+    }
+
     /// Verifies that a type matches the kernel arg info:
     ///
     /// This function does nothing and always returns `Ok` if argument type
@@ -701,6 +780,45 @@ impl Kernel {
         }
     }
 
+    /// Passes additional execution information to the kernel.
+    ///
+    /// Use `KernelExecInfo::SvmPtrs` to declare any shared virtual memory
+    /// allocations which will be accessed through pointers stored within
+    /// another allocation (rather than passed directly as arguments).
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    pub fn set_exec_info(&self, info: KernelExecInfo) -> OclResult<()> {
+        let device_versions = self.obj_core.device_versions()?;
+        core::set_kernel_exec_info(&self.obj_core, &info, Some(&device_versions))
+            .map_err(OclError::from)
+    }
+
+    /// Returns an independent copy of this kernel.
+    ///
+    /// The copy has the same argument values (including buffers and images,
+    /// which are kept alive by the copy), named arguments, default queue and
+    /// default work sizes as this kernel. Arguments set on one afterwards do
+    /// not affect the other, allowing each to be used from a different
+    /// thread. Unlike re-using a `KernelBuilder`, argument types are not
+    /// queried or checked again.
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    pub fn try_clone(&self) -> OclResult<Kernel> {
+        let device_versions = self.obj_core.device_versions()?;
+        let obj_core = core::clone_kernel(&self.obj_core, Some(&device_versions))?;
+
+        Ok(Kernel {
+            obj_core,
+            named_args: self.named_args.clone(),
+            mem_args: self.mem_args.clone(),
+            queue: self.queue.clone(),
+            gwo: self.gwo,
+            gws: self.gws,
+            lws: self.lws,
+            arg_types: self.arg_types.clone(),
+        })
+    }
+
     /// Returns a command builder which is used to chain parameters of an
     /// 'enqueue' command together.
     pub fn cmd(&self) -> KernelCmd {
@@ -824,6 +942,76 @@ impl Kernel {
         core::get_kernel_work_group_info(&self.obj_core, device, info_kind).map_err(OclError::from)
     }
 
+    /// Returns sub-group information for this kernel.
+    ///
+    /// See `core::get_kernel_sub_group_info` for the contents of
+    /// `input_value`.
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    pub fn sub_group_info(&self, device: Device, info_kind: KernelSubGroupInfo,
+            input_value: &[usize]) -> OclResult<KernelSubGroupInfoResult> {
+        core::get_kernel_sub_group_info(&self.obj_core, *device.as_core(), info_kind,
+            input_value, None).map_err(OclError::from)
+    }
+
+    /// Resolves a local work size for a sub-group query, using the default
+    /// if `lws` is unspecified.
+    fn sub_group_lws(&self, lws: SpatialDims) -> OclResult<Vec<usize>> {
+        let lws = if lws.is_unspecified() { self.lws } else { lws };
+        let lens = lws.to_work_size().ok_or(KernelError::SubGroupInfoNoLws)?;
+        Ok(lens[..lws.dim_count() as usize].to_vec())
+    }
+
+    /// Returns the maximum sub-group size of this kernel when enqueued on
+    /// `device` with a local work size of `local_work_size` (or the default
+    /// local work size if unspecified).
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    pub fn max_sub_group_size<D>(&self, device: Device, local_work_size: D) -> OclResult<usize>
+            where D: Into<SpatialDims> {
+        let lws = self.sub_group_lws(local_work_size.into())?;
+        match self.sub_group_info(device, KernelSubGroupInfo::MaxSubGroupSizeForNdrange, &lws)? {
+            KernelSubGroupInfoResult::MaxSubGroupSizeForNdrange(size) => Ok(size),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the number of sub-groups in each work-group of this kernel
+    /// when enqueued on `device` with a local work size of `local_work_size`
+    /// (or the default local work size if unspecified).
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    pub fn sub_group_count<D>(&self, device: Device, local_work_size: D) -> OclResult<usize>
+            where D: Into<SpatialDims> {
+        let lws = self.sub_group_lws(local_work_size.into())?;
+        match self.sub_group_info(device, KernelSubGroupInfo::SubGroupCountForNdrange, &lws)? {
+            KernelSubGroupInfoResult::SubGroupCountForNdrange(count) => Ok(count),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the local work size which would produce `sub_group_count`
+    /// sub-groups per work-group when this kernel is enqueued on `device`, or
+    /// `SpatialDims::Unspecified` if no local work size would.
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    pub fn local_size_for_sub_group_count(&self, device: Device, sub_group_count: usize)
+            -> OclResult<SpatialDims> {
+        match self.sub_group_info(device, KernelSubGroupInfo::LocalSizeForSubGroupCount,
+                &[sub_group_count])? {
+            KernelSubGroupInfoResult::LocalSizeForSubGroupCount(lens) => {
+                Ok(match lens.as_slice() {
+                    _ if lens.iter().all(|&l| l == 0) => SpatialDims::Unspecified,
+                    &[x] | &[x, 1] | &[x, 1, 1] => SpatialDims::One(x),
+                    &[x, y] | &[x, y, 1] => SpatialDims::Two(x, y),
+                    &[x, y, z, ..] => SpatialDims::Three(x, y, z),
+                    _ => SpatialDims::Unspecified,
+                })
+            },
+            _ => unreachable!(),
+        }
+    }
+
     /// Returns argument information for this kernel.
     pub fn arg_info(&self, arg_idx: u32, info_kind: KernelArgInfo)
             -> OclResult<KernelArgInfoResult> {
@@ -1422,34 +1610,15 @@ impl<'b> KernelBuilder<'b> {
              }.into())
         }
 
-        let mut arg_types = Vec::with_capacity(num_args as usize);
-        let mut all_arg_types_unknown = true;
-        let mut disable_arg_check = self.disable_arg_check;
-
-        // Cache argument types for later use, bypassing if the OpenCL version
-        // is too low (v1.1).
-        for arg_idx in 0..num_args {
-            let arg_type = match ArgType::from_kern_and_idx(&obj_core, arg_idx) {
-                Ok(at) => {
-                    if !at.is_unknown() { all_arg_types_unknown = false; }
-                    at
-                },
-                Err(err) => {
-                    if let OclErrorKind::OclCore(ref core_err) = *err.kind() {
-                        if let OclCoreErrorKind::VersionLow { .. } = *core_err.kind() {
-                            disable_arg_check = true;
-                            break;
-                        }
-                    }
-                    return Err(err);
-                },
-            };
-            arg_types.push(arg_type);
-        }
+        let arg_types = if self.disable_arg_check {
+            None
+        } else {
+            cache_arg_types(&obj_core, num_args)?
+        };
 
         // Check argument types then set arguments.
         for (arg_idx, &(ref arg, ref type_id_opt)) in self.args.iter().enumerate() {
-            if !disable_arg_check {
+            if let Some(ref arg_types) = arg_types {
                 if let Some(type_id) = *type_id_opt {
                     if !arg_types[arg_idx].matches(type_id) {
                         let ty_name = arg_type_name(&obj_core, arg_idx as u32)?;
@@ -1468,12 +1637,6 @@ impl<'b> KernelBuilder<'b> {
             }
         }
 
-        let arg_types = if all_arg_types_unknown || disable_arg_check {
-            None
-        } else {
-            Some(arg_types)
-        };
-
         Ok(Kernel {
             obj_core,
             named_args: self.named_args.clone(),
@@ -1488,6 +1651,37 @@ impl<'b> KernelBuilder<'b> {
 }
 
 
+/// Queries and caches the type of each argument of a kernel.
+///
+/// Returns `None` if argument type information is unavailable, either because
+/// a device does not support OpenCL 1.2+ or because the platform does not
+/// report it.
+fn cache_arg_types(obj_core: &KernelCore, num_args: u32) -> OclResult<Option<Vec<ArgType>>> {
+    let mut arg_types = Vec::with_capacity(num_args as usize);
+    let mut all_arg_types_unknown = true;
+
+    for arg_idx in 0..num_args {
+        let arg_type = match ArgType::from_kern_and_idx(obj_core, arg_idx) {
+            Ok(at) => {
+                if !at.is_unknown() { all_arg_types_unknown = false; }
+                at
+            },
+            Err(err) => {
+                if let OclErrorKind::OclCore(ref core_err) = *err.kind() {
+                    if let OclCoreErrorKind::VersionLow { .. } = *core_err.kind() {
+                        return Ok(None);
+                    }
+                }
+                return Err(err);
+            },
+        };
+        arg_types.push(arg_type);
+    }
+
+    if all_arg_types_unknown { Ok(None) } else { Ok(Some(arg_types)) }
+}
+
+
 /// Returns argument information for a kernel.
 pub fn arg_info(core: &KernelCore, arg_idx: u32, info_kind: KernelArgInfo)
         -> OclResult<KernelArgInfoResult> {
@@ -1524,7 +1718,8 @@ pub mod arg_type {
     use std::any::{Any, TypeId};
     use crate::ffi::{cl_char, cl_uchar, cl_short, cl_ushort, cl_int, cl_uint, cl_long, cl_ulong,
         cl_half, cl_float, cl_double, cl_bool, cl_bitfield};
-    use crate::core::{Error as OclCoreError, Result as OclCoreResult, Status, OclPrm, Kernel as KernelCore};
+    use crate::core::{Error as OclCoreError, Result as OclCoreResult, Status, OclPrm, Kernel as KernelCore,
+        KernelArgInfo, KernelArgInfoResult, KernelArgTypeQualifier};
     use crate::error::{Error as OclError, Result as OclResult, ErrorKind as OclErrorKind};
     use crate::standard::Sampler;
     use super::{arg_info, arg_type_name};
@@ -1579,6 +1774,7 @@ pub mod arg_type {
         base_type: BaseType,
         cardinality: Cardinality,
         is_ptr: bool,
+        is_pipe: bool,
     }
 
     impl ArgType {
@@ -1589,6 +1785,7 @@ pub mod arg_type {
                 base_type: BaseType::Unknown,
                 cardinality: Cardinality::One,
                 is_ptr: false,
+                is_pipe: false,
             })
         }
 
@@ -1600,6 +1797,7 @@ pub mod arg_type {
         /// `::starts_with` be used for base type names instead?
         pub fn from_str(type_name: &str) -> OclResult<ArgType> {
             let is_ptr = type_name.contains('*');
+            let is_pipe = type_name.trim_start().starts_with("pipe ");
 
             let card = if type_name.contains("16") {
                 Cardinality::Sixteen
@@ -1647,6 +1845,7 @@ pub mod arg_type {
                 base_type: base,
                 cardinality: card,
                 is_ptr,
+                is_pipe,
             })
         }
 
@@ -1663,7 +1862,19 @@ pub mod arg_type {
             use crate::core::ErrorKind as OclCoreErrorKind;
 
             match arg_type_name(core, arg_idx) {
-                Ok(type_name) => ArgType::from_str(type_name.as_str()),
+                Ok(type_name) => {
+                    let mut arg_type = ArgType::from_str(type_name.as_str())?;
+
+                    // The type name of a pipe argument is that of its packets:
+                    if let Ok(KernelArgInfoResult::TypeQualifier(qualifier)) =
+                            arg_info(core, arg_idx, KernelArgInfo::TypeQualifier) {
+                        if qualifier.contains(KernelArgTypeQualifier::PIPE) {
+                            arg_type.is_pipe = true;
+                        }
+                    }
+
+                    Ok(arg_type)
+                },
                 Err(err) => {
                     // Escape hatches for known, platform-specific errors.
                     if let OclErrorKind::OclCore(ref core_err) = *err.kind() {
@@ -1747,6 +1958,12 @@ pub mod arg_type {
             self.is_ptr
         }
 
+        /// Returns true if the argument is a pipe (of packets of the base
+        /// type).
+        pub fn is_pipe(&self) -> bool {
+            self.is_pipe
+        }
+
         pub fn is_unknown(&self) -> bool {
             match self.base_type {
                 BaseType::Unknown => true,
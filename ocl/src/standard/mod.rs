@@ -6,6 +6,8 @@ mod platform;
 mod device;
 mod context;
 mod program;
+mod program_cache;
+mod source_map;
 mod kernel;
 mod queue;
 mod buffer;
@@ -14,20 +16,33 @@ mod sampler;
 mod pro_que;
 mod event;
 mod spatial_dims;
+mod svm;
+mod pipe;
+mod device_clock;
+mod device_extensions;
+mod device_selector;
 
 pub use self::platform::{PlatformError, Extensions, Platform};
-pub use self::device::{DeviceError, Device, DeviceSpecifier};
+pub use self::device::{DeviceError, Device, SubDevice, DeviceSpecifier};
 pub use self::context::{Context, ContextBuilder};
-pub use self::program::{Program, ProgramBuilder, BuildOpt};
-pub use self::queue::Queue;
+pub use self::program::{Program, ProgramBuilder, BuildOpt, FutureProgram};
+pub use self::program_cache::{ProgramCache, PROGRAM_CACHE_DIR_ENV_VAR};
+pub use self::source_map::{SourceMap, SourceLocation};
+pub use self::queue::{QueueError, Queue, NativeMemArg, NativeArgs};
 pub use self::kernel::{KernelError, KernelCmd, Kernel, KernelBuilder};
 pub use self::buffer::{BufferCmdKind, BufferCmdDataShape, BufferCmd, Buffer, QueCtx,
     BufferBuilder, BufferReadCmd, BufferWriteCmd, BufferMapCmd, BufferCmdError, WriteSrc};
 pub use self::image::{ImageCmdKind, ImageCmd, Image, ImageBuilder};
-pub use self::sampler::Sampler;
+pub use self::sampler::{Sampler, SamplerBuilder};
 pub use self::pro_que::{ProQue, ProQueBuilder};
 pub use self::event::{Event, EventArray, EventList, IntoMarker, RawEventArray, IntoRawEventArray};
 pub use self::spatial_dims::SpatialDims;
+pub use self::svm::{SvmError, SvmCmdKind, SvmCmd, SvmMapCmd, SvmMap, SvmUnmapCmd, SvmVec,
+    SvmVecBuilder};
+pub use self::pipe::{PipeError, Pipe, PipeBuilder};
+pub use self::device_clock::DeviceClock;
+pub use self::device_extensions::{DeviceExtension, DeviceExtensions};
+pub use self::device_selector::{DeviceSelector, DevicePreference, DEVICE_SELECTOR_ENV_VAR};
 #[cfg(not(feature = "async_block"))]
 pub use self::cb::{_unpark_task, box_raw_void};
 pub use self::traits::{MemLen, WorkDims};
@@ -0,0 +1,173 @@
+//! An OpenCL pipe.
+
+use std;
+use std::ops::{Deref, DerefMut};
+use std::marker::PhantomData;
+use crate::core::{self, OclPrm, Mem as MemCore, MemFlags, MemInfo, MemInfoResult, PipeInfo,
+    PipeInfoResult, AsMem};
+use crate::error::{Error as OclError, Result as OclResult};
+use crate::standard::Context;
+
+
+/// A pipe error.
+#[derive(Debug, Fail)]
+pub enum PipeError {
+    #[fail(display = "No context specified.")]
+    BuilderNoContext,
+    #[fail(display = "The maximum number of packets must be greater than zero.")]
+    BuilderMaxPacketsZero,
+}
+
+
+/// A memory object which stores packets of type `T` in FIFO order.
+///
+/// Pipes connect kernels to one another: one kernel writes packets (using
+/// the `write_pipe` built-in functions) which another kernel, enqueued
+/// afterwards, reads (using `read_pipe`). A pipe can not be read from or
+/// written to by the host.
+///
+/// Pass a pipe to a kernel with `KernelBuilder::arg` or `Kernel::set_arg`
+/// as with a `Buffer`. The packet type of the pipe must match the element
+/// type declared by the kernel (e.g. `Pipe<i32>` for `pipe int`).
+///
+/// [Version Controlled: OpenCL 2.0+]
+#[derive(Clone, Debug)]
+pub struct Pipe<T: OclPrm> {
+    obj_core: MemCore,
+    max_packets: u32,
+    _packet: PhantomData<T>,
+}
+
+impl<T: OclPrm> Pipe<T> {
+    /// Returns a new `PipeBuilder`.
+    pub fn builder<'a>() -> PipeBuilder<'a, T> {
+        PipeBuilder::new()
+    }
+
+    /// Returns the size (in bytes) of each packet.
+    #[inline]
+    pub fn packet_size(&self) -> u32 {
+        std::mem::size_of::<T>() as u32
+    }
+
+    /// Returns the maximum number of packets the pipe can hold.
+    #[inline]
+    pub fn max_packets(&self) -> u32 {
+        self.max_packets
+    }
+
+    /// Returns info about this pipe.
+    pub fn info(&self, info_kind: PipeInfo) -> OclResult<PipeInfoResult> {
+        core::get_pipe_info(&self.obj_core, info_kind).map_err(OclError::from)
+    }
+
+    /// Returns info about this pipe's memory.
+    pub fn mem_info(&self, info_kind: MemInfo) -> OclResult<MemInfoResult> {
+        core::get_mem_object_info(&self.obj_core, info_kind).map_err(OclError::from)
+    }
+
+    /// Returns a reference to the core pointer wrapper, usable by functions in
+    /// the `core` module.
+    #[inline]
+    pub fn as_core(&self) -> &MemCore {
+        &self.obj_core
+    }
+
+    /// Format pipe info.
+    fn fmt_info(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Pipe")
+            .field("PacketSize", &self.info(PipeInfo::PacketSize))
+            .field("MaxPackets", &self.info(PipeInfo::MaxPackets))
+            .field("Flags", &self.mem_info(MemInfo::Flags))
+            .field("Size", &self.mem_info(MemInfo::Size))
+            .field("ReferenceCount", &self.mem_info(MemInfo::ReferenceCount))
+            .finish()
+    }
+}
+
+impl<T: OclPrm> std::fmt::Display for Pipe<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.fmt_info(f)
+    }
+}
+
+impl<T: OclPrm> Deref for Pipe<T> {
+    type Target = MemCore;
+
+    fn deref(&self) -> &MemCore {
+        &self.obj_core
+    }
+}
+
+impl<T: OclPrm> DerefMut for Pipe<T> {
+    fn deref_mut(&mut self) -> &mut MemCore {
+        &mut self.obj_core
+    }
+}
+
+impl<T: OclPrm> AsMem<T> for Pipe<T> {
+    fn as_mem(&self) -> &MemCore {
+        &self.obj_core
+    }
+}
+
+
+/// A builder for `Pipe`.
+#[must_use = "builders do nothing unless '::build' is called"]
+pub struct PipeBuilder<'a, T> where T: OclPrm {
+    context: Option<&'a Context>,
+    flags: Option<MemFlags>,
+    max_packets: u32,
+    _packet: PhantomData<T>,
+}
+
+impl<'a, T> PipeBuilder<'a, T> where T: 'a + OclPrm {
+    /// Returns a new `PipeBuilder`.
+    pub fn new() -> PipeBuilder<'a, T> {
+        PipeBuilder {
+            context: None,
+            flags: None,
+            max_packets: 0,
+            _packet: PhantomData,
+        }
+    }
+
+    /// Sets the context within which to create the pipe.
+    pub fn context(mut self, context: &'a Context) -> PipeBuilder<'a, T> {
+        self.context = Some(context);
+        self
+    }
+
+    /// Sets the flags used when creating the pipe.
+    ///
+    /// Only `MEM_READ_WRITE` and `MEM_HOST_NO_ACCESS` are valid. Defaults to
+    /// both.
+    pub fn flags(mut self, flags: MemFlags) -> PipeBuilder<'a, T> {
+        self.flags = Some(flags);
+        self
+    }
+
+    /// Sets the maximum number of packets the pipe can hold.
+    pub fn max_packets(mut self, max_packets: u32) -> PipeBuilder<'a, T> {
+        self.max_packets = max_packets;
+        self
+    }
+
+    /// Creates and returns a new `Pipe`.
+    pub fn build(self) -> OclResult<Pipe<T>> {
+        let context = self.context.ok_or(PipeError::BuilderNoContext)?;
+        if self.max_packets == 0 { return Err(PipeError::BuilderMaxPacketsZero.into()); }
+
+        let flags = self.flags.unwrap_or(MemFlags::new().read_write().host_no_access());
+        let device_versions = context.device_versions()?;
+
+        let obj_core = core::create_pipe(context, flags, std::mem::size_of::<T>() as u32,
+            self.max_packets, Some(&device_versions))?;
+
+        Ok(Pipe {
+            obj_core,
+            max_packets: self.max_packets,
+            _packet: PhantomData,
+        })
+    }
+}
@@ -8,13 +8,15 @@ use std;
 use std::ops::{Deref, DerefMut};
 use std::str::SplitWhitespace;
 use crate::ffi::cl_platform_id;
-use crate::core::{self, PlatformId as PlatformIdCore, PlatformInfo, PlatformInfoResult, ClPlatformIdPtr};
+use crate::core::{self, PlatformId as PlatformIdCore, PlatformInfo, PlatformInfoResult, ClPlatformIdPtr,
+    IcdDiagnostics};
 use crate::error::{Error as OclError, Result as OclResult};
 
 
 #[derive(Debug, Fail)]
 pub enum PlatformError {
-    #[fail(display = "No platforms found.")]
+    #[fail(display = "No platforms found. Use 'Platform::diagnose' to check the \
+        installed OpenCL drivers.")]
     NoPlatforms,
 }
 
@@ -66,6 +68,15 @@ impl Platform {
             .ok_or(PlatformError::NoPlatforms.into())
     }
 
+    /// Searches for OpenCL ICD vendor libraries in the same way as the ICD
+    /// loader and reports which could not be loaded and why.
+    ///
+    /// Useful when `Platform::list` returns no platforms. See
+    /// `core::diagnose_icd` for more.
+    pub fn diagnose() -> IcdDiagnostics {
+        core::diagnose_icd()
+    }
+
     /// Creates a new `Platform` from a `PlatformIdCore`.
     ///
     /// ## Safety
@@ -4,7 +4,7 @@ use std::ops::Deref;
 use crate::error::{Error as OclError, Result as OclResult};
 use crate::core::{OclPrm, CommandQueueProperties};
 use crate::standard::{Platform, Device, Context, ProgramBuilder, Program, Queue, Kernel, Buffer,
-    MemLen, SpatialDims, WorkDims, DeviceSpecifier, KernelBuilder, BufferBuilder};
+    MemLen, SpatialDims, WorkDims, DeviceSpecifier, DeviceSelector, KernelBuilder, BufferBuilder};
 
 static DIMS_ERR_MSG: &'static str = "This 'ProQue' has not had any dimensions specified. Use
     'ProQueBuilder::dims' during creation or 'ProQue::set_dims' after creation to specify.";
@@ -301,7 +301,10 @@ impl<'b> ProQueBuilder<'b> {
     /// Sets a device or devices to be used and returns a `ProQueBuilder`
     /// reference.
     ///
-    /// Must specify only a single device.
+    /// Must specify only a single device, with the exception of
+    /// `DeviceSpecifier::Selector` for which the best match is used. If no
+    /// device (or context) is set, the `OCL_DEVICE` environment variable
+    /// selector is used if set.
     ///
     pub fn device<D: Into<DeviceSpecifier>>(&mut self, device_spec: D)
             -> &mut ProQueBuilder<'b>
@@ -395,6 +398,15 @@ impl<'b> ProQueBuilder<'b> {
                 'ProQueBuilder' and 'ProgramBuilder' documentation for more information.".into()),
         };
 
+        // The `OCL_DEVICE` selector is used if no device or context is set:
+        let device_spec = match self.device_spec {
+            Some(ref ds) => Some(ds.clone()),
+            None if self.context.is_none() => {
+                DeviceSelector::from_env()?.map(DeviceSpecifier::from)
+            },
+            None => None,
+        };
+
         // If no platform is set or no context platform is set, use the first available:
         let platform = match self.platform {
             Some(ref plt) => {
@@ -411,17 +423,21 @@ impl<'b> ProQueBuilder<'b> {
 
                     plat.unwrap_or_default()
                 },
-                None => Platform::default(),
+                None => match device_spec {
+                    Some(ref ds) => ds.platform()?,
+                    None => Platform::default(),
+                },
             },
         };
 
 
         // Resolve the device and ensure only one was specified.
-        let device = match self.device_spec {
+        let device = match device_spec {
             Some(ref ds) => {
                 let device_list = ds.to_device_list(Some(platform))?;
 
-                if device_list.len() == 1 {
+                // Selectors list every matching device, best match first.
+                if device_list.len() == 1 || ds.is_selector() {
                     device_list[0]
                 } else {
                     return Err(format!("Invalid number of devices specified ({}). Each 'ProQue' \
@@ -454,18 +470,7 @@ impl<'b> ProQueBuilder<'b> {
 
         let queue = Queue::new(&context, device, self.queue_properties)?;
 
-        // println!("PROQUEBUILDER: About to load SRC_STRINGS.");
-        let src_strings = program_builder.get_src_strings().map_err(|e| e.to_string())?;
-        // println!("PROQUEBUILDER: About to load CMPLR_OPTS.");
-        let cmplr_opts = program_builder.get_compiler_options().map_err(|e| e.to_string())?;
-        // println!("PROQUEBUILDER: All done.");
-
-        let program = Program::with_source(
-            &context,
-            &src_strings,
-            Some(&[device]),
-            &cmplr_opts,
-        )?;
+        let program = program_builder.build_for(&context, &[device])?;
 
         Ok(ProQue::new(context, queue, program, self.dims))
     }
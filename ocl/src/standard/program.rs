@@ -2,19 +2,23 @@
 use std;
 use std::ops::{Deref, DerefMut};
 use std::ffi::CString;
-use std::io::Read;
-use std::fs::File;
 use std::path::PathBuf;
-use std::collections::HashSet;
+use std::collections::{HashSet, HashMap};
 use std::convert::Into;
+use std::slice;
+use futures::{Future, Poll, Async};
+use futures::sync::oneshot::{self, Receiver};
 
 
 use crate::core::{self, Result as OclCoreResult, Program as ProgramCore, Context as ContextCore,
-    ProgramInfo, ProgramInfoResult, ProgramBuildInfo, ProgramBuildInfoResult};
-#[cfg(feature = "opencl_version_2_1")]
+    ProgramInfo, ProgramInfoResult, ProgramBuildInfo, ProgramBuildInfoResult, ProgramBuildLog,
+    ProgramBinaryType, OclPrm};
 use core::ClVersions;
 use crate::error::{Result as OclResult, Error as OclError};
-use crate::standard::{Context, Device, DeviceSpecifier};
+use crate::core::{ErrorKind as OclCoreErrorKind, Error as OclCoreError, ProgramBuildError};
+use crate::error::ErrorKind as OclErrorKind;
+use crate::standard::{Context, Device, DeviceSpecifier, Kernel, ProgramCache, SourceMap};
+use crate::standard::source_map::{SourceBuilder, is_combined_source};
 
 
 /// A program from which kernels can be created from.
@@ -59,9 +63,91 @@ impl Program {
         Ok(Program(program))
     }
 
+    /// Returns a new program containing `src`, uncompiled, for use as an
+    /// embedded header when compiling other programs (see `::compile`).
+    pub fn header(context: &ContextCore, src: &str) -> OclResult<Program> {
+        let program = core::create_program_with_source(context, &[CString::new(src)?])?;
+        Ok(Program(program))
+    }
+
+    /// Returns a new program compiled, but not linked, from pre-created build
+    /// components and device list.
+    ///
+    /// `headers` lists the programs (see `::header`) to use for each
+    /// `#include` of the source, by the name used to include them (e.g.
+    /// `("math.h", &math_header)` for `#include "math.h"`). Link compiled
+    /// programs into an executable or library with `::link`.
+    ///
+    /// Prefer `::builder` (and `ProgramBuilder::compile`) to create a new
+    /// `Program`.
+    ///
+    /// [Version Controlled: OpenCL 1.2+]
+    pub fn compile(context: &ContextCore, src_strings: &[CString], devices: Option<&[Device]>,
+            cmplr_opts: &CString, headers: &[(&str, &Program)]) -> OclResult<Program> {
+        let device_versions = context.device_versions()?;
+        let program = core::create_program_with_source(context, src_strings)?;
+
+        let mut header_names = Vec::with_capacity(headers.len());
+        for &(name, _) in headers {
+            header_names.push(CString::new(name)?);
+        }
+        let header_programs: Vec<&ProgramCore> = headers.iter().map(|&(_, p)| &p.0).collect();
+
+        core::compile_program(&program, devices, cmplr_opts, &header_programs, &header_names,
+            None, None, Some(&device_versions))?;
+        Ok(Program(program))
+    }
+
+    /// Links compiled programs (see `::compile`) and libraries into a new
+    /// executable program for the devices of the first of `programs`.
+    ///
+    /// If `options` contains `-create-library` a library is created instead,
+    /// which may itself be linked into other programs. This allows a library
+    /// to be compiled once and linked into any number of programs.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore
+    /// let math = Program::builder().src(MATH_SRC).compile(&context)?;
+    /// let math_lib = Program::link(&[&math], "-create-library")?;
+    ///
+    /// let kernels = Program::builder().src(KERNEL_SRC).header("math.h", &math_header)
+    ///     .compile(&context)?;
+    /// let program = Program::link(&[&math_lib, &kernels], "")?;
+    /// ```
+    ///
+    /// [Version Controlled: OpenCL 1.2+]
+    pub fn link(programs: &[&Program], options: &str) -> OclResult<Program> {
+        let first = programs.first().ok_or("Program::link: No programs specified.")?;
+        let context = match first.info(ProgramInfo::Context)? {
+            ProgramInfoResult::Context(context) => context,
+            _ => unreachable!(),
+        };
+        let devices = first.0.devices()?;
+        let device_versions = first.0.device_versions()?;
+        let program_cores: Vec<&ProgramCore> = programs.iter().map(|p| &p.0).collect();
+
+        core::link_program(&context, Some(&devices), &CString::new(options)?, &program_cores,
+            None, None, Some(&device_versions)).map(Program).map_err(OclError::from)
+    }
+
+    /// Returns a new program containing the named built-in kernels of each of
+    /// `devices`.
+    ///
+    /// Built-in kernel programs are ready for use once created and are not
+    /// built. Use `Device::built_in_kernels` to list the kernels a device
+    /// provides.
+    ///
+    /// Prefer `::builder` to create a new `Program`.
+    ///
+    pub fn with_built_in_kernels<S: AsRef<str>>(context: &ContextCore, devices: &[Device],
+            kernel_names: &[S]) -> OclResult<Program> {
+        core::create_program_with_built_in_kernels(context, devices, kernel_names, None)
+            .map(Program).map_err(OclError::from)
+    }
+
     /// Returns a new program built from pre-created build components and device
     /// list for programs with intermediate language byte source.
-    #[cfg(feature = "opencl_version_2_1")]
     pub fn with_il(il: &[u8], devices: Option<&[Device]>, cmplr_opts: &CString,
             context: &ContextCore) -> OclResult<Program> {
         let device_versions = context.device_versions()?;
@@ -71,6 +157,30 @@ impl Program {
         Ok(Program(program))
     }
 
+    /// Returns a new program built from intermediate language byte source
+    /// after setting the value of each of `spec_constants`, a list of
+    /// `(spec_id, value_bytes)`.
+    ///
+    /// Prefer `::builder` (and `ProgramBuilder::spec_constant`) to create a
+    /// new `Program`.
+    ///
+    /// [Version Controlled: OpenCL 2.2+]
+    pub fn with_specialized_il(il: &[u8], spec_constants: &[(u32, &[u8])],
+            devices: Option<&[Device]>, cmplr_opts: &CString, context: &ContextCore)
+            -> OclResult<Program> {
+        let device_versions = context.device_versions()?;
+        let program = core::create_program_with_il(context, il, Some(&device_versions))?;
+
+        for &(spec_id, value) in spec_constants {
+            core::set_program_specialization_constant(&program, spec_id, value,
+                Some(&device_versions))?;
+        }
+
+        core::build_program(&program, devices, cmplr_opts, None, None)?;
+
+        Ok(Program(program))
+    }
+
     /// Returns a reference to the core pointer wrapper, usable by functions in
     /// the `core` module.
     #[inline]
@@ -83,6 +193,54 @@ impl Program {
         core::get_program_info(&self.0, info_kind)
     }
 
+    /// Returns the names of every kernel function in this program.
+    pub fn kernel_names(&self) -> OclResult<Vec<String>> {
+        match core::get_program_info(&self.0, ProgramInfo::KernelNames) {
+            Ok(ProgramInfoResult::KernelNames(names)) => Ok(names.split(';')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect()),
+            Err(err) => Err(err.into()),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Creates a `Kernel` for every kernel function in this program, indexed
+    /// by name.
+    ///
+    /// Each kernel has no arguments set and no default queue or work sizes.
+    /// Argument types are cached for type checking exactly as with
+    /// `KernelBuilder` and, where the platform reports argument names, each
+    /// argument may also be set by the name used in the kernel source (e.g.
+    /// `kernel.set_arg("buffer", &buffer)`).
+    ///
+    /// Use `Kernel::num_args` and `Kernel::arg_info` to inspect the
+    /// arguments of kernels whose signatures are not known in advance.
+    pub fn kernels(&self) -> OclResult<HashMap<String, Kernel>> {
+        let kernels = core::create_kernels_in_program(&self.0)?;
+        let mut map = HashMap::with_capacity(kernels.len());
+
+        for obj_core in kernels {
+            let kernel = Kernel::from_core_unset(obj_core)?;
+            map.insert(kernel.name()?, kernel);
+        }
+        Ok(map)
+    }
+
+    /// Registers a callback to be run when this program is destroyed (when
+    /// the last clone of it, and any kernel created from it, is dropped).
+    ///
+    /// The callback may be run from a thread belonging to the OpenCL runtime.
+    /// Some platforms do not support release callbacks and return an error.
+    ///
+    /// [Version Controlled: OpenCL 2.2+]
+    pub fn set_release_callback<F>(&self, callback: F) -> OclResult<()>
+            where F: FnOnce() + Send + 'static {
+        core::set_program_release_callback(&self.0, Box::new(callback), None)
+            .map_err(OclError::from)
+    }
+
     /// Returns info about this program's build.
     ///
     /// * TODO: Check that device is valid.
@@ -91,6 +249,26 @@ impl Program {
         core::get_program_build_info(&self.0, &device, info_kind)
     }
 
+    /// Returns the type of the binary of this program for `device`: none, a
+    /// compiled object, a library or an executable.
+    pub fn binary_type(&self, device: Device) -> OclResult<ProgramBinaryType> {
+        match core::get_program_build_info(&self.0, &device, ProgramBuildInfo::BinaryType) {
+            Ok(ProgramBuildInfoResult::BinaryType(binary_type)) => Ok(binary_type),
+            Err(err) => Err(err.into()),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the build status, log and diagnostics of each of this
+    /// program's devices.
+    ///
+    /// Use this to retrieve the warnings of a successful build (failed
+    /// builds return the build log within their error).
+    pub fn build_log(&self) -> OclResult<ProgramBuildLog> {
+        let devices = self.devices()?;
+        core::get_program_build_log(&self.0, &devices).map_err(OclError::from)
+    }
+
     fn fmt_info(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         f.debug_struct("Program")
             .field("ReferenceCount", &self.info(ProgramInfo::ReferenceCount))
@@ -151,6 +329,7 @@ pub enum BuildOpt {
     IncludeDefine { ident: String, val: String },
     IncludeRaw(String),
     IncludeRawEof(String),
+    SpecConstant { id: u32, value: Vec<u8> },
 }
 
 impl BuildOpt {
@@ -169,6 +348,17 @@ impl BuildOpt {
             val,
         }
     }
+
+    /// Returns a `BuildOpt::SpecConstant` containing the bytes of `val`.
+    pub fn spec_constant<T: OclPrm>(id: u32, val: T) -> BuildOpt {
+        let bytes = unsafe { slice::from_raw_parts(&val as *const T as *const u8,
+            std::mem::size_of::<T>()) };
+
+        BuildOpt::SpecConstant {
+            id,
+            value: bytes.to_vec(),
+        }
+    }
 }
 
 
@@ -180,20 +370,23 @@ enum CreateWith<'b> {
     Source(Vec<PathBuf>),
     Binaries(&'b[&'b [u8]]),
     Il(&'b [u8]),
+    BuiltInKernels(&'b [&'b str]),
 }
 
 
 /// A builder for `Program`.
 ///
-// * [SOMEDAY TODO]: Keep track of line number range for each string and print
-// out during build failure.
-//
+/// Line numbers within build logs which refer to the combined program source
+/// are mapped back to the original file or string (see `SourceMap`).
 #[must_use = "builders do nothing unless '::build' is called"]
 #[derive(Clone, Debug)]
 pub struct ProgramBuilder<'b> {
     options: Vec<BuildOpt>,
     with: CreateWith<'b>,
     device_spec: Option<DeviceSpecifier>,
+    cache: Option<ProgramCache>,
+    resolve_includes: bool,
+    headers: Vec<(String, Program)>,
 }
 
 impl<'b> ProgramBuilder<'b> {
@@ -203,6 +396,9 @@ impl<'b> ProgramBuilder<'b> {
             options: Vec::with_capacity(64),
             with: CreateWith::None,
             device_spec: None,
+            cache: None,
+            resolve_includes: false,
+            headers: Vec::new(),
         }
     }
 
@@ -230,6 +426,54 @@ impl<'b> ProgramBuilder<'b> {
         self
     }
 
+    /// Adds a directory to search for included files.
+    ///
+    /// Passed to the compiler as `-I {path}` and, when `::resolve_includes`
+    /// is enabled, searched when resolving `#include` directives.
+    ///
+    /// ## Example
+    ///
+    /// `...include_dir("kernels/include")...`
+    ///
+    pub fn include_dir<'a, P: Into<PathBuf>>(&'a mut self, path: P) -> &'a mut ProgramBuilder<'b> {
+        self.options.push(BuildOpt::CmplrInclDir { path: path.into().display().to_string() });
+        self
+    }
+
+    /// Resolves `#include "..."` directives before passing source to the
+    /// compiler, rather than leaving them to the platform.
+    ///
+    /// Included files are searched for relative to the including file, then
+    /// within each directory added with `::include_dir` (or a
+    /// `BuildOpt::CmplrInclDir`). Files containing `#pragma once` or wrapped
+    /// in an include guard are included only once. `#line` directives are
+    /// inserted so that build logs refer to the original files.
+    ///
+    /// Unresolvable includes cause `::build` to fail. Disabled by default.
+    ///
+    pub fn resolve_includes<'a>(&'a mut self, resolve: bool) -> &'a mut ProgramBuilder<'b> {
+        self.resolve_includes = resolve;
+        self
+    }
+
+    /// Sets the value of the SPIR-V specialization constant identified by
+    /// `id` before the program is built.
+    ///
+    /// The size of `T` must match that of the constant (use `u8` for boolean
+    /// constants). Specialization constants may only be used when building
+    /// from IL (`::il`). Constants which are not set use the default value
+    /// specified in the module.
+    ///
+    /// ## Example
+    ///
+    /// `...il(SPIRV).spec_constant(0, 16u32)...`
+    ///
+    /// [Version Controlled: OpenCL 2.2+]
+    pub fn spec_constant<'a, T: OclPrm>(&'a mut self, id: u32, val: T) -> &'a mut ProgramBuilder<'b> {
+        self.options.push(BuildOpt::spec_constant(id, val));
+        self
+    }
+
     /// Pushes pre-created build option to the list of options.
     ///
     /// If either `::il` or `::binaries` are used and raw source is added, it
@@ -259,7 +503,7 @@ impl<'b> ProgramBuilder<'b> {
                 self.with = CreateWith::Source(paths);
             }
             CreateWith::Source(ref mut paths) => paths.push(file_path),
-            _ => panic!("Source may not be used with binaries or il."),
+            _ => panic!("Source may not be used with binaries, il, or built-in kernels."),
         }
         self
     }
@@ -284,12 +528,23 @@ impl<'b> ProgramBuilder<'b> {
             CreateWith::Source(_) => {
                 self.options.push(BuildOpt::IncludeRawEof(src.into()));
             }
-            _ => panic!("Source may not be used with binaries or il."),
+            _ => panic!("Source may not be used with binaries, il, or built-in kernels."),
         }
 
         self
     }
 
+    /// Adds an embedded header, included by the source as `#include
+    /// "{name}"`, for use by `::compile`.
+    ///
+    /// Create header programs with `Program::header`. Headers may not be
+    /// used with `::build`.
+    pub fn header<'a, S: Into<String>>(&'a mut self, name: S, header: &Program)
+            -> &'a mut ProgramBuilder<'b> {
+        self.headers.push((name.into(), header.clone()));
+        self
+    }
+
     /// Adds a binary to be loaded.
     ///
     /// There must be one binary for each device listed in `::devices`.
@@ -297,12 +552,27 @@ impl<'b> ProgramBuilder<'b> {
         match self.with {
             CreateWith::None => self.with = CreateWith::Binaries(bins),
             CreateWith::Binaries(_) => panic!("Binaries have already been specified."),
-            _ => panic!("Binaries may not be used with source or il."),
+            _ => panic!("Binaries may not be used with source, il, or built-in kernels."),
         }
         self
     }
 
-    /// Adds
+    /// Specifies the names of built-in kernels, provided by the device(s), to
+    /// create the program from.
+    ///
+    /// Every device listed in `::devices` (or every device in the context if
+    /// none are listed) must support each of the named kernels (see
+    /// `Device::built_in_kernels`). Compiler options are ignored as built-in
+    /// kernel programs are not built.
+    pub fn built_in_kernels<'a>(&'a mut self, kernel_names: &'b [&'b str])
+            -> &'a mut ProgramBuilder<'b> {
+        match self.with {
+            CreateWith::None => self.with = CreateWith::BuiltInKernels(kernel_names),
+            CreateWith::BuiltInKernels(_) => panic!("Built-in kernels have already been specified."),
+            _ => panic!("Built-in kernels may not be used with source, binaries, or il."),
+        }
+        self
+    }
 
     /// Adds SPIR-V or an implementation-defined intermediate language to this program.
     ///
@@ -314,12 +584,11 @@ impl<'b> ProgramBuilder<'b> {
     /// * TODO: Future addition: Allow IL to be loaded directly from a file
     /// in the same way that text source is.
     ///
-    #[cfg(feature = "opencl_version_2_1")]
     pub fn il<'a>(&'a mut self, il: &'b [u8]) -> &'a mut ProgramBuilder<'b> {
         match self.with {
             CreateWith::None => self.with = CreateWith::Il(il),
             CreateWith::Il(_) => panic!("Il has already been specified."),
-            _ => panic!("Il may not be used with source or binaries."),
+            _ => panic!("Il may not be used with source, binaries, or built-in kernels."),
         }
         self
     }
@@ -352,6 +621,23 @@ impl<'b> ProgramBuilder<'b> {
         &self.device_spec
     }
 
+    /// Specifies a cache of program binaries to use when building from
+    /// source.
+    ///
+    /// The program is created from the cached binaries of its devices when
+    /// available and otherwise built from source, storing the resulting
+    /// binaries. See `ProgramCache` for details.
+    ///
+    pub fn cache<'a>(&'a mut self, cache: ProgramCache) -> &'a mut ProgramBuilder<'b> {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Returns the program binary cache, if specified.
+    pub fn get_cache(&self) -> Option<&ProgramCache> {
+        self.cache.as_ref()
+    }
+
     /// Returns a concatenated string of command line options to be passed to
     /// the compiler when building this program.
     pub fn get_compiler_options(&self) -> OclResult<CString> {
@@ -378,47 +664,13 @@ impl<'b> ProgramBuilder<'b> {
         CString::new(opts.join(" ").into_bytes()).map_err(OclError::from)
     }
 
-    /// Parses `self.options` for options intended for inclusion at the beginning of
-    /// the final program source and returns them as a list of strings.
-    ///
-    /// Generally used for #define directives, constants, etc. Normally called from
-    /// `::get_src_strings()`.
-    fn get_includes(&self) -> OclResult<Vec<CString>> {
-        let mut strings = Vec::with_capacity(64);
-        strings.push(CString::new("\n".as_bytes())?);
-
-        for option in &self.options {
-            match *option {
-                BuildOpt::IncludeDefine { ref ident, ref val } => {
-                    strings.push(CString::new(format!("#define {}  {}\n", ident, val)
-                        .into_bytes())?);
-                },
-                BuildOpt::IncludeRaw(ref text) => {
-                    strings.push(CString::new(text.clone().into_bytes())?);
-                },
-                _ => (),
-            };
-
-        }
-
-        strings.shrink_to_fit();
-        Ok(strings)
-    }
-
-    /// Parses `self.options` for options intended for inclusion at the end of
-    /// the final program source and returns them as a list of strings.
-    fn get_includes_eof(&self) -> OclResult<Vec<CString>> {
-        let mut strings = Vec::with_capacity(64);
-        strings.push(CString::new("\n".as_bytes())?);
-
-        for option in &self.options {
-            if let BuildOpt::IncludeRawEof(ref text) = *option {
-                strings.push(CString::new(text.clone().into_bytes())?);
-            }
-        }
-
-        strings.shrink_to_fit();
-        Ok(strings)
+    /// Returns the specialization constants specified by
+    /// `BuildOpt::SpecConstant` options as a list of `(spec_id, value_bytes)`.
+    fn get_spec_constants(&self) -> Vec<(u32, &[u8])> {
+        self.options.iter().filter_map(|option| match *option {
+            BuildOpt::SpecConstant { id, ref value } => Some((id, &value[..])),
+            _ => None,
+        }).collect()
     }
 
     /// Returns the final program source code as a list of strings.
@@ -432,32 +684,70 @@ impl<'b> ProgramBuilder<'b> {
     ///   `BuildOpt::IncludeRawEof` via `::bo`
     ///
     pub fn get_src_strings(&self) -> OclResult<Vec<CString>> {
-        let mut src_strings: Vec<CString> = Vec::with_capacity(64);
-        let mut src_file_history: HashSet<PathBuf> = HashSet::with_capacity(64);
+        self.get_source().map(|(src_strings, _)| src_strings)
+    }
+
+    /// Returns a map from lines of the final program source (the
+    /// concatenation of `::get_src_strings`) to lines of the original files
+    /// and strings.
+    pub fn get_source_map(&self) -> OclResult<SourceMap> {
+        self.get_source().map(|(_, source_map)| source_map)
+    }
+
+    /// Returns the directories specified by `BuildOpt::CmplrInclDir` options.
+    fn get_include_dirs(&self) -> Vec<PathBuf> {
+        self.options.iter().filter_map(|option| match *option {
+            BuildOpt::CmplrInclDir { ref path } => Some(PathBuf::from(path)),
+            _ => None,
+        }).collect()
+    }
 
-        src_strings.extend_from_slice(&self.get_includes()?);
+    /// Assembles the final program source and its source map.
+    fn get_source(&self) -> OclResult<(Vec<CString>, SourceMap)> {
+        self.build_source(self.resolve_includes)
+    }
+
+    /// Returns the source strings and source map, resolving `#include`
+    /// directives if `resolve_includes` is set.
+    fn build_source(&self, resolve_includes: bool) -> OclResult<(Vec<CString>, SourceMap)> {
+        const BUILD_OPTIONS: &str = "<build options>";
 
         let src_paths = match self.with {
             CreateWith::Source(ref paths) => paths,
             _ => panic!("Cannot build program. No source specified."),
         };
 
-        for src_path in src_paths {
-            let mut src_bytes: Vec<u8> = Vec::with_capacity(100_000);
+        let include_dirs = self.get_include_dirs();
+        let mut source = SourceBuilder::new(&include_dirs, resolve_includes);
+        let mut src_file_history: HashSet<&PathBuf> = HashSet::with_capacity(64);
 
-            if src_file_history.contains(src_path) { continue; }
-            src_file_history.insert(src_path.clone());
+        source.push_str(BUILD_OPTIONS, "\n")?;
+        for option in &self.options {
+            match *option {
+                BuildOpt::IncludeDefine { ref ident, ref val } => {
+                    source.push_str(BUILD_OPTIONS, &format!("#define {}  {}\n", ident, val))?;
+                },
+                BuildOpt::IncludeRaw(ref text) => source.push_str(BUILD_OPTIONS, text)?,
+                _ => (),
+            }
+        }
 
-            let mut src_file_handle = File::open(src_path)?;
+        for src_path in src_paths {
+            if src_file_history.insert(src_path) {
+                source.push_file(src_path)?;
+            }
+        }
 
-            src_file_handle.read_to_end(&mut src_bytes)?;
-            src_bytes.shrink_to_fit();
-            src_strings.push(CString::new(src_bytes)?);
+        source.push_str(BUILD_OPTIONS, "\n")?;
+        let eof_strings = self.options.iter().filter_map(|option| match *option {
+            BuildOpt::IncludeRawEof(ref text) => Some(text),
+            _ => None,
+        });
+        for (idx, text) in eof_strings.enumerate() {
+            source.push_str(&format!("<src {}>", idx + 1), text)?;
         }
 
-        src_strings.extend_from_slice(&self.get_includes_eof()?);
-        src_strings.shrink_to_fit();
-        Ok(src_strings)
+        Ok(source.finish())
     }
 
     /// Returns a newly built Program.
@@ -465,82 +755,264 @@ impl<'b> ProgramBuilder<'b> {
     // * TODO: If the context is associated with more than one device,
     // check that at least one of those devices has been specified. An empty
     // device list will cause an `OpenCL` error in that case.
+    //
     // * TODO: Check for duplicate devices in the final device list.
-    // * TODO: Consider moving context to its own method.
-    #[cfg(not(feature = "opencl_version_2_1"))]
     pub fn build(&self, context: &Context) -> OclResult<Program> {
         let device_list = match self.device_spec {
             Some(ref ds) => ds.to_device_list(context.platform()?)?,
-            None => context.devices(),
+            None => context.devices().to_owned(),
         };
 
-        match self.with {
-            CreateWith::Il(_) => {
-                return Err("ocl::ProgramBuilder::build: Unreachable section (IL).".into());
-            },
+        self.build_for(context, &device_list)
+    }
+
+    /// Starts building a new program, returning a future which resolves to
+    /// the built program once the build completes.
+    ///
+    /// The build runs in the background (using the build callback), allowing
+    /// many programs to be built concurrently:
+    ///
+    /// ```rust,ignore
+    /// let builds = sources.iter()
+    ///     .map(|src| Program::builder().src(*src).build_async(&context))
+    ///     .collect::<OclResult<Vec<_>>>()?;
+    /// let programs = future::join_all(builds).wait()?;
+    /// ```
+    ///
+    /// Build failures resolve to an error containing the build log (see
+    /// `::build`). Only programs created from source or binaries may be
+    /// built asynchronously and program caches (`::cache`) are not used. Some
+    /// platforms build synchronously, blocking until the build completes.
+    pub fn build_async(&self, context: &Context) -> OclResult<FutureProgram> {
+        let device_list = match self.device_spec {
+            Some(ref ds) => ds.to_device_list(context.platform()?)?,
+            None => context.devices().to_owned(),
+        };
+
+        if !self.headers.is_empty() {
+            return Err("Unable to build program: embedded headers may only be used with \
+                '::compile'.".into());
+        }
+        if !self.get_spec_constants().is_empty() {
+            return Err("Unable to build program asynchronously: specialization constants \
+                are not supported.".into());
+        }
+
+        let (program, source) = match self.with {
             CreateWith::Source(_) => {
-                Program::with_source(
-                    context,
-                    &self.get_src_strings()?,
-                    Some(&device_list[..]),
-                    &self.get_compiler_options()?,
-                ).map_err(OclError::from)
+                let (src_strings, source_map) = self.get_source()?;
+                let program = core::create_program_with_source(context, &src_strings)?;
+                (program, Some((src_strings, source_map)))
             },
             CreateWith::Binaries(bins) => {
-                Program::with_binary(
-                    context,
-                    &device_list[..],
-                    bins,
-                    &self.get_compiler_options()?,
-                )
+                (core::create_program_with_binary(context, &device_list, bins)?, None)
             },
-            CreateWith::None => return Err("Unable to build program: no source, binary, \
-                or IL has been specified".into()),
-        }
+            _ => return Err("Unable to build program asynchronously: only programs created \
+                from source or binaries may be built asynchronously.".into()),
+        };
+
+        let (tx, rx) = oneshot::channel();
+        core::build_program_async(&program, Some(&device_list), &self.get_compiler_options()?,
+            Box::new(move |_| { let _ = tx.send(()); }))?;
+
+        Ok(FutureProgram {
+            program: Some(Program(program)),
+            devices: device_list,
+            source,
+            rx,
+        })
     }
 
-    /// Returns a newly built Program.
-    //
-    // * TODO: If the context is associated with more than one device,
-    // check that at least one of those devices has been specified. An empty
-    // device list will cause an `OpenCL` error in that case.
-    //
-    // * TODO: Check for duplicate devices in the final device list.
-    #[cfg(feature = "opencl_version_2_1")]
-    pub fn build(&self, context: &Context) -> OclResult<Program> {
+    /// Returns a new program compiled, but not linked, from source.
+    ///
+    /// Link the program, along with any others and libraries it uses, into
+    /// an executable with `Program::link`. Program caches (`::cache`) are
+    /// not used when compiling.
+    ///
+    /// [Version Controlled: OpenCL 1.2+]
+    pub fn compile(&self, context: &Context) -> OclResult<Program> {
         let device_list = match self.device_spec {
             Some(ref ds) => ds.to_device_list(context.platform()?)?,
             None => context.devices().to_owned(),
         };
 
         match self.with {
+            CreateWith::Source(_) => {
+                let (src_strings, source_map) = self.get_source()?;
+                let headers: Vec<(&str, &Program)> = self.headers.iter()
+                    .map(|&(ref name, ref header)| (name.as_str(), header))
+                    .collect();
+
+                Program::compile(context, &src_strings, Some(&device_list),
+                    &self.get_compiler_options()?, &headers)
+                    .map_err(|err| map_build_log(err, &source_map, &src_strings))
+            },
+            _ => Err("Unable to compile program: only programs created from source may be \
+                compiled.".into()),
+        }
+    }
+
+    /// Returns a newly built Program for the devices in `device_list`,
+    /// ignoring any devices specified with `::devices`.
+    pub(crate) fn build_for(&self, context: &Context, device_list: &[Device])
+            -> OclResult<Program> {
+        let spec_constants = self.get_spec_constants();
+
+        if !self.headers.is_empty() {
+            return Err("Unable to build program: embedded headers may only be used with \
+                '::compile'.".into());
+        }
+
+        match self.with {
+            CreateWith::Il(il) if !spec_constants.is_empty() => {
+                self.build_specialized_il(il, &spec_constants, device_list, context)
+            },
             CreateWith::Il(il) => {
                 Program::with_il(
                     il,
-                    Some(&device_list[..]),
+                    Some(device_list),
                     &self.get_compiler_options()?,
                     context
                 )
             },
+            _ if !spec_constants.is_empty() => {
+                Err("Unable to build program: specialization constants may only be used \
+                    with IL.".into())
+            },
             CreateWith::Source(_) => {
-                Program::with_source(
-                    context,
-                    &self.get_src_strings()?,
-                    Some(&device_list[..]),
-                    &self.get_compiler_options()?,
-                )
+                let (src_strings, source_map) = self.get_source()?;
+                let cmplr_opts = self.get_compiler_options()?;
+
+                let result = match self.cache {
+                    Some(ref cache) => {
+                        // Includes left to the compiler are resolved here so
+                        // that changes to included files are detected:
+                        let key_strings = if self.resolve_includes {
+                            None
+                        } else {
+                            self.build_source(true).ok().map(|(strings, _)| strings)
+                        };
+                        cache.build(context, &src_strings,
+                            key_strings.as_ref().unwrap_or(&src_strings), device_list,
+                            &cmplr_opts)
+                    },
+                    None => Program::with_source(context, &src_strings, Some(device_list),
+                        &cmplr_opts),
+                };
+                result.map_err(|err| map_build_log(err, &source_map, &src_strings))
             },
             CreateWith::Binaries(bins) => {
                 Program::with_binary(
                     context,
-                    &device_list[..],
+                    device_list,
                     bins,
                     &self.get_compiler_options()?,
                 )
             },
+            CreateWith::BuiltInKernels(names) => {
+                Program::with_built_in_kernels(
+                    context,
+                    device_list,
+                    names,
+                )
+            },
             CreateWith::None => Err("Unable to build program: no source, binary, \
-                or IL has been specified".into()),
+                IL, or built-in kernels have been specified".into()),
         }
     }
+
+    /// Builds a program from IL with specialization constants set.
+    fn build_specialized_il(&self, il: &[u8], spec_constants: &[(u32, &[u8])],
+            device_list: &[Device], context: &Context) -> OclResult<Program> {
+        Program::with_specialized_il(
+            il,
+            spec_constants,
+            Some(device_list),
+            &self.get_compiler_options()?,
+            context
+        )
+    }
+}
+
+
+/// A future which resolves to a built `Program`.
+///
+/// Returned by `ProgramBuilder::build_async`.
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct FutureProgram {
+    program: Option<Program>,
+    devices: Vec<Device>,
+    source: Option<(Vec<CString>, SourceMap)>,
+    rx: Receiver<()>,
+}
+
+impl FutureProgram {
+    /// Returns the program being built.
+    ///
+    /// Kernels may not be created from the program until the build has
+    /// completed.
+    pub fn program(&self) -> Option<&Program> {
+        self.program.as_ref()
+    }
 }
 
+impl Future for FutureProgram {
+    type Item = Program;
+    type Error = OclError;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        if let Async::NotReady = self.rx.poll()? {
+            return Ok(Async::NotReady);
+        }
+
+        let program = self.program.take()
+            .ok_or("FutureProgram::poll: Program already resolved.")?;
+
+        match core::program_build_err(&program.0, &self.devices) {
+            Ok(()) => Ok(Async::Ready(program)),
+            Err(err) => {
+                let err = OclError::from(OclCoreError::from(err));
+                Err(match self.source {
+                    Some((ref src_strings, ref source_map)) => {
+                        map_build_log(err, source_map, src_strings)
+                    },
+                    None => err,
+                })
+            },
+        }
+    }
+}
+
+
+/// Maps the combined source line numbers within the build log of a program
+/// build error to original locations, adding the source line to diagnostics
+/// lacking one.
+fn map_build_log(err: OclError, source_map: &SourceMap, src_strings: &[CString]) -> OclError {
+    let mut build_log = match *err.kind() {
+        OclErrorKind::OclCore(ref core_err) => match *core_err.kind() {
+            OclCoreErrorKind::ProgramBuild(ProgramBuildError::BuildLog(ref log)) => log.clone(),
+            _ => return err,
+        },
+        _ => return err,
+    };
+
+    let source: String = src_strings.iter().map(|s| s.to_string_lossy()).collect();
+    let source_lines: Vec<&str> = source.lines().collect();
+
+    for device_log in build_log.devices_mut() {
+        device_log.log = source_map.map_log(&device_log.log);
+
+        for diagnostic in &mut device_log.diagnostics {
+            if diagnostic.source.is_none()
+                    && is_combined_source(diagnostic.file.as_ref().map(String::as_str)) {
+                diagnostic.source = diagnostic.line
+                    .and_then(|line| source_lines.get(line.wrapping_sub(1)))
+                    .map(|line| (*line).to_owned());
+            }
+            source_map.map_diagnostic(diagnostic);
+        }
+    }
+
+    OclCoreError::from(ProgramBuildError::BuildLog(build_log)).into()
+}
@@ -0,0 +1,318 @@
+//! An on-disk cache of program binaries.
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::ffi::CString;
+use std::time::{Duration, SystemTime};
+use crate::core::{DeviceInfo, ProgramInfo, ProgramInfoResult};
+use crate::error::Result as OclResult;
+use crate::standard::{Context, Device, Program};
+
+
+/// The environment variable which overrides the default cache directory
+/// (see `ProgramCache::default_dir`).
+pub const PROGRAM_CACHE_DIR_ENV_VAR: &str = "OCL_PROGRAM_CACHE_DIR";
+
+/// Identifies cache entry files and their format version.
+const ENTRY_MAGIC: &[u8] = b"OCLPROGCACHE1\n";
+const ENTRY_EXTENSION: &str = "bin";
+
+
+/// An on-disk cache of program binaries, used by `ProgramBuilder::cache`.
+///
+/// Programs built from source store the binary of each device within the
+/// cache directory. Subsequent builds with identical source strings, compiler
+/// options and devices (same name, vendor, driver and OpenCL versions) are
+/// created from the cached binaries instead of being recompiled. Binaries
+/// rejected by the platform are removed and the program is built from source.
+///
+/// Entries are invalidated by:
+///
+/// * Changes to the source, including files included with `#include` which
+///   can be found relative to the including file or within the directories
+///   added with `ProgramBuilder::include_dir`. Changes to files found only
+///   within the compiler's own search paths are not detected.
+/// * `::version`: Entries stored under a different version are never used.
+/// * `::max_age`: Entries older than the maximum age are removed when found.
+/// * `::max_size`: The oldest entries are removed once the total size of the
+///   cache exceeds the limit.
+/// * `::clear`: Removes every entry.
+///
+/// Failing to write to the cache does not cause a build to fail.
+///
+/// ### Example
+///
+/// ```rust,ignore
+/// let cache = ProgramCache::new("/var/cache/my_app/kernels")
+///     .max_size(64 << 20)
+///     .version(env!("CARGO_PKG_VERSION"));
+///
+/// let program = Program::builder()
+///     .src(src)
+///     .cache(cache)
+///     .build(&context)?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramCache {
+    dir: PathBuf,
+    max_size: Option<u64>,
+    max_age: Option<Duration>,
+    version: String,
+}
+
+impl ProgramCache {
+    /// Returns a new cache which stores binaries within `dir`.
+    ///
+    /// The directory is created when the first entry is stored.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> ProgramCache {
+        ProgramCache {
+            dir: dir.into(),
+            max_size: None,
+            max_age: None,
+            version: String::new(),
+        }
+    }
+
+    /// Returns the directory specified by the `OCL_PROGRAM_CACHE_DIR`
+    /// environment variable, if set, otherwise `ocl_program_cache` within
+    /// the temporary directory.
+    pub fn default_dir() -> PathBuf {
+        match env::var_os(PROGRAM_CACHE_DIR_ENV_VAR) {
+            Some(ref dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => env::temp_dir().join("ocl_program_cache"),
+        }
+    }
+
+    /// Limits the total size of the cache to `bytes`, removing the oldest
+    /// entries once exceeded.
+    pub fn max_size(mut self, bytes: u64) -> ProgramCache {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Ignores (and removes) entries stored more than `age` ago.
+    pub fn max_age(mut self, age: Duration) -> ProgramCache {
+        self.max_age = Some(age);
+        self
+    }
+
+    /// Sets a version (such as the application version) which must match
+    /// that of an entry for it to be used.
+    pub fn version<S: Into<String>>(mut self, version: S) -> ProgramCache {
+        self.version = version.into();
+        self
+    }
+
+    /// Returns the cache directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the total size of every entry in the cache, in bytes.
+    pub fn size(&self) -> OclResult<u64> {
+        Ok(self.entries()?.iter().map(|&(_, size, _)| size).sum())
+    }
+
+    /// Removes every entry from the cache.
+    pub fn clear(&self) -> OclResult<()> {
+        for (path, _, _) in self.entries()? {
+            remove_entry(&path)?;
+        }
+        Ok(())
+    }
+
+    /// Returns a program for `devices` created from cached binaries if each
+    /// device has an entry, otherwise built from source and stored.
+    ///
+    /// Entries are identified by `key_strings`: `src_strings` with any
+    /// `#include` directives resolved.
+    pub(crate) fn build(&self, context: &Context, src_strings: &[CString],
+            key_strings: &[CString], devices: &[Device], cmplr_opts: &CString)
+            -> OclResult<Program> {
+        let src_hash = key_strings.iter()
+            .fold(FNV_OFFSET, |hash, src| fnv1a(hash, src.as_bytes_with_nul()));
+
+        let mut keys = Vec::with_capacity(devices.len());
+        for device in devices {
+            keys.push(self.entry_key(device, cmplr_opts, src_hash)?);
+        }
+
+        let binaries: Option<Vec<Vec<u8>>> = keys.iter().map(|key| self.load(key)).collect();
+
+        if let Some(binaries) = binaries {
+            let binaries: Vec<&[u8]> = binaries.iter().map(|bin| &bin[..]).collect();
+
+            match Program::with_binary(context, devices, &binaries, cmplr_opts) {
+                Ok(program) => return Ok(program),
+                // Rejected (stale or corrupt) binaries are rebuilt from source:
+                Err(_) => for key in &keys {
+                    let _ = remove_entry(&self.entry_path(key));
+                },
+            }
+        }
+
+        let program = Program::with_source(context, src_strings, Some(devices), cmplr_opts)?;
+        let _ = self.store_all(&program, devices, &keys);
+        Ok(program)
+    }
+
+    /// Stores the binary of each device of `program`.
+    fn store_all(&self, program: &Program, devices: &[Device], keys: &[String]) -> OclResult<()> {
+        let program_devices = match program.info(ProgramInfo::Devices)? {
+            ProgramInfoResult::Devices(d) => d,
+            _ => panic!("ProgramCache::store_all: Unexpected 'ProgramInfoResult' variant."),
+        };
+        let binaries = match program.info(ProgramInfo::Binaries)? {
+            ProgramInfoResult::Binaries(b) => b,
+            _ => panic!("ProgramCache::store_all: Unexpected 'ProgramInfoResult' variant."),
+        };
+
+        for (device, key) in devices.iter().zip(keys) {
+            let idx = program_devices.iter().position(|d| d == device.as_core());
+            if let Some(binary) = idx.and_then(|idx| binaries.get(idx)) {
+                if !binary.is_empty() {
+                    self.store(key, binary)?;
+                }
+            }
+        }
+
+        if let Some(max_size) = self.max_size {
+            self.evict(max_size)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the text identifying an entry: everything a binary depends on
+    /// aside from the source, which is included as a hash.
+    fn entry_key(&self, device: &Device, cmplr_opts: &CString, src_hash: u64)
+            -> OclResult<String> {
+        Ok(format!("version: {}\nplatform: {}\ndevice: {}\nvendor: {}\ndriver: {}\n\
+            device_version: {}\noptions: {}\nsource: {:016x}\n",
+            self.version,
+            device.platform()?.version()?,
+            device.name()?,
+            device.vendor()?,
+            device.info(DeviceInfo::DriverVersion)?,
+            device.info(DeviceInfo::Version)?,
+            cmplr_opts.to_string_lossy(),
+            src_hash))
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let hash = fnv1a(FNV_OFFSET, key.as_bytes());
+        self.dir.join(format!("{:016x}.{}", hash, ENTRY_EXTENSION))
+    }
+
+    /// Returns the binary stored for `key`, if any.
+    fn load(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.entry_path(key);
+
+        if let Some(max_age) = self.max_age {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+            if age > max_age {
+                let _ = remove_entry(&path);
+                return None;
+            }
+        }
+
+        let mut bytes = Vec::new();
+        File::open(&path).and_then(|mut file| file.read_to_end(&mut bytes)).ok()?;
+
+        // Hash collisions and truncated files are treated as misses:
+        let header_len = ENTRY_MAGIC.len() + key.len();
+        if bytes.len() > header_len && bytes.starts_with(ENTRY_MAGIC)
+                && &bytes[ENTRY_MAGIC.len()..header_len] == key.as_bytes() {
+            Some(bytes.split_off(header_len))
+        } else {
+            None
+        }
+    }
+
+    /// Stores `binary` for `key`, replacing any existing entry.
+    fn store(&self, key: &str, binary: &[u8]) -> OclResult<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.entry_path(key);
+
+        // Writes to a temporary file (unique to this call, so that concurrent
+        // writers never share one) first so that concurrent readers never
+        // see a partial entry:
+        static STORE_COUNT: AtomicUsize = AtomicUsize::new(0);
+        let tmp_path = path.with_extension(format!("{}.{}.tmp", process::id(),
+            STORE_COUNT.fetch_add(1, Ordering::Relaxed)));
+        let result = File::create(&tmp_path).and_then(|mut file| {
+            file.write_all(ENTRY_MAGIC)?;
+            file.write_all(key.as_bytes())?;
+            file.write_all(binary)
+        }).and_then(|_| fs::rename(&tmp_path, &path));
+
+        if result.is_err() { let _ = fs::remove_file(&tmp_path); }
+        result.map_err(Into::into)
+    }
+
+    /// Removes the oldest entries until the cache is no larger than
+    /// `max_size`.
+    fn evict(&self, max_size: u64) -> OclResult<()> {
+        let mut entries = self.entries()?;
+        let mut size: u64 = entries.iter().map(|&(_, size, _)| size).sum();
+        entries.sort_by_key(|&(_, _, modified)| modified);
+
+        for (path, entry_size, _) in entries {
+            if size <= max_size { break; }
+            remove_entry(&path)?;
+            size -= entry_size;
+        }
+        Ok(())
+    }
+
+    /// Returns the path, size and modification time of every entry.
+    fn entries(&self) -> OclResult<Vec<(PathBuf, u64, SystemTime)>> {
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut entries = Vec::new();
+        for entry in dir {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == ENTRY_EXTENSION) {
+                let metadata = fs::metadata(&path)?;
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                entries.push((path, metadata.len(), modified));
+            }
+        }
+        Ok(entries)
+    }
+}
+
+impl Default for ProgramCache {
+    /// Returns a cache within `ProgramCache::default_dir` with no size or
+    /// age limits.
+    fn default() -> ProgramCache {
+        ProgramCache::new(ProgramCache::default_dir())
+    }
+}
+
+
+/// Removes an entry, ignoring entries already removed (by another process).
+fn remove_entry(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Returns the 64-bit FNV-1a hash of `bytes` continuing from `hash`.
+///
+/// Unlike `DefaultHasher`, the result is stable across Rust releases.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME))
+}
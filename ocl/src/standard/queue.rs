@@ -1,11 +1,26 @@
 //! An `OpenCL` command queue.
 
 use std;
+use std::any::TypeId;
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
+use std::{mem, panic, ptr, slice};
+use crate::ffi::c_void;
 use crate::core::{self, Result as OclCoreResult, CommandQueue as CommandQueueCore, CommandQueueInfo,
-    CommandQueueInfoResult, OpenclVersion, CommandQueueProperties, ClWaitListPtr, ClContextPtr};
+    CommandQueueInfoResult, OpenclVersion, CommandQueueProperties, QueueProperties, ClWaitListPtr,
+    ClContextPtr, ClNullEventPtr, Mem as MemCore, OclPrm, DeviceInfo, DeviceInfoResult,
+    DeviceExecCapabilities};
 use crate::error::{Error as OclError, Result as OclResult};
-use crate::standard::{Context, Device, Event};
+use crate::standard::{Context, Device, Event, Buffer};
+
+
+/// An error related to a `Queue`.
+#[derive(Debug, Fail)]
+pub enum QueueError {
+    #[fail(display = "The device associated with this queue ('{}') does not support native \
+        kernels.", _0)]
+    NativeKernelsUnsupported(String),
+}
 
 /// A command queue which manages all actions taken on kernels, buffers, and
 /// images.
@@ -23,9 +38,12 @@ pub struct Queue {
 }
 
 impl Queue {
-    /// Returns a new Queue on the device specified by `device`.
-    pub fn new(context: &Context, device: Device, properties: Option<CommandQueueProperties>)
-            -> OclResult<Queue> {
+    /// Returns a new Queue on the device specified by `device` (a `Device` or
+    /// `&SubDevice`).
+    pub fn new<D>(context: &Context, device: D, properties: Option<CommandQueueProperties>)
+            -> OclResult<Queue>
+            where D: Into<Device> {
+        let device = device.into();
         let obj_core = core::create_command_queue(context, &device, properties)?;
         let device_version = device.version()?;
 
@@ -35,6 +53,36 @@ impl Queue {
         })
     }
 
+    /// Returns a new Queue on the device specified by `device`, created using
+    /// a list of properties.
+    ///
+    /// Use this to create on-device queues or queues with a specific size or
+    /// priority or throttle hint, e.g.:
+    ///
+    /// ```rust,ignore
+    /// let high = Queue::with_properties(&context, device,
+    ///     QueueProperties::new().priority(QueuePriority::High))?;
+    /// let low = Queue::with_properties(&context, device,
+    ///     QueueProperties::new().priority(QueuePriority::Low))?;
+    /// ```
+    ///
+    /// Devices supporting OpenCL 1.x (determined by `Device::version`) fall
+    /// back to creating the queue in the same way as `::new`, in which case
+    /// only the out of order and profiling flags may be specified.
+    pub fn with_properties<D, P>(context: &Context, device: D, properties: P)
+            -> OclResult<Queue>
+            where D: Into<Device>, P: Into<QueueProperties> {
+        let device = device.into();
+        let device_version = device.version()?;
+        let obj_core = core::create_command_queue_with_properties(context, *device.as_core(),
+            &properties.into(), Some(&device_version))?;
+
+        Ok(Queue {
+            obj_core,
+            device_version,
+        })
+    }
+
     /// Issues all previously queued OpenCL commands to the device.
     pub fn flush(&self) -> OclResult<()> {
         core::flush(&self.obj_core).map_err(OclError::from)
@@ -56,6 +104,67 @@ impl Queue {
             .map_err(OclError::from)
     }
 
+    /// Enqueues a native kernel: a closure which runs on the host once every
+    /// event in `ewait` (and, for in-order queues, every previously enqueued
+    /// command) has completed.
+    ///
+    /// The contents of each buffer in `mem_args` are made available to the
+    /// closure as slices (see `NativeArgs::take`). Native kernels allow host
+    /// code to be interleaved with device commands using only the queue's
+    /// ordering and events.
+    ///
+    /// The closure may be run on a thread belonging to the OpenCL runtime and
+    /// must not block on commands enqueued after it. A panic within the
+    /// closure is caught (the command still completes). If the command never
+    /// runs (e.g. because an event it waits on fails) the closure is leaked.
+    ///
+    /// Returns an error if the queue's device does not support native kernels
+    /// (`DeviceExecCapabilities::NATIVE_KERNEL`), which is generally only the
+    /// case for CPU devices.
+    ///
+    /// ## Safety
+    ///
+    /// The caller must ensure that no other command (e.g. on another queue)
+    /// accesses any of `mem_args` while the native kernel runs.
+    pub unsafe fn enqueue_native<F, Ewl, En>(&self, func: F, mem_args: &[NativeMemArg],
+            ewait: Option<Ewl>, enew: Option<En>) -> OclResult<()>
+            where F: FnOnce(NativeArgs) + Send + 'static, Ewl: ClWaitListPtr, En: ClNullEventPtr
+    {
+        let device = self.device();
+        match device.info(DeviceInfo::ExecutionCapabilities)? {
+            DeviceInfoResult::ExecutionCapabilities(caps) => {
+                if !caps.contains(DeviceExecCapabilities::NATIVE_KERNEL) {
+                    return Err(QueueError::NativeKernelsUnsupported(device.name()?).into());
+                }
+            },
+            _ => unreachable!(),
+        }
+
+        let callback = Box::into_raw(Box::new(NativeCallback {
+            func: Box::new(func),
+            regions: mem_args.iter().map(|arg| (arg.size, arg.type_id, arg.type_name)).collect(),
+        }));
+
+        // The arguments consist of the callback pointer followed by one
+        // pointer-sized slot per memory object:
+        let word = mem::size_of::<usize>();
+        let mut args = Vec::with_capacity((mem_args.len() + 1) * word);
+        args.extend_from_slice(&(callback as usize).to_ne_bytes());
+        args.resize((mem_args.len() + 1) * word, 0);
+
+        let mems: Vec<&MemCore> = mem_args.iter().map(|arg| arg.mem).collect();
+        let offsets: Vec<usize> = (1..=mem_args.len()).map(|i| i * word).collect();
+
+        let result = core::enqueue_native_kernel(&self.obj_core, _native_kernel, &args, &mems,
+            &offsets, ewait, enew);
+
+        if result.is_err() {
+            // The callback will never be run:
+            drop(Box::from_raw(callback));
+        }
+        result.map_err(OclError::from)
+    }
+
     /// Returns a reference to the core pointer wrapper, usable by functions in
     /// the `core` module.
     #[inline]
@@ -131,3 +240,108 @@ unsafe impl<'a> ClContextPtr for &'a Queue {
             Unable to obtain a context pointer.")
     }
 }
+
+
+
+/// A buffer passed to a native kernel (see `Queue::enqueue_native`).
+#[derive(Clone, Debug)]
+pub struct NativeMemArg<'a> {
+    mem: &'a MemCore,
+    size: usize,
+    type_id: TypeId,
+    type_name: &'static str,
+}
+
+impl<'a, T: OclPrm> From<&'a Buffer<T>> for NativeMemArg<'a> {
+    fn from(buffer: &'a Buffer<T>) -> NativeMemArg<'a> {
+        NativeMemArg {
+            mem: buffer.as_core(),
+            size: buffer.len() * mem::size_of::<T>(),
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+        }
+    }
+}
+
+
+/// The contents of the buffers passed to a native kernel, available from
+/// within the kernel's closure.
+#[derive(Debug)]
+pub struct NativeArgs<'a> {
+    regions: Vec<NativeRegion>,
+    _p: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> NativeArgs<'a> {
+    /// Returns the number of buffers passed to the native kernel.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Returns true if no buffers were passed to the native kernel.
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Returns the contents of the buffer at index `idx` of the `mem_args`
+    /// passed to `Queue::enqueue_native`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `idx` is out of range, if `T` is not the element type of the
+    /// buffer, or if the buffer (or any buffer overlapping it) has already
+    /// been taken.
+    pub fn take<T: OclPrm>(&mut self, idx: usize) -> &'a mut [T] {
+        let region = self.regions.get(idx).unwrap_or_else(|| panic!("NativeArgs::take: \
+            Index out of range (index: {}, len: {}).", idx, self.regions.len()));
+
+        assert!(region.type_id == TypeId::of::<T>(), "NativeArgs::take: Type mismatch for \
+            argument [{}] (expected: '{}', requested: '{}').", idx, region.type_name,
+            std::any::type_name::<T>());
+
+        let (start, end) = (region.ptr as usize, region.ptr as usize + region.size);
+        assert!(!self.regions.iter().any(|r| r.taken && (r.ptr as usize) < end
+            && start < r.ptr as usize + r.size), "NativeArgs::take: Argument [{}] has already \
+            been taken or overlaps an argument which has.", idx);
+
+        self.regions[idx].taken = true;
+        unsafe { slice::from_raw_parts_mut(self.regions[idx].ptr as *mut T,
+            self.regions[idx].size / mem::size_of::<T>()) }
+    }
+}
+
+
+/// The location, size, and type of a memory object within a native kernel.
+#[derive(Debug)]
+struct NativeRegion {
+    ptr: *mut u8,
+    size: usize,
+    type_id: TypeId,
+    type_name: &'static str,
+    taken: bool,
+}
+
+
+/// A native kernel closure along with the size (in bytes) and type of each of
+/// its memory arguments.
+struct NativeCallback {
+    func: Box<dyn FnOnce(NativeArgs) + Send>,
+    regions: Vec<(usize, TypeId, &'static str)>,
+}
+
+
+/// Unpacks and runs a `NativeCallback` (see `Queue::enqueue_native`).
+extern "C" fn _native_kernel(args: *mut c_void) {
+    let words = args as *const usize;
+    let callback = unsafe { Box::from_raw(ptr::read_unaligned(words) as *mut NativeCallback) };
+    let NativeCallback { func, regions } = *callback;
+
+    let regions = regions.into_iter().enumerate().map(|(i, (size, type_id, type_name))| {
+        let ptr = unsafe { ptr::read_unaligned(words.add(i + 1)) } as *mut u8;
+        NativeRegion { ptr, size, type_id, type_name, taken: false }
+    }).collect();
+
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(move || {
+        func(NativeArgs { regions, _p: PhantomData })
+    }));
+}
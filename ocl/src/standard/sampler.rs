@@ -3,7 +3,7 @@
 use std;
 use std::ops::{Deref, DerefMut};
 use crate::core::{self, Result as OclCoreResult, Sampler as SamplerCore, AddressingMode, FilterMode,
-    SamplerInfo, SamplerInfoResult};
+    SamplerInfo, SamplerInfoResult, SamplerProperties};
 use crate::error::{Error as OclError, Result as OclResult};
 use crate::standard::Context;
 
@@ -56,6 +56,24 @@ impl Sampler {
         Ok(Sampler(sampler_core))
     }
 
+    /// Returns a new `SamplerBuilder`, used to create samplers with a mip
+    /// filter mode or level of detail range.
+    pub fn builder() -> SamplerBuilder {
+        SamplerBuilder::new()
+    }
+
+    /// Creates and returns a new sampler using a list of properties.
+    ///
+    /// See `SamplerBuilder` for more information.
+    pub fn with_properties(context: &Context, properties: SamplerProperties)
+            -> OclResult<Sampler> {
+        let device_versions = context.device_versions()?;
+        let sampler_core = core::create_sampler_with_properties(context, &properties,
+            Some(&device_versions))?;
+
+        Ok(Sampler(sampler_core))
+    }
+
     /// Returns various kinds of information about the sampler.
     pub fn info(&self, info_kind: SamplerInfo) -> OclCoreResult<SamplerInfoResult> {
         // match core::get_sampler_info(&self.0, info_kind) {
@@ -95,3 +113,100 @@ impl DerefMut for Sampler {
         &mut self.0
     }
 }
+
+
+/// A builder for `Sampler`.
+///
+/// Samplers reading mipmapped images may specify a mip filter mode and a
+/// level of detail range (requires the `cl_khr_mipmap_image` extension),
+/// e.g.:
+///
+/// ```rust,ignore
+/// let sampler = Sampler::builder()
+///     .normalized_coords(true)
+///     .addressing_mode(AddressingMode::ClampToEdge)
+///     .filter_mode(FilterMode::Linear)
+///     .mip_filter_mode(FilterMode::Linear)
+///     .lod_min(0.0)
+///     .lod_max(4.0)
+///     .build(&context)?;
+/// ```
+///
+/// The mip filter mode and level of detail range require every device in
+/// the context to support OpenCL 2.0+. Other samplers are created in the
+/// same way as `Sampler::new` on OpenCL 1.x devices.
+///
+/// ## Defaults
+///
+/// - `normalized_coords`: true
+/// - `addressing_mode`: `AddressingMode::Clamp`
+/// - `filter_mode`: `FilterMode::Nearest`
+///
+#[must_use = "builders do nothing unless '::build' is called"]
+#[derive(Clone, Debug, Default)]
+pub struct SamplerBuilder {
+    properties: SamplerProperties,
+}
+
+impl SamplerBuilder {
+    /// Returns a new `SamplerBuilder`.
+    pub fn new() -> SamplerBuilder {
+        SamplerBuilder::default()
+    }
+
+    /// Specifies whether image coordinates are normalized.
+    pub fn normalized_coords(mut self, normalized_coords: bool) -> SamplerBuilder {
+        self.properties = self.properties.normalized_coords(normalized_coords);
+        self
+    }
+
+    /// Specifies how out of range image coordinates are handled.
+    pub fn addressing_mode(mut self, addressing_mode: AddressingMode) -> SamplerBuilder {
+        self.properties = self.properties.addressing_mode(addressing_mode);
+        self
+    }
+
+    /// Specifies the filter used when reading an image.
+    pub fn filter_mode(mut self, filter_mode: FilterMode) -> SamplerBuilder {
+        self.properties = self.properties.filter_mode(filter_mode);
+        self
+    }
+
+    /// Specifies the filter used between mip-levels when reading a mipmapped
+    /// image.
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    pub fn mip_filter_mode(mut self, mip_filter_mode: FilterMode) -> SamplerBuilder {
+        self.properties = self.properties.mip_filter_mode(mip_filter_mode);
+        self
+    }
+
+    /// Specifies the minimum level of detail used when reading a mipmapped
+    /// image.
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    pub fn lod_min(mut self, lod_min: f32) -> SamplerBuilder {
+        self.properties = self.properties.lod_min(lod_min);
+        self
+    }
+
+    /// Specifies the maximum level of detail used when reading a mipmapped
+    /// image.
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    pub fn lod_max(mut self, lod_max: f32) -> SamplerBuilder {
+        self.properties = self.properties.lod_max(lod_max);
+        self
+    }
+
+    /// Replaces all previously specified properties.
+    pub fn properties(mut self, properties: SamplerProperties) -> SamplerBuilder {
+        self.properties = properties;
+        self
+    }
+
+    /// Creates and returns a new `Sampler` within `context`.
+    pub fn build(self, context: &Context) -> OclResult<Sampler> {
+        Sampler::with_properties(context, self.properties)
+    }
+}
@@ -0,0 +1,357 @@
+//! Program source assembly, `#include` resolution and source line mapping.
+
+use std::fmt;
+use std::fs;
+use std::ffi::CString;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use regex::{Regex, Captures};
+use crate::core::Diagnostic;
+use crate::error::Result as OclResult;
+
+
+/// The names Clang-based compilers give to program source.
+const COMBINED_SOURCE_NAMES: &[&str] = &["<source>", "<kernel>", "<stdin>", "<input>"];
+
+/// The maximum depth of nested `#include` directives.
+const INCLUDE_DEPTH_MAX: usize = 64;
+
+
+/// A location within an original source file or string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    /// The file path, or a name such as `<src 1>` for source strings.
+    pub file: String,
+    /// The line number (1-based).
+    pub line: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+
+/// A run of consecutive lines of the combined source originating from a
+/// single file or string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Segment {
+    start: usize,
+    len: usize,
+    file: String,
+    first_line: usize,
+}
+
+
+/// Maps lines of a program's combined source (its source strings, as
+/// concatenated by the OpenCL compiler) to lines of the original source files
+/// and strings.
+///
+/// Returned by `ProgramBuilder::get_source_map`. Strings added with
+/// `ProgramBuilder::src` are named `<src 1>`, `<src 2>` and so on, and
+/// definitions and text added using build options are named
+/// `<build options>`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    segments: Vec<Segment>,
+}
+
+impl SourceMap {
+    /// Returns the original location of `line` (1-based) of the combined
+    /// source, if it originates from a file or string.
+    ///
+    /// Lines added by `#line` directives have no original location.
+    pub fn lookup(&self, line: usize) -> Option<SourceLocation> {
+        self.segments.iter().rev()
+            .find(|seg| seg.start <= line && line < seg.start + seg.len)
+            .map(|seg| SourceLocation {
+                file: seg.file.clone(),
+                line: seg.first_line + (line - seg.start),
+            })
+    }
+
+    /// Returns true if `file` is the name of one of the mapped files or
+    /// strings.
+    pub fn contains_file(&self, file: &str) -> bool {
+        self.segments.iter().any(|seg| seg.file == file)
+    }
+
+    /// Rewrites references to lines of the combined source within a compiler
+    /// build log to refer to original locations instead.
+    ///
+    /// References are recognized in the forms `<source>:12`, `<kernel>:12`,
+    /// `<stdin>:12` and `<input>:12` (Clang-based compilers) or `line 12`,
+    /// optionally preceded by a quoted file name (`"prog.cl", line 12`).
+    /// References to mapped file names (reported by compilers honoring
+    /// `#line` directives) are left as is.
+    pub fn map_log(&self, log: &str) -> String {
+        let anonymous = Regex::new(&format!(r"(?:{}):(\d+)", COMBINED_SOURCE_NAMES.join("|")))
+            .unwrap();
+        let log = anonymous.replace_all(log, |caps: &Captures| {
+            match caps[1].parse().ok().and_then(|line| self.lookup(line)) {
+                Some(loc) => loc.to_string(),
+                None => caps[0].to_owned(),
+            }
+        });
+
+        let line_ref = Regex::new(r#"(?:"([^"]*)", )?\bline (\d+)\b"#).unwrap();
+        line_ref.replace_all(&log, |caps: &Captures| {
+            let file = caps.get(1).map(|file| file.as_str());
+            if file.map_or(false, |file| self.contains_file(file)) {
+                return caps[0].to_owned();
+            }
+
+            match (caps[2].parse().ok().and_then(|line| self.lookup(line)), file) {
+                (Some(loc), Some(_)) => format!("\"{}\", line {}", loc.file, loc.line),
+                (Some(loc), None) => loc.to_string(),
+                (None, _) => caps[0].to_owned(),
+            }
+        }).into_owned()
+    }
+
+    /// Rewrites the location of a build diagnostic referring to a line of the
+    /// combined source to refer to the original location instead.
+    ///
+    /// Diagnostics referring to mapped file names are left as is.
+    pub fn map_diagnostic(&self, diagnostic: &mut Diagnostic) {
+        if !is_combined_source(diagnostic.file.as_ref().map(String::as_str)) { return; }
+
+        if let Some(loc) = diagnostic.line.and_then(|line| self.lookup(line)) {
+            diagnostic.file = Some(loc.file);
+            diagnostic.line = Some(loc.line);
+        }
+    }
+}
+
+
+/// Returns true if `file`, as reported by a compiler, refers to the combined
+/// program source.
+pub(crate) fn is_combined_source(file: Option<&str>) -> bool {
+    file.map_or(true, |file| COMBINED_SOURCE_NAMES.contains(&file))
+}
+
+
+/// Assembles program source strings, recording a `SourceMap` and optionally
+/// resolving `#include "..."` directives.
+///
+/// When resolving includes, the contents of included files are inserted in
+/// place of each directive, surrounded by `#line` directives so that
+/// compilers report original file names and line numbers. Files containing
+/// `#pragma once` or wrapped in an include guard are included only once.
+/// Includes are searched for relative to the including file then within
+/// each of the include directories. `#include <...>` directives are left for
+/// the compiler.
+pub(crate) struct SourceBuilder<'d> {
+    include_dirs: &'d [PathBuf],
+    resolve_includes: bool,
+    include_re: Regex,
+    pragma_once_re: Regex,
+    strings: Vec<CString>,
+    map: SourceMap,
+    /// The current line of the combined source (1-based).
+    line: usize,
+    at_line_start: bool,
+    /// Files which are not to be included again.
+    included_once: HashSet<PathBuf>,
+}
+
+impl<'d> SourceBuilder<'d> {
+    pub fn new(include_dirs: &'d [PathBuf], resolve_includes: bool) -> SourceBuilder<'d> {
+        SourceBuilder {
+            include_dirs,
+            resolve_includes,
+            include_re: Regex::new(r#"^\s*#\s*include\s*"([^"]+)""#).unwrap(),
+            pragma_once_re: Regex::new(r"^\s*#\s*pragma\s+once\b").unwrap(),
+            strings: Vec::with_capacity(64),
+            map: SourceMap::default(),
+            line: 1,
+            at_line_start: true,
+            included_once: HashSet::new(),
+        }
+    }
+
+    /// Adds a source string named `name`.
+    pub fn push_str(&mut self, name: &str, text: &str) -> OclResult<()> {
+        if !self.resolve_includes {
+            return self.push_raw(name, text.as_bytes().to_vec());
+        }
+
+        let mut out = String::with_capacity(text.len());
+        self.push_text(&mut out, name, None, text, 0)?;
+        self.strings.push(CString::new(out)?);
+        Ok(())
+    }
+
+    /// Adds the contents of the file at `path`.
+    pub fn push_file(&mut self, path: &Path) -> OclResult<()> {
+        let name = path.display().to_string();
+        if !self.resolve_includes {
+            return self.push_raw(&name, read_source(path)?);
+        }
+
+        let text = source_text(path, read_source(path)?)?;
+        if is_included_once(&text, &self.pragma_once_re) {
+            self.included_once.insert(canonical(path));
+        }
+
+        let mut out = String::with_capacity(text.len());
+        self.push_text(&mut out, &name, path.parent(), &text, 0)?;
+        self.strings.push(CString::new(out)?);
+        Ok(())
+    }
+
+    /// Returns the source strings and their source map.
+    pub fn finish(self) -> (Vec<CString>, SourceMap) {
+        (self.strings, self.map)
+    }
+
+    /// Adds `bytes` unmodified.
+    fn push_raw(&mut self, name: &str, bytes: Vec<u8>) -> OclResult<()> {
+        let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+        let partial = !bytes.is_empty() && !bytes.ends_with(b"\n");
+
+        if newlines + partial as usize > 0 {
+            self.map.segments.push(Segment {
+                start: self.line,
+                len: newlines + partial as usize,
+                file: name.to_owned(),
+                first_line: 1,
+            });
+        }
+        self.line += newlines;
+        if !bytes.is_empty() { self.at_line_start = !partial; }
+
+        self.strings.push(CString::new(bytes)?);
+        Ok(())
+    }
+
+    /// Adds `text`, replacing `#include` directives with the contents of the
+    /// files they refer to.
+    fn push_text(&mut self, out: &mut String, name: &str, dir: Option<&Path>, text: &str,
+            depth: usize) -> OclResult<()> {
+        self.push_line_directive(out, 1, name);
+
+        for (idx, line) in text.split_terminator('\n').enumerate() {
+            let orig_line = idx + 1;
+            if idx > 0 && !self.at_line_start { self.end_line(out); }
+
+            if let Some(caps) = self.include_re.captures(line) {
+                let include = caps[1].to_owned();
+                let path = self.resolve_include(&include, dir).ok_or_else(|| {
+                    format!("{}:{}: Unable to resolve '#include \"{}\"' (include directories: \
+                        {:?}).", name, orig_line, include, self.include_dirs)
+                })?;
+
+                let canonical_path = canonical(&path);
+                if self.included_once.contains(&canonical_path) {
+                    self.push_line(out, name, orig_line, "");
+                    continue;
+                }
+                if depth + 1 >= INCLUDE_DEPTH_MAX {
+                    return Err(format!("{}:{}: '#include' nested too deeply (recursive \
+                        include?).", name, orig_line).into());
+                }
+
+                let included = source_text(&path, read_source(&path)?)?;
+                if is_included_once(&included, &self.pragma_once_re) {
+                    self.included_once.insert(canonical_path);
+                }
+                self.push_text(out, &path.display().to_string(), path.parent(), &included,
+                    depth + 1)?;
+                self.push_line_directive(out, orig_line + 1, name);
+            } else if self.pragma_once_re.is_match(line) {
+                // Meaningless within the combined source:
+                self.push_line(out, name, orig_line, "");
+            } else {
+                self.push_line(out, name, orig_line, line);
+            }
+        }
+
+        if text.ends_with('\n') && !self.at_line_start { self.end_line(out); }
+        Ok(())
+    }
+
+    /// Appends a line of original source, without its newline.
+    fn push_line(&mut self, out: &mut String, name: &str, orig_line: usize, line: &str) {
+        out.push_str(line);
+        self.at_line_start = false;
+
+        if let Some(seg) = self.map.segments.last_mut() {
+            if seg.file == name && seg.start + seg.len == self.line
+                    && seg.first_line + seg.len == orig_line {
+                seg.len += 1;
+                return;
+            }
+        }
+        self.map.segments.push(Segment {
+            start: self.line,
+            len: 1,
+            file: name.to_owned(),
+            first_line: orig_line,
+        });
+    }
+
+    /// Appends a `#line` directive on its own line.
+    fn push_line_directive(&mut self, out: &mut String, line: usize, name: &str) {
+        if !self.at_line_start { self.end_line(out); }
+        let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+        out.push_str(&format!("#line {} \"{}\"", line, name));
+        self.at_line_start = false;
+        self.end_line(out);
+    }
+
+    fn end_line(&mut self, out: &mut String) {
+        out.push('\n');
+        self.line += 1;
+        self.at_line_start = true;
+    }
+
+    /// Returns the path of an included file: relative to the including file,
+    /// if any, otherwise the first match within the include directories.
+    fn resolve_include(&self, include: &str, dir: Option<&Path>) -> Option<PathBuf> {
+        dir.into_iter().map(Path::to_path_buf)
+            .chain(self.include_dirs.iter().cloned())
+            .map(|dir| dir.join(include))
+            .find(|path| path.is_file())
+    }
+}
+
+
+fn read_source(path: &Path) -> OclResult<Vec<u8>> {
+    fs::read(path)
+        .map_err(|err| format!("Unable to read program source file '{}': {}", path.display(),
+            err).into())
+}
+
+fn source_text(path: &Path, bytes: Vec<u8>) -> OclResult<String> {
+    String::from_utf8(bytes)
+        .map_err(|_| format!("Program source file '{}' is not valid UTF-8.", path.display())
+            .into())
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Returns true if `text` contains `#pragma once` or is entirely wrapped in an
+/// include guard (`#ifndef X`, `#define X`, ..., `#endif`).
+fn is_included_once(text: &str, pragma_once_re: &Regex) -> bool {
+    let mut lines = text.lines().map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("//"));
+
+    if text.lines().any(|line| pragma_once_re.is_match(line)) { return true; }
+
+    let directive = |line: Option<&str>, name: &str| -> Option<String> {
+        let rest = line?.strip_prefix('#')?.trim_start().strip_prefix(name)?;
+        Some(rest.trim().to_owned())
+    };
+
+    match (directive(lines.next(), "ifndef"), directive(lines.next(), "define")) {
+        (Some(guard), Some(define)) => {
+            define.split_whitespace().next() == Some(guard.as_str())
+                && directive(lines.last(), "endif").is_some()
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,673 @@
+//! Shared virtual memory.
+
+use std;
+use std::ops::{Deref, DerefMut};
+use std::marker::PhantomData;
+use crate::core::{self, OclPrm, SvmMemFlags, MapFlags, ClNullEventPtr};
+use crate::core::ffi::c_void;
+use crate::standard::{ClNullEventPtrEnum, ClWaitListPtrEnum, SpatialDims};
+use crate::{Context, Queue, Event};
+use crate::error::{Error as OclError, Result as OclResult};
+
+
+/// A shared virtual memory error.
+#[derive(Debug, Fail)]
+pub enum SvmError {
+    #[fail(display = "No queue specified.")]
+    NoQueue,
+    #[fail(display = "No operation specified. Use '.read(...)', '.write(...)', etc. \
+        before calling '.enq()'.")]
+    CmdNoOperation,
+    #[fail(display = "Region out of range (offset: {}, len: {}, svm_len: {}).",
+        offset, len, svm_len)]
+    RegionOor { offset: usize, len: usize, svm_len: usize },
+    #[fail(display = "No context or queue specified.")]
+    BuilderNoContext,
+    #[fail(display = "Length must be greater than zero.")]
+    BuilderLenZero,
+    #[fail(display = "The host slice length ({}) must equal the length ({}).", _0, _1)]
+    BuilderHostSliceLenMismatch(usize, usize),
+}
+
+
+fn check_region(svm_len: usize, offset: usize, len: usize) -> OclResult<()> {
+    if offset > svm_len || len > svm_len - offset {
+        Err(SvmError::RegionOor { offset, len, svm_len }.into())
+    } else {
+        Ok(())
+    }
+}
+
+
+/// The type of operation to be performed by a shared virtual memory command.
+pub enum SvmCmdKind<'c, T> where T: 'c + OclPrm {
+    Unspecified,
+    Read { data: &'c mut [T] },
+    Write { data: &'c [T] },
+    Fill { pattern: T, len: Option<usize> },
+    Copy { dst: &'c SvmVec<T>, dst_offset: Option<usize>, len: Option<usize> },
+}
+
+impl<'c, T> SvmCmdKind<'c, T> where T: OclPrm {
+    fn is_unspec(&self) -> bool {
+        match *self {
+            SvmCmdKind::Unspecified => true,
+            _ => false,
+        }
+    }
+}
+
+
+/// A shared virtual memory command builder used to enqueue reads, writes,
+/// fills, and copies.
+///
+/// See [`SvmVec::cmd`] for more information.
+///
+/// [`SvmVec::cmd`]: struct.SvmVec.html#method.cmd
+#[must_use = "commands do nothing unless enqueued"]
+pub struct SvmCmd<'c, T> where T: 'c + OclPrm {
+    svm: &'c SvmVec<T>,
+    queue: Option<&'c Queue>,
+    block: bool,
+    kind: SvmCmdKind<'c, T>,
+    offset: usize,
+    ewait: Option<ClWaitListPtrEnum<'c>>,
+    enew: Option<ClNullEventPtrEnum<'c>>,
+}
+
+impl<'c, T> SvmCmd<'c, T> where T: 'c + OclPrm {
+    /// Returns a new command builder associated with `svm`.
+    fn new(svm: &'c SvmVec<T>) -> SvmCmd<'c, T> {
+        SvmCmd {
+            svm,
+            queue: svm.queue.as_ref(),
+            block: true,
+            kind: SvmCmdKind::Unspecified,
+            offset: 0,
+            ewait: None,
+            enew: None,
+        }
+    }
+
+    /// Specifies that this command will copy from the device side memory
+    /// (starting at the offset) into `dst_data`.
+    ///
+    /// ### Panics
+    ///
+    /// The command operation kind must not have already been specified.
+    pub fn read(mut self, dst_data: &'c mut [T]) -> SvmCmd<'c, T> {
+        assert!(self.kind.is_unspec(), "ocl::SvmCmd::read(): Operation kind \
+            already set for this command.");
+        self.kind = SvmCmdKind::Read { data: dst_data };
+        self
+    }
+
+    /// Specifies that this command will copy `src_data` into the device side
+    /// memory (starting at the offset).
+    ///
+    /// ### Panics
+    ///
+    /// The command operation kind must not have already been specified.
+    pub fn write(mut self, src_data: &'c [T]) -> SvmCmd<'c, T> {
+        assert!(self.kind.is_unspec(), "ocl::SvmCmd::write(): Operation kind \
+            already set for this command.");
+        self.kind = SvmCmdKind::Write { data: src_data };
+        self
+    }
+
+    /// Specifies that this command will fill `len` elements (or all elements
+    /// following the offset if `None`) with `pattern`.
+    ///
+    /// ### Panics
+    ///
+    /// The command operation kind must not have already been specified.
+    pub fn fill(mut self, pattern: T, len: Option<usize>) -> SvmCmd<'c, T> {
+        assert!(self.kind.is_unspec(), "ocl::SvmCmd::fill(): Operation kind \
+            already set for this command.");
+        self.kind = SvmCmdKind::Fill { pattern, len };
+        self
+    }
+
+    /// Specifies that this command will copy `len` elements (or all elements
+    /// following the offset if `None`) into `dst`, starting at `dst_offset`
+    /// (or zero if `None`).
+    ///
+    /// ### Panics
+    ///
+    /// The command operation kind must not have already been specified.
+    pub fn copy(mut self, dst: &'c SvmVec<T>, dst_offset: Option<usize>, len: Option<usize>)
+            -> SvmCmd<'c, T> {
+        assert!(self.kind.is_unspec(), "ocl::SvmCmd::copy(): Operation kind \
+            already set for this command.");
+        self.kind = SvmCmdKind::Copy { dst, dst_offset, len };
+        self
+    }
+
+    /// Specifies a queue to use for this call only.
+    ///
+    /// Overrides the default queue if one is set. If no default queue is set,
+    /// this method **must** be called before enqueuing the command.
+    pub fn queue(mut self, queue: &'c Queue) -> SvmCmd<'c, T> {
+        self.queue = Some(queue);
+        self
+    }
+
+    /// Specifies whether or not to block the current thread until completion.
+    ///
+    /// Default is `block = true`.
+    ///
+    /// ## Safety
+    ///
+    /// When not blocking, the caller must ensure that neither the host data
+    /// nor the `SvmVec`(s) involved are accessed improperly or dropped until
+    /// the command completes. Use events (`Event::wait_for`) or the command
+    /// queue (`Queue::finish`) to synchronize.
+    pub unsafe fn block(mut self, block: bool) -> SvmCmd<'c, T> {
+        self.block = block;
+        self
+    }
+
+    /// Sets the offset (in elements) at which the operation begins.
+    pub fn offset(mut self, offset: usize) -> SvmCmd<'c, T> {
+        self.offset = offset;
+        self
+    }
+
+    /// Specifies an event or list of events to wait on before the command
+    /// will run.
+    pub fn ewait<'e, Ewl>(mut self, ewait: Ewl) -> SvmCmd<'c, T>
+            where 'e: 'c, Ewl: Into<ClWaitListPtrEnum<'e>> {
+        self.ewait = Some(ewait.into());
+        self
+    }
+
+    /// Specifies the destination to store a new, optionally created event
+    /// associated with this command.
+    pub fn enew<'e, En>(mut self, enew: En) -> SvmCmd<'c, T>
+            where 'e: 'c, En: Into<ClNullEventPtrEnum<'e>> {
+        self.enew = Some(enew.into());
+        self
+    }
+
+    /// Enqueues this command.
+    pub fn enq(self) -> OclResult<()> {
+        let queue = self.queue.ok_or(SvmError::NoQueue)?;
+        let version = Some(queue.device_version());
+        let (svm_len, offset) = (self.svm.len, self.offset);
+
+        match self.kind {
+            SvmCmdKind::Read { data } => {
+                check_region(svm_len, offset, data.len())?;
+
+                unsafe { core::enqueue_svm_memcpy(queue, self.block, data.as_mut_ptr(),
+                    self.svm.ptr.add(offset), data.len(), self.ewait, self.enew,
+                    version.as_ref()) }.map_err(OclError::from)
+            },
+            SvmCmdKind::Write { data } => {
+                check_region(svm_len, offset, data.len())?;
+
+                unsafe { core::enqueue_svm_memcpy(queue, self.block, self.svm.ptr.add(offset),
+                    data.as_ptr(), data.len(), self.ewait, self.enew, version.as_ref()) }
+                    .map_err(OclError::from)
+            },
+            SvmCmdKind::Fill { pattern, len } => {
+                let len = len.unwrap_or_else(|| svm_len.saturating_sub(offset));
+                check_region(svm_len, offset, len)?;
+
+                // Fills have no blocking variant, wait on an event instead:
+                let mut fill_event = Event::empty();
+
+                unsafe { core::enqueue_svm_mem_fill(queue, self.svm.ptr.add(offset), pattern,
+                    len, self.ewait, Some(&mut fill_event), version.as_ref())?; }
+
+                if let Some(mut enew) = self.enew {
+                    unsafe { enew.clone_from(&fill_event) }
+                }
+
+                if self.block { fill_event.wait_for()?; }
+                Ok(())
+            },
+            SvmCmdKind::Copy { dst, dst_offset, len } => {
+                let dst_offset = dst_offset.unwrap_or(0);
+                let len = len.unwrap_or_else(|| svm_len.saturating_sub(offset));
+                check_region(svm_len, offset, len)?;
+                check_region(dst.len, dst_offset, len)?;
+
+                unsafe { core::enqueue_svm_memcpy(queue, self.block, dst.ptr.add(dst_offset),
+                    self.svm.ptr.add(offset), len, self.ewait, self.enew, version.as_ref()) }
+                    .map_err(OclError::from)
+            },
+            SvmCmdKind::Unspecified => Err(SvmError::CmdNoOperation.into()),
+        }
+    }
+}
+
+
+/// A command builder used to map a region of an `SvmVec` for access by the
+/// host.
+///
+/// Mapping is required before accessing coarse-grained buffers from the host
+/// and synchronizes fine-grained buffers.
+#[must_use = "commands do nothing unless enqueued"]
+pub struct SvmMapCmd<'c, T> where T: 'c + OclPrm {
+    cmd: SvmCmd<'c, T>,
+    flags: Option<MapFlags>,
+    len: Option<usize>,
+}
+
+impl<'c, T> SvmMapCmd<'c, T> where T: OclPrm {
+    /// Specifies the flags to be used for this map command.
+    ///
+    /// Defaults to `MAP_READ | MAP_WRITE`.
+    pub fn flags(mut self, flags: MapFlags) -> SvmMapCmd<'c, T> {
+        self.flags = Some(flags);
+        self
+    }
+
+    /// Specifies that the region is being mapped for reading only.
+    pub fn read(mut self) -> SvmMapCmd<'c, T> {
+        self.flags = Some(crate::flags::MAP_READ);
+        self
+    }
+
+    /// Specifies that the region is being mapped for writing only.
+    pub fn write(mut self) -> SvmMapCmd<'c, T> {
+        self.flags = Some(crate::flags::MAP_WRITE);
+        self
+    }
+
+    /// Specifies that the region is being mapped for writing and that its
+    /// contents will be completely overwritten before being unmapped.
+    pub fn write_invalidate(mut self) -> SvmMapCmd<'c, T> {
+        self.flags = Some(crate::flags::MAP_WRITE_INVALIDATE_REGION);
+        self
+    }
+
+    /// Specifies the length of the region to map.
+    ///
+    /// If unspecified, all elements following the offset will be mapped.
+    pub fn len(mut self, len: usize) -> SvmMapCmd<'c, T> {
+        self.len = Some(len);
+        self
+    }
+
+    /// Specifies a queue to use for this call only.
+    ///
+    /// The region will also be unmapped using this queue.
+    pub fn queue(mut self, queue: &'c Queue) -> SvmMapCmd<'c, T> {
+        self.cmd.queue = Some(queue);
+        self
+    }
+
+    /// Sets the offset (in elements) of the region to map.
+    pub fn offset(self, offset: usize) -> SvmMapCmd<'c, T> {
+        SvmMapCmd { cmd: self.cmd.offset(offset), ..self }
+    }
+
+    /// Specifies an event or list of events to wait on before the command
+    /// will run.
+    pub fn ewait<'e, Ewl>(mut self, ewait: Ewl) -> SvmMapCmd<'c, T>
+            where 'e: 'c, Ewl: Into<ClWaitListPtrEnum<'e>> {
+        self.cmd.ewait = Some(ewait.into());
+        self
+    }
+
+    /// Specifies the destination to store a new, optionally created event
+    /// associated with this command.
+    pub fn enew<'e, En>(mut self, enew: En) -> SvmMapCmd<'c, T>
+            where 'e: 'c, En: Into<ClNullEventPtrEnum<'e>> {
+        self.cmd.enew = Some(enew.into());
+        self
+    }
+
+    /// Enqueues a map command, blocking the current thread until it
+    /// completes and returns a reference to the mapped memory.
+    ///
+    /// ## Safety
+    ///
+    /// The caller must ensure that the mapped region is not simultaneously
+    /// accessed by a device or through another mapping while it is mapped.
+    pub unsafe fn enq(self) -> OclResult<SvmMap<'c, T>> {
+        let queue = self.cmd.queue.ok_or(SvmError::NoQueue)?;
+        let offset = self.cmd.offset;
+        let len = self.len.unwrap_or_else(|| self.cmd.svm.len.saturating_sub(offset));
+        check_region(self.cmd.svm.len, offset, len)?;
+
+        let flags = self.flags.unwrap_or(crate::flags::MAP_READ | crate::flags::MAP_WRITE);
+        let ptr = self.cmd.svm.ptr.add(offset);
+
+        core::enqueue_svm_map(queue, true, flags, ptr, len, self.cmd.ewait, self.cmd.enew,
+            Some(&queue.device_version()))?;
+
+        Ok(SvmMap { ptr, len, queue: queue.clone(), is_unmapped: false, _svm: PhantomData })
+    }
+}
+
+
+/// A region of an `SvmVec` mapped for access by the host.
+///
+/// Dereferences to a slice. The region is unmapped when dropped or when
+/// [`::unmap`] is enqueued.
+///
+/// [`::unmap`]: #method.unmap
+#[derive(Debug)]
+pub struct SvmMap<'a, T> where T: OclPrm {
+    ptr: *mut T,
+    len: usize,
+    queue: Queue,
+    is_unmapped: bool,
+    _svm: PhantomData<&'a SvmVec<T>>,
+}
+
+impl<'a, T> SvmMap<'a, T> where T: OclPrm {
+    /// Returns an unmap command builder.
+    ///
+    /// Call `::enq` on it to unmap the region.
+    pub fn unmap(self) -> SvmUnmapCmd<'a, T> {
+        SvmUnmapCmd { map: self, queue: None, ewait: None, enew: None }
+    }
+}
+
+impl<'a, T> Deref for SvmMap<'a, T> where T: OclPrm {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl<'a, T> DerefMut for SvmMap<'a, T> where T: OclPrm {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl<'a, T> Drop for SvmMap<'a, T> where T: OclPrm {
+    fn drop(&mut self) {
+        if !self.is_unmapped {
+            unsafe {
+                core::enqueue_svm_unmap::<T, &mut Event, &Event>(&self.queue, self.ptr, None,
+                    None, Some(&self.queue.device_version())).ok();
+            }
+        }
+    }
+}
+
+
+/// An unmap command builder.
+#[must_use = "commands do nothing unless enqueued"]
+pub struct SvmUnmapCmd<'c, T> where T: 'c + OclPrm {
+    map: SvmMap<'c, T>,
+    queue: Option<&'c Queue>,
+    ewait: Option<ClWaitListPtrEnum<'c>>,
+    enew: Option<ClNullEventPtrEnum<'c>>,
+}
+
+impl<'c, T> SvmUnmapCmd<'c, T> where T: OclPrm {
+    /// Specifies a queue to use for this call only.
+    pub fn queue(mut self, queue: &'c Queue) -> SvmUnmapCmd<'c, T> {
+        self.queue = Some(queue);
+        self
+    }
+
+    /// Specifies an event or list of events to wait on before the command
+    /// will run.
+    pub fn ewait<'e, Ewl>(mut self, ewait: Ewl) -> SvmUnmapCmd<'c, T>
+            where 'e: 'c, Ewl: Into<ClWaitListPtrEnum<'e>> {
+        self.ewait = Some(ewait.into());
+        self
+    }
+
+    /// Specifies the destination to store a new, optionally created event
+    /// associated with this command.
+    pub fn enew<'e, En>(mut self, enew: En) -> SvmUnmapCmd<'c, T>
+            where 'e: 'c, En: Into<ClNullEventPtrEnum<'e>> {
+        self.enew = Some(enew.into());
+        self
+    }
+
+    /// Enqueues this command.
+    pub fn enq(mut self) -> OclResult<()> {
+        let queue = self.queue.unwrap_or(&self.map.queue);
+
+        unsafe { core::enqueue_svm_unmap(queue, self.map.ptr, self.ewait, self.enew,
+            Some(&queue.device_version()))?; }
+
+        self.map.is_unmapped = true;
+        Ok(())
+    }
+}
+
+
+/// A buffer of shared virtual memory (SVM).
+///
+/// Unlike a `Buffer`, the contents of an `SvmVec` share an address space
+/// with the host and with every device in its context. Pointers into it
+/// (see [`::as_ptr`]) remain valid on both sides, which allows pointer-based
+/// data structures such as linked lists and trees to be built on the host
+/// and traversed within kernels.
+///
+/// `SvmVec`s are passed to kernels using `KernelBuilder::arg` or
+/// `Kernel::set_arg` exactly as buffers are. Any other SVM allocations a
+/// kernel will access through pointers stored within an argument must be
+/// declared with `Kernel::set_exec_info`.
+///
+/// Coarse-grained buffers (the default) must be mapped (see [`::map`])
+/// before being accessed by the host.
+///
+/// The memory is freed when the `SvmVec` is dropped. Unlike buffers, SVM
+/// allocations are not reference counted: all commands (including kernels)
+/// using an `SvmVec` must have completed before it is dropped.
+///
+/// [Version Controlled: OpenCL 2.0+]
+///
+/// [`::as_ptr`]: #method.as_ptr
+/// [`::map`]: #method.map
+#[derive(Debug)]
+pub struct SvmVec<T: OclPrm> {
+    ptr: *mut T,
+    len: usize,
+    flags: SvmMemFlags,
+    context: Context,
+    queue: Option<Queue>,
+}
+
+impl<T: OclPrm> SvmVec<T> {
+    /// Returns a new `SvmVecBuilder`.
+    pub fn builder<'a>() -> SvmVecBuilder<'a, T> {
+        SvmVecBuilder::new()
+    }
+
+    /// Returns a command builder used to read, write, fill, or copy.
+    ///
+    /// Call `.enq()` to enqueue the command.
+    pub fn cmd<'c>(&'c self) -> SvmCmd<'c, T> {
+        SvmCmd::new(self)
+    }
+
+    /// Returns a command builder used to read into `dst`.
+    pub fn read<'c>(&'c self, dst: &'c mut [T]) -> SvmCmd<'c, T> {
+        self.cmd().read(dst)
+    }
+
+    /// Returns a command builder used to write from `src`.
+    pub fn write<'c>(&'c self, src: &'c [T]) -> SvmCmd<'c, T> {
+        self.cmd().write(src)
+    }
+
+    /// Returns a command builder used to map a region for access by the
+    /// host.
+    ///
+    /// Call `.enq()` to enqueue the command and return an `SvmMap`.
+    pub fn map<'c>(&'c self) -> SvmMapCmd<'c, T> {
+        SvmMapCmd { cmd: self.cmd(), flags: None, len: None }
+    }
+
+    /// Returns the length (in elements) of the allocation.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the allocation has a length of zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the flags used to allocate this `SvmVec`.
+    #[inline]
+    pub fn flags(&self) -> SvmMemFlags {
+        self.flags
+    }
+
+    /// Returns the context this `SvmVec` was allocated within.
+    #[inline]
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Returns a pointer to the start of the allocation.
+    ///
+    /// The pointer is valid on both the host and the devices of the context
+    /// and may be stored within SVM allocations (as a `u64` or `usize` for
+    /// example) for use within kernels.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr
+    }
+
+    /// Returns a mutable pointer to the start of the allocation.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr
+    }
+
+    /// Changes the default queue.
+    ///
+    /// Commands will be enqueued on this queue unless otherwise specified.
+    pub fn set_default_queue<'a>(&'a mut self, queue: Queue) -> &'a mut SvmVec<T> {
+        self.queue = Some(queue);
+        self
+    }
+
+    /// Returns a reference to the default queue.
+    #[inline]
+    pub fn default_queue(&self) -> Option<&Queue> {
+        self.queue.as_ref()
+    }
+}
+
+impl<T: OclPrm> Drop for SvmVec<T> {
+    fn drop(&mut self) {
+        // `clSVMFree` is available whenever the allocation succeeded:
+        let _ = unsafe { core::svm_free(self.context.as_core(), self.ptr as *mut c_void) };
+    }
+}
+
+unsafe impl<T: OclPrm> Send for SvmVec<T> {}
+unsafe impl<T: OclPrm> Sync for SvmVec<T> {}
+
+
+/// A builder for `SvmVec`.
+#[must_use = "builders do nothing unless '::build' is called"]
+pub struct SvmVecBuilder<'a, T> where T: OclPrm {
+    queue: Option<Queue>,
+    context: Option<&'a Context>,
+    flags: Option<SvmMemFlags>,
+    len: usize,
+    host_slice: Option<&'a [T]>,
+    fill_val: Option<T>,
+}
+
+impl<'a, T> SvmVecBuilder<'a, T> where T: 'a + OclPrm {
+    /// Returns a new `SvmVecBuilder`.
+    pub fn new() -> SvmVecBuilder<'a, T> {
+        SvmVecBuilder {
+            queue: None,
+            context: None,
+            flags: None,
+            len: 0,
+            host_slice: None,
+            fill_val: None,
+        }
+    }
+
+    /// Sets the context within which to allocate.
+    ///
+    /// Either a context or a default queue must be specified.
+    pub fn context(mut self, context: &'a Context) -> SvmVecBuilder<'a, T> {
+        self.context = Some(context);
+        self
+    }
+
+    /// Sets the default queue (and the context within which to allocate, if
+    /// one has not been specified).
+    pub fn queue(mut self, default_queue: Queue) -> SvmVecBuilder<'a, T> {
+        self.queue = Some(default_queue);
+        self
+    }
+
+    /// Sets the flags used when allocating.
+    ///
+    /// Defaults to `SvmMemFlags::READ_WRITE` (a coarse-grained buffer).
+    pub fn flags(mut self, flags: SvmMemFlags) -> SvmVecBuilder<'a, T> {
+        self.flags = Some(flags);
+        self
+    }
+
+    /// Sets the length (in elements).
+    pub fn len<D>(mut self, len: D) -> SvmVecBuilder<'a, T>
+            where D: Into<SpatialDims> {
+        self.len = len.into().to_len();
+        self
+    }
+
+    /// Specifies a host slice to copy into the new `SvmVec`.
+    ///
+    /// Requires a default queue.
+    pub fn copy_host_slice(mut self, host_slice: &'a [T]) -> SvmVecBuilder<'a, T> {
+        self.host_slice = Some(host_slice);
+        self
+    }
+
+    /// Specifies a value with which to fill the new `SvmVec`.
+    ///
+    /// Requires a default queue. Ignored if a host slice is specified.
+    pub fn fill_val(mut self, fill_val: T) -> SvmVecBuilder<'a, T> {
+        self.fill_val = Some(fill_val);
+        self
+    }
+
+    /// Allocates and returns a new `SvmVec`.
+    pub fn build(self) -> OclResult<SvmVec<T>> {
+        let context = match (self.context, self.queue.as_ref()) {
+            (Some(context), _) => context.clone(),
+            (None, Some(queue)) => queue.context(),
+            (None, None) => return Err(SvmError::BuilderNoContext.into()),
+        };
+
+        if self.len == 0 { return Err(SvmError::BuilderLenZero.into()); }
+
+        if let Some(host_slice) = self.host_slice {
+            if host_slice.len() != self.len {
+                return Err(SvmError::BuilderHostSliceLenMismatch(host_slice.len(), self.len)
+                    .into());
+            }
+        }
+
+        let flags = self.flags.unwrap_or_default();
+        let ptr = core::svm_alloc(context.as_core(), flags, self.len * std::mem::size_of::<T>(),
+            0, None)? as *mut T;
+
+        let svm = SvmVec { ptr, len: self.len, flags, context, queue: self.queue };
+
+        if let Some(host_slice) = self.host_slice {
+            svm.write(host_slice).enq()?;
+        } else if let Some(fill_val) = self.fill_val {
+            svm.cmd().fill(fill_val, None).enq()?;
+        }
+
+        Ok(svm)
+    }
+}
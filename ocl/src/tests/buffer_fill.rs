@@ -43,6 +43,7 @@ fn fill() {
 }
 
 #[test]
+#[ignore = "requires a real OpenCL platform (vector loads and stores)"]
 fn fill_with_float4() {
     use crate::prm::Float4;
 
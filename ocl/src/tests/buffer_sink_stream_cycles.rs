@@ -460,6 +460,7 @@ pub fn verify_add(dst_buf_stream: &BufferStream<Int4>,
 ///   4. and verifies the sum.
 ///
 #[test]
+#[ignore = "requires a real OpenCL platform (vector loads and stores)"]
 pub fn buffer_sink_stream_cycles() {
     let platform = Platform::default();
     println!("Platform: {}", platform.name().unwrap());
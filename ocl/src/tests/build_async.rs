@@ -0,0 +1,49 @@
+//! Tests asynchronous program builds.
+
+use futures::{future, Future};
+use crate::standard::{Buffer, Context, Kernel, Program, Queue};
+use super::build_log::build_log;
+
+#[test]
+fn build_async() {
+    let context = Context::builder().build().unwrap();
+    let queue = Queue::new(&context, context.devices()[0], None).unwrap();
+
+    let builds: Vec<_> = (0..8).map(|i| {
+        Program::builder()
+            .src(format!("__kernel void add(__global int* buffer) {{\n    \
+                buffer[get_global_id(0)] += {};\n}}\n", i))
+            .build_async(&context).unwrap()
+    }).collect();
+    let programs = future::join_all(builds).wait().unwrap();
+    assert_eq!(programs.len(), 8);
+
+    let buffer = Buffer::<i32>::builder().queue(queue.clone()).len(16).fill_val(1)
+        .build().unwrap();
+    let kernel = Kernel::builder().program(&programs[5]).name("add").queue(queue)
+        .global_work_size(16).arg(&buffer).build().unwrap();
+    unsafe { kernel.enq().unwrap(); }
+
+    let mut vec = vec![0; 16];
+    buffer.read(&mut vec).enq().unwrap();
+    assert!(vec.iter().all(|&val| val == 6));
+}
+
+#[test]
+fn build_async_errors() {
+    let context = Context::builder().build().unwrap();
+
+    // Build failures resolve to an error containing the build log:
+    let err = Program::builder()
+        .src("__kernel void a(__global int* buffer) {}\n")
+        .src("__kernel void b(__global int* buffer) {\n    oops;\n}\n")
+        .build_async(&context).unwrap()
+        .wait().unwrap_err();
+    let log = build_log(&err);
+    assert!(log.has_errors());
+    assert!(log.errors().any(|d| d.file.as_ref().map(String::as_str) == Some("<src 2>")
+        && d.line == Some(2)), "{}", log);
+
+    // Only source and binaries may be built asynchronously:
+    assert!(Program::builder().build_async(&context).is_err());
+}
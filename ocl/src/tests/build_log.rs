@@ -0,0 +1,61 @@
+//! Tests structured build logs.
+
+use crate::core::{ErrorKind as OclCoreErrorKind, ProgramBuildError, ProgramBuildLog, Severity};
+use crate::error::{Error as OclError, ErrorKind as OclErrorKind};
+use crate::standard::{Context, Program};
+
+/// Returns the build log contained within a program build error.
+pub(crate) fn build_log(err: &OclError) -> &ProgramBuildLog {
+    match *err.kind() {
+        OclErrorKind::OclCore(ref core_err) => match *core_err.kind() {
+            OclCoreErrorKind::ProgramBuild(ProgramBuildError::BuildLog(ref log)) => log,
+            ref other => panic!("unexpected error: {:?}", other),
+        },
+        ref other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn build_log_errors() {
+    let context = Context::builder().build().unwrap();
+
+    let err = Program::builder()
+        .src("__kernel void a(__global float* buffer) {\n    buffer[0] = 1.0f;\n}\n")
+        .src("__kernel void b(__global float* buffer) {\n    buffer[0] = oops;\n}\n")
+        .build(&context).unwrap_err();
+
+    let log = build_log(&err);
+    assert!(log.has_errors());
+    assert_eq!(log.devices().len(), context.devices().len());
+
+    // Drivers may report additional errors and columns:
+    let errors: Vec<_> = log.errors().collect();
+    assert!(errors.len() >= context.devices().len());
+    let error = errors.iter()
+        .find(|d| d.file.as_ref().map(String::as_str) == Some("<src 2>") && d.line == Some(2))
+        .unwrap_or_else(|| panic!("no error at '<src 2>:2': {:?}", errors));
+    assert_eq!(error.severity, Severity::Error);
+    assert!(error.source.as_ref().map_or(false, |src| src.contains("oops")), "{:?}", error);
+
+    let rendered = err.to_string();
+    assert!(rendered.contains("build failed"), "{}", rendered);
+    assert!(rendered.contains("<src 2>:2"), "{}", rendered);
+}
+
+#[test]
+fn build_log_warnings() {
+    let context = Context::builder().build().unwrap();
+
+    // Warnings alone do not cause a build to fail:
+    let program = Program::builder()
+        .src("#warning slow path\n__kernel void a(__global float* buffer) {}\n")
+        .build(&context).unwrap();
+
+    let log = program.build_log().unwrap();
+    assert!(!log.has_errors());
+    assert_eq!(log.errors().count(), 0);
+
+    let warnings: Vec<_> = log.warnings().collect();
+    assert!(warnings.iter().any(|w| w.message.contains("slow path")), "{:?}", warnings);
+    assert!(log.to_string().contains("build succeeded"), "{}", log);
+}
@@ -0,0 +1,64 @@
+//! Tests creating programs from the built-in kernels of a device.
+
+use crate::standard::{Platform, Device, Context, Queue, Program, Kernel, Buffer};
+
+#[test]
+fn built_in_kernels() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if device.version().unwrap() < [1, 2].into() { continue; }
+
+            let names = device.built_in_kernels().unwrap();
+            if names.is_empty() { continue; }
+
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+
+            let name_refs: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+            let program = Program::builder()
+                .devices(device)
+                .built_in_kernels(&name_refs)
+                .build(&context).unwrap();
+
+            let mut program_names = program.kernel_names().unwrap();
+            program_names.sort();
+            let mut expected = names.clone();
+            expected.sort();
+            assert_eq!(program_names, expected);
+
+            // Run the known kernel of the mock platform (see `ocl-mock`):
+            if !names.iter().any(|n| n == "ocl_mock_fill_float") { continue; }
+
+            let queue = Queue::new(&context, device, None).unwrap();
+            let buffer = Buffer::<f32>::builder()
+                .queue(queue.clone())
+                .len(64)
+                .build().unwrap();
+
+            let kernel = Kernel::builder()
+                .program(&program)
+                .name("ocl_mock_fill_float")
+                .queue(queue)
+                .global_work_size(64)
+                .arg(&buffer)
+                .arg(3.0f32)
+                .build().unwrap();
+
+            unsafe { kernel.enq().unwrap(); }
+
+            let mut vec = vec![0.0f32; 64];
+            buffer.read(&mut vec).enq().unwrap();
+            assert!(vec.iter().all(|&v| v == 3.0));
+
+            // Argument types are checked as with any other kernel:
+            assert!(Kernel::builder()
+                .program(&program)
+                .name("ocl_mock_fill_float")
+                .arg(&buffer)
+                .arg(3u32)
+                .build().is_err());
+        }
+    }
+}
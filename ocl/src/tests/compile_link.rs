@@ -0,0 +1,75 @@
+//! Tests separate compilation and linking of programs.
+
+use crate::core::ProgramBinaryType;
+use crate::standard::{Buffer, Context, Kernel, Program, Queue};
+
+static MATH_H: &'static str = "float scale(float val, float factor);\n";
+
+static MATH_SRC: &'static str = "#include \"math.h\"
+
+float scale(float val, float factor) {
+    return val * factor;
+}
+";
+
+static KERNEL_SRC: &'static str = "#include \"math.h\"
+
+__kernel void scale_all(__global float* buffer, float factor) {
+    buffer[get_global_id(0)] = scale(buffer[get_global_id(0)], factor);
+}
+";
+
+#[test]
+fn compile_link() {
+    let context = Context::builder().build().unwrap();
+    let device = context.devices()[0];
+    let queue = Queue::new(&context, device, None).unwrap();
+    let math_h = Program::header(&context, MATH_H).unwrap();
+
+    // Compile the library source once into a library:
+    let math = Program::builder().src(MATH_SRC).header("math.h", &math_h)
+        .compile(&context).unwrap();
+    assert_eq!(math.binary_type(device).unwrap(), ProgramBinaryType::COMPILED_OBJECT);
+    let math_lib = Program::link(&[&math], "-create-library").unwrap();
+    assert_eq!(math_lib.binary_type(device).unwrap(), ProgramBinaryType::LIBRARY);
+
+    // ...then link it into a program using it:
+    let kernels = Program::builder().src(KERNEL_SRC).header("math.h", &math_h)
+        .compile(&context).unwrap();
+    let program = Program::link(&[&math_lib, &kernels], "").unwrap();
+    assert_eq!(program.binary_type(device).unwrap(), ProgramBinaryType::EXECUTABLE);
+    assert_eq!(program.kernel_names().unwrap(), vec!["scale_all".to_owned()]);
+
+    let buffer = Buffer::<f32>::builder().queue(queue.clone()).len(64).fill_val(3.0f32)
+        .build().unwrap();
+    let kernel = Kernel::builder().program(&program).name("scale_all").queue(queue)
+        .global_work_size(64).arg(&buffer).arg(2.0f32).build().unwrap();
+    unsafe { kernel.enq().unwrap(); }
+
+    let mut vec = vec![0.0f32; 64];
+    buffer.read(&mut vec).enq().unwrap();
+    assert!(vec.iter().all(|&val| val == 6.0));
+
+    // Headers are only used when compiling:
+    assert!(Program::builder().src(KERNEL_SRC).header("math.h", &math_h)
+        .build(&context).is_err());
+    assert!(Program::link(&[], "").is_err());
+}
+
+#[test]
+fn compile_link_errors() {
+    let context = Context::builder().build().unwrap();
+
+    // Compile errors refer to the source string:
+    let err = Program::builder().src("__kernel void k() {}\n").src("#error no\n")
+        .compile(&context).unwrap_err();
+    assert!(err.to_string().contains("<src 2>:1"), "{}", err);
+
+    // Link errors, here an undefined function, contain the build log:
+    let kernels = Program::builder().src(KERNEL_SRC)
+        .header("math.h", &Program::header(&context, MATH_H).unwrap())
+        .compile(&context).unwrap();
+    let err = Program::link(&[&kernels], "").unwrap_err();
+    assert!(err.to_string().contains("error: call to unknown or unsupported function 'scale'"),
+        "{}", err);
+}
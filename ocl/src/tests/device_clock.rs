@@ -0,0 +1,66 @@
+//! Tests converting event profiling timestamps into host instants.
+
+use std::time::{Duration, Instant};
+use crate::core::ProfilingInfo;
+use crate::flags::CommandQueueProperties;
+use crate::standard::{Platform, Device, ProQue, Event};
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float scalar) {
+        buffer[get_global_id(0)] += scalar;
+    }
+"#;
+
+#[test]
+fn device_clock() {
+    // Allows for timer resolution and the imprecision of the correlation:
+    let tolerance = Duration::from_millis(2);
+
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if device.version().unwrap() < [2, 1].into() {
+                assert!(device.clock().is_err());
+                continue;
+            }
+
+            let pro_que = ProQue::builder()
+                .platform(platform)
+                .device(device)
+                .src(SRC)
+                .queue_properties(CommandQueueProperties::new().profiling())
+                .dims(1 << 16)
+                .build().unwrap();
+
+            let mut clock = device.clock().unwrap();
+            assert!(clock.sample_count() > 1);
+
+            let buffer = pro_que.create_buffer::<f32>().unwrap();
+            let kernel = pro_que.kernel_builder("add")
+                .arg(&buffer)
+                .arg(1.0f32)
+                .build().unwrap();
+
+            let mut event = Event::empty();
+            let before = Instant::now();
+            unsafe { kernel.cmd().enew(&mut event).enq().unwrap(); }
+            event.wait_for().unwrap();
+            let after = Instant::now();
+
+            let start = clock.profiling_instant(&event.profiling_info(ProfilingInfo::Start).unwrap());
+            let end = clock.profiling_instant(&event.profiling_info(ProfilingInfo::End).unwrap());
+
+            assert!(start <= end);
+            assert!(start + tolerance + clock.uncertainty() >= before);
+            assert!(end <= after + tolerance + clock.uncertainty());
+
+            // Conversions round trip:
+            let now = Instant::now();
+            let round_trip = clock.to_instant(clock.to_device_ns(now));
+            assert!(round_trip + Duration::from_micros(1) >= now &&
+                round_trip <= now + Duration::from_micros(1));
+
+            clock.sample().unwrap();
+            assert!(clock.drift_ppm().is_finite());
+        }
+    }
+}
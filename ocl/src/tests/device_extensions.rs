@@ -0,0 +1,59 @@
+//! Tests typed device extensions and filtering devices by extension.
+
+use crate::error::ErrorKind;
+use crate::standard::{Platform, Device, DeviceSpecifier, DeviceExtension, DeviceExtensions,
+    DeviceError};
+
+#[test]
+fn parse_extensions() {
+    let exts = DeviceExtensions::from_names(" cl_khr_fp64  cl_khr_gl_sharing\ncl_vendor_thing ");
+    assert_eq!(exts.len(), 3);
+    assert!(exts.has(DeviceExtension::Fp64));
+    assert!(exts.has("cl_khr_gl_sharing"));
+    assert!(exts.has(DeviceExtension::Unknown("cl_vendor_thing".to_owned())));
+    assert!(!exts.has(DeviceExtension::Fp16));
+    assert!(exts.has_all(vec!["cl_khr_fp64", "cl_khr_gl_sharing"]));
+    assert!(!exts.has_all(vec![DeviceExtension::Fp64, DeviceExtension::Subgroups]));
+    assert!(exts.has_any(vec![DeviceExtension::Fp16, DeviceExtension::GlSharing]));
+    assert_eq!(exts.to_string(), "cl_khr_fp64 cl_khr_gl_sharing cl_vendor_thing");
+
+    assert_eq!(DeviceExtension::from_name("cl_khr_int64_base_atomics"),
+        DeviceExtension::Int64BaseAtomics);
+    assert_eq!(DeviceExtension::ImageWrites3d.name(), "cl_khr_3d_image_writes");
+    assert!(DeviceExtensions::from_names("").is_empty());
+}
+
+#[test]
+fn device_extensions() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            let exts = device.extensions().unwrap();
+            let names = device.info(crate::core::DeviceInfo::Extensions).unwrap().to_string();
+            assert_eq!(exts.len(), names.split_whitespace().count());
+            assert!(exts.iter().all(|ext| names.contains(ext.name())));
+        }
+    }
+}
+
+#[test]
+fn filter_by_extensions() {
+    let platform = Platform::default();
+    let devices = Device::list_all(platform).unwrap();
+    let exts = devices[0].extensions().unwrap();
+
+    // Every extension of the first device selects at least that device:
+    let filtered = DeviceSpecifier::All.with_extensions(&exts)
+        .to_device_list(Some(platform)).unwrap();
+    assert!(filtered.contains(&devices[0]));
+    assert!(filtered.iter().all(|d| d.extensions().unwrap().has_all(&exts)));
+
+    // No device supports an unknown extension:
+    let err = DeviceSpecifier::All.with_extensions(vec!["cl_ocl_no_such_extension"])
+        .to_device_list(Some(platform)).unwrap_err();
+    match *err.kind() {
+        ErrorKind::Device(DeviceError::NoDevicesWithExtensions(ref names)) => {
+            assert_eq!(names, "cl_ocl_no_such_extension");
+        },
+        ref kind => panic!("Unexpected error: {}", kind),
+    }
+}
@@ -0,0 +1,68 @@
+//! Tests partitioning devices into sub-devices.
+
+use crate::standard::{Platform, Device, Context, Queue, Buffer};
+use crate::core::{DeviceInfo, DeviceInfoResult, DevicePartition, DevicePartitionProperty};
+
+/// Returns the compute unit count of `device`.
+fn compute_units(device: &Device) -> u32 {
+    match device.info(DeviceInfo::MaxComputeUnits).unwrap() {
+        DeviceInfoResult::MaxComputeUnits(cu) => cu,
+        _ => unreachable!(),
+    }
+}
+
+fn ref_count(device: &Device) -> u32 {
+    match device.info(DeviceInfo::ReferenceCount).unwrap() {
+        DeviceInfoResult::ReferenceCount(rc) => rc,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn device_partition() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if device.version().unwrap() < [1, 2].into() { continue; }
+
+            let props = match device.info(DeviceInfo::PartitionProperties).unwrap() {
+                DeviceInfoResult::PartitionProperties(props) => props,
+                _ => unreachable!(),
+            };
+            if !props.contains(&DevicePartitionProperty::Equally) { continue; }
+
+            let sub_devices = device.partition(DevicePartition::Equally(1)).unwrap();
+            assert_eq!(sub_devices.len() as u32, compute_units(&device));
+
+            for sub_device in sub_devices.iter() {
+                assert_eq!(compute_units(sub_device), 1);
+                assert_eq!(sub_device.parent(), device);
+                match sub_device.info(DeviceInfo::ParentDevice).unwrap() {
+                    DeviceInfoResult::ParentDevice(Some(parent)) => assert_eq!(parent, *device),
+                    _ => panic!("sub-device has no parent"),
+                }
+            }
+
+            // Clones share a single reference, released with the last clone:
+            let rc = ref_count(&sub_devices[0]);
+            let clone = sub_devices[0].clone();
+            assert_eq!(ref_count(&clone), rc);
+            drop(clone);
+            assert_eq!(ref_count(&sub_devices[0]), rc);
+
+            // Sub-devices can be used like any other device:
+            let context = Context::builder()
+                .platform(platform)
+                .devices(&sub_devices[0])
+                .build().unwrap();
+            let queue = Queue::new(&context, &sub_devices[0], None).unwrap();
+            let buffer = Buffer::<u32>::builder()
+                .queue(queue)
+                .len(64)
+                .fill_val(7u32)
+                .build().unwrap();
+            let mut vec = vec![0u32; 64];
+            buffer.read(&mut vec).enq().unwrap();
+            assert!(vec.iter().all(|&v| v == 7));
+        }
+    }
+}
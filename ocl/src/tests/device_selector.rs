@@ -0,0 +1,105 @@
+//! Tests selecting devices by name, vendor, memory size, version and
+//! extensions.
+
+use std::env;
+use regex;
+use crate::core::{DeviceType, DeviceInfo, DeviceInfoResult};
+use crate::error::ErrorKind;
+use crate::standard::{Platform, Device, Context, ProQue, DeviceSelector, DevicePreference,
+    DeviceError, DeviceExtension, DEVICE_SELECTOR_ENV_VAR};
+
+#[test]
+fn parse_selector() {
+    let selector: DeviceSelector = " vendor=nvidia|amd ; type=gpu|Accelerator; \
+        min_global_mem=4G;min_version=1.2;extensions=cl_khr_fp64, cl_khr_gl_sharing;\
+        prefer=Discrete;".parse().unwrap();
+
+    assert_eq!(selector, DeviceSelector::new()
+        .vendor("nvidia|amd")
+        .device_type(DeviceType::GPU | DeviceType::ACCELERATOR)
+        .min_global_mem(4 << 30)
+        .min_version([1, 2])
+        .extensions(vec![DeviceExtension::Fp64, DeviceExtension::GlSharing])
+        .prefer(DevicePreference::Discrete));
+
+    // Display uses the same syntax:
+    assert_eq!(selector.to_string().parse::<DeviceSelector>().unwrap(), selector);
+    assert_eq!("".parse::<DeviceSelector>().unwrap(), DeviceSelector::new());
+
+    for invalid in &["vendor", "colour=red", "type=QPU", "min_global_mem=4X",
+            "min_version=one", "prefer=fastest", "name=("] {
+        match invalid.parse::<DeviceSelector>() {
+            Err(DeviceError::InvalidSelector { .. }) => (),
+            other => panic!("Selector '{}' should be invalid: {:?}", invalid, other),
+        }
+    }
+}
+
+#[test]
+fn select_devices() {
+    let platform = Platform::default();
+    let device = Device::first(platform).unwrap();
+    let name = regex::escape(&device.name().unwrap());
+    let global_mem = match device.info(DeviceInfo::GlobalMemSize).unwrap() {
+        DeviceInfoResult::GlobalMemSize(size) => size,
+        _ => unreachable!(),
+    };
+
+    let selector = DeviceSelector::new()
+        .platform(&regex::escape(&platform.name().unwrap()))
+        .name(&name.to_uppercase())
+        .vendor(&regex::escape(&device.vendor().unwrap()))
+        .min_global_mem(global_mem)
+        .min_version(device.version().unwrap())
+        .extensions(&device.extensions().unwrap())
+        .prefer(DevicePreference::Integrated);
+    assert!(selector.matches(&device).unwrap());
+    assert!(selector.select().unwrap().contains(&device));
+    assert!(selector.select_on(platform).unwrap().contains(&device));
+
+    let context = Context::builder().devices(selector.clone()).build().unwrap();
+    assert!(context.devices().contains(&device));
+    assert_eq!(device.platform().unwrap().as_core(), platform.as_core());
+
+    // Each criteria must match:
+    let unmatched = vec![
+        selector.clone().min_global_mem(global_mem + 1),
+        selector.clone().min_version([u16::max_value(), 0]),
+        selector.clone().extensions(vec!["cl_ocl_no_such_extension"]),
+        selector.clone().name("^no such device$"),
+    ];
+    for selector in unmatched {
+        assert!(!selector.matches(&device).unwrap());
+        assert!(!selector.select().unwrap().contains(&device));
+    }
+
+    let err = Context::builder().devices(DeviceSelector::new().vendor("^no such vendor$"))
+        .build().unwrap_err();
+    match *err.kind() {
+        ErrorKind::Device(DeviceError::NoMatchingDevices(ref selector)) => {
+            assert_eq!(selector, "vendor=^no such vendor$");
+        },
+        ref kind => panic!("Unexpected error: {}", kind),
+    }
+}
+
+#[test]
+fn select_from_env() {
+    let device = Device::first(Platform::default()).unwrap();
+
+    // Selects the same device as the default so that concurrently running
+    // tests are unaffected:
+    let selector = format!("name=^{}$", regex::escape(&device.name().unwrap()));
+    env::set_var(DEVICE_SELECTOR_ENV_VAR, &selector);
+    assert_eq!(DeviceSelector::from_env().unwrap(), Some(selector.parse().unwrap()));
+
+    let pro_que = ProQue::builder()
+        .src("__kernel void add(__global float* buffer) { buffer[get_global_id(0)] += 1.0f; }")
+        .dims(1)
+        .build().unwrap();
+    assert_eq!(pro_que.device().name().unwrap(), device.name().unwrap());
+
+    let context = Context::builder().build().unwrap();
+    assert!(context.devices().iter().all(|d| d.name().unwrap() == device.name().unwrap()));
+    env::remove_var(DEVICE_SELECTOR_ENV_VAR);
+}
@@ -0,0 +1,35 @@
+//! Tests loading extension function tables.
+
+use crate::core::{KhrSubgroupsFns, KhrIlProgramFns, IntelUsmFns};
+use crate::standard::Platform;
+
+#[test]
+fn extension_fns() {
+    for platform in Platform::list() {
+        if platform.version().unwrap().contains("OpenCL 1.1") { continue; }
+        let extensions = platform.extensions().unwrap();
+        let supported = |ext: &str| extensions.iter().any(|e| e == ext);
+
+        match KhrSubgroupsFns::load(platform.as_core()) {
+            Ok(fns) => {
+                // Subsequent loads return the cached table:
+                let cached = KhrSubgroupsFns::load(platform.as_core()).unwrap();
+                assert_eq!(fns.get_kernel_sub_group_info as usize,
+                    cached.get_kernel_sub_group_info as usize);
+            },
+            Err(err) => {
+                assert!(!supported(KhrSubgroupsFns::EXTENSION));
+                assert!(err.to_string().contains("clGetKernelSubGroupInfoKHR"));
+            },
+        }
+
+        if supported(KhrIlProgramFns::EXTENSION) {
+            KhrIlProgramFns::load(platform.as_core()).unwrap();
+        }
+
+        if supported(IntelUsmFns::EXTENSION) {
+            let usm = IntelUsmFns::load(platform.as_core()).unwrap();
+            assert!(format!("{:?}", usm).contains("host_mem_alloc"));
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Tests ICD vendor diagnostics using fake `.icd` files.
+
+use std::env;
+use std::fs;
+use std::process;
+use crate::core::{self, IcdVendorStatus};
+use crate::ffi::LIBRARY_PATH_ENV_VAR;
+
+#[test]
+fn icd_diagnostics() {
+    let dir = env::temp_dir().join(format!("ocl_icd_diagnostics_{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    fs::write(dir.join("a_missing.icd"), "/nonexistent/libOpenCLVendor.so\n").unwrap();
+    fs::write(dir.join("b_empty.icd"), "\n").unwrap();
+    fs::write(dir.join("c_unloadable.icd"), "libOpenCLVendorMissing.so").unwrap();
+    fs::write(dir.join("readme.txt"), "Not an .icd file.").unwrap();
+    #[cfg(target_os = "linux")]
+    fs::write(dir.join("d_not_icd.icd"), "libc.so.6").unwrap();
+
+    // The mock library (if in use) can be listed as a vendor:
+    let mock_path = env::var(LIBRARY_PATH_ENV_VAR).ok().filter(|p| p.contains("ocl_mock"));
+    if let Some(ref path) = mock_path {
+        fs::write(dir.join("e_mock.icd"), path).unwrap();
+    }
+
+    let diagnostics = core::diagnose_icd_vendors(&dir);
+    let vendors = diagnostics.vendors();
+    assert!(diagnostics.vendors_path_error().is_none());
+    assert!(vendors.iter().all(|v| v.icd_file().unwrap().extension().unwrap() == "icd"));
+
+    assert_eq!(*vendors[0].status(), IcdVendorStatus::MissingFile);
+    assert_eq!(vendors[0].library(), "/nonexistent/libOpenCLVendor.so");
+    match *vendors[1].status() {
+        IcdVendorStatus::InvalidIcdFile(_) => (),
+        ref status => panic!("Unexpected status: {}", status),
+    }
+    match *vendors[2].status() {
+        IcdVendorStatus::LoadFailed(ref reason) => assert!(!reason.is_empty()),
+        ref status => panic!("Unexpected status: {}", status),
+    }
+    #[cfg(target_os = "linux")]
+    assert_eq!(*vendors[3].status(), IcdVendorStatus::MissingSymbol("clIcdGetPlatformIDsKHR"));
+
+    if mock_path.is_some() {
+        let mock = vendors.last().unwrap();
+        assert_eq!(*mock.status(), IcdVendorStatus::Ok { platform_count: 1 });
+        assert!(diagnostics.is_ok());
+        assert_eq!(diagnostics.platform_count(), 1);
+    } else {
+        assert!(!diagnostics.is_ok());
+    }
+
+    assert_eq!(diagnostics.failed().count(), vendors.len() - mock_path.iter().count());
+    let report = diagnostics.to_string();
+    assert!(report.contains("a_missing.icd: '/nonexistent/libOpenCLVendor.so': library file \
+        not found"));
+
+    // A single `.icd` file:
+    let single = core::diagnose_icd_vendors(dir.join("a_missing.icd"));
+    assert_eq!(single.vendors().len(), 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+
+    let missing = core::diagnose_icd_vendors(&dir);
+    assert!(missing.vendors_path_error().is_some());
+    assert!(missing.vendors().is_empty());
+    assert!(missing.to_string().contains("No vendor libraries found."));
+}
@@ -0,0 +1,79 @@
+//! Tests cloning kernels and querying sub-group info.
+
+use std::thread;
+use crate::standard::{Platform, Device, Context, Queue, Program, Kernel, Buffer, SpatialDims};
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+#[test]
+fn kernel_try_clone() {
+    const LEN: usize = 256;
+
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+            let queue = Queue::new(&context, device, None).unwrap();
+
+            let program = Program::builder()
+                .devices(device)
+                .src(SRC)
+                .build(&context).unwrap();
+
+            let buffer = Buffer::<f32>::builder()
+                .queue(queue.clone())
+                .len(LEN)
+                .fill_val(0.0)
+                .build().unwrap();
+
+            let kernel = Kernel::builder()
+                .program(&program)
+                .name("add")
+                .queue(queue.clone())
+                .global_work_size(LEN)
+                .local_work_size(64)
+                .arg(&buffer)
+                .arg_named("addend", 1.0f32)
+                .build().unwrap();
+
+            if device.version().unwrap() < [2, 1].into() {
+                assert!(kernel.try_clone().is_err());
+                continue;
+            }
+
+            let clone = kernel.try_clone().unwrap();
+            assert_eq!(clone.default_global_work_size(), SpatialDims::One(LEN));
+            assert_eq!(clone.named_arg_idx("addend"), Some(1));
+
+            // Arguments set on the clone do not affect the original:
+            clone.set_arg("addend", 10.0f32).unwrap();
+            unsafe { kernel.enq().unwrap(); }
+
+            // The clone keeps the buffer argument and can be used elsewhere:
+            thread::spawn(move || {
+                unsafe { clone.enq().unwrap(); }
+                clone.default_queue().unwrap().finish().unwrap();
+            }).join().unwrap();
+
+            let mut vec = vec![0.0f32; LEN];
+            buffer.read(&mut vec).enq().unwrap();
+            assert!(vec.iter().all(|&v| v == 11.0));
+
+            let max_size = kernel.max_sub_group_size(device, SpatialDims::Unspecified).unwrap();
+            let count = kernel.sub_group_count(device, 64).unwrap();
+            assert!(max_size > 0);
+            assert_eq!(count, (64 + max_size - 1) / max_size);
+
+            let lws = kernel.local_size_for_sub_group_count(device, count).unwrap();
+            if !lws.is_unspecified() {
+                assert_eq!(kernel.sub_group_count(device, lws).unwrap(), count);
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Tests memory object destructor callbacks.
+
+use std::sync::mpsc;
+use std::time::Duration;
+use crate::standard::{ProQue, Buffer};
+
+#[test]
+fn mem_destructor_callback() {
+    let pro_que = ProQue::builder()
+        .src("__kernel void add(__global float* buf) { buf[get_global_id(0)] += 1.0f; }")
+        .dims(256)
+        .build().unwrap();
+
+    let host_data = vec![0.0f32; 256];
+    let (tx, rx) = mpsc::channel();
+
+    {
+        let buffer = unsafe {
+            Buffer::<f32>::builder()
+                .queue(pro_que.queue().clone())
+                .len(256)
+                .use_host_slice(&host_data)
+                .build().unwrap()
+        };
+        let mem_ptr = buffer.as_core().as_ptr() as usize;
+
+        let first_tx = tx.clone();
+        buffer.set_destructor_callback(move |mem| {
+            assert_eq!(mem.as_ptr() as usize, mem_ptr);
+            first_tx.send("first").unwrap();
+        }).unwrap();
+
+        // A panicking callback does not prevent the others from running:
+        buffer.set_destructor_callback(|_| panic!("destructor callback panic")).unwrap();
+
+        let second_tx = tx.clone();
+        buffer.set_destructor_callback(move |_| second_tx.send("second").unwrap()).unwrap();
+
+        let kernel = pro_que.kernel_builder("add")
+            .arg(&buffer)
+            .build().unwrap();
+        unsafe { kernel.enq().unwrap(); }
+        pro_que.queue().finish().unwrap();
+
+        // The kernel still holds a reference to the buffer:
+        drop(buffer);
+        assert!(rx.try_recv().is_err());
+    }
+
+    // Once the kernel is dropped, the callbacks run in reverse order of
+    // registration:
+    let timeout = Duration::from_secs(5);
+    assert_eq!(rx.recv_timeout(timeout).unwrap(), "second");
+    assert_eq!(rx.recv_timeout(timeout).unwrap(), "first");
+
+    // The host region is no longer in use:
+    drop(host_data);
+}
@@ -19,6 +19,29 @@ pub mod concurrent;
 pub mod kernel_arg;
 pub mod vector_types;
 pub mod context_props;
+pub mod device_partition;
+pub mod program_kernels;
+pub mod built_in_kernels;
+pub mod mem_destructor;
+pub mod native_kernel;
+pub mod svm;
+pub mod pipe;
+pub mod device_clock;
+pub mod kernel_clone;
+pub mod spec_constants;
+pub mod queue_properties;
+pub mod sampler_properties;
+pub mod extension_fns;
+pub mod opencl_3_0;
+pub mod runtime_versions;
+pub mod icd_diagnostics;
+pub mod device_extensions;
+pub mod device_selector;
+pub mod program_cache;
+pub mod source_map;
+pub mod build_log;
+pub mod compile_link;
+pub mod build_async;
 pub mod r#async;
 pub mod buffer_sink_stream_cycles;
 
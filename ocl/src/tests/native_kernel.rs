@@ -0,0 +1,97 @@
+//! Tests enqueuing native (host closure) kernels.
+
+use std::sync::{Arc, Mutex};
+use crate::core::{DeviceInfo, DeviceInfoResult, DeviceExecCapabilities};
+use crate::standard::{Platform, Device, Context, Queue, Program, Kernel, Buffer, Event};
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+fn supports_native_kernels(device: &Device) -> bool {
+    match device.info(DeviceInfo::ExecutionCapabilities).unwrap() {
+        DeviceInfoResult::ExecutionCapabilities(caps) => {
+            caps.contains(DeviceExecCapabilities::NATIVE_KERNEL)
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn native_kernel() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+            let queue = Queue::new(&context, device, None).unwrap();
+
+            if !supports_native_kernels(&device) {
+                let res = unsafe {
+                    queue.enqueue_native(|_| (), &[], None::<Event>, None::<&mut Event>)
+                };
+                assert!(res.is_err());
+                continue;
+            }
+
+            let program = Program::builder()
+                .devices(device)
+                .src(SRC)
+                .build(&context).unwrap();
+
+            let src = Buffer::<f32>::builder()
+                .queue(queue.clone())
+                .len(64)
+                .fill_val(1.0f32)
+                .build().unwrap();
+            let dst = Buffer::<u32>::builder()
+                .queue(queue.clone())
+                .len(64)
+                .build().unwrap();
+
+            let kernel = Kernel::builder()
+                .program(&program)
+                .name("add")
+                .queue(queue.clone())
+                .global_work_size(64)
+                .arg(&src)
+                .arg(2.0f32)
+                .build().unwrap();
+
+            let mut kernel_event = Event::empty();
+            unsafe { kernel.cmd().enew(&mut kernel_event).enq().unwrap(); }
+
+            // Runs after the kernel: converts `src` into `dst` on the host.
+            let sum = Arc::new(Mutex::new(0.0f32));
+            let sum_native = sum.clone();
+            let mut native_event = Event::empty();
+            unsafe {
+                queue.enqueue_native(move |mut args| {
+                    assert_eq!(args.len(), 2);
+                    let src = args.take::<f32>(0);
+                    let dst = args.take::<u32>(1);
+                    for (d, s) in dst.iter_mut().zip(src.iter()) {
+                        *d = (*s * 10.0) as u32;
+                    }
+                    *sum_native.lock().unwrap() = src.iter().sum();
+                }, &[(&src).into(), (&dst).into()], Some(&kernel_event),
+                    Some(&mut native_event)).unwrap();
+            }
+
+            // A panicking closure does not affect the queue:
+            unsafe {
+                queue.enqueue_native(|mut args| { args.take::<u32>(0); },
+                    &[(&src).into()], Some(&native_event), None::<&mut Event>).unwrap();
+            }
+
+            let mut vec = vec![0u32; 64];
+            dst.read(&mut vec).enq().unwrap();
+            assert!(vec.iter().all(|&v| v == 30));
+            assert_eq!(*sum.lock().unwrap(), 192.0);
+            assert!(native_event.is_complete().unwrap());
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Tests OpenCL 3.0 device queries and API functions.
+
+use crate::core::{NumericVersion, NameVersion, DeviceInfo, DeviceInfoResult};
+use crate::ffi::{cl_name_version, CL_NAME_VERSION_MAX_NAME_SIZE};
+use crate::standard::{Platform, Device};
+use std::sync::mpsc;
+use std::time::Duration;
+use crate::core::MemProperties;
+use crate::standard::{Context, Buffer};
+
+#[test]
+fn numeric_version() {
+    let ver = NumericVersion::new(3, 0, 12);
+    assert_eq!(ver.to_raw(), (3 << 22) | 12);
+    assert_eq!(NumericVersion::from_raw(ver.to_raw()), ver);
+    assert_eq!(ver.to_string(), "3.0.12");
+    assert!(NumericVersion::new(2, 2, 0) < ver);
+    assert_eq!(crate::core::OpenclVersion::from(ver), [3, 0].into());
+}
+
+#[test]
+fn name_version_parsing() {
+    let mut name = [0; CL_NAME_VERSION_MAX_NAME_SIZE];
+    for (c, b) in name.iter_mut().zip(b"cl_khr_fp64".iter()) {
+        *c = *b as _;
+    }
+    let raw = [
+        cl_name_version { version: NumericVersion::new(1, 0, 0).to_raw(), name },
+        cl_name_version { version: 0, name: [0; CL_NAME_VERSION_MAX_NAME_SIZE] },
+    ];
+    let bytes = unsafe { crate::core::util::into_bytes(raw) };
+
+    let list = NameVersion::list_from_bytes(&bytes).unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0].name(), "cl_khr_fp64");
+    assert_eq!(list[0].version(), NumericVersion::new(1, 0, 0));
+    assert_eq!(list[0].to_string(), "cl_khr_fp64 1.0.0");
+    assert_eq!(list[1].name(), "");
+
+    assert!(NameVersion::list_from_bytes(&bytes[1..]).is_err());
+}
+
+#[test]
+fn device_info_3_0() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if device.version().unwrap() < [3, 0].into() {
+                continue;
+            }
+
+            assert!(device.numeric_version().unwrap().major() >= 3);
+
+            // Every extension listed with a version is also listed by name:
+            let extensions = device.info(DeviceInfo::Extensions).unwrap().to_string();
+            for ext in device.extensions_with_version().unwrap() {
+                assert!(extensions.split_whitespace().any(|e| e == ext.name()));
+            }
+
+            for feature in device.opencl_c_features().unwrap() {
+                assert!(feature.name().starts_with("__opencl_c_"));
+            }
+
+            match device.info(DeviceInfo::AtomicMemoryCapabilities).unwrap() {
+                DeviceInfoResult::AtomicMemoryCapabilities(caps) => assert!(!caps.is_empty()),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+#[test]
+fn opencl_3_0_functions() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+
+            let buffer = Buffer::<f32>::builder()
+                .context(&context)
+                .properties(MemProperties::new())
+                .len(256)
+                .build();
+
+            let (tx, rx) = mpsc::channel();
+            let callback = context.set_destructor_callback(move || tx.send(()).unwrap());
+
+            if device.version().unwrap() < [3, 0].into() {
+                assert!(buffer.is_err());
+                assert!(callback.is_err());
+                continue;
+            }
+
+            callback.unwrap();
+            assert_eq!(buffer.unwrap().len(), 256);
+
+            // The buffer was dropped above, releasing the last reference:
+            drop(context);
+            rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Tests pipes connecting producer and consumer kernels.
+
+use crate::core::{PipeInfo, PipeInfoResult};
+use crate::standard::{Platform, Device, Context, Queue, Program, Kernel, Buffer, Pipe};
+
+static SRC: &'static str = r#"
+    __kernel void produce(__write_only pipe int out) {
+        int val = (int)get_global_id(0) * 2;
+        write_pipe(out, &val);
+    }
+
+    __kernel void consume(__read_only pipe int in, __global int* sum) {
+        int val;
+        if (read_pipe(in, &val) == 0) {
+            atomic_add(sum, val);
+        }
+    }
+"#;
+
+#[test]
+fn pipe_producer_consumer() {
+    const PACKETS: usize = 256;
+
+    assert!(Pipe::<i32>::builder().max_packets(1).build().is_err());
+
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if device.version().unwrap() < [2, 0].into() { continue; }
+
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+            let queue = Queue::new(&context, device, None).unwrap();
+
+            let program = Program::builder()
+                .devices(device)
+                .src(SRC)
+                .cmplr_opt("-cl-std=CL2.0")
+                .build(&context).unwrap();
+
+            let pipe = Pipe::<i32>::builder()
+                .context(&context)
+                .max_packets(PACKETS as u32)
+                .build().unwrap();
+
+            match pipe.info(PipeInfo::PacketSize).unwrap() {
+                PipeInfoResult::PacketSize(size) => assert_eq!(size, 4),
+                _ => unreachable!(),
+            }
+            match pipe.info(PipeInfo::MaxPackets).unwrap() {
+                PipeInfoResult::MaxPackets(max) => assert!(max >= PACKETS as u32),
+                _ => unreachable!(),
+            }
+
+            let sum = Buffer::<i32>::builder()
+                .queue(queue.clone())
+                .len(1)
+                .fill_val(0)
+                .build().unwrap();
+
+            let produce = Kernel::builder()
+                .program(&program)
+                .name("produce")
+                .queue(queue.clone())
+                .global_work_size(PACKETS)
+                .arg(&pipe)
+                .build().unwrap();
+
+            let consume = Kernel::builder()
+                .program(&program)
+                .name("consume")
+                .queue(queue.clone())
+                .global_work_size(PACKETS)
+                .arg(&pipe)
+                .arg(&sum)
+                .build().unwrap();
+
+            // The packet type must match the one declared by the kernel:
+            assert!(Kernel::builder()
+                .program(&program)
+                .name("produce")
+                .arg(&Pipe::<f32>::builder()
+                    .context(&context)
+                    .max_packets(1)
+                    .build().unwrap())
+                .build().is_err());
+
+            // Commands on an in-order queue run one after another:
+            unsafe {
+                produce.enq().unwrap();
+                consume.enq().unwrap();
+            }
+
+            let mut vec = vec![0i32];
+            sum.read(&mut vec).enq().unwrap();
+            assert_eq!(vec[0], (0..PACKETS as i32).map(|i| i * 2).sum::<i32>());
+        }
+    }
+}
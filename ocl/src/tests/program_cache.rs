@@ -0,0 +1,100 @@
+//! Tests the on-disk program binary cache.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use crate::standard::{Context, Program, ProgramCache};
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+const MOCK_BINARY_MAGIC: &[u8] = b"OCLMOCK1\n";
+
+/// Splits an entry into its header (ending with the source hash line) and
+/// binary.
+fn split_entry(mut bytes: Vec<u8>) -> (Vec<u8>, Vec<u8>) {
+    let source_line = bytes.windows(9).position(|w| w == b"\nsource: ").unwrap() + 1;
+    let header_len = source_line + bytes[source_line..].iter().position(|&b| b == b'\n').unwrap() + 1;
+    let binary = bytes.split_off(header_len);
+    (bytes, binary)
+}
+
+/// Returns the path of each entry in the cache.
+fn entries(cache: &ProgramCache) -> Vec<PathBuf> {
+    match fs::read_dir(cache.dir()) {
+        Ok(dir) => dir.map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().map_or(false, |ext| ext == "bin"))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+#[test]
+fn program_cache() {
+    let dir = env::temp_dir().join(format!("ocl_program_cache_{}", process::id()));
+    let cache = ProgramCache::new(&dir);
+    cache.clear().unwrap();
+    assert_eq!(cache.size().unwrap(), 0);
+
+    let context = Context::builder().build().unwrap();
+    let device_count = context.devices().len();
+
+    let build = |cache: &ProgramCache, addend_def: i32| {
+        Program::builder()
+            .src(SRC)
+            .cmplr_def("ADDEND", addend_def)
+            .cache(cache.clone())
+            .build(&context).unwrap()
+    };
+
+    // Builds from source, storing a binary for each device:
+    let program = build(&cache, 1);
+    assert_eq!(program.kernel_names().unwrap(), vec!["add".to_owned()]);
+    assert_eq!(entries(&cache).len(), device_count);
+    let size = cache.size().unwrap();
+    assert!(size > 0);
+
+    // Created from the stored binaries:
+    let program = build(&cache, 1);
+    assert_eq!(program.kernel_names().unwrap(), vec!["add".to_owned()]);
+    assert_eq!(cache.size().unwrap(), size);
+
+    // The mock platform's binaries contain the source, which can be swapped
+    // to confirm that cached binaries are used:
+    for path in entries(&cache) {
+        let (header, binary) = split_entry(fs::read(&path).unwrap());
+        if binary.starts_with(MOCK_BINARY_MAGIC) {
+            let src = SRC.replace("void add(", "void cached_add(");
+            fs::write(&path, [&header[..], MOCK_BINARY_MAGIC, src.as_bytes()].concat()).unwrap();
+            let program = build(&cache, 1);
+            assert_eq!(program.kernel_names().unwrap(), vec!["cached_add".to_owned()]);
+        }
+    }
+
+    // Rejected binaries are rebuilt from source and replaced:
+    for path in entries(&cache) {
+        let (header, _) = split_entry(fs::read(&path).unwrap());
+        fs::write(&path, [&header[..], b"not a program binary"].concat()).unwrap();
+    }
+    let program = build(&cache, 1);
+    assert_eq!(program.kernel_names().unwrap(), vec!["add".to_owned()]);
+    assert_eq!(cache.size().unwrap(), size);
+
+    // Different compiler options and cache versions use separate entries:
+    build(&cache, 2);
+    assert_eq!(entries(&cache).len(), device_count * 2);
+    build(&cache.clone().version("2"), 1);
+    assert_eq!(entries(&cache).len(), device_count * 3);
+
+    // The oldest entries are removed once the size limit is exceeded:
+    build(&cache.clone().max_size(cache.size().unwrap() - 1), 3);
+    assert!(entries(&cache).len() < device_count * 4);
+
+    cache.clear().unwrap();
+    assert!(entries(&cache).is_empty());
+    fs::remove_dir(&dir).unwrap();
+}
@@ -0,0 +1,65 @@
+//! Tests creating every kernel in a program at once.
+
+use crate::standard::ProQue;
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+
+    __kernel void mul(__global float* buffer, float coeff) {
+        buffer[get_global_id(0)] *= coeff;
+    }
+
+    __kernel void fill(__global float* buffer) {
+        buffer[get_global_id(0)] = 1.0f;
+    }
+"#;
+
+#[test]
+fn program_kernels() {
+    let pro_que = ProQue::builder()
+        .src(SRC)
+        .dims(64)
+        .build().unwrap();
+
+    let mut names = pro_que.program().kernel_names().unwrap();
+    names.sort();
+    assert_eq!(names, ["add", "fill", "mul"]);
+
+    let mut kernels = pro_que.program().kernels().unwrap();
+    assert_eq!(kernels.len(), 3);
+    assert_eq!(kernels["add"].num_args().unwrap(), 2);
+    assert_eq!(kernels["mul"].num_args().unwrap(), 2);
+    assert_eq!(kernels["fill"].num_args().unwrap(), 1);
+
+    let buffer = pro_que.create_buffer::<f32>().unwrap();
+
+    for name in ["fill", "add", "mul"].iter() {
+        let kernel = kernels.get_mut(*name).unwrap();
+        kernel.set_default_queue(pro_que.queue().clone());
+        kernel.set_default_global_work_size(pro_que.dims().clone());
+        kernel.set_arg(0, &buffer).unwrap();
+    }
+
+    // Where the platform reports argument info, arguments can be set by
+    // their source names and their types are checked:
+    if kernels["add"].named_arg_idx("addend").is_some() {
+        assert!(kernels["add"].set_arg("addend", 5i32).is_err());
+        kernels["add"].set_arg("addend", 5.0f32).unwrap();
+        kernels["mul"].set_arg("coeff", 2.0f32).unwrap();
+    } else {
+        kernels["add"].set_arg(1, 5.0f32).unwrap();
+        kernels["mul"].set_arg(1, 2.0f32).unwrap();
+    }
+
+    unsafe {
+        kernels["fill"].enq().unwrap();
+        kernels["add"].enq().unwrap();
+        kernels["mul"].enq().unwrap();
+    }
+
+    let mut vec = vec![0.0f32; buffer.len()];
+    buffer.read(&mut vec).enq().unwrap();
+    assert!(vec.iter().all(|&v| v == 12.0));
+}
@@ -0,0 +1,86 @@
+//! Tests creating command queues with properties.
+
+use crate::core::{CommandQueueInfo, CommandQueueInfoResult, DeviceInfo, DeviceInfoResult,
+    QueuePriority, QueueProperties};
+use crate::flags::CommandQueueProperties;
+use crate::standard::{Platform, Device, Context, Queue, Buffer};
+
+fn supports_priority_hints(device: &Device) -> bool {
+    if device.version().unwrap() < [2, 0].into() {
+        return false;
+    }
+
+    match device.info(DeviceInfo::Extensions).unwrap() {
+        DeviceInfoResult::Extensions(exts) => exts.contains("cl_khr_priority_hints"),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn queue_properties_to_raw() {
+    assert_eq!(QueueProperties::new().to_raw(), vec![0]);
+    assert!(QueueProperties::new().profiling().is_bitfield_only());
+
+    let props = QueueProperties::new().on_device(true).size(4096)
+        .priority(QueuePriority::Low);
+    assert!(!props.is_bitfield_only());
+    assert_eq!(props.to_raw(), vec![
+        crate::ffi::CL_QUEUE_PROPERTIES as u64,
+        (CommandQueueProperties::OUT_OF_ORDER_EXEC_MODE_ENABLE |
+            CommandQueueProperties::ON_DEVICE | CommandQueueProperties::ON_DEVICE_DEFAULT).bits(),
+        crate::ffi::CL_QUEUE_SIZE as u64, 4096,
+        crate::ffi::CL_QUEUE_PRIORITY_KHR, crate::ffi::CL_QUEUE_PRIORITY_LOW_KHR as u64,
+        0]);
+}
+
+#[test]
+fn queue_with_properties() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+
+            let queue = Queue::with_properties(&context, device,
+                QueueProperties::new().profiling()).unwrap();
+            match queue.info(CommandQueueInfo::Properties).unwrap() {
+                CommandQueueInfoResult::Properties(props) => {
+                    assert!(props.contains(CommandQueueProperties::PROFILING_ENABLE))
+                },
+                _ => unreachable!(),
+            }
+
+            // Plain flags convert directly:
+            Queue::with_properties(&context, device, CommandQueueProperties::new()).unwrap();
+
+            let high = Queue::with_properties(&context, device,
+                QueueProperties::new().priority(QueuePriority::High));
+            let low = Queue::with_properties(&context, device,
+                QueueProperties::new().priority(QueuePriority::Low));
+
+            if !supports_priority_hints(&device) {
+                if device.version().unwrap() < [2, 0].into() {
+                    assert!(high.is_err() && low.is_err());
+                }
+                continue;
+            }
+
+            // Both queues can be used at once:
+            let (high, low) = (high.unwrap(), low.unwrap());
+            let buffers = [&high, &low].iter().map(|&q| {
+                Buffer::<u32>::builder()
+                    .queue(q.clone())
+                    .len(64)
+                    .fill_val(7)
+                    .build().unwrap()
+            }).collect::<Vec<_>>();
+
+            for buffer in buffers.iter() {
+                let mut vec = vec![0u32; 64];
+                buffer.read(&mut vec).enq().unwrap();
+                assert!(vec.iter().all(|&v| v == 7));
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Tests runtime resolution and version checks of newer API functions.
+
+use crate::core::{self, ErrorKind as CoreErrorKind};
+use crate::error::ErrorKind;
+use crate::ffi;
+use crate::standard::{Platform, Device};
+
+#[test]
+fn function_availability() {
+    assert!(ffi::function_is_available("clGetPlatformIDs"));
+    assert!(!ffi::function_is_available("clNotAnOpenclFunction"));
+}
+
+#[test]
+fn runtime_version_checks() {
+    for platform in Platform::list() {
+        if !platform.version().unwrap().contains("OpenCL 1.1") {
+            core::unload_platform_compiler(platform.as_core(), None).unwrap();
+        }
+
+        for device in Device::list_all(platform).unwrap() {
+            if device.version().unwrap() >= [2, 1].into() {
+                continue;
+            }
+
+            // Functions newer than the device are rejected without being called:
+            let err = device.host_timer().unwrap_err();
+            match *err.kind() {
+                ErrorKind::OclCore(ref err) => match *err.kind() {
+                    CoreErrorKind::VersionLow(_) => (),
+                    ref kind => panic!("Unexpected error: {}", kind),
+                },
+                ref kind => panic!("Unexpected error: {}", kind),
+            }
+        }
+    }
+}
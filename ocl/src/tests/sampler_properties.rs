@@ -0,0 +1,98 @@
+//! Tests creating samplers with properties.
+
+use crate::core::{DeviceInfo, DeviceInfoResult, SamplerInfo, SamplerInfoResult, SamplerProperties,
+    AddressingMode, FilterMode};
+use crate::standard::{Platform, Device, Context, Sampler};
+
+fn supports_images(device: &Device) -> bool {
+    match device.info(DeviceInfo::ImageSupport).unwrap() {
+        DeviceInfoResult::ImageSupport(is) => is,
+        _ => unreachable!(),
+    }
+}
+
+fn supports_mipmap_images(device: &Device) -> bool {
+    if device.version().unwrap() < [2, 0].into() {
+        return false;
+    }
+
+    match device.info(DeviceInfo::Extensions).unwrap() {
+        DeviceInfoResult::Extensions(exts) => exts.contains("cl_khr_mipmap_image"),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn sampler_properties_to_raw() {
+    assert_eq!(SamplerProperties::new().to_raw(), vec![0]);
+    assert!(SamplerProperties::new().filter_mode(FilterMode::Linear).is_basic());
+
+    let props = SamplerProperties::new().normalized_coords(false).lod(0.5, 4.0);
+    assert!(!props.is_basic());
+    assert_eq!(props.to_raw(), vec![
+        crate::ffi::CL_SAMPLER_NORMALIZED_COORDS as u64, 0,
+        crate::ffi::CL_SAMPLER_LOD_MIN as u64, 0.5f32.to_bits() as u64,
+        crate::ffi::CL_SAMPLER_LOD_MAX as u64, 4.0f32.to_bits() as u64,
+        0]);
+}
+
+#[test]
+fn sampler_builder() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if !supports_images(&device) { continue; }
+
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+
+            let sampler = Sampler::builder()
+                .normalized_coords(false)
+                .addressing_mode(AddressingMode::ClampToEdge)
+                .filter_mode(FilterMode::Linear)
+                .build(&context).unwrap();
+
+            match sampler.info(SamplerInfo::NormalizedCoords).unwrap() {
+                SamplerInfoResult::NormalizedCoords(nc) => assert!(!nc),
+                _ => unreachable!(),
+            }
+            match sampler.info(SamplerInfo::AddressingMode).unwrap() {
+                SamplerInfoResult::AddressingMode(am) => assert_eq!(am, AddressingMode::ClampToEdge),
+                _ => unreachable!(),
+            }
+            match sampler.info(SamplerInfo::FilterMode).unwrap() {
+                SamplerInfoResult::FilterMode(fm) => assert_eq!(fm, FilterMode::Linear),
+                _ => unreachable!(),
+            }
+
+            let mip_sampler = Sampler::builder()
+                .filter_mode(FilterMode::Linear)
+                .mip_filter_mode(FilterMode::Linear)
+                .lod_min(1.0)
+                .lod_max(3.0)
+                .build(&context);
+
+            if !supports_mipmap_images(&device) {
+                if device.version().unwrap() < [2, 0].into() {
+                    assert!(mip_sampler.is_err());
+                }
+                continue;
+            }
+
+            let mip_sampler = mip_sampler.unwrap();
+            match mip_sampler.info(SamplerInfo::MipFilterMode).unwrap() {
+                SamplerInfoResult::MipFilterMode(fm) => assert_eq!(fm, FilterMode::Linear),
+                _ => unreachable!(),
+            }
+            match mip_sampler.info(SamplerInfo::LodMin).unwrap() {
+                SamplerInfoResult::LodMin(lod) => assert_eq!(lod, 1.0),
+                _ => unreachable!(),
+            }
+            match mip_sampler.info(SamplerInfo::LodMax).unwrap() {
+                SamplerInfoResult::LodMax(lod) => assert_eq!(lod, 3.0),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Tests `#include` resolution and the mapping of build log line numbers to
+//! original source files.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use crate::standard::{Context, Program, SourceLocation};
+
+static HELPERS: &'static str = "#pragma once
+#include \"consts.cl\"
+
+float scale(float val) {
+    return val * SCALE;
+}
+";
+
+static CONSTS: &'static str = "#ifndef CONSTS_CL
+#define CONSTS_CL
+
+#define SCALE 2.0f
+
+#endif
+";
+
+static MAIN: &'static str = "#include \"helpers.cl\"
+#include \"inc/consts.cl\"
+#include \"helpers.cl\"
+
+__kernel void scale_all(__global float* buffer) {
+    buffer[get_global_id(0)] = scale(buffer[get_global_id(0)]);
+}
+";
+
+fn write(dir: &Path, name: &str, text: &str) -> PathBuf {
+    let path = dir.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, text).unwrap();
+    path
+}
+
+fn loc(file: &Path, line: usize) -> Option<SourceLocation> {
+    Some(SourceLocation { file: file.display().to_string(), line })
+}
+
+#[test]
+fn source_map() {
+    let dir = env::temp_dir().join(format!("ocl_source_map_{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let main = write(&dir, "main.cl", MAIN);
+    let helpers = write(&dir, "helpers.cl", HELPERS);
+    let consts = write(&dir, "inc/consts.cl", CONSTS);
+    let context = Context::builder().build().unwrap();
+
+    // Without resolution the source is unchanged and only strings are mapped:
+    let mut builder = Program::builder();
+    builder.src_file(&main).src("// eof");
+    let src_strings = builder.get_src_strings().unwrap();
+    assert_eq!(src_strings.len(), 4);
+    assert_eq!(src_strings[1].to_str().unwrap(), MAIN);
+    let map = builder.get_source_map().unwrap();
+    assert_eq!(map.lookup(2), loc(&main, 1));
+    assert_eq!(map.lookup(8), loc(&main, 7));
+    assert_eq!(map.lookup(10), Some(SourceLocation { file: "<src 1>".to_owned(), line: 1 }));
+
+    // Includes are resolved relative to the including file then within the
+    // include directories, and included files are only included once:
+    let mut builder = Program::builder();
+    builder.src_file(&main).include_dir(dir.join("inc")).resolve_includes(true);
+    let src: String = builder.get_src_strings().unwrap().iter()
+        .map(|s| s.to_str().unwrap().to_owned()).collect();
+    assert_eq!(src.matches("float scale(").count(), 1);
+    assert_eq!(src.matches("#define SCALE").count(), 1);
+    assert!(!src.contains("#include"));
+    assert!(!src.contains("#pragma once"));
+
+    let map = builder.get_source_map().unwrap();
+    let line_of = |text: &str| src.lines().position(|l| l.contains(text)).unwrap() + 1;
+    assert_eq!(map.lookup(line_of("float scale(")), loc(&helpers, 4));
+    assert_eq!(map.lookup(line_of("#define SCALE")), loc(&consts, 4));
+    assert_eq!(map.lookup(line_of("__kernel void scale_all")), loc(&main, 5));
+
+    let program = builder.build(&context).unwrap();
+    assert_eq!(program.kernel_names().unwrap(), vec!["scale_all".to_owned()]);
+
+    // Build errors within included files refer to the original location:
+    write(&dir, "broken.cl", "float broken(float val) {\n    return val * undeclared;\n}\n");
+    let err = Program::builder()
+        .src("#include \"broken.cl\"\n__kernel void k() {}\n")
+        .include_dir(&dir)
+        .resolve_includes(true)
+        .build(&context).unwrap_err();
+    let broken = dir.join("broken.cl").display().to_string();
+    assert!(err.to_string().contains(&format!("{}:2", broken)), "{}", err);
+
+    // Unresolvable includes fail to build:
+    let err = Program::builder()
+        .src("\n#include \"missing.cl\"\n")
+        .resolve_includes(true)
+        .build(&context).unwrap_err();
+    assert!(err.to_string().contains("<src 1>:2"), "{}", err);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn source_map_log() {
+    let mut builder = Program::builder();
+    builder.src("__kernel void a() {}\n").src("__kernel void b() {\n    oops\n}\n");
+    let map = builder.get_source_map().unwrap();
+
+    // The leading build option string occupies line 1 and the second
+    // separates files from source strings:
+    assert_eq!(map.lookup(3), Some(SourceLocation { file: "<src 1>".to_owned(), line: 1 }));
+    assert_eq!(map.lookup(5), Some(SourceLocation { file: "<src 2>".to_owned(), line: 2 }));
+
+    assert_eq!(map.map_log("<source>:5:5: error: use of undeclared identifier 'oops'"),
+        "<src 2>:2:5: error: use of undeclared identifier 'oops'");
+    assert_eq!(map.map_log("line 5: use of undeclared identifier 'oops'"),
+        "<src 2>:2: use of undeclared identifier 'oops'");
+    assert_eq!(map.map_log("\"<src 2>\", line 2: error"), "\"<src 2>\", line 2: error");
+    assert_eq!(map.map_log("<source>:99: error"), "<source>:99: error");
+}
@@ -0,0 +1,69 @@
+//! Tests specialization constants and program release callbacks.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::standard::{Platform, Device, Context, Program, BuildOpt};
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+#[test]
+fn spec_constant_bytes() {
+    match BuildOpt::spec_constant(3, 0x0102_0304u32) {
+        BuildOpt::SpecConstant { id, value } => {
+            assert_eq!(id, 3);
+            assert_eq!(value, 0x0102_0304u32.to_ne_bytes().to_vec());
+        },
+        _ => unreachable!(),
+    }
+
+    match BuildOpt::spec_constant(0, 1u8) {
+        BuildOpt::SpecConstant { value, .. } => assert_eq!(value, vec![1]),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn spec_constants_and_release_callback() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+
+            // Specialization constants can not be applied to source:
+            assert!(Program::builder()
+                .devices(device)
+                .src(SRC)
+                .spec_constant(0, 16u32)
+                .build(&context).is_err());
+
+            let program = Program::builder()
+                .devices(device)
+                .src(SRC)
+                .build(&context).unwrap();
+
+            let released = Arc::new(AtomicBool::new(false));
+            let released_cb = released.clone();
+            let result = program.set_release_callback(move || {
+                released_cb.store(true, Ordering::SeqCst);
+            });
+
+            if device.version().unwrap() < [2, 2].into() {
+                assert!(result.is_err());
+                continue;
+            }
+
+            // Release callbacks are optional on OpenCL 3.0 platforms:
+            if result.is_ok() {
+                assert!(!released.load(Ordering::SeqCst));
+                drop(program);
+                assert!(released.load(Ordering::SeqCst));
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Tests shared virtual memory.
+
+use crate::core::{DeviceInfo, DeviceInfoResult, DeviceSvmCapabilities, KernelExecInfo};
+use crate::core::ffi::c_void;
+use crate::standard::{Platform, Device, Context, Queue, Program, Kernel, SvmVec};
+
+static SRC: &'static str = r#"
+    // Each node is a pair: the address of the next node (or zero) and the
+    // address of its value (within another allocation).
+    __kernel void sum_list(__global ulong* head, __global ulong* result) {
+        ulong sum = 0;
+        __global ulong* node = head;
+        while (node) {
+            sum += *((__global ulong*)node[1]);
+            node = (__global ulong*)node[0];
+        }
+        result[0] = sum;
+    }
+"#;
+
+fn supports_coarse_grain_svm(device: &Device) -> bool {
+    if device.version().unwrap() < [2, 0].into() { return false; }
+
+    match device.info(DeviceInfo::SvmCapabilities).unwrap() {
+        DeviceInfoResult::SvmCapabilities(caps) => {
+            caps.contains(DeviceSvmCapabilities::COARSE_GRAIN_BUFFER)
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn svm_linked_list() {
+    const NODES: usize = 16;
+
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if !supports_coarse_grain_svm(&device) { continue; }
+
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+            let queue = Queue::new(&context, device, None).unwrap();
+
+            let program = Program::builder()
+                .devices(device)
+                .src(SRC)
+                .build(&context).unwrap();
+
+            let values = SvmVec::<u64>::builder()
+                .queue(queue.clone())
+                .len(NODES)
+                .fill_val(0)
+                .build().unwrap();
+            let nodes = SvmVec::<u64>::builder()
+                .queue(queue.clone())
+                .len(NODES * 2)
+                .build().unwrap();
+            let result = SvmVec::<u64>::builder()
+                .queue(queue.clone())
+                .len(1)
+                .fill_val(0)
+                .build().unwrap();
+
+            // Fill the values through a mapping:
+            {
+                let mut map = unsafe { values.map().write_invalidate().enq().unwrap() };
+                for (i, v) in map.iter_mut().enumerate() { *v = i as u64 + 1; }
+                map.unmap().enq().unwrap();
+            }
+
+            // Link each node to the one following it:
+            let mut links = vec![0u64; NODES * 2];
+            for i in 0..NODES {
+                if i + 1 < NODES {
+                    links[i * 2] = unsafe { nodes.as_ptr().add((i + 1) * 2) as u64 };
+                }
+                links[i * 2 + 1] = unsafe { values.as_ptr().add(i) as u64 };
+            }
+            nodes.write(&links).enq().unwrap();
+
+            let kernel = Kernel::builder()
+                .program(&program)
+                .name("sum_list")
+                .queue(queue.clone())
+                .global_work_size(1)
+                .arg(&nodes)
+                .arg(&result)
+                .build().unwrap();
+
+            // The values are only reachable through pointers:
+            let svm_ptrs = [values.as_ptr() as *const c_void];
+            kernel.set_exec_info(KernelExecInfo::SvmPtrs(&svm_ptrs)).unwrap();
+            unsafe { kernel.enq().unwrap(); }
+
+            let mut sum = [0u64];
+            result.read(&mut sum).enq().unwrap();
+            assert_eq!(sum[0], (1..=NODES as u64).sum::<u64>());
+
+            // Cut the list after the first node:
+            nodes.write(&[0u64]).enq().unwrap();
+            unsafe { kernel.enq().unwrap(); }
+            result.read(&mut sum).enq().unwrap();
+            assert_eq!(sum[0], 1);
+
+            // Copies and fills:
+            let copy = SvmVec::<u64>::builder()
+                .queue(queue.clone())
+                .len(NODES)
+                .build().unwrap();
+            values.cmd().copy(&copy, None, None).enq().unwrap();
+            copy.cmd().offset(NODES / 2).fill(0, None).enq().unwrap();
+            let mut vec = vec![0u64; NODES];
+            copy.read(&mut vec).enq().unwrap();
+            for (i, &v) in vec.iter().enumerate() {
+                assert_eq!(v, if i < NODES / 2 { i as u64 + 1 } else { 0 });
+            }
+
+            // Out of range regions are rejected:
+            assert!(copy.read(&mut vec).offset(1).enq().is_err());
+            assert!(copy.cmd().fill(0, Some(NODES + 1)).enq().is_err());
+
+            queue.finish().unwrap();
+        }
+    }
+}
@@ -8,6 +8,7 @@ use crate::prm::Int4;
 const DATASET_SIZE: usize = 1 << 20;
 
 #[test]
+#[ignore = "requires a real OpenCL platform (vector loads and stores)"]
 fn test_vector_types() {
 
     let src = r#"